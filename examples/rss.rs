@@ -1,13 +1,15 @@
-//! `cargo run --example rss`
+//! `cargo run --example rss --features="spider/sync spider/feeds"`
 extern crate spider;
 
 use spider::tokio;
+use spider::utils::feeds::FeedConfig;
 use spider::website::Website;
 use tokio::io::AsyncWriteExt;
 
 #[tokio::main]
 async fn main() {
-    let mut website: Website = Website::new("https://a11ywatch.com/rss")
+    let mut website: Website = Website::new("https://a11ywatch.com")
+        .with_feeds(Some(FeedConfig::new(["/rss"]).with_discover(true)))
         .with_limit(5)
         .build()
         .unwrap();
@@ -27,6 +29,10 @@ async fn main() {
     website.crawl().await;
     let duration = start.elapsed();
 
+    for item in website.get_feed_items().values() {
+        println!("{:?} - {} {:?}", item.published, item.url, item.title);
+    }
+
     println!(
         "Time elapsed in website.crawl() is: {:?} for total pages: {:?}",
         duration,
@@ -1,10 +1,11 @@
 use crate::compact_str::CompactString;
 use crate::features::chrome_common::RequestInterceptConfiguration;
 pub use crate::features::chrome_common::{
-    AuthChallengeResponse, AuthChallengeResponseResponse, AutomationScripts, AutomationScriptsMap,
-    CaptureScreenshotFormat, CaptureScreenshotParams, ClipViewport, ExecutionScripts,
-    ExecutionScriptsMap, ScreenShotConfig, ScreenshotParams, Viewport, WaitFor, WaitForDelay,
-    WaitForIdleNetwork, WaitForSelector, WebAutomation,
+    AccessibilityConfig, AuthChallengeResponse, AuthChallengeResponseResponse, AutomationScripts,
+    AutomationScriptsMap, CaptureScreenshotFormat, CaptureScreenshotParams, ClipViewport,
+    ExecutionScripts, ExecutionScriptsMap, MhtmlConfig, PaperFormat, PdfConfig, PrintToPdfParams,
+    ScreenShotConfig, ScreenshotParams, Viewport, WaitFor, WaitForDelay, WaitForIdleNetwork,
+    WaitForSelector, WebAutomation,
 };
 pub use crate::features::gemini_common::GeminiConfigs;
 pub use crate::features::openai_common::GPTConfigs;
@@ -83,6 +84,19 @@ pub struct ChromeEventTracker {
     pub requests: bool,
     /// Track the changes between web automation.
     pub automation: bool,
+    #[cfg(feature = "har")]
+    /// Capture a HAR 1.2 network log for each page.
+    pub har: Option<crate::utils::har::HarConfig>,
+    #[cfg(feature = "browser_logs")]
+    /// Capture the browser console messages, JS exceptions and failed resources for each page.
+    pub browser_logs: Option<crate::utils::browser_logs::BrowserLogsConfig>,
+    #[cfg(feature = "web_vitals")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    /// Collect the Core Web Vitals and performance metrics for each page.
+    pub web_vitals: bool,
+    #[cfg(feature = "accessibility")]
+    /// Extract the accessibility tree and audit it for each page.
+    pub accessibility: Option<AccessibilityConfig>,
 }
 
 #[cfg(feature = "chrome")]
@@ -93,6 +107,14 @@ impl ChromeEventTracker {
             requests,
             responses,
             automation: true,
+            #[cfg(feature = "har")]
+            har: None,
+            #[cfg(feature = "browser_logs")]
+            browser_logs: None,
+            #[cfg(feature = "web_vitals")]
+            web_vitals: false,
+            #[cfg(feature = "accessibility")]
+            accessibility: None,
         }
     }
 }
@@ -347,6 +369,10 @@ pub struct CustomAntibotPatterns {
 pub struct Configuration {
     /// Respect robots.txt file and not scrape not allowed files. This may slow down crawls if robots.txt file has a delay included.
     pub respect_robots_txt: bool,
+    /// Honor robots meta tags and the `X-Robots-Tag` header: do not follow links on pages declaring `nofollow`, and do not send pages declaring `noindex` or `noarchive` to subscribers.
+    pub respect_robots_meta: bool,
+    /// Do not follow links marked `rel="nofollow"`.
+    pub respect_rel_nofollow: bool,
     /// Allow sub-domains.
     pub subdomains: bool,
     /// Allow all tlds for domain.
@@ -392,6 +418,18 @@ pub struct Configuration {
     #[cfg(feature = "sitemap")]
     /// Prevent including the sitemap links with the crawl.
     pub ignore_sitemap: bool,
+    #[cfg(feature = "sitemap")]
+    /// Sitemap index depth limit, `lastmod` filtering and entry metadata collection.
+    pub sitemap_config: Option<crate::utils::sitemap_meta::SitemapConfig>,
+    #[cfg(feature = "feeds")]
+    /// Feeds to seed the crawl queue with.
+    pub feeds: Option<crate::utils::feeds::FeedConfig>,
+    #[cfg(feature = "monitor")]
+    /// Compare the pages of each crawl against the previous run.
+    pub monitor: Option<crate::utils::monitor::MonitorConfig>,
+    #[cfg(feature = "cluster")]
+    /// The place of this node in a crawl spread across several workers.
+    pub cluster: Option<crate::utils::cluster::ClusterConfig>,
     /// The max redirections allowed for request.
     pub redirect_limit: usize,
     /// The redirect policy type to use.
@@ -478,6 +516,10 @@ pub struct Configuration {
     pub concurrency_limit: Option<usize>,
     /// Normalize the html de-deplucating the content.
     pub normalize: bool,
+    /// De-duplicate pages by their canonical URL so alternates are not emitted or followed.
+    pub canonical_dedup: bool,
+    /// Detect pages whose visible text nearly matches an earlier page.
+    pub near_duplicate: Option<crate::utils::near_duplicate::NearDuplicateConfig>,
     /// Share the state of the crawl requires the 'disk' feature flag.
     pub shared: bool,
     /// Modify the headers to act like a real-browser
@@ -560,6 +602,12 @@ pub struct Configuration {
     /// Take a screenshot of the page.
     pub screenshot: Option<ScreenShotConfig>,
     #[cfg(feature = "chrome")]
+    /// Print the page as PDF.
+    pub pdf: Option<PdfConfig>,
+    #[cfg(feature = "chrome")]
+    /// Capture an MHTML snapshot of the page.
+    pub mhtml: Option<MhtmlConfig>,
+    #[cfg(feature = "chrome")]
     /// Track the events made via chrome.
     pub track_events: Option<ChromeEventTracker>,
     #[cfg(feature = "chrome")]
@@ -672,6 +720,11 @@ pub struct Configuration {
     /// Latency-based auto-throttle configuration. When enabled, dynamically
     /// adjusts per-domain crawl delay based on measured server response time.
     pub auto_throttle: Option<crate::utils::auto_throttle::AutoThrottleConfig>,
+    #[cfg(feature = "politeness")]
+    /// Per-host politeness scheduling. When set, robots.txt `Crawl-delay` and
+    /// `Request-rate`, a concurrency cap and `Retry-After` are enforced per host
+    /// instead of a single crawl-wide delay.
+    pub politeness: Option<crate::utils::politeness::PolitenessConfig>,
     #[cfg(feature = "etag_cache")]
     /// Enable ETag / conditional request caching. When true, stores ETag and
     /// Last-Modified headers from responses and sends If-None-Match /
@@ -682,6 +735,11 @@ pub struct Configuration {
     /// WARC output configuration. When set, the crawl writes a WARC 1.1 file
     /// containing all fetched pages as `response` records.
     pub warc: Option<crate::utils::warc::WarcConfig>,
+    #[cfg(feature = "checkpoint")]
+    /// Checkpoint configuration. When set, the crawl periodically snapshots its
+    /// visited set, pending queue and budget so it can be resumed with
+    /// `Website::resume_from`.
+    pub checkpoint: Option<crate::utils::checkpoint::CheckpointConfig>,
     #[cfg(feature = "parallel_backends")]
     /// Parallel crawl backend configuration. Race CDP / Servo backends alongside
     /// the primary crawl path. Requires the `parallel_backends` feature.
@@ -1095,6 +1153,26 @@ impl Configuration {
         self
     }
 
+    /// Respect `nofollow`, `noindex` and `noarchive` from robots meta tags and the `X-Robots-Tag` header.
+    pub fn with_respect_robots_meta(&mut self, respect_robots_meta: bool) -> &mut Self {
+        self.respect_robots_meta = respect_robots_meta;
+        self
+    }
+
+    /// Skip links marked `rel="nofollow"`.
+    pub fn with_respect_rel_nofollow(&mut self, respect_rel_nofollow: bool) -> &mut Self {
+        self.respect_rel_nofollow = respect_rel_nofollow;
+        self
+    }
+
+    /// The `nofollow` signals honored during link extraction.
+    pub fn nofollow_policy(&self) -> crate::utils::robots_meta::NofollowPolicy {
+        crate::utils::robots_meta::NofollowPolicy::new(
+            self.respect_robots_meta,
+            self.respect_rel_nofollow,
+        )
+    }
+
     /// Include subdomains detection.
     pub fn with_subdomains(&mut self, subdomains: bool) -> &mut Self {
         self.subdomains = subdomains;
@@ -1204,6 +1282,61 @@ impl Configuration {
         self
     }
 
+    #[cfg(feature = "sitemap")]
+    /// Configure the sitemap crawl: nested `<sitemapindex>` depth, skipping entries unchanged since a timestamp, and recording each entry's `lastmod`, `changefreq` and `priority`.
+    pub fn with_sitemap_config(
+        &mut self,
+        sitemap_config: Option<crate::utils::sitemap_meta::SitemapConfig>,
+    ) -> &mut Self {
+        self.sitemap_config = sitemap_config;
+        self
+    }
+
+    #[cfg(feature = "feeds")]
+    /// Seed the crawl queue with the items of RSS, Atom and JSON Feed documents. This method does nothing if the `feeds` flag is not enabled.
+    pub fn with_feeds(&mut self, feeds: Option<crate::utils::feeds::FeedConfig>) -> &mut Self {
+        self.feeds = feeds;
+        self
+    }
+
+    #[cfg(not(feature = "feeds"))]
+    /// Seed the crawl queue with the items of RSS, Atom and JSON Feed documents. This method does nothing if the `feeds` flag is not enabled.
+    pub fn with_feeds(&mut self, _feeds: Option<()>) -> &mut Self {
+        self
+    }
+
+    #[cfg(feature = "monitor")]
+    /// Detect page changes across crawl runs. This method does nothing if the `monitor` flag is not enabled.
+    pub fn with_monitor(
+        &mut self,
+        monitor: Option<crate::utils::monitor::MonitorConfig>,
+    ) -> &mut Self {
+        self.monitor = monitor;
+        self
+    }
+
+    #[cfg(not(feature = "monitor"))]
+    /// Detect page changes across crawl runs. This method does nothing if the `monitor` flag is not enabled.
+    pub fn with_monitor(&mut self, _monitor: Option<()>) -> &mut Self {
+        self
+    }
+
+    #[cfg(feature = "cluster")]
+    /// Crawl as one node of a cluster, sending links of hosts owned by other nodes to them. This method does nothing if the `cluster` flag is not enabled.
+    pub fn with_cluster(
+        &mut self,
+        cluster: Option<crate::utils::cluster::ClusterConfig>,
+    ) -> &mut Self {
+        self.cluster = cluster;
+        self
+    }
+
+    #[cfg(not(feature = "cluster"))]
+    /// Crawl as one node of a cluster. This method does nothing if the `cluster` flag is not enabled.
+    pub fn with_cluster(&mut self, _cluster: Option<()>) -> &mut Self {
+        self
+    }
+
     /// Add user agent to request.
     pub fn with_user_agent(&mut self, user_agent: Option<&str>) -> &mut Self {
         match user_agent {
@@ -2301,6 +2434,21 @@ impl Configuration {
         self
     }
 
+    /// De-duplicate pages by the URL of their `<link rel="canonical">` tag or `Link: <...>; rel=canonical` header. The page served at the canonical URL is kept; alternates are neither sent to subscribers nor have their links followed, and an alternate whose canonical URL was not crawled yet queues it instead.
+    pub fn with_canonical_dedup(&mut self, canonical_dedup: bool) -> &mut Self {
+        self.canonical_dedup = canonical_dedup;
+        self
+    }
+
+    /// Detect near-duplicate pages by the SimHash of their visible text. Matches are flagged with `Page::near_duplicate_of`, or skipped like canonical alternates when the config asks for it.
+    pub fn with_near_duplicate(
+        &mut self,
+        config: Option<crate::utils::near_duplicate::NearDuplicateConfig>,
+    ) -> &mut Self {
+        self.near_duplicate = config;
+        self
+    }
+
     #[cfg(not(feature = "disk"))]
     /// Store all the links found on the disk to share the state. This does nothing without the `disk` flag enabled.
     pub fn with_shared_state(&mut self, _shared: bool) -> &mut Self {
@@ -2347,6 +2495,97 @@ impl Configuration {
         self
     }
 
+    #[cfg(feature = "har")]
+    /// Capture a HAR 1.2 network log for each page rendered with chrome.
+    pub fn with_har(&mut self, har: Option<crate::utils::har::HarConfig>) -> &mut Self {
+        match self.track_events.as_mut() {
+            Some(tracker) => tracker.har = har,
+            _ if har.is_some() => {
+                self.track_events = Some(ChromeEventTracker {
+                    har,
+                    ..ChromeEventTracker::new(false, false)
+                })
+            }
+            _ => (),
+        }
+        self
+    }
+
+    /// Capture a HAR 1.2 network log for each page (no-op without `har` feature).
+    #[cfg(not(feature = "har"))]
+    pub fn with_har(&mut self, _har: Option<()>) -> &mut Self {
+        self
+    }
+
+    #[cfg(feature = "browser_logs")]
+    /// Capture the browser console messages, JS exceptions and failed resources for each page rendered with chrome.
+    pub fn with_browser_logs(
+        &mut self,
+        browser_logs: Option<crate::utils::browser_logs::BrowserLogsConfig>,
+    ) -> &mut Self {
+        match self.track_events.as_mut() {
+            Some(tracker) => tracker.browser_logs = browser_logs,
+            _ if browser_logs.is_some() => {
+                self.track_events = Some(ChromeEventTracker {
+                    browser_logs,
+                    ..ChromeEventTracker::new(false, false)
+                })
+            }
+            _ => (),
+        }
+        self
+    }
+
+    /// Capture the browser logs for each page (no-op without `browser_logs` feature).
+    #[cfg(not(feature = "browser_logs"))]
+    pub fn with_browser_logs(&mut self, _browser_logs: Option<()>) -> &mut Self {
+        self
+    }
+
+    #[cfg(feature = "web_vitals")]
+    /// Collect the Core Web Vitals and performance metrics for each page rendered with chrome.
+    pub fn with_web_vitals(&mut self, web_vitals: bool) -> &mut Self {
+        match self.track_events.as_mut() {
+            Some(tracker) => tracker.web_vitals = web_vitals,
+            _ if web_vitals => {
+                self.track_events = Some(ChromeEventTracker {
+                    web_vitals,
+                    ..ChromeEventTracker::new(false, false)
+                })
+            }
+            _ => (),
+        }
+        self
+    }
+
+    /// Collect the Core Web Vitals for each page (no-op without `web_vitals` feature).
+    #[cfg(not(feature = "web_vitals"))]
+    pub fn with_web_vitals(&mut self, _web_vitals: bool) -> &mut Self {
+        self
+    }
+
+    #[cfg(feature = "accessibility")]
+    /// Extract the accessibility tree and run the a11y audit for each page rendered with chrome.
+    pub fn with_accessibility(&mut self, accessibility: Option<AccessibilityConfig>) -> &mut Self {
+        match self.track_events.as_mut() {
+            Some(tracker) => tracker.accessibility = accessibility,
+            _ if accessibility.is_some() => {
+                self.track_events = Some(ChromeEventTracker {
+                    accessibility,
+                    ..ChromeEventTracker::new(false, false)
+                })
+            }
+            _ => (),
+        }
+        self
+    }
+
+    /// Run the a11y audit for each page (no-op without `accessibility` feature).
+    #[cfg(not(feature = "accessibility"))]
+    pub fn with_accessibility(&mut self, _accessibility: Option<AccessibilityConfig>) -> &mut Self {
+        self
+    }
+
     /// Set the chrome screenshot configuration. This does nothing without the `chrome` flag enabled.
     #[cfg(not(feature = "chrome"))]
     pub fn with_screenshot(&mut self, _screenshot_config: Option<ScreenShotConfig>) -> &mut Self {
@@ -2360,6 +2599,32 @@ impl Configuration {
         self
     }
 
+    /// Set the chrome PDF configuration. This does nothing without the `chrome` flag enabled.
+    #[cfg(not(feature = "chrome"))]
+    pub fn with_pdf(&mut self, _pdf_config: Option<PdfConfig>) -> &mut Self {
+        self
+    }
+
+    /// Set the chrome PDF configuration. This does nothing without the `chrome` flag enabled.
+    #[cfg(feature = "chrome")]
+    pub fn with_pdf(&mut self, pdf_config: Option<PdfConfig>) -> &mut Self {
+        self.pdf = pdf_config;
+        self
+    }
+
+    /// Set the chrome MHTML snapshot configuration. This does nothing without the `chrome` flag enabled.
+    #[cfg(not(feature = "chrome"))]
+    pub fn with_mhtml(&mut self, _mhtml_config: Option<MhtmlConfig>) -> &mut Self {
+        self
+    }
+
+    /// Set the chrome MHTML snapshot configuration. This does nothing without the `chrome` flag enabled.
+    #[cfg(feature = "chrome")]
+    pub fn with_mhtml(&mut self, mhtml_config: Option<MhtmlConfig>) -> &mut Self {
+        self.mhtml = mhtml_config;
+        self
+    }
+
     /// Set the max amount of bytes to collect per page. This method does nothing if the `chrome` is not enabled.
     pub fn with_max_page_bytes(&mut self, max_page_bytes: Option<f64>) -> &mut Self {
         self.max_page_bytes = max_page_bytes;
@@ -2460,6 +2725,8 @@ impl Configuration {
         crate::utils::ChromeFetchParams {
             wait_for: &self.wait_for,
             screenshot: &self.screenshot,
+            pdf: &self.pdf,
+            mhtml: &self.mhtml,
             openai_config: &self.openai_config,
             execution_scripts: &self.execution_scripts,
             automation_scripts: &self.automation_scripts,
@@ -2484,6 +2751,7 @@ impl Configuration {
                 .chrome_failover
                 .last_connected_url()
                 .or(self.chrome_connection_url.as_deref()),
+            nofollow: self.nofollow_policy(),
         }
     }
 
@@ -2701,6 +2969,22 @@ impl Configuration {
         self
     }
 
+    #[cfg(feature = "politeness")]
+    /// Set the per-host politeness configuration.
+    pub fn with_politeness(
+        &mut self,
+        config: Option<crate::utils::politeness::PolitenessConfig>,
+    ) -> &mut Self {
+        self.politeness = config;
+        self
+    }
+
+    /// Set the per-host politeness configuration (no-op without `politeness` feature).
+    #[cfg(not(feature = "politeness"))]
+    pub fn with_politeness(&mut self, _config: Option<()>) -> &mut Self {
+        self
+    }
+
     #[cfg(feature = "etag_cache")]
     /// Enable or disable ETag / conditional request caching for bandwidth-efficient re-crawls.
     pub fn with_etag_cache(&mut self, enabled: bool) -> &mut Self {
@@ -2726,6 +3010,22 @@ impl Configuration {
     pub fn with_warc(&mut self, _config: ()) -> &mut Self {
         self
     }
+
+    #[cfg(feature = "checkpoint")]
+    /// Configure periodic crawl checkpoints for resumable crawls.
+    pub fn with_checkpoint(
+        &mut self,
+        config: crate::utils::checkpoint::CheckpointConfig,
+    ) -> &mut Self {
+        self.checkpoint = Some(config);
+        self
+    }
+
+    /// Configure crawl checkpoints (no-op without `checkpoint` feature).
+    #[cfg(not(feature = "checkpoint"))]
+    pub fn with_checkpoint(&mut self, _config: ()) -> &mut Self {
+        self
+    }
 }
 
 /// Search provider configuration for web search integration.
@@ -3595,4 +3895,23 @@ mod tests {
         assert!(config.chrome_connection_url.is_some());
         assert!(config.chrome_connection_urls.is_none());
     }
+
+    #[cfg(feature = "web_vitals")]
+    #[test]
+    fn test_with_web_vitals_keeps_automation_tracking() {
+        let mut config = Configuration::default();
+        config.with_web_vitals(true);
+        let tracker = config.track_events.as_ref().unwrap();
+        assert!(tracker.web_vitals);
+        assert!(tracker.automation);
+    }
+
+    #[cfg(all(feature = "web_vitals", feature = "serde"))]
+    #[test]
+    fn test_chrome_event_tracker_web_vitals_defaults_when_missing() {
+        let tracker: ChromeEventTracker =
+            serde_json::from_str(r#"{"responses":true,"requests":false,"automation":true}"#)
+                .unwrap();
+        assert!(!tracker.web_vitals);
+    }
 }
@@ -196,6 +196,27 @@ impl Viewport {
             ..Default::default()
         }
     }
+    /// The mobile profile used by Lighthouse: a 412x823 touch screen at a 1.75 scale factor.
+    /// Pair with a mobile user agent for sites that serve mobile markup by agent.
+    pub fn mobile() -> Self {
+        Viewport {
+            width: 412,
+            height: 823,
+            device_scale_factor: Some(1.75),
+            emulating_mobile: true,
+            is_landscape: false,
+            has_touch: true,
+        }
+    }
+    /// The desktop profile used by Lighthouse: 1350x940 at a 1.0 scale factor.
+    pub fn desktop() -> Self {
+        Viewport {
+            width: 1350,
+            height: 940,
+            device_scale_factor: Some(1.0),
+            ..Default::default()
+        }
+    }
     /// Determine if the layout is a mobile device or not to emulate.
     pub fn set_mobile(&mut self, emulating_mobile: bool) {
         self.emulating_mobile = emulating_mobile;
@@ -291,6 +312,39 @@ impl From<ClipViewport> for chromiumoxide::cdp::browser_protocol::page::Viewport
     }
 }
 
+/// The accessibility audit configuration. This does nothing without the `accessibility` flag enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccessibilityConfig {
+    /// Keep the flattened accessibility tree on the report.
+    pub include_tree: bool,
+    /// Check the text contrast from the computed styles.
+    pub contrast: bool,
+}
+
+impl Default for AccessibilityConfig {
+    fn default() -> Self {
+        Self {
+            include_tree: false,
+            contrast: true,
+        }
+    }
+}
+
+impl AccessibilityConfig {
+    /// Keep the flattened accessibility tree on the report.
+    pub fn with_tree(mut self, include_tree: bool) -> Self {
+        self.include_tree = include_tree;
+        self
+    }
+
+    /// Check the text contrast from the computed styles.
+    pub fn with_contrast(mut self, contrast: bool) -> Self {
+        self.contrast = contrast;
+        self
+    }
+}
+
 /// Screenshot configuration.
 #[derive(Debug, Default, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -420,6 +474,175 @@ impl From<ScreenshotParams> for chromiumoxide::page::ScreenshotParams {
     }
 }
 
+/// PDF paper formats with the dimensions in inches.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PaperFormat {
+    #[default]
+    /// 8.5 x 11 in.
+    Letter,
+    /// 8.5 x 14 in.
+    Legal,
+    /// 11 x 17 in.
+    Tabloid,
+    /// 17 x 11 in.
+    Ledger,
+    /// 33.1 x 46.8 in.
+    A0,
+    /// 23.4 x 33.1 in.
+    A1,
+    /// 16.54 x 23.4 in.
+    A2,
+    /// 11.7 x 16.54 in.
+    A3,
+    /// 8.27 x 11.7 in.
+    A4,
+    /// 5.83 x 8.27 in.
+    A5,
+    /// 4.13 x 5.83 in.
+    A6,
+}
+
+impl PaperFormat {
+    /// The width and height in inches.
+    pub fn dimensions(&self) -> (f64, f64) {
+        match self {
+            PaperFormat::Letter => (8.5, 11.0),
+            PaperFormat::Legal => (8.5, 14.0),
+            PaperFormat::Tabloid => (11.0, 17.0),
+            PaperFormat::Ledger => (17.0, 11.0),
+            PaperFormat::A0 => (33.1, 46.8),
+            PaperFormat::A1 => (23.4, 33.1),
+            PaperFormat::A2 => (16.54, 23.4),
+            PaperFormat::A3 => (11.7, 16.54),
+            PaperFormat::A4 => (8.27, 11.7),
+            PaperFormat::A5 => (5.83, 8.27),
+            PaperFormat::A6 => (4.13, 5.83),
+        }
+    }
+}
+
+#[doc = "Print the page as PDF.\n[printToPDF](https://chromedevtools.github.io/devtools-protocol/tot/Page/#method-printToPDF)"]
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrintToPdfParams {
+    #[doc = "Paper orientation. Defaults to false."]
+    pub landscape: Option<bool>,
+    #[doc = "Display header and footer. Defaults to false."]
+    pub display_header_footer: Option<bool>,
+    #[doc = "Print background graphics. Defaults to false."]
+    pub print_background: Option<bool>,
+    #[doc = "Scale of the webpage rendering. Defaults to 1."]
+    pub scale: Option<f64>,
+    #[doc = "The paper format. `paper_width` and `paper_height` take precedence."]
+    pub paper_format: Option<PaperFormat>,
+    #[doc = "Paper width in inches. Defaults to 8.5 inches."]
+    pub paper_width: Option<f64>,
+    #[doc = "Paper height in inches. Defaults to 11 inches."]
+    pub paper_height: Option<f64>,
+    #[doc = "Top margin in inches. Defaults to 1cm (~0.4 inches)."]
+    pub margin_top: Option<f64>,
+    #[doc = "Bottom margin in inches. Defaults to 1cm (~0.4 inches)."]
+    pub margin_bottom: Option<f64>,
+    #[doc = "Left margin in inches. Defaults to 1cm (~0.4 inches)."]
+    pub margin_left: Option<f64>,
+    #[doc = "Right margin in inches. Defaults to 1cm (~0.4 inches)."]
+    pub margin_right: Option<f64>,
+    #[doc = "Paper ranges to print, one based, e.g., '1-5, 8, 11-13'. Defaults to all pages."]
+    pub page_ranges: Option<String>,
+    #[doc = "HTML template for the print header. Uses the classes `date`, `title`, `url`, `pageNumber` and `totalPages` to inject values."]
+    pub header_template: Option<String>,
+    #[doc = "HTML template for the print footer. Uses the same classes as `header_template`."]
+    pub footer_template: Option<String>,
+    #[doc = "Prefer the page size defined by css. Defaults to false, the content is scaled to fit the paper size."]
+    pub prefer_css_page_size: Option<bool>,
+}
+
+#[cfg(feature = "chrome")]
+impl From<PrintToPdfParams> for chromiumoxide::cdp::browser_protocol::page::PrintToPdfParams {
+    fn from(params: PrintToPdfParams) -> Self {
+        let (width, height) = match params.paper_format {
+            Some(format) => {
+                let (width, height) = format.dimensions();
+                (Some(width), Some(height))
+            }
+            _ => (None, None),
+        };
+
+        Self {
+            landscape: params.landscape,
+            display_header_footer: params.display_header_footer,
+            print_background: params.print_background,
+            scale: params.scale,
+            paper_width: params.paper_width.or(width),
+            paper_height: params.paper_height.or(height),
+            margin_top: params.margin_top,
+            margin_bottom: params.margin_bottom,
+            margin_left: params.margin_left,
+            margin_right: params.margin_right,
+            page_ranges: params.page_ranges,
+            header_template: params.header_template,
+            footer_template: params.footer_template,
+            prefer_css_page_size: params.prefer_css_page_size,
+            ..Default::default()
+        }
+    }
+}
+
+/// PDF capture configuration. Printing requires a headless browser.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PdfConfig {
+    /// The print params.
+    pub params: PrintToPdfParams,
+    /// Return the bytes of the PDF on the Page.
+    pub bytes: bool,
+    /// Store the PDF to disk. This can be used with output_dir. If disabled will not store the file to the output directory.
+    pub save: bool,
+    /// The output directory to store the file. Parant folders may be created inside the directory.
+    pub output_dir: Option<std::path::PathBuf>,
+}
+
+impl PdfConfig {
+    /// Create a new PDF configuration.
+    pub fn new(
+        params: PrintToPdfParams,
+        bytes: bool,
+        save: bool,
+        output_dir: Option<std::path::PathBuf>,
+    ) -> Self {
+        Self {
+            params,
+            bytes,
+            save,
+            output_dir,
+        }
+    }
+}
+
+/// MHTML snapshot configuration. The snapshot is a single file with the page and its resources.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MhtmlConfig {
+    /// Return the bytes of the snapshot on the Page.
+    pub bytes: bool,
+    /// Store the snapshot to disk. This can be used with output_dir. If disabled will not store the file to the output directory.
+    pub save: bool,
+    /// The output directory to store the file. Parant folders may be created inside the directory.
+    pub output_dir: Option<std::path::PathBuf>,
+}
+
+impl MhtmlConfig {
+    /// Create a new MHTML configuration.
+    pub fn new(bytes: bool, save: bool, output_dir: Option<std::path::PathBuf>) -> Self {
+        Self {
+            bytes,
+            save,
+            output_dir,
+        }
+    }
+}
+
 #[doc = "The decision on what to do in response to the authorization challenge.  Default means\ndeferring to the default behavior of the net stack, which will likely either the Cancel\nauthentication or display a popup dialog box."]
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -587,6 +810,18 @@ pub enum WebAutomation {
         /// The output file to store the screenshot.
         output: String,
     },
+    /// Print the page as PDF with the default print params. Requires a headless browser.
+    PrintToPdf {
+        /// Print background graphics.
+        print_background: bool,
+        /// The output file to store the PDF.
+        output: String,
+    },
+    /// Capture an MHTML snapshot of the page.
+    Mhtml {
+        /// The output file to store the snapshot.
+        output: String,
+    },
     /// Only continue to the next automation if the prior step was valid. Use this intermediate after a step to break out of the chain.
     ValidateChain,
 }
@@ -620,6 +855,8 @@ impl WebAutomation {
             Type { .. } => "Type",
             InfiniteScroll(_) => "InfiniteScroll",
             Screenshot { .. } => "Screenshot",
+            PrintToPdf { .. } => "PrintToPdf",
+            Mhtml { .. } => "Mhtml",
             ValidateChain => "ValidateChain",
         }
     }
@@ -683,6 +920,11 @@ impl WebAutomation {
                 "Screenshot full={} omit_bg={} -> {}",
                 full_page, omit_background, output
             ),
+            PrintToPdf {
+                print_background,
+                output,
+            } => format!("PrintToPdf background={} -> {}", print_background, output),
+            Mhtml { output } => format!("Mhtml -> {}", output),
             ValidateChain => "ValidateChain".into(),
         }
     }
@@ -999,6 +1241,33 @@ impl WebAutomation {
                     .await
                     .is_ok();
             }
+            WebAutomation::PrintToPdf {
+                print_background,
+                output,
+            } => {
+                let params = chromiumoxide::cdp::browser_protocol::page::PrintToPdfParams {
+                    print_background: Some(*print_background),
+                    ..Default::default()
+                };
+
+                valid = page.save_pdf(params, output).await.is_ok();
+            }
+            WebAutomation::Mhtml { output } => {
+                if let Ok(snapshot) = page
+                    .execute(
+                        chromiumoxide::cdp::browser_protocol::page::CaptureSnapshotParams::default(
+                        ),
+                    )
+                    .await
+                {
+                    valid = crate::utils::uring_fs::write_file(
+                        output.clone(),
+                        snapshot.result.data.into_bytes(),
+                    )
+                    .await
+                    .is_ok();
+                }
+            }
             _ => (),
         };
 
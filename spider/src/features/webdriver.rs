@@ -765,6 +765,12 @@ pub async fn run_automation(driver: &WebDriver, action: &WebAutomation) -> bool
                 log::warn!("Screenshot feature not enabled");
             }
         }
+        WebAutomation::PrintToPdf { output, .. } | WebAutomation::Mhtml { output } => {
+            log::warn!(
+                "Document capture is not supported with webdriver: {}",
+                output
+            );
+        }
         WebAutomation::ValidateChain => {
             // This is a control flow marker, always returns current valid state
             valid = true;
@@ -4,7 +4,9 @@
 //! that has improvements that help our case for speed.
 //!
 //! The robots.txt Exclusion Protocol is implemented as specified in
-//! <http://www.robotstxt.org/norobots-rfc.txt>
+//! [RFC 9309](https://www.rfc-editor.org/rfc/rfc9309): `*` wildcards, `$` end
+//! anchors, longest-match precedence with `Allow` winning ties, a 500 KiB
+//! parse limit and `Sitemap:` directives.
 //!
 //!
 //! Add ``extern crate robotparser`` to your crate root and your're good to go!
@@ -29,32 +31,25 @@ use crate::compact_str::CompactString;
 use crate::Client;
 #[cfg(feature = "regex")]
 use hashbrown::HashSet;
+use percent_encoding::percent_decode_str;
 #[cfg(feature = "regex")]
 use regex::RegexSet;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// Maximum robots.txt size parsed, per RFC 9309. Content past it is ignored.
+pub const ROBOTS_TXT_MAX_BYTES: usize = 500 * 1024;
+
 /// A rule line is a single "Allow:" (allowance==True) or "Disallow:"
-/// (allowance==False) followed by a path."""
+/// (allowance==False) followed by a path pattern. `*` matches any sequence
+/// of characters and a trailing `$` anchors the end of the path.
 #[derive(Debug, Eq, PartialEq, Clone)]
-#[cfg(not(feature = "regex"))]
 pub struct RuleLine {
-    /// Path of the rule
+    /// Path pattern of the rule
     pub path: String,
     /// Is the rule allowed?
     pub allowance: bool,
 }
 
-/// A rule line is a single "Allow:" (allowance==True) or "Disallow:"
-/// (allowance==False) followed by a path."""
-#[derive(Debug, Clone)]
-#[cfg(feature = "regex")]
-pub struct RuleLine {
-    /// Path of the rule
-    pub path: Option<regex::Regex>,
-    /// Is the rule allowed?
-    pub allowance: bool,
-}
-
 #[derive(Debug, Eq, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Determine the amount of request allowed between navigation or crawls.
@@ -66,8 +61,7 @@ pub struct RequestRate {
 }
 
 /// An entry has one or more user-agents and zero or more rulelines
-#[derive(Debug, Clone)]
-#[cfg_attr(not(feature = "regex"), derive(Eq, PartialEq))]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Entry {
     /// Multiple user agents to use
     pub useragents: Vec<String>,
@@ -79,6 +73,12 @@ pub struct Entry {
     pub req_rate: Option<RequestRate>,
 }
 
+/// A rule line compiled to a regex. Rules are matched as RFC 9309 patterns
+/// by [`RuleLine`] now.
+#[cfg(feature = "regex")]
+#[deprecated(note = "rules are matched as RFC 9309 patterns, use `RuleLine`")]
+pub type RegexRuleLine = RuleLine;
+
 /// robots.txt file parser
 #[derive(Debug, Clone)]
 #[cfg_attr(not(feature = "regex"), derive(Eq, PartialEq))]
@@ -93,35 +93,31 @@ pub struct RobotFileParser {
     pub allow_all: bool,
     /// Time last checked robots.txt file
     pub last_checked: i64,
-    /// Disallow list of regex paths to ignore.
+    /// Sitemaps declared with `Sitemap:` directives.
+    sitemaps: Vec<String>,
+    /// Unused, always empty.
     #[cfg(feature = "regex")]
+    #[deprecated(note = "rules are matched as RFC 9309 patterns, see `can_fetch`")]
     pub disallow_paths_regex: RegexSet,
-    /// Disallow list of paths to ignore.
+    /// Unused, always empty.
     #[cfg(feature = "regex")]
+    #[deprecated(note = "rules are matched as RFC 9309 patterns, see `can_fetch`")]
     pub disallow_paths: HashSet<String>,
-    /// Disallow list of regex agents to ignore.
+    /// Unused, always empty.
     #[cfg(feature = "regex")]
+    #[deprecated(note = "rules are matched as RFC 9309 patterns, see `can_fetch`")]
     pub disallow_agents_regex: RegexSet,
-    /// Wild card agent provided.
+    /// Unused, always `false`.
     #[cfg(feature = "regex")]
+    #[deprecated(note = "rules are matched as RFC 9309 patterns, see `can_fetch`")]
     pub wild_card_agent: bool,
-    /// Disallow list of agents to ignore.
+    /// Unused, always empty.
     #[cfg(feature = "regex")]
+    #[deprecated(note = "rules are matched as RFC 9309 patterns, see `can_fetch`")]
     pub disallow_agents: HashSet<String>,
 }
 
 impl RuleLine {
-    #[cfg(feature = "regex")]
-    fn new(path: &str, allowance: bool) -> RuleLine {
-        use regex::Regex;
-
-        RuleLine {
-            path: Regex::new(path).ok(),
-            allowance: path.is_empty() && !allowance || allowance,
-        }
-    }
-
-    #[cfg(not(feature = "regex"))]
     fn new(path: &str, allowance: bool) -> RuleLine {
         RuleLine {
             path: path.into(),
@@ -129,27 +125,65 @@ impl RuleLine {
         }
     }
 
-    #[cfg(not(feature = "regex"))]
+    /// Does the rule match the path? An empty rule matches nothing.
     fn applies_to(&self, pathname: &str) -> bool {
-        if self.path == "*"
-            || self.path == "/" && pathname == "/"
-            || self.path.ends_with("/") && pathname.starts_with(&self.path)
-        {
-            true
-        } else {
-            self.path
-                .strip_suffix('*')
-                .is_some_and(|prefix| pathname.starts_with(prefix))
-                || pathname == self.path
-        }
+        !self.path.is_empty() && pattern_matches(self.path.as_bytes(), pathname.as_bytes())
     }
 
-    #[cfg(feature = "regex")]
-    fn applies_to(&self, pathname: &str) -> bool {
-        match self.path {
-            Some(ref regex) => regex.is_match(pathname),
-            _ => false,
+    /// Does this rule take precedence over `other`? The longer pattern wins and
+    /// `Allow` wins a tie.
+    fn overrides(&self, other: &RuleLine) -> bool {
+        self.path.len() > other.path.len()
+            || self.path.len() == other.path.len() && self.allowance && !other.allowance
+    }
+}
+
+/// Match a robots.txt path pattern against a path. The pattern is anchored at the
+/// start, `*` matches any sequence of bytes and a trailing `$` anchors the end.
+fn pattern_matches(pattern: &[u8], path: &[u8]) -> bool {
+    let (pattern, anchored) = match pattern.split_last() {
+        Some((b'$', rest)) => (rest, true),
+        _ => (pattern, false),
+    };
+    let (mut p, mut s) = (0, 0);
+    // Position of the last `*` in the pattern and the path offset it resumes from.
+    let mut star: Option<(usize, usize)> = None;
+
+    loop {
+        if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, s));
+            p += 1;
+            continue;
+        }
+        if p == pattern.len() {
+            if !anchored || s == path.len() {
+                return true;
+            }
+        } else if s < path.len() && pattern[p] == path[s] {
+            p += 1;
+            s += 1;
+            continue;
         }
+        match star {
+            Some((star_p, star_s)) if star_s < path.len() => {
+                star = Some((star_p, star_s + 1));
+                p = star_p + 1;
+                s = star_s + 1;
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// Pick the rule that takes precedence.
+fn strongest<'a>(
+    current: Option<&'a RuleLine>,
+    rule: Option<&'a RuleLine>,
+) -> Option<&'a RuleLine> {
+    match (current, rule) {
+        (Some(current), Some(rule)) if !rule.overrides(current) => Some(current),
+        (current, None) => current,
+        (_, rule) => rule,
     }
 }
 
@@ -194,12 +228,32 @@ impl Entry {
     /// - our agent applies to this entry
     /// - filename is URL decoded
     fn allowance(&self, filename: &str) -> bool {
-        for line in &self.rulelines {
-            if line.applies_to(filename) {
-                return line.allowance;
+        self.matching_rule(filename)
+            .is_none_or(|line| line.allowance)
+    }
+
+    /// The rule deciding `filename`: the longest matching pattern, `Allow` winning ties.
+    fn matching_rule(&self, filename: &str) -> Option<&RuleLine> {
+        self.rulelines
+            .iter()
+            .filter(|line| line.applies_to(filename))
+            .fold(None, |best, line| strongest(best, Some(line)))
+    }
+
+    /// Merge a later group for the same agents into this one.
+    fn merge(&mut self, entry: Entry) {
+        for agent in entry.useragents {
+            if !self.useragents.contains(&agent) {
+                self.useragents.push(agent);
             }
         }
-        true
+        self.rulelines.extend(entry.rulelines);
+        if self.crawl_delay.is_none() {
+            self.crawl_delay = entry.crawl_delay;
+        }
+        if self.req_rate.is_none() {
+            self.req_rate = entry.req_rate;
+        }
     }
 
     /// Add to user agent list
@@ -250,7 +304,18 @@ impl Default for Entry {
 }
 
 /// extract the path of a string
+#[cfg(test)]
 fn extract_path(url: &str) -> &str {
+    let path = extract_path_query(url);
+
+    match memchr::memchr(b'?', path.as_bytes()) {
+        Some(query_pos) => &path[..query_pos],
+        _ => path,
+    }
+}
+
+/// extract the path and query of a string, the part robots.txt rules are matched against
+fn extract_path_query(url: &str) -> &str {
     if !url.is_empty() {
         let bytes = url.as_bytes();
         let prefix = if url.starts_with("https://") {
@@ -266,10 +331,9 @@ fn extract_path(url: &str) -> &str {
         if let Some(path_start) = memchr::memchr(b'/', url_slice) {
             let path_offset = prefix + path_start;
 
-            if let Some(query_pos) = memchr::memchr(b'?', &bytes[path_offset..]) {
-                &url[path_offset..path_offset + query_pos]
-            } else {
-                &url[path_offset..]
+            match memchr::memchr(b'#', &bytes[path_offset..]) {
+                Some(fragment_pos) => &url[path_offset..path_offset + fragment_pos],
+                _ => &url[path_offset..],
             }
         } else {
             "/"
@@ -281,7 +345,7 @@ fn extract_path(url: &str) -> &str {
 
 impl RobotFileParser {
     /// Establish a new robotparser for a website domain
-    #[cfg(not(feature = "regex"))]
+    #[allow(deprecated)]
     pub fn new() -> Box<RobotFileParser> {
         RobotFileParser {
             entries: vec![],
@@ -289,24 +353,17 @@ impl RobotFileParser {
             disallow_all: false,
             allow_all: false,
             last_checked: 0i64,
-        }
-        .into()
-    }
-
-    /// Establish a new robotparser for a website domain
-    #[cfg(feature = "regex")]
-    pub fn new() -> Box<RobotFileParser> {
-        RobotFileParser {
-            entries: vec![],
-            default_entry: Entry::new(),
-            disallow_all: false,
+            sitemaps: Vec::new(),
+            #[cfg(feature = "regex")]
             disallow_paths_regex: RegexSet::default(),
-            disallow_agents_regex: RegexSet::default(),
+            #[cfg(feature = "regex")]
             disallow_paths: Default::default(),
-            disallow_agents: Default::default(),
+            #[cfg(feature = "regex")]
+            disallow_agents_regex: RegexSet::default(),
+            #[cfg(feature = "regex")]
             wild_card_agent: false,
-            allow_all: false,
-            last_checked: 0i64,
+            #[cfg(feature = "regex")]
+            disallow_agents: Default::default(),
         }
         .into()
     }
@@ -337,9 +394,13 @@ impl RobotFileParser {
         &self.default_entry
     }
 
+    /// Get the sitemaps declared with `Sitemap:` directives.
+    pub fn get_sitemaps(&self) -> &[String] {
+        &self.sitemaps
+    }
+
     /// Reads the robots.txt URL and feeds it to the parser.
     pub async fn read(&mut self, client: &Client, url: &str) {
-        use crate::client::StatusCode;
         self.modified();
 
         let request = client.get(string_concat!(url, "robots.txt"));
@@ -352,39 +413,51 @@ impl RobotFileParser {
         };
         let status = res.status();
 
+        // RFC 9309: a missing robots.txt (4xx, including 401 and 403) allows
+        // everything, an unreachable one (5xx) disallows everything.
         match status {
-            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            status if status.is_server_error() => {
                 self.disallow_all = true;
             }
-            status
-                if status >= StatusCode::BAD_REQUEST
-                    && status < StatusCode::INTERNAL_SERVER_ERROR =>
-            {
+            status if status.is_client_error() => {
                 self.allow_all = true;
             }
-            StatusCode::OK => self.from_response(res).await,
+            status if status.is_success() => self.from_response(res).await,
             _ => (),
         }
     }
 
-    /// Reads the HTTP response and feeds it to the parser.
-    pub async fn from_response(&mut self, response: crate::client::Response) {
-        match response.text().await {
-            Ok(buf) => {
-                self.parse_str(&buf);
-            }
-            _ => {
-                self.allow_all = true;
+    /// Reads the HTTP response and feeds it to the parser. The body is read
+    /// up to [`ROBOTS_TXT_MAX_BYTES`], the rest is never downloaded.
+    pub async fn from_response(&mut self, mut response: crate::client::Response) {
+        let mut body = Vec::new();
+
+        // One byte past the limit tells `parse_str` the file was cut short.
+        while body.len() <= ROBOTS_TXT_MAX_BYTES {
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    let room = ROBOTS_TXT_MAX_BYTES + 1 - body.len();
+                    body.extend_from_slice(&chunk[..chunk.len().min(room)]);
+                }
+                Ok(None) => break,
+                Err(_) => {
+                    self.allow_all = true;
+                    return;
+                }
             }
         }
+
+        self.parse_str(&String::from_utf8_lossy(&body));
     }
 
     fn _add_entry(&mut self, entry: Entry) {
         if entry.has_useragent() {
             // the default entry is considered last
             if self.default_entry.is_empty() {
-                // the first default entry wins
                 self.default_entry = entry;
+            } else {
+                // RFC 9309: groups for the same agent are combined.
+                self.default_entry.merge(entry);
             }
         } else {
             self.entries.push(entry);
@@ -398,6 +471,19 @@ impl RobotFileParser {
         if text.is_empty() {
             return;
         }
+        let text = if text.len() > ROBOTS_TXT_MAX_BYTES {
+            // Cut at the last full line within the limit.
+            let end = match memchr::memrchr(b'\n', &text.as_bytes()[..ROBOTS_TXT_MAX_BYTES]) {
+                Some(end) => end,
+                _ => (0..=ROBOTS_TXT_MAX_BYTES)
+                    .rev()
+                    .find(|i| text.is_char_boundary(*i))
+                    .unwrap_or_default(),
+            };
+            &text[..end]
+        } else {
+            text
+        };
         let bytes = text.as_bytes();
         let mut lines = Vec::with_capacity(bytes.len() / 20 + 1);
         let mut start = 0;
@@ -469,12 +555,10 @@ impl RobotFileParser {
                     }
                     entry.push_useragent(&part1);
                     state = 1;
-                    self.set_disallow_agents_list(&part1);
                 } else if part0.eq_ignore_ascii_case("disallow") {
                     if state != 0 {
                         entry.push_ruleline(RuleLine::new(&part1, false));
                         state = 2;
-                        self.set_disallow_list(&part1);
                     }
                 } else if part0.eq_ignore_ascii_case("allow") {
                     if state != 0 {
@@ -496,8 +580,9 @@ impl RobotFileParser {
                         state = 2;
                     }
                 } else if part0.eq_ignore_ascii_case("sitemap") {
-                    if state != 0 {
-                        state = 2;
+                    // Sitemaps are not part of any group.
+                    if !part1_raw.is_empty() && !self.sitemaps.iter().any(|s| s == part1_raw) {
+                        self.sitemaps.push(part1_raw.to_string());
                     }
                 } else if part0.eq_ignore_ascii_case("request-rate") && state != 0 {
                     let numbers: Vec<Result<usize, _>> =
@@ -517,56 +602,20 @@ impl RobotFileParser {
         if state == 2 {
             self._add_entry(entry);
         }
-
-        self.build_disallow_list()
     }
 
-    /// Include the disallow paths in the regex set. This does nothing without the 'regex' feature.
-    #[cfg(not(feature = "regex"))]
+    /// Does nothing, rules are matched as RFC 9309 patterns.
+    #[deprecated(note = "rules are matched as RFC 9309 patterns, see `can_fetch`")]
     pub fn set_disallow_list(&mut self, _path: &str) {}
 
-    /// Include the disallow  paths in the regex set. This does nothing without the 'regex' feature.
-    #[cfg(feature = "regex")]
-    pub fn set_disallow_list(&mut self, path: &str) {
-        if !path.is_empty() {
-            self.disallow_paths.insert(path.into());
-        }
-    }
-
-    /// Include the disallow agents in the regex set. This does nothing without the 'regex' feature.
-    #[cfg(not(feature = "regex"))]
+    /// Does nothing, rules are matched as RFC 9309 patterns.
+    #[deprecated(note = "rules are matched as RFC 9309 patterns, see `can_fetch`")]
     pub fn set_disallow_agents_list(&mut self, _agent: &str) {}
 
-    /// Include the disallow agents in the regex set. This does nothing without the 'regex' feature.
-    #[cfg(feature = "regex")]
-    pub fn set_disallow_agents_list(&mut self, agent: &str) {
-        if !agent.is_empty() {
-            if agent == "*" {
-                self.wild_card_agent = true;
-            }
-            self.disallow_agents.insert(agent.into());
-        }
-    }
-
-    /// Build the regex disallow list. This does nothing without the 'regex' feature.
-    #[cfg(not(feature = "regex"))]
+    /// Does nothing, rules are matched as RFC 9309 patterns.
+    #[deprecated(note = "rules are matched as RFC 9309 patterns, see `can_fetch`")]
     pub fn build_disallow_list(&mut self) {}
 
-    /// Build the regex disallow list. This does nothing without the 'regex' feature.
-    #[cfg(feature = "regex")]
-    pub fn build_disallow_list(&mut self) {
-        if !self.disallow_paths.is_empty() {
-            if let Ok(s) = RegexSet::new(&self.disallow_paths) {
-                self.disallow_paths_regex = s
-            }
-        }
-        if !self.disallow_agents.is_empty() {
-            if let Ok(s) = RegexSet::new(&self.disallow_agents) {
-                self.disallow_agents_regex = s
-            }
-        }
-    }
-
     /// Using the parsed robots.txt decide if useragent can fetch url
     pub fn can_fetch<T: AsRef<str>>(&self, useragent: T, url: &str) -> bool {
         // Until the robots.txt file has been read or found not
@@ -578,46 +627,53 @@ impl RobotFileParser {
         } else if self.last_checked == 0 || self.disallow_all {
             false
         } else {
-            // search for given user agent matches
-            // the first match counts
-            let url_str = extract_path(url);
+            let url_str = percent_decode_str(extract_path_query(url)).decode_utf8_lossy();
 
-            if self.entry_allowed(&useragent, url_str) {
-                true
-            } else {
-                // try the default entry last
-                let default_entry = &self.default_entry;
+            // the robots.txt itself is always allowed
+            if url_str == "/robots.txt" {
+                return true;
+            }
 
-                if !default_entry.is_empty() {
-                    default_entry.allowance(url_str)
-                } else {
-                    // agent not found ==> access granted
-                    true
+            match self.group_rule(useragent.as_ref(), &url_str) {
+                Some(rule) => rule.is_none_or(|line| line.allowance),
+                _ => {
+                    // try the default entry last
+                    let default_entry = &self.default_entry;
+
+                    if !default_entry.is_empty() {
+                        default_entry.allowance(&url_str)
+                    } else {
+                        // agent not found ==> access granted
+                        true
+                    }
                 }
             }
         }
     }
 
-    /// Is the entry apply to the robots.txt?
-    #[cfg(not(feature = "regex"))]
+    /// Is the entry apply to the robots.txt? False when no group names the agent.
     pub fn entry_allowed<T: AsRef<str>>(&self, useragent: &T, url_str: &str) -> bool {
-        let ua_lower = Entry::prepare_useragent(useragent.as_ref());
+        match self.group_rule(useragent.as_ref(), url_str) {
+            Some(rule) => rule.is_none_or(|line| line.allowance),
+            _ => false,
+        }
+    }
+
+    /// The rule deciding `url_str` across every group naming the agent, or `None` when
+    /// no group names it. Groups for the same agent are combined.
+    fn group_rule(&self, useragent: &str, url_str: &str) -> Option<Option<&RuleLine>> {
+        let ua_lower = Entry::prepare_useragent(useragent);
+        let mut matched = false;
+        let mut rule = None;
+
         for entry in &self.entries {
             if entry.applies_to_prepared(&ua_lower) {
-                return entry.allowance(url_str);
+                matched = true;
+                rule = strongest(rule, entry.matching_rule(url_str));
             }
         }
-        false
-    }
-
-    /// Is the entry apply to the robots.txt?
-    #[cfg(feature = "regex")]
-    pub fn entry_allowed<T: AsRef<str>>(&self, useragent: &T, url_str: &str) -> bool {
-        let agent_checked =
-            self.wild_card_agent || self.disallow_agents_regex.is_match(useragent.as_ref());
-        let disallow = agent_checked && self.disallow_paths_regex.is_match(url_str);
 
-        !disallow
+        matched.then_some(rule)
     }
 
     /// Returns the crawl delay for this user agent as a `Duration`, or None if no crawl delay is defined.
@@ -701,7 +757,6 @@ mod tests {
         assert_eq!(extract_path("example.com/page"), "/page");
     }
 
-    #[cfg(not(feature = "regex"))]
     #[test]
     fn test_rule_line_applies_wildcard() {
         let rule = RuleLine::new("*", false);
@@ -709,7 +764,6 @@ mod tests {
         assert!(rule.applies_to("/foo/bar"));
     }
 
-    #[cfg(not(feature = "regex"))]
     #[test]
     fn test_rule_line_applies_prefix() {
         let rule = RuleLine::new("/foo*", false);
@@ -718,16 +772,38 @@ mod tests {
         assert!(!rule.applies_to("/bar"));
     }
 
-    #[cfg(not(feature = "regex"))]
     #[test]
     fn test_rule_line_applies_exact() {
+        // RFC 9309: rules are prefixes unless anchored with `$`.
         let rule = RuleLine::new("/exact", false);
         assert!(rule.applies_to("/exact"));
+        assert!(rule.applies_to("/exact/more"));
+        assert!(!rule.applies_to("/other"));
+
+        let rule = RuleLine::new("/exact$", false);
+        assert!(rule.applies_to("/exact"));
         assert!(!rule.applies_to("/exact/more"));
         assert!(!rule.applies_to("/other"));
     }
 
-    #[cfg(not(feature = "regex"))]
+    #[test]
+    fn test_rule_line_applies_wildcard_anchor() {
+        let rule = RuleLine::new("/*.php$", false);
+        assert!(rule.applies_to("/index.php"));
+        assert!(rule.applies_to("/dir/file.php"));
+        assert!(!rule.applies_to("/index.php5"));
+        assert!(!rule.applies_to("/index.php?q=1"));
+
+        let rule = RuleLine::new("/fish*.php", false);
+        assert!(rule.applies_to("/fish.php"));
+        assert!(rule.applies_to("/fishheads/catfish.php?parameters"));
+        assert!(!rule.applies_to("/Fish.PHP"));
+
+        let rule = RuleLine::new("/*?", false);
+        assert!(rule.applies_to("/page?id=1"));
+        assert!(!rule.applies_to("/page"));
+    }
+
     #[test]
     fn test_rule_line_applies_directory() {
         let rule = RuleLine::new("/dir/", false);
@@ -753,7 +829,6 @@ mod tests {
         assert!(entry.applies_to("AnyAgent"));
     }
 
-    #[cfg(not(feature = "regex"))]
     #[test]
     fn test_entry_allowance() {
         let mut entry = Entry::new();
@@ -855,14 +930,12 @@ mod tests {
         assert!(base.has_useragent());
     }
 
-    #[cfg(not(feature = "regex"))]
     #[test]
     fn test_parser_empty_disallow() {
         let rule = RuleLine::new("", false);
         assert!(rule.allowance);
     }
 
-    #[cfg(not(feature = "regex"))]
     #[test]
     fn test_can_fetch_case_insensitive() {
         let mut parser = RobotFileParser::new();
@@ -878,7 +951,6 @@ mod tests {
         assert!(parser.entry_allowed(&"GoogleBot", "/public"));
     }
 
-    #[cfg(not(feature = "regex"))]
     #[test]
     fn test_can_fetch_with_version() {
         let mut parser = RobotFileParser::new();
@@ -891,7 +963,6 @@ mod tests {
         assert!(parser.entry_allowed(&"Googlebot/2.1", "/public"));
     }
 
-    #[cfg(not(feature = "regex"))]
     #[test]
     fn test_can_fetch_multiple_entries() {
         let mut parser = RobotFileParser::new();
@@ -970,4 +1041,151 @@ mod tests {
         // Unknown agent should return None
         assert!(parser.get_req_rate("unknownbot").is_none());
     }
+
+    #[test]
+    fn test_longest_match_precedence() {
+        let mut parser = RobotFileParser::new();
+        parser.modified();
+        let lines = vec![
+            "User-agent: *",
+            "Allow: /p",
+            "Disallow: /",
+            "Disallow: /folder/",
+            "Allow: /folder/page",
+            "Allow: /tie",
+            "Disallow: /tie",
+            "Disallow: /*.gif$",
+            "Allow: /im/",
+        ];
+        parser.parse(&lines);
+
+        assert!(parser.can_fetch("bot", "https://example.com/page"));
+        assert!(!parser.can_fetch("bot", "https://example.com/other"));
+        assert!(!parser.can_fetch("bot", "https://example.com/folder/other"));
+        assert!(parser.can_fetch("bot", "https://example.com/folder/page.html"));
+        assert!(parser.can_fetch("bot", "https://example.com/tie"));
+        assert!(!parser.can_fetch("bot", "https://example.com/im/a.gif"));
+        assert!(parser.can_fetch("bot", "https://example.com/im/a.png"));
+        assert!(parser.can_fetch("bot", "https://example.com/robots.txt"));
+    }
+
+    #[test]
+    fn test_agent_groups_combined() {
+        let mut parser = RobotFileParser::new();
+        parser.modified();
+        let lines = vec![
+            "User-agent: googlebot",
+            "Disallow: /a",
+            "",
+            "User-agent: *",
+            "Disallow: /b",
+            "",
+            "User-agent: googlebot",
+            "Disallow: /c",
+            "",
+            "User-agent: *",
+            "Disallow: /d",
+        ];
+        parser.parse(&lines);
+
+        // The agent's own groups replace the default group entirely.
+        assert!(!parser.can_fetch("Googlebot", "https://example.com/a"));
+        assert!(parser.can_fetch("Googlebot", "https://example.com/b"));
+        assert!(!parser.can_fetch("Googlebot", "https://example.com/c"));
+        assert!(!parser.can_fetch("Bingbot", "https://example.com/b"));
+        assert!(!parser.can_fetch("Bingbot", "https://example.com/d"));
+        assert!(parser.can_fetch("Bingbot", "https://example.com/a"));
+    }
+
+    #[test]
+    fn test_query_and_percent_encoding() {
+        let mut parser = RobotFileParser::new();
+        parser.modified();
+        let lines = vec![
+            "User-agent: *",
+            "Disallow: /*?sort=",
+            "Disallow: /caf%C3%A9",
+        ];
+        parser.parse(&lines);
+
+        assert!(!parser.can_fetch("bot", "https://example.com/list?sort=asc"));
+        assert!(parser.can_fetch("bot", "https://example.com/list?page=2"));
+        assert!(!parser.can_fetch("bot", "https://example.com/caf%C3%A9/menu"));
+    }
+
+    #[test]
+    fn test_sitemaps_collected() {
+        let mut parser = RobotFileParser::new();
+        parser.modified();
+        parser.parse_str(
+            "Sitemap: https://example.com/sitemap.xml\nUser-agent: *\nSitemap: https://example.com/news.xml\nDisallow: /private\nSitemap: https://example.com/sitemap.xml\n",
+        );
+
+        assert_eq!(
+            parser.get_sitemaps(),
+            [
+                "https://example.com/sitemap.xml",
+                "https://example.com/news.xml"
+            ]
+        );
+        assert!(!parser.can_fetch("bot", "https://example.com/private"));
+    }
+
+    #[test]
+    fn test_parse_size_limit() {
+        let mut text = String::from("User-agent: *\nDisallow: /early\n");
+        while text.len() < ROBOTS_TXT_MAX_BYTES {
+            text.push_str("# padding padding padding padding padding padding\n");
+        }
+        text.push_str("Disallow: /late\n");
+
+        let mut parser = RobotFileParser::new();
+        parser.modified();
+        parser.parse_str(&text);
+
+        assert!(!parser.can_fetch("bot", "https://example.com/early"));
+        assert!(parser.can_fetch("bot", "https://example.com/late"));
+    }
+
+    #[cfg(not(feature = "wreq"))]
+    #[tokio::test]
+    async fn test_from_response_stops_reading_at_limit() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let served = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stream.read(&mut buf).await;
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n")
+                .await;
+            let mut written = 0;
+            let head = b"User-agent: *\nDisallow: /a\n";
+            let filler = [b'#'; 64 * 1024];
+            let mut chunk = head.to_vec();
+            // Stream up to 64 MiB, the parser must hang up long before.
+            while written < 64 * 1024 * 1024 {
+                let frame = [format!("{:x}\r\n", chunk.len()).as_bytes(), &chunk, b"\r\n"].concat();
+                if stream.write_all(&frame).await.is_err() {
+                    break;
+                }
+                written += chunk.len();
+                chunk = filler.to_vec();
+            }
+            written
+        });
+
+        let response = reqwest::get(format!("http://{addr}/robots.txt"))
+            .await
+            .unwrap();
+        let mut parser = RobotFileParser::new();
+        parser.modified();
+        parser.from_response(response).await;
+
+        assert!(!parser.can_fetch("bot", &format!("http://{addr}/a")));
+        assert!(parser.can_fetch("bot", &format!("http://{addr}/b")));
+        assert!(served.await.unwrap() < 64 * 1024 * 1024);
+    }
 }
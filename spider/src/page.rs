@@ -2183,53 +2183,52 @@ pub struct Metadata {
     pub image: Option<CompactString>,
     #[cfg(feature = "chrome")]
     /// The web automation metadata:
-    pub automation: Option<Vec<AutomationResults>>, // /// Optional Open Graph metadata (`<meta property="og:*">`) extracted from the page.
-                                                    // #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-                                                    // pub og: Option<Box<OpenGraph>>,
+    pub automation: Option<Vec<AutomationResults>>,
+    #[cfg(feature = "rich_metadata")]
+    /// Open Graph, Twitter card, `hreflang`, `<html lang>`, canonical and
+    /// JSON-LD metadata.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub rich: Option<Box<RichMetadata>>,
 }
 
 impl Metadata {
     /// Does metadata exist?
     pub fn exist(&self) -> bool {
-        self.title.is_some() || self.description.is_some() || self.image.is_some()
-    }
-}
-
-// /// Open Graph metadata extracted from `<meta property="og:*">` tags.
-// #[derive(Debug, Default, Clone)]
-// #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-// pub struct OpenGraph {
-//     /// The Open Graph title (`og:title`). NOT USED.
-//     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-//     pub title: Option<CompactString>,
-//     /// The Open Graph description (`og:description`). NOT USED.
-//     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-//     pub description: Option<CompactString>,
-//     /// The Open Graph image URL (`og:image`).
-//     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-//     pub image: Option<CompactString>,
-//     /// The canonical page URL (`og:url`). NOT USED.
-//     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-//     pub url: Option<CompactString>,
-//     /// The content type (`og:type`, e.g., "article", "website"). NOT USED.
-//     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-//     pub content_type: Option<CompactString>,
-//     /// The site name (`og:site_name`). NOT USED.
-//     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-//     pub site_name: Option<CompactString>,
-//     /// The locale of the content (`og:locale`, e.g., "en_US"). NOT USED.
-//     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-//     pub locale: Option<CompactString>,
-//     /// The author's name (`article:author` or `og:author`). NOT USED.
-//     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-//     pub author: Option<CompactString>,
-//     /// The time the content was first published (`article:published_time`). NOT USED.
-//     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-//     pub published_time: Option<CompactString>,
-//     /// The time the content was last modified (`article:modified_time`). NOT USED.
-//     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
-//     pub modified_time: Option<CompactString>,
-// }
+        let exist = self.title.is_some() || self.description.is_some() || self.image.is_some();
+        #[cfg(feature = "rich_metadata")]
+        let exist = exist || self.rich.is_some();
+        exist
+    }
+}
+
+#[cfg(feature = "rich_metadata")]
+pub use crate::utils::rich_metadata::{
+    FeedKind, FeedLink, HreflangAlternate, OpenGraph, RichMetadata, TwitterCard,
+};
+
+#[cfg(feature = "rich_metadata")]
+pub(crate) use crate::utils::rich_metadata::RichMetadataCollector as RichMetadataSlot;
+
+/// Stand-in for the rich metadata collector when `rich_metadata` is off.
+#[cfg(not(feature = "rich_metadata"))]
+#[derive(Debug, Default)]
+pub(crate) struct RichMetadataSlot {}
+
+#[cfg(not(feature = "rich_metadata"))]
+impl RichMetadataSlot {
+    /// Nothing is ever collected.
+    #[inline]
+    pub(crate) fn exist(&self) -> bool {
+        false
+    }
+
+    /// Nothing to apply.
+    #[inline]
+    pub(crate) fn apply(self, _metadata: &mut Metadata) {}
+}
 
 /// Enumeration of known anti-bot and fraud prevention technologies.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -2409,6 +2408,12 @@ pub struct Page {
     #[cfg(feature = "chrome")]
     /// The screenshot bytes of the page.
     pub screenshot_bytes: Option<Vec<u8>>,
+    #[cfg(feature = "chrome")]
+    /// The PDF bytes of the page.
+    pub pdf_bytes: Option<Vec<u8>>,
+    #[cfg(feature = "chrome")]
+    /// The MHTML snapshot bytes of the page.
+    pub mhtml_bytes: Option<Vec<u8>>,
     #[cfg(feature = "openai")]
     /// The credits used from OpenAI in order.
     pub openai_credits_used: Option<Vec<crate::features::openai_common::OpenAIUsage>>,
@@ -2452,10 +2457,33 @@ pub struct Page {
     #[cfg(feature = "chrome")]
     /// All of the request events mapped with the time period of the event sent.
     pub request_map: Option<hashbrown::HashMap<String, f64>>,
+    #[cfg(feature = "har")]
+    /// The HAR 1.2 network log of the page.
+    pub har: Option<Box<crate::utils::har::Har>>,
+    #[cfg(feature = "browser_logs")]
+    /// The browser console messages, JS exceptions and failed resources of the page.
+    pub browser_logs: Option<Vec<crate::utils::browser_logs::BrowserLog>>,
+    #[cfg(feature = "web_vitals")]
+    /// The Core Web Vitals and performance metrics of the page.
+    pub web_vitals: Option<Box<crate::utils::web_vitals::WebVitals>>,
+    #[cfg(feature = "accessibility")]
+    /// The accessibility audit of the page.
+    pub accessibility: Option<Box<crate::utils::accessibility::AccessibilityReport>>,
     /// The anti-bot tech used.
     pub anti_bot_tech: AntiBotTech,
     /// Page metadata.
     pub metadata: Option<Box<Metadata>>,
+    /// Robots directives from the `X-Robots-Tag` header. The robots meta tags
+    /// are read on demand by [`Page::robots_directives`].
+    pub robots: crate::utils::robots_meta::RobotsDirectives,
+    /// The absolute canonical URL from a `Link: <...>; rel=canonical` header
+    /// or a `<link rel="canonical">` tag.
+    pub canonical_url: Option<String>,
+    /// The URL of an earlier page with near-identical visible text, set when
+    /// near-duplicate detection is enabled.
+    pub near_duplicate_of: Option<String>,
+    /// The `nofollow` signals honored when extracting links from the page.
+    pub(crate) nofollow_policy: crate::utils::robots_meta::NofollowPolicy,
     /// Whether the response content was truncated due to a stream error,
     /// chunk idle timeout, or Content-Length mismatch.
     pub content_truncated: bool,
@@ -2558,6 +2586,12 @@ pub struct Page {
     #[cfg(feature = "chrome")]
     /// The screenshot bytes of the page.
     pub screenshot_bytes: Option<Vec<u8>>,
+    #[cfg(feature = "chrome")]
+    /// The PDF bytes of the page.
+    pub pdf_bytes: Option<Vec<u8>>,
+    #[cfg(feature = "chrome")]
+    /// The MHTML snapshot bytes of the page.
+    pub mhtml_bytes: Option<Vec<u8>>,
     #[cfg(feature = "openai")]
     /// The credits used from OpenAI in order.
     pub openai_credits_used: Option<Vec<crate::features::openai_common::OpenAIUsage>>,
@@ -2601,10 +2635,33 @@ pub struct Page {
     #[cfg(feature = "chrome")]
     /// All of the request events mapped with the time period of the event sent.
     pub request_map: Option<hashbrown::HashMap<String, f64>>,
+    #[cfg(feature = "har")]
+    /// The HAR 1.2 network log of the page.
+    pub har: Option<Box<crate::utils::har::Har>>,
+    #[cfg(feature = "browser_logs")]
+    /// The browser console messages, JS exceptions and failed resources of the page.
+    pub browser_logs: Option<Vec<crate::utils::browser_logs::BrowserLog>>,
+    #[cfg(feature = "web_vitals")]
+    /// The Core Web Vitals and performance metrics of the page.
+    pub web_vitals: Option<Box<crate::utils::web_vitals::WebVitals>>,
+    #[cfg(feature = "accessibility")]
+    /// The accessibility audit of the page.
+    pub accessibility: Option<Box<crate::utils::accessibility::AccessibilityReport>>,
     /// The anti-bot tech used.
     pub anti_bot_tech: AntiBotTech,
     /// Page metadata.
     pub metadata: Option<Box<Metadata>>,
+    /// Robots directives from the `X-Robots-Tag` header. The robots meta tags
+    /// are read on demand by [`Page::robots_directives`].
+    pub robots: crate::utils::robots_meta::RobotsDirectives,
+    /// The absolute canonical URL from a `Link: <...>; rel=canonical` header
+    /// or a `<link rel="canonical">` tag.
+    pub canonical_url: Option<String>,
+    /// The URL of an earlier page with near-identical visible text, set when
+    /// near-duplicate detection is enabled.
+    pub near_duplicate_of: Option<String>,
+    /// The `nofollow` signals honored when extracting links from the page.
+    pub(crate) nofollow_policy: crate::utils::robots_meta::NofollowPolicy,
     /// Whether the response content was truncated due to a stream error,
     /// chunk idle timeout, or Content-Length mismatch.
     pub content_truncated: bool,
@@ -2700,6 +2757,22 @@ pub fn page_assign(page: &mut Page, mut new_page: Page) {
         page.request_map = std::mem::take(&mut new_page.request_map);
         page.response_map = std::mem::take(&mut new_page.response_map);
     }
+    #[cfg(feature = "har")]
+    {
+        page.har = std::mem::take(&mut new_page.har);
+    }
+    #[cfg(feature = "browser_logs")]
+    {
+        page.browser_logs = std::mem::take(&mut new_page.browser_logs);
+    }
+    #[cfg(feature = "web_vitals")]
+    {
+        page.web_vitals = std::mem::take(&mut new_page.web_vitals);
+    }
+    #[cfg(feature = "accessibility")]
+    {
+        page.accessibility = std::mem::take(&mut new_page.accessibility);
+    }
 
     #[cfg(feature = "cookies")]
     {
@@ -3578,6 +3651,9 @@ pub fn build(url: &str, mut res: PageResponse) -> Page {
         // code paths), fall back to that value.
         let precomputed_signature = spool.signature.or(res.signature);
         return Page {
+            robots: robots_directives(res.headers.as_ref()),
+            canonical_url: canonical_url(url, res.headers.as_ref(), None),
+            near_duplicate_of: None,
             html: None,
             binary_file: spool.vitals.binary_file,
             is_valid_utf8: spool.vitals.is_valid_utf8,
@@ -3597,6 +3673,10 @@ pub fn build(url: &str, mut res: PageResponse) -> Page {
             chrome_page: None,
             #[cfg(feature = "chrome")]
             screenshot_bytes: res.screenshot_bytes,
+            #[cfg(feature = "chrome")]
+            pdf_bytes: res.pdf_bytes,
+            #[cfg(feature = "chrome")]
+            mhtml_bytes: res.mhtml_bytes,
             #[cfg(feature = "openai")]
             openai_credits_used: res.openai_credits_used,
             #[cfg(feature = "openai")]
@@ -3619,8 +3699,17 @@ pub fn build(url: &str, mut res: PageResponse) -> Page {
             response_map: res.response_map,
             #[cfg(feature = "chrome")]
             request_map: res.request_map,
+            #[cfg(feature = "har")]
+            har: res.har,
+            #[cfg(feature = "browser_logs")]
+            browser_logs: res.browser_logs,
+            #[cfg(feature = "web_vitals")]
+            web_vitals: res.web_vitals,
+            #[cfg(feature = "accessibility")]
+            accessibility: res.accessibility,
             anti_bot_tech: res.anti_bot_tech,
             metadata: res.metadata,
+            nofollow_policy: Default::default(),
             content_truncated: res.content_truncated,
             balance_bytes_tracked: false,
             base: None,
@@ -3684,6 +3773,13 @@ pub fn build(url: &str, mut res: PageResponse) -> Page {
     };
 
     Page {
+        robots: robots_directives(res.headers.as_ref()),
+        canonical_url: canonical_url(
+            url,
+            res.headers.as_ref(),
+            res.content.as_deref().filter(|_| !binary_file),
+        ),
+        near_duplicate_of: None,
         html: res.content.map(bytes::Bytes::from),
         binary_file,
         is_valid_utf8,
@@ -3703,6 +3799,10 @@ pub fn build(url: &str, mut res: PageResponse) -> Page {
         chrome_page: None,
         #[cfg(feature = "chrome")]
         screenshot_bytes: res.screenshot_bytes,
+        #[cfg(feature = "chrome")]
+        pdf_bytes: res.pdf_bytes,
+        #[cfg(feature = "chrome")]
+        mhtml_bytes: res.mhtml_bytes,
         #[cfg(feature = "openai")]
         openai_credits_used: res.openai_credits_used,
         #[cfg(feature = "openai")]
@@ -3725,8 +3825,17 @@ pub fn build(url: &str, mut res: PageResponse) -> Page {
         response_map: res.response_map,
         #[cfg(feature = "chrome")]
         request_map: res.request_map,
+        #[cfg(feature = "har")]
+        har: res.har,
+        #[cfg(feature = "browser_logs")]
+        browser_logs: res.browser_logs,
+        #[cfg(feature = "web_vitals")]
+        web_vitals: res.web_vitals,
+        #[cfg(feature = "accessibility")]
+        accessibility: res.accessibility,
         anti_bot_tech: res.anti_bot_tech,
         metadata: res.metadata,
+        nofollow_policy: Default::default(),
         content_truncated: res.content_truncated,
         #[cfg(all(feature = "balance", not(feature = "decentralized")))]
         balance_bytes_tracked: balance_has_bytes,
@@ -3744,6 +3853,33 @@ pub fn build(url: &str, mut res: PageResponse) -> Page {
     }
 }
 
+/// Robots directives from the response headers. The document head is only
+/// scanned when a directive is asked for.
+#[cfg(not(feature = "decentralized"))]
+fn robots_directives(
+    headers: Option<&reqwest::header::HeaderMap>,
+) -> crate::utils::robots_meta::RobotsDirectives {
+    headers
+        .map(crate::utils::robots_meta::RobotsDirectives::from_headers)
+        .unwrap_or_default()
+}
+
+/// The canonical URL from the `Link` headers, else the document head,
+/// resolved against the page URL.
+#[cfg(not(feature = "decentralized"))]
+fn canonical_url(
+    url: &str,
+    headers: Option<&reqwest::header::HeaderMap>,
+    html: Option<&[u8]>,
+) -> Option<String> {
+    use crate::utils::canonical::{canonical_from_headers, canonical_from_html, resolve_canonical};
+
+    headers
+        .and_then(canonical_from_headers)
+        .or_else(|| html.and_then(canonical_from_html))
+        .and_then(|href| resolve_canonical(url, &href))
+}
+
 /// Instantiate a new page without scraping it (used for testing purposes).
 #[cfg(feature = "decentralized")]
 pub fn build(_: &str, res: PageResponse) -> Page {
@@ -3815,6 +3951,8 @@ pub struct PageLinkBuildSettings {
     pub normalize: bool,
     /// Skip link extraction (single-page crawls that don't need links).
     pub skip_links: bool,
+    /// The `nofollow` signals to honor while extracting links.
+    pub nofollow: crate::utils::robots_meta::NofollowPolicy,
 }
 
 impl PageLinkBuildSettings {
@@ -3842,8 +3980,18 @@ impl PageLinkBuildSettings {
             tld,
             normalize,
             skip_links: false,
+            nofollow: Default::default(),
         }
     }
+
+    /// Honor the `nofollow` signals of the policy.
+    pub(crate) fn with_nofollow(
+        mut self,
+        nofollow: crate::utils::robots_meta::NofollowPolicy,
+    ) -> Self {
+        self.nofollow = nofollow;
+        self
+    }
 }
 
 /// Get the content type from the responses
@@ -3917,11 +4065,13 @@ pub(crate) fn metadata_handlers<'h>(
     meta_title: &'h mut Option<CompactString>,
     meta_description: &'h mut Option<CompactString>,
     meta_og_image: &'h mut Option<CompactString>,
+    meta_rich: &'h mut RichMetadataSlot,
 ) -> Vec<(
     std::borrow::Cow<'static, lol_html::Selector>,
     lol_html::send::ElementContentHandlers<'h>,
 )> {
-    vec![
+    #[cfg_attr(not(feature = "rich_metadata"), allow(unused_mut))]
+    let mut handlers = vec![
         lol_html::text!("head title", |el| {
             let t = el.as_str();
             if !t.is_empty() {
@@ -3946,9 +4096,25 @@ pub(crate) fn metadata_handlers<'h>(
             }
             Ok(())
         }),
-    ]
+    ];
+
+    #[cfg(feature = "rich_metadata")]
+    handlers.extend(crate::utils::rich_metadata::rich_metadata_handlers(
+        meta_rich,
+    ));
+    #[cfg(not(feature = "rich_metadata"))]
+    let _ = meta_rich;
+
+    handlers
 }
 
+/// Number of handlers [`metadata_handlers`] registers.
+#[cfg(feature = "rich_metadata")]
+const METADATA_HANDLERS: usize = 3 + crate::utils::rich_metadata::RICH_METADATA_HANDLERS;
+/// Number of handlers [`metadata_handlers`] registers.
+#[cfg(not(feature = "rich_metadata"))]
+const METADATA_HANDLERS: usize = 3;
+
 /// Type-erased streaming link/metadata extractor used by the Chrome
 /// fetch chain.  Wraps a [`lol_html::send::HtmlRewriter`] behind a
 /// `Box<dyn FnMut>` output sink so the struct itself is non-generic —
@@ -4185,6 +4351,10 @@ pub(crate) struct LinkExtractCtx<'h, A> {
     /// and SSG handlers still install.  Used for single-page crawls
     /// where the caller only wants page-level data.
     pub skip_links: bool,
+    /// `nofollow` filter — `None` when no signal is honored.  Installs
+    /// a robots meta handler and drops `rel="nofollow"` links or every
+    /// link after a page-level `nofollow`.
+    pub nofollow: Option<&'h crate::utils::robots_meta::NofollowFilter>,
 }
 
 /// Build the canonical link+metadata handler vector.  Single source of
@@ -4196,13 +4366,14 @@ pub(crate) struct LinkExtractCtx<'h, A> {
 ///   - feeding bytes to the rewriter (`rewriter.write(chunk)`),
 ///   - calling `rewriter.end()` on success,
 ///   - draining `links_pages` into `self.page_links` after `end()`,
-///   - building `Metadata` from `meta_title`/`meta_description`/`meta_og_image` after `end()`,
+///   - building `Metadata` from `meta_title`/`meta_description`/`meta_og_image`/`meta_rich` after `end()`,
 ///   - resolving `ssg_raw_src_cell` via `convert_abs_path` after `end()` (the resolved-path cell mode does this inline).
 pub(crate) fn build_link_extract_handlers<'h, A>(
     ctx: LinkExtractCtx<'h, A>,
     meta_title: &'h mut Option<CompactString>,
     meta_description: &'h mut Option<CompactString>,
     meta_og_image: &'h mut Option<CompactString>,
+    meta_rich: &'h mut RichMetadataSlot,
 ) -> Vec<(
     std::borrow::Cow<'static, lol_html::Selector>,
     lol_html::send::ElementContentHandlers<'h>,
@@ -4231,6 +4402,7 @@ where
         xml_file,
         full_resources,
         skip_links,
+        nofollow,
     } = ctx;
 
     // Borrow projections from selectors — derived once so each closure
@@ -4241,18 +4413,21 @@ where
     let sub_matcher = &selectors.0;
 
     let mut handlers = Vec::with_capacity(
-        3 /* metadata */
+        METADATA_HANDLERS
             + 1 /* base element */
             + (!skip_links) as usize
+            + nofollow.is_some() as usize
             + ssg_raw_src_cell.is_some() as usize
             + ssg_resolved_path_cell.is_some() as usize,
     );
 
-    // 1. Metadata: title / meta[name=description] / meta[property=og:image].
+    // 1. Metadata: title / meta[name=description] / meta[property=og:image],
+    // plus the rich metadata handlers when `rich_metadata` is on.
     handlers.extend(metadata_handlers(
         meta_title,
         meta_description,
         meta_og_image,
+        meta_rich,
     ));
 
     // 2. <base href> capture — runs first because well-formed docs
@@ -4270,6 +4445,19 @@ where
         }
     ));
 
+    // 2b. Robots meta — flips the page-level `nofollow` before the
+    // body anchors are reached.
+    if let Some(filter) = nofollow.filter(|f| f.policy().robots_meta) {
+        handlers.push(lol_html::element!("meta[name][content]", move |el| {
+            if let (Some(name), Some(content)) =
+                (el.get_attribute("name"), el.get_attribute("content"))
+            {
+                filter.observe_meta(&name, &content);
+            }
+            Ok(())
+        }));
+    }
+
     // 3. Link handler — full_resources unifies a/script/link, otherwise
     //    pick the precompiled HTML or XML anchor selector.
     if !skip_links {
@@ -4277,6 +4465,10 @@ where
             handlers.push(lol_html::element!(
                 "a[href]:not([aria-hidden=\"true\"]),script[src],link[href]",
                 move |el| {
+                    if nofollow.is_some_and(|f| f.skip(el.get_attribute("rel").as_deref())) {
+                        return Ok(());
+                    }
+
                     let tag_name = el.tag_name();
                     let attribute = if tag_name == "script" { "src" } else { "href" };
 
@@ -4317,6 +4509,10 @@ where
                     compiled_selector()
                 },
                 move |el| {
+                    if nofollow.is_some_and(|f| f.skip(el.get_attribute("rel").as_deref())) {
+                        return Ok(());
+                    }
+
                     if let Some(href) = el.get_attribute("href") {
                         let b = if relative_directory_url(&href) || base.is_none() {
                             original_page
@@ -4401,11 +4597,26 @@ impl Page {
     }
 
     /// Instantiate a new page and gather the html repro of standard fetch_page_html.
-    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "attempt",
+            skip_all,
+            fields(
+                url.full = url,
+                spider.attempt = crate::utils::trace::next_attempt(),
+                http.response.status_code = tracing::field::Empty,
+                spider.bytes = tracing::field::Empty,
+            )
+        )
+    )]
     pub async fn new_page(url: &str, client: &Client) -> Self {
         let page_resource: PageResponse = crate::utils::fetch_page_html_raw(url, client).await;
 
-        build(url, page_resource)
+        let page = build(url, page_resource);
+        #[cfg(feature = "tracing")]
+        crate::utils::trace::record_response(&tracing::Span::current(), &page);
+        page
     }
 
     /// Same as [`new_page`] but arms the HTTP first-byte watchdog. When
@@ -4637,7 +4848,19 @@ impl Page {
     /// pre-watchdog code path. Callers typically pass
     /// `Configuration::auto_http_first_byte_args()` so the watchdog only
     /// arms when there are ≥ 2 HTTP-eligible proxies under `balance`.
-    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "attempt",
+            skip_all,
+            fields(
+                url.full = url,
+                spider.attempt = crate::utils::trace::next_attempt(),
+                http.response.status_code = tracing::field::Empty,
+                spider.bytes = tracing::field::Empty,
+            )
+        )
+    )]
     pub async fn new_page_streaming<
         A: PartialEq
             + Eq
@@ -4672,6 +4895,7 @@ impl Page {
         let mut meta_title: Option<_> = None;
         let mut meta_description: Option<_> = None;
         let mut meta_og_image: Option<_> = None;
+        let mut meta_rich = RichMetadataSlot::default();
 
         let duration = if cfg!(feature = "time") {
             Some(tokio::time::Instant::now())
@@ -4690,7 +4914,7 @@ impl Page {
         // primary HTTP fetch path produces (`build_first_byte_timeout_page_response`)
         // so the caller's retry code rotates the proxy.
         let send_outcome = crate::utils::timeout_first_byte(
-            client.get(url).send(),
+            crate::utils::send_request(client.get(url)),
             http_first_byte_timeout,
             http_first_byte_timeout_jitter,
         )
@@ -4706,10 +4930,13 @@ impl Page {
                     crate::utils::vitals::request_error();
                     crate::utils::vitals::request_end();
                 }
-                return build(
+                let page = build(
                     url,
                     crate::utils::build_first_byte_timeout_page_response(url),
                 );
+                #[cfg(feature = "tracing")]
+                crate::utils::trace::record_response(&tracing::Span::current(), &page);
+                return page;
             }
             crate::utils::HttpSendOutcome::Ok(send_result) => match send_result {
                 Ok(res)
@@ -4763,6 +4990,11 @@ impl Page {
                     let base_input_domain = &selectors.2;
                     let sub_matcher = &selectors.0;
 
+                    let nofollow = r_settings.nofollow.filter(
+                        crate::utils::robots_meta::RobotsDirectives::from_headers(res.headers())
+                            .nofollow,
+                    );
+
                     let element_content_handlers = build_link_extract_handlers(
                         LinkExtractCtx {
                             selectors,
@@ -4781,10 +5013,12 @@ impl Page {
                             xml_file,
                             full_resources: r_settings.full_resources,
                             skip_links: r_settings.skip_links,
+                            nofollow: nofollow.as_ref(),
                         },
                         &mut meta_title,
                         &mut meta_description,
                         &mut meta_og_image,
+                        &mut meta_rich,
                     );
 
                     let settings = lol_html::send::Settings {
@@ -4926,6 +5160,7 @@ impl Page {
         let valid_meta = meta_title.is_some()
             || meta_description.is_some()
             || meta_og_image.is_some()
+            || meta_rich.exist()
             || metadata.is_some();
 
         if valid_meta {
@@ -4933,6 +5168,7 @@ impl Page {
             metadata_inner.title = meta_title;
             metadata_inner.description = meta_description;
             metadata_inner.image = meta_og_image;
+            meta_rich.apply(&mut metadata_inner);
 
             if metadata_inner.exist() {
                 // Preserve automation results from existing metadata if present
@@ -4947,7 +5183,11 @@ impl Page {
 
         crate::utils::set_page_response_duration(&mut page_response, duration);
 
-        build(url, page_response)
+        let mut page = build(url, page_response);
+        page.nofollow_policy = r_settings.nofollow;
+        #[cfg(feature = "tracing")]
+        crate::utils::trace::record_response(&tracing::Span::current(), &page);
+        page
     }
 
     /// Instantiate a new page and gather the html repro of standard fetch_page_html only gathering resources to crawl.
@@ -4996,6 +5236,7 @@ impl Page {
         let mut meta_title: Option<_> = None;
         let mut meta_description: Option<_> = None;
         let mut meta_og_image: Option<_> = None;
+        let mut meta_rich = RichMetadataSlot::default();
 
         let duration = if cfg!(feature = "time") {
             Some(tokio::time::Instant::now())
@@ -5029,6 +5270,7 @@ impl Page {
         };
 
         let xml_file = url.ends_with(".xml");
+        let nofollow = r_settings.nofollow.filter(false);
 
         let element_content_handlers = build_link_extract_handlers(
             LinkExtractCtx {
@@ -5047,10 +5289,12 @@ impl Page {
                 xml_file,
                 full_resources: r_settings.full_resources,
                 skip_links: false,
+                nofollow: nofollow.as_ref(),
             },
             &mut meta_title,
             &mut meta_description,
             &mut meta_og_image,
+            &mut meta_rich,
         );
 
         let settings = lol_html::send::Settings {
@@ -5087,6 +5331,7 @@ impl Page {
         let valid_meta = meta_title.is_some()
             || meta_description.is_some()
             || meta_og_image.is_some()
+            || meta_rich.exist()
             || metadata.is_some();
 
         if valid_meta {
@@ -5094,6 +5339,7 @@ impl Page {
             metadata_inner.title = meta_title;
             metadata_inner.description = meta_description;
             metadata_inner.image = meta_og_image;
+            meta_rich.apply(&mut metadata_inner);
 
             if metadata_inner.exist() {
                 // Preserve automation results from existing metadata if present
@@ -5108,7 +5354,9 @@ impl Page {
 
         crate::utils::set_page_response_duration(&mut page_response, duration);
 
-        build(url, page_response)
+        let mut page = build(url, page_response);
+        page.nofollow_policy = r_settings.nofollow;
+        page
     }
 
     #[cfg(all(not(feature = "decentralized"), feature = "chrome"))]
@@ -5265,7 +5513,19 @@ impl Page {
     }
 
     #[cfg(all(not(feature = "decentralized"), feature = "chrome"))]
-    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "attempt",
+            skip_all,
+            fields(
+                url.full = url,
+                spider.attempt = crate::utils::trace::next_attempt(),
+                http.response.status_code = tracing::field::Empty,
+                spider.bytes = tracing::field::Empty,
+            )
+        )
+    )]
     /// Instantiate a new page and gather the html.
     pub async fn new(
         url: &str,
@@ -5278,7 +5538,7 @@ impl Page {
         cache_namespace: Option<&str>,
         params: &crate::utils::ChromeFetchParams<'_>,
     ) -> Self {
-        Self::new_base(
+        let p = Self::new_base(
             url,
             client,
             page,
@@ -5292,7 +5552,10 @@ impl Page {
             params,
             None,
         )
-        .await
+        .await;
+        #[cfg(feature = "tracing")]
+        crate::utils::trace::record_response(&tracing::Span::current(), &p);
+        p
     }
 
     /// Streaming-extraction variant of [`Page::new`].  Used by
@@ -5313,7 +5576,19 @@ impl Page {
     /// the rewriter ends, mirroring the legacy `links_stream_base`
     /// post-stream block exactly.
     #[cfg(all(not(feature = "decentralized"), feature = "chrome"))]
-    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "attempt",
+            skip_all,
+            fields(
+                url.full = url,
+                spider.attempt = crate::utils::trace::next_attempt(),
+                http.response.status_code = tracing::field::Empty,
+                spider.bytes = tracing::field::Empty,
+            )
+        )
+    )]
     pub(crate) async fn new_streaming<A>(
         url: &str,
         client: &Client,
@@ -5366,6 +5641,7 @@ impl Page {
         let mut meta_title: Option<CompactString> = None;
         let mut meta_description: Option<CompactString> = None;
         let mut meta_og_image: Option<CompactString> = None;
+        let mut meta_rich = RichMetadataSlot::default();
 
         let (page_out, mut extract_succeeded) = if asset_url {
             // Skip the rewriter setup entirely. `new_base` still runs
@@ -5388,6 +5664,7 @@ impl Page {
             .await;
             (p, false)
         } else {
+            let nofollow = params.nofollow.filter(false);
             let handlers = build_link_extract_handlers(
                 LinkExtractCtx {
                     selectors,
@@ -5407,10 +5684,12 @@ impl Page {
                     // capture still install so meta_title/description/
                     // og_image stay populated for downstream consumers.
                     skip_links,
+                    nofollow: nofollow.as_ref(),
                 },
                 &mut meta_title,
                 &mut meta_description,
                 &mut meta_og_image,
+                &mut meta_rich,
             );
 
             let mut extract = ChromeStreamingExtractor::new(handlers, None, true);
@@ -5435,6 +5714,16 @@ impl Page {
 
         let mut p = page_out;
 
+        // Headers only arrive with the rendered page — apply a page-level
+        // `X-Robots-Tag: nofollow` now that the directives are known.
+        p.nofollow_policy = params.nofollow;
+        if params.nofollow.robots_meta && p.robots.nofollow {
+            links.clear();
+            if let Some(links_pages) = links_pages.as_mut() {
+                links_pages.clear();
+            }
+        }
+
         // SSG manifest capture (post-stream).  Mirrors
         // `Page::links_stream_base_ssg` exactly — fetches the build
         // manifest URL captured by the rewriter and appends every quoted
@@ -5520,14 +5809,17 @@ impl Page {
         }
 
         if extract_succeeded {
-            let valid_meta =
-                meta_title.is_some() || meta_description.is_some() || meta_og_image.is_some();
+            let valid_meta = meta_title.is_some()
+                || meta_description.is_some()
+                || meta_og_image.is_some()
+                || meta_rich.exist();
 
             if valid_meta {
                 let mut metadata_inner = Metadata::default();
                 metadata_inner.title = meta_title;
                 metadata_inner.description = meta_description;
                 metadata_inner.image = meta_og_image;
+                meta_rich.apply(&mut metadata_inner);
 
                 if metadata_inner.exist() {
                     set_metadata(&mut p.metadata, &mut metadata_inner);
@@ -5549,11 +5841,25 @@ impl Page {
         )
         .await;
 
+        #[cfg(feature = "tracing")]
+        crate::utils::trace::record_response(&tracing::Span::current(), &p);
         (p, extract_succeeded)
     }
 
     #[cfg(all(not(feature = "decentralized"), feature = "chrome"))]
-    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "attempt",
+            skip_all,
+            fields(
+                url.full = url,
+                spider.attempt = crate::utils::trace::next_attempt(),
+                http.response.status_code = tracing::field::Empty,
+                spider.bytes = tracing::field::Empty,
+            )
+        )
+    )]
     /// Instantiate a new page and gather the html seeded.
     pub async fn new_seeded(
         url: &str,
@@ -5568,7 +5874,7 @@ impl Page {
         cache_namespace: Option<&str>,
         params: &crate::utils::ChromeFetchParams<'_>,
     ) -> Self {
-        Self::new_base(
+        let p = Self::new_base(
             url,
             client,
             page,
@@ -5582,7 +5888,10 @@ impl Page {
             params,
             None,
         )
-        .await
+        .await;
+        #[cfg(feature = "tracing")]
+        crate::utils::trace::record_response(&tracing::Span::current(), &p);
+        p
     }
 
     /// Streaming-extraction variant of [`Page::new_seeded`].
@@ -5595,7 +5904,19 @@ impl Page {
     /// land the streaming optimization there too.
     #[allow(dead_code)]
     #[cfg(all(not(feature = "decentralized"), feature = "chrome"))]
-    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "attempt",
+            skip_all,
+            fields(
+                url.full = url,
+                spider.attempt = crate::utils::trace::next_attempt(),
+                http.response.status_code = tracing::field::Empty,
+                spider.bytes = tracing::field::Empty,
+            )
+        )
+    )]
     pub(crate) async fn new_seeded_streaming<A>(
         url: &str,
         client: &Client,
@@ -5643,6 +5964,7 @@ impl Page {
         let mut meta_title: Option<CompactString> = None;
         let mut meta_description: Option<CompactString> = None;
         let mut meta_og_image: Option<CompactString> = None;
+        let mut meta_rich = RichMetadataSlot::default();
 
         let (page_out, mut extract_succeeded) = if asset_url {
             // Skip the rewriter setup entirely — see `Page::new_streaming`.
@@ -5663,6 +5985,7 @@ impl Page {
             .await;
             (p, false)
         } else {
+            let nofollow = params.nofollow.filter(false);
             let handlers = build_link_extract_handlers(
                 LinkExtractCtx {
                     selectors,
@@ -5677,10 +6000,12 @@ impl Page {
                     xml_file,
                     full_resources,
                     skip_links,
+                    nofollow: nofollow.as_ref(),
                 },
                 &mut meta_title,
                 &mut meta_description,
                 &mut meta_og_image,
+                &mut meta_rich,
             );
 
             let mut extract = ChromeStreamingExtractor::new(handlers, None, true);
@@ -5705,6 +6030,16 @@ impl Page {
 
         let mut p = page_out;
 
+        // Headers only arrive with the rendered page — apply a page-level
+        // `X-Robots-Tag: nofollow` now that the directives are known.
+        p.nofollow_policy = params.nofollow;
+        if params.nofollow.robots_meta && p.robots.nofollow {
+            links.clear();
+            if let Some(links_pages) = links_pages.as_mut() {
+                links_pages.clear();
+            }
+        }
+
         // See `Page::new_streaming` — same redirect-base parity guard.
         if extract_succeeded {
             if let Some(redirect) = p.final_redirect_destination.as_deref() {
@@ -5776,14 +6111,17 @@ impl Page {
         }
 
         if extract_succeeded {
-            let valid_meta =
-                meta_title.is_some() || meta_description.is_some() || meta_og_image.is_some();
+            let valid_meta = meta_title.is_some()
+                || meta_description.is_some()
+                || meta_og_image.is_some()
+                || meta_rich.exist();
 
             if valid_meta {
                 let mut metadata_inner = Metadata::default();
                 metadata_inner.title = meta_title;
                 metadata_inner.description = meta_description;
                 metadata_inner.image = meta_og_image;
+                meta_rich.apply(&mut metadata_inner);
 
                 if metadata_inner.exist() {
                     set_metadata(&mut p.metadata, &mut metadata_inner);
@@ -5803,6 +6141,8 @@ impl Page {
         )
         .await;
 
+        #[cfg(feature = "tracing")]
+        crate::utils::trace::record_response(&tracing::Span::current(), &p);
         (p, extract_succeeded)
     }
 
@@ -6124,6 +6464,53 @@ impl Page {
         }
     }
 
+    /// The robots directives of the `X-Robots-Tag` header merged with the
+    /// robots meta tags of the document, scanned on each call.
+    pub fn robots_directives(&self) -> crate::utils::robots_meta::RobotsDirectives {
+        let mut robots = self.robots;
+
+        if let Some(html) = self.html.as_deref().filter(|_| !self.binary_file) {
+            robots.merge(crate::utils::robots_meta::RobotsDirectives::from_html(html));
+        }
+
+        robots
+    }
+
+    /// The page asked not to be indexed through `noindex`.
+    pub fn is_noindex(&self) -> bool {
+        self.robots_directives().noindex
+    }
+
+    /// The page asked for its links not to be followed through `nofollow`.
+    pub fn is_nofollow(&self) -> bool {
+        self.robots_directives().nofollow
+    }
+
+    /// The page asked not to be archived through `noarchive`.
+    pub fn is_noarchive(&self) -> bool {
+        self.robots_directives().noarchive
+    }
+
+    /// The page is withheld from subscribers: robots meta is respected and
+    /// the page declares `noindex` or `noarchive`.
+    pub(crate) fn is_robots_withheld(&self) -> bool {
+        self.nofollow_policy.robots_meta && {
+            let robots = self.robots_directives();
+            robots.noindex || robots.noarchive
+        }
+    }
+
+    /// Honor the `nofollow` signals of the policy when extracting links from the page.
+    pub fn set_nofollow_policy(&mut self, policy: crate::utils::robots_meta::NofollowPolicy) {
+        self.nofollow_policy = policy;
+    }
+
+    /// The link filter for the page, seeded with its own `nofollow` directive.
+    #[cfg(not(feature = "decentralized"))]
+    pub(crate) fn nofollow_filter(&self) -> Option<crate::utils::robots_meta::NofollowFilter> {
+        self.nofollow_policy.filter(self.robots.nofollow)
+    }
+
     /// Set the external domains to treat as one
     pub fn set_external(&mut self, external_domains_caseless: Arc<HashSet<CaseInsensitiveString>>) {
         self.external_domains_caseless = external_domains_caseless;
@@ -6855,6 +7242,36 @@ impl Page {
         &self.request_map
     }
 
+    /// Get the HAR 1.2 network log of the page.
+    #[cfg(feature = "har")]
+    pub fn get_har(&self) -> Option<&crate::utils::har::Har> {
+        self.har.as_deref()
+    }
+
+    /// Get the browser console messages, JS exceptions and failed resources of the page.
+    #[cfg(feature = "browser_logs")]
+    pub fn get_browser_logs(&self) -> &[crate::utils::browser_logs::BrowserLog] {
+        self.browser_logs.as_deref().unwrap_or_default()
+    }
+
+    /// Did the page throw or log a JS error while rendering.
+    #[cfg(feature = "browser_logs")]
+    pub fn has_js_errors(&self) -> bool {
+        self.get_browser_logs().iter().any(|log| log.is_js_error())
+    }
+
+    /// Get the Core Web Vitals and performance metrics of the page.
+    #[cfg(feature = "web_vitals")]
+    pub fn get_web_vitals(&self) -> Option<&crate::utils::web_vitals::WebVitals> {
+        self.web_vitals.as_deref()
+    }
+
+    /// Get the accessibility audit of the page.
+    #[cfg(feature = "accessibility")]
+    pub fn get_accessibility(&self) -> Option<&crate::utils::accessibility::AccessibilityReport> {
+        self.accessibility.as_deref()
+    }
+
     /// Html getter for getting the content with proper encoding. Pass in a proper encoding label like SHIFT_JIS. This fallsback to get_html without the `encoding` flag enabled.
     #[cfg(feature = "encoding")]
     pub fn get_html_encoded(&self, label: &str) -> String {
@@ -7127,6 +7544,7 @@ impl Page {
         let mut meta_title: Option<_> = None;
         let mut meta_description: Option<_> = None;
         let mut meta_og_image: Option<_> = None;
+        let mut meta_rich = RichMetadataSlot::default();
 
         if !html.is_empty() {
             if self.is_xml {
@@ -7145,6 +7563,7 @@ impl Page {
                 let original_page = self.get_url_parsed_ref().as_ref();
                 let external_domains_caseless = &self.external_domains_caseless;
 
+                let nofollow = self.nofollow_filter();
                 let element_content_handlers = build_link_extract_handlers(
                     LinkExtractCtx {
                         selectors,
@@ -7159,10 +7578,12 @@ impl Page {
                         xml_file,
                         full_resources: false,
                         skip_links: false,
+                        nofollow: nofollow.as_ref(),
                     },
                     &mut meta_title,
                     &mut meta_description,
                     &mut meta_og_image,
+                    &mut meta_rich,
                 );
 
                 let rewriter_settings = lol_html::Settings {
@@ -7199,14 +7620,17 @@ impl Page {
             page_links.extend(lp.into_iter().map(Into::into));
         }
 
-        let valid_meta =
-            meta_title.is_some() || meta_description.is_some() || meta_og_image.is_some();
+        let valid_meta = meta_title.is_some()
+            || meta_description.is_some()
+            || meta_og_image.is_some()
+            || meta_rich.exist();
 
         if valid_meta {
             let mut metadata_inner = Metadata::default();
             metadata_inner.title = meta_title;
             metadata_inner.description = meta_description;
             metadata_inner.image = meta_og_image;
+            meta_rich.apply(&mut metadata_inner);
 
             if metadata_inner.exist() {
                 metadata.replace(Box::new(metadata_inner));
@@ -7258,6 +7682,7 @@ impl Page {
         let mut meta_title: Option<_> = None;
         let mut meta_description: Option<_> = None;
         let mut meta_og_image: Option<_> = None;
+        let mut meta_rich = RichMetadataSlot::default();
 
         // XML path: stream from disk via BufReader<File> → quick_xml async
         // reader.  Never materialises the full document in memory.
@@ -7273,6 +7698,7 @@ impl Page {
             let original_page = self.get_url_parsed_ref().as_ref();
             let external_domains_caseless = &self.external_domains_caseless;
 
+            let nofollow = self.nofollow_filter();
             let element_content_handlers = build_link_extract_handlers(
                 LinkExtractCtx {
                     selectors,
@@ -7287,10 +7713,12 @@ impl Page {
                     xml_file,
                     full_resources: false,
                     skip_links: false,
+                    nofollow: nofollow.as_ref(),
                 },
                 &mut meta_title,
                 &mut meta_description,
                 &mut meta_og_image,
+                &mut meta_rich,
             );
 
             let rewriter_settings = lol_html::Settings {
@@ -7331,14 +7759,17 @@ impl Page {
             page_links.extend(lp.into_iter().map(Into::into));
         }
 
-        let valid_meta =
-            meta_title.is_some() || meta_description.is_some() || meta_og_image.is_some();
+        let valid_meta = meta_title.is_some()
+            || meta_description.is_some()
+            || meta_og_image.is_some()
+            || meta_rich.exist();
 
         if valid_meta {
             let mut metadata_inner = Metadata::default();
             metadata_inner.title = meta_title;
             metadata_inner.description = meta_description;
             metadata_inner.image = meta_og_image;
+            meta_rich.apply(&mut metadata_inner);
 
             if metadata_inner.exist() {
                 metadata.replace(Box::new(metadata_inner));
@@ -7389,6 +7820,7 @@ impl Page {
         let mut meta_title: Option<_> = None;
         let mut meta_description: Option<_> = None;
         let mut meta_og_image: Option<_> = None;
+        let mut meta_rich = RichMetadataSlot::default();
 
         // XML path: stream from disk via BufReader<File> → quick_xml async
         // reader — no full in-memory buffer.
@@ -7415,6 +7847,7 @@ impl Page {
                 // borrow ends before the post-rewriter SSG block re-borrows
                 // self for `Page::new_page` and `push_link` into map_ssg.
                 let external_domains_caseless = &self.external_domains_caseless;
+                let nofollow = self.nofollow_filter();
                 let element_content_handlers = build_link_extract_handlers(
                     LinkExtractCtx {
                         selectors,
@@ -7429,10 +7862,12 @@ impl Page {
                         xml_file,
                         full_resources: false,
                         skip_links: false,
+                        nofollow: nofollow.as_ref(),
                     },
                     &mut meta_title,
                     &mut meta_description,
                     &mut meta_og_image,
+                    &mut meta_rich,
                 );
 
                 let rewriter_settings = lol_html::Settings {
@@ -7516,6 +7951,7 @@ impl Page {
         let valid_meta = meta_title.is_some()
             || meta_description.is_some()
             || meta_og_image.is_some()
+            || meta_rich.exist()
             || self.get_metadata().is_some();
 
         if valid_meta {
@@ -7523,6 +7959,7 @@ impl Page {
             metadata_inner.title = meta_title;
             metadata_inner.description = meta_description;
             metadata_inner.image = meta_og_image;
+            meta_rich.apply(&mut metadata_inner);
 
             if metadata_inner.exist() && self.metadata.is_some() {
                 set_metadata(&mut self.metadata, &mut metadata_inner);
@@ -7579,6 +8016,7 @@ impl Page {
         let mut meta_title: Option<_> = None;
         let mut meta_description: Option<_> = None;
         let mut meta_og_image: Option<_> = None;
+        let mut meta_rich = RichMetadataSlot::default();
 
         if !html.is_empty() {
             if self.is_xml {
@@ -7611,6 +8049,7 @@ impl Page {
                     // are released before the post-rewriter SSG block
                     // mutates `map_ssg` and re-reads them.
                     let external_domains_caseless = &self.external_domains_caseless;
+                    let nofollow = self.nofollow_filter();
                     let element_content_handlers = build_link_extract_handlers(
                         LinkExtractCtx {
                             selectors,
@@ -7625,10 +8064,12 @@ impl Page {
                             xml_file,
                             full_resources: false,
                             skip_links: false,
+                            nofollow: nofollow.as_ref(),
                         },
                         &mut meta_title,
                         &mut meta_description,
                         &mut meta_og_image,
+                        &mut meta_rich,
                     );
 
                     let rewriter_settings = lol_html::Settings {
@@ -7712,6 +8153,7 @@ impl Page {
         let valid_meta = meta_title.is_some()
             || meta_description.is_some()
             || meta_og_image.is_some()
+            || meta_rich.exist()
             || self.get_metadata().is_some();
 
         if valid_meta {
@@ -7719,6 +8161,7 @@ impl Page {
             metadata_inner.title = meta_title;
             metadata_inner.description = meta_description;
             metadata_inner.image = meta_og_image;
+            meta_rich.apply(&mut metadata_inner);
 
             if metadata_inner.exist() && self.metadata.is_some() {
                 set_metadata(&mut self.metadata, &mut metadata_inner);
@@ -7916,6 +8359,7 @@ impl Page {
         let mut meta_title: Option<_> = None;
         let mut meta_description: Option<_> = None;
         let mut meta_og_image: Option<_> = None;
+        let mut meta_rich = RichMetadataSlot::default();
 
         // Handle XML streaming first — can stream from memory or disk without
         // loading full bytes, then skip the HTML rewriter path entirely.
@@ -7995,6 +8439,7 @@ impl Page {
                         &mut meta_title,
                         &mut meta_description,
                         &mut meta_og_image,
+                        &mut meta_rich,
                     );
 
                     element_content_handlers.push(element_precompiled!(
@@ -8302,13 +8747,17 @@ impl Page {
                                 let mut chrome_meta_description_unused: Option<CompactString> =
                                     None;
                                 let mut chrome_meta_og_image_unused: Option<CompactString> = None;
+                                let mut chrome_meta_rich_unused = RichMetadataSlot::default();
                                 let mut chrome_links_pages_unused: Option<HashSet<A>> = None;
                                 let mut chrome_extracted_links: HashSet<A> =
                                     HashSet::with_capacity(link_set_capacity());
 
+                                #[cfg(feature = "tracing")]
+                                let chrome_attempt = crate::utils::trace::attempt_span(&self.url);
                                 let (page_resource, chrome_extract_succeeded) = {
                                     let chrome_external_domains_caseless =
                                         &self.external_domains_caseless;
+                                    let nofollow = self.nofollow_filter();
                                     let chrome_handlers = build_link_extract_handlers(
                                         LinkExtractCtx {
                                             selectors,
@@ -8324,41 +8773,51 @@ impl Page {
                                             xml_file: chrome_xml_file,
                                             full_resources: false,
                                             skip_links: false,
+                                            nofollow: nofollow.as_ref(),
                                         },
                                         &mut chrome_meta_title_unused,
                                         &mut chrome_meta_description_unused,
                                         &mut chrome_meta_og_image_unused,
+                                        &mut chrome_meta_rich_unused,
                                     );
 
                                     let mut chrome_extract =
                                         ChromeStreamingExtractor::new(chrome_handlers, None, true);
-                                    let resource = crate::utils::fetch_page_html_chrome_base(
-                                        &html_bytes_taken,
-                                        &new_page,
-                                        true,
-                                        true,
-                                        false,
-                                        Some(&self.url),
-                                        configuration.referer.clone(),
-                                        configuration.max_page_bytes,
-                                        configuration.get_cache_options(),
-                                        {
-                                            #[cfg(feature = "headers")]
+                                    let resource = async {
+                                        crate::utils::fetch_page_html_chrome_base(
+                                            &html_bytes_taken,
+                                            &new_page,
+                                            true,
+                                            true,
+                                            false,
+                                            Some(&self.url),
+                                            configuration.referer.clone(),
+                                            configuration.max_page_bytes,
+                                            configuration.get_cache_options(),
                                             {
-                                                &self.headers
-                                            }
-                                            #[cfg(not(feature = "headers"))]
-                                            {
-                                                &None
-                                            }
-                                        },
-                                        &Some(&configuration.chrome_intercept),
-                                        jar,
-                                        configuration.cache_namespace_str(),
-                                        &fetch_params,
-                                        Some(&mut chrome_extract),
-                                    )
-                                    .await;
+                                                #[cfg(feature = "headers")]
+                                                {
+                                                    &self.headers
+                                                }
+                                                #[cfg(not(feature = "headers"))]
+                                                {
+                                                    &None
+                                                }
+                                            },
+                                            &Some(&configuration.chrome_intercept),
+                                            jar,
+                                            configuration.cache_namespace_str(),
+                                            &fetch_params,
+                                            Some(&mut chrome_extract),
+                                        )
+                                        .await
+                                    };
+                                    #[cfg(feature = "tracing")]
+                                    let resource = tracing::Instrument::instrument(
+                                        resource,
+                                        chrome_attempt.clone(),
+                                    );
+                                    let resource = resource.await;
                                     let succeeded = chrome_extract.end();
                                     (resource, succeeded)
                                 };
@@ -8386,6 +8845,11 @@ impl Page {
                                     bytes_transferred = resource.bytes_transferred;
 
                                     let new_page = build(&self.url, resource);
+                                    #[cfg(feature = "tracing")]
+                                    crate::utils::trace::record_response(
+                                        &chrome_attempt,
+                                        &new_page,
+                                    );
 
                                     page_assign(self, new_page);
 
@@ -8441,14 +8905,17 @@ impl Page {
             page_links.extend(map.iter().map(|item| item.clone().into()));
         }
 
-        let valid_meta =
-            meta_title.is_some() || meta_description.is_some() || meta_og_image.is_some();
+        let valid_meta = meta_title.is_some()
+            || meta_description.is_some()
+            || meta_og_image.is_some()
+            || meta_rich.exist();
 
         if valid_meta {
             let mut metadata_inner = Metadata::default();
             metadata_inner.title = meta_title;
             metadata_inner.description = meta_description;
             metadata_inner.image = meta_og_image;
+            meta_rich.apply(&mut metadata_inner);
 
             if metadata_inner.exist() {
                 metadata.replace(Box::new(metadata_inner));
@@ -8508,6 +8975,7 @@ impl Page {
         let mut meta_title: Option<_> = None;
         let mut meta_description: Option<_> = None;
         let mut meta_og_image: Option<_> = None;
+        let mut meta_rich = RichMetadataSlot::default();
 
         if self.is_xml {
             if let Some(html_bytes_taken) = self.html.take() {
@@ -8748,6 +9216,7 @@ impl Page {
                         &mut meta_title,
                         &mut meta_description,
                         &mut meta_og_image,
+                        &mut meta_rich,
                     ));
 
                     let rewriter_settings = lol_html::Settings {
@@ -8879,13 +9348,17 @@ impl Page {
                                 let mut chrome_meta_description_unused: Option<CompactString> =
                                     None;
                                 let mut chrome_meta_og_image_unused: Option<CompactString> = None;
+                                let mut chrome_meta_rich_unused = RichMetadataSlot::default();
                                 let mut chrome_links_pages_unused: Option<HashSet<A>> = None;
                                 let mut chrome_extracted_links: HashSet<A> =
                                     HashSet::with_capacity(link_set_capacity());
 
+                                #[cfg(feature = "tracing")]
+                                let chrome_attempt = crate::utils::trace::attempt_span(&self.url);
                                 let (page_resource, chrome_extract_succeeded) = {
                                     let chrome_external_domains_caseless =
                                         &self.external_domains_caseless;
+                                    let nofollow = self.nofollow_filter();
                                     let chrome_handlers = build_link_extract_handlers(
                                         LinkExtractCtx {
                                             selectors,
@@ -8901,41 +9374,51 @@ impl Page {
                                             xml_file: chrome_xml_file,
                                             full_resources: true,
                                             skip_links: false,
+                                            nofollow: nofollow.as_ref(),
                                         },
                                         &mut chrome_meta_title_unused,
                                         &mut chrome_meta_description_unused,
                                         &mut chrome_meta_og_image_unused,
+                                        &mut chrome_meta_rich_unused,
                                     );
 
                                     let mut chrome_extract =
                                         ChromeStreamingExtractor::new(chrome_handlers, None, true);
-                                    let resource = crate::utils::fetch_page_html_chrome_base(
-                                        &html_bytes_taken,
-                                        &new_page,
-                                        true,
-                                        true,
-                                        false,
-                                        Some(&self.url),
-                                        configuration.referer.clone(),
-                                        configuration.max_page_bytes,
-                                        configuration.get_cache_options(),
-                                        {
-                                            #[cfg(feature = "headers")]
-                                            {
-                                                &self.headers
-                                            }
-                                            #[cfg(not(feature = "headers"))]
+                                    let resource = async {
+                                        crate::utils::fetch_page_html_chrome_base(
+                                            &html_bytes_taken,
+                                            &new_page,
+                                            true,
+                                            true,
+                                            false,
+                                            Some(&self.url),
+                                            configuration.referer.clone(),
+                                            configuration.max_page_bytes,
+                                            configuration.get_cache_options(),
                                             {
-                                                &None
-                                            }
-                                        },
-                                        &Some(&configuration.chrome_intercept),
-                                        jar,
-                                        configuration.cache_namespace_str(),
-                                        &fetch_params,
-                                        Some(&mut chrome_extract),
-                                    )
-                                    .await;
+                                                #[cfg(feature = "headers")]
+                                                {
+                                                    &self.headers
+                                                }
+                                                #[cfg(not(feature = "headers"))]
+                                                {
+                                                    &None
+                                                }
+                                            },
+                                            &Some(&configuration.chrome_intercept),
+                                            jar,
+                                            configuration.cache_namespace_str(),
+                                            &fetch_params,
+                                            Some(&mut chrome_extract),
+                                        )
+                                        .await
+                                    };
+                                    #[cfg(feature = "tracing")]
+                                    let resource = tracing::Instrument::instrument(
+                                        resource,
+                                        chrome_attempt.clone(),
+                                    );
+                                    let resource = resource.await;
                                     let succeeded = chrome_extract.end();
                                     (resource, succeeded)
                                 };
@@ -8956,6 +9439,11 @@ impl Page {
                                 if let Ok(v) = page_resource {
                                     bytes_transferred = v.bytes_transferred;
                                     let new_page = build(&self.url, v);
+                                    #[cfg(feature = "tracing")]
+                                    crate::utils::trace::record_response(
+                                        &chrome_attempt,
+                                        &new_page,
+                                    );
                                     page_assign(self, new_page);
 
                                     // Behavior parity with the legacy
@@ -9003,14 +9491,17 @@ impl Page {
             page_links.extend(map.iter().map(|item| item.clone().into()));
         }
 
-        let valid_meta =
-            meta_title.is_some() || meta_description.is_some() || meta_og_image.is_some();
+        let valid_meta = meta_title.is_some()
+            || meta_description.is_some()
+            || meta_og_image.is_some()
+            || meta_rich.exist();
 
         if valid_meta {
             let mut metadata_inner = Metadata::default();
             metadata_inner.title = meta_title;
             metadata_inner.description = meta_description;
             metadata_inner.image = meta_og_image;
+            meta_rich.apply(&mut metadata_inner);
 
             if metadata_inner.exist() {
                 metadata.replace(Box::new(metadata_inner));
@@ -9058,6 +9549,7 @@ impl Page {
         let mut meta_title: Option<_> = None;
         let mut meta_description: Option<_> = None;
         let mut meta_og_image: Option<_> = None;
+        let mut meta_rich = RichMetadataSlot::default();
 
         if self.is_xml {
             if let Some(html_bytes_taken) = self.html.take() {
@@ -9111,6 +9603,7 @@ impl Page {
                     // drops, before the outer `self.html = Some(..)` write.
                     let external_domains_caseless = &self.external_domains_caseless;
 
+                    let nofollow = self.nofollow_filter();
                     let element_content_handlers = build_link_extract_handlers(
                         LinkExtractCtx {
                             selectors,
@@ -9125,10 +9618,12 @@ impl Page {
                             xml_file,
                             full_resources: true,
                             skip_links: false,
+                            nofollow: nofollow.as_ref(),
                         },
                         &mut meta_title,
                         &mut meta_description,
                         &mut meta_og_image,
+                        &mut meta_rich,
                     );
 
                     let settings = lol_html::send::Settings {
@@ -9163,14 +9658,17 @@ impl Page {
             }
         }
 
-        let valid_meta =
-            meta_title.is_some() || meta_description.is_some() || meta_og_image.is_some();
+        let valid_meta = meta_title.is_some()
+            || meta_description.is_some()
+            || meta_og_image.is_some()
+            || meta_rich.exist();
 
         if valid_meta {
             let mut metadata_inner = Metadata::default();
             metadata_inner.title = meta_title;
             metadata_inner.description = meta_description;
             metadata_inner.image = meta_og_image;
+            meta_rich.apply(&mut metadata_inner);
 
             if metadata_inner.exist() {
                 metadata.replace(Box::new(metadata_inner));
@@ -9619,6 +10117,8 @@ fn test_metadata_exist_all_fields() {
         image: Some(CompactString::from("https://example.com/image.png")),
         #[cfg(feature = "chrome")]
         automation: None,
+        #[cfg(feature = "rich_metadata")]
+        rich: None,
     };
     assert!(metadata.exist(), "Metadata with all fields should exist");
 }
@@ -9635,6 +10135,8 @@ fn test_metadata_via_build() {
         image: Some(CompactString::from("https://example.com/build-image.png")),
         #[cfg(feature = "chrome")]
         automation: None,
+        #[cfg(feature = "rich_metadata")]
+        rich: None,
     };
 
     let page_response = PageResponse {
@@ -9679,6 +10181,8 @@ fn test_metadata_via_build_with_parse() {
         image: Some(CompactString::from("https://example.com/parse-image.png")),
         #[cfg(feature = "chrome")]
         automation: None,
+        #[cfg(feature = "rich_metadata")]
+        rich: None,
     };
 
     let page_response = PageResponse {
@@ -10153,6 +10657,8 @@ fn test_metadata_with_automation() {
         description: None,
         image: None,
         automation: Some(automation_results),
+        #[cfg(feature = "rich_metadata")]
+        rich: None,
     };
 
     assert!(metadata.exist(), "Metadata with title should exist");
@@ -10187,6 +10693,8 @@ fn test_set_metadata_preserves_automation() {
         description: None,
         image: None,
         automation: Some(automation_results),
+        #[cfg(feature = "rich_metadata")]
+        rich: None,
     };
 
     let mut existing = Some(Box::new(existing_metadata));
@@ -10196,6 +10704,8 @@ fn test_set_metadata_preserves_automation() {
         description: Some(CompactString::from("New Description")),
         image: None,
         automation: None,
+        #[cfg(feature = "rich_metadata")]
+        rich: None,
     };
 
     set_metadata(&mut existing, &mut new_metadata);
@@ -10233,6 +10743,8 @@ async fn test_metadata_chrome_real_page() {
         description: Some(CompactString::from("Description available")),
         image: Some(CompactString::from("https://example.com/image.png")),
         automation: Some(automation_results),
+        #[cfg(feature = "rich_metadata")]
+        rich: None,
     };
 
     let page = build(
@@ -10416,6 +10928,41 @@ fn test_chrome_screenshot_bytes_field() {
     );
 }
 
+/// Test chrome feature - pdf_bytes and mhtml_bytes fields carry over to the page.
+#[test]
+#[cfg(all(feature = "chrome", not(feature = "decentralized")))]
+fn test_chrome_document_capture_bytes_fields() {
+    use crate::utils::PageResponse;
+
+    let pdf_data = b"%PDF-1.4".to_vec();
+    let mhtml_data = b"From: <Saved by Blink>".to_vec();
+
+    let page_response = PageResponse {
+        content: Some(b"<html></html>".to_vec()),
+        status_code: StatusCode::OK,
+        pdf_bytes: Some(pdf_data.clone()),
+        mhtml_bytes: Some(mhtml_data.clone()),
+        ..Default::default()
+    };
+
+    let page = build("https://example.com", page_response);
+
+    assert_eq!(page.pdf_bytes.as_ref(), Some(&pdf_data));
+    assert_eq!(page.mhtml_bytes.as_ref(), Some(&mhtml_data));
+
+    // explicit paper dimensions win over the paper format.
+    let params = chromiumoxide::cdp::browser_protocol::page::PrintToPdfParams::from(
+        crate::configuration::PrintToPdfParams {
+            paper_format: Some(crate::configuration::PaperFormat::A4),
+            paper_height: Some(5.0),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(params.paper_width, Some(8.27));
+    assert_eq!(params.paper_height, Some(5.0));
+}
+
 /// Test time feature - duration field and get_duration_elapsed.
 #[test]
 #[cfg(all(feature = "time", not(feature = "decentralized")))]
@@ -10530,6 +11077,8 @@ fn test_metadata_serde() {
         image: Some("https://example.com/image.png".into()),
         #[cfg(feature = "chrome")]
         automation: None,
+        #[cfg(feature = "rich_metadata")]
+        rich: None,
     };
 
     // Serialize to JSON
@@ -11108,6 +11657,83 @@ async fn test_same_domain_page_links_resolution() {
     );
 }
 
+/// Robots meta and `X-Robots-Tag` directives are exposed on the page and
+/// honored by link extraction once a nofollow policy is set.
+#[tokio::test]
+#[cfg(all(
+    not(feature = "decentralized"),
+    not(feature = "chrome"),
+    not(feature = "cache_request")
+))]
+async fn test_robots_meta_nofollow_links() {
+    use crate::utils::robots_meta::NofollowPolicy;
+    use crate::utils::PageResponse;
+
+    let selectors = get_page_selectors("https://www.example.com/", false, false);
+    let page_base = url::Url::parse("https://www.example.com/page")
+        .ok()
+        .map(Box::new);
+    let about: CaseInsensitiveString = "https://www.example.com/about".into();
+    let ads: CaseInsensitiveString = "https://www.example.com/ads".into();
+
+    let html = br#"<html><head><title>t</title></head><body>
+        <a href="/about">About</a>
+        <a href="/ads" rel="sponsored nofollow">Ads</a>
+    </body></html>"#;
+    let response = || PageResponse {
+        content: Some(html.to_vec()),
+        status_code: reqwest::StatusCode::OK,
+        ..Default::default()
+    };
+
+    let mut page = build_with_parse("https://www.example.com/page", response());
+    assert!(page.robots.is_empty());
+    let links = page.links(&selectors, &page_base).await;
+    assert!(links.contains(&about) && links.contains(&ads));
+
+    let mut page = build_with_parse("https://www.example.com/page", response());
+    page.set_nofollow_policy(NofollowPolicy::new(false, true));
+    let links = page.links(&selectors, &page_base).await;
+    assert!(links.contains(&about));
+    assert!(
+        !links.contains(&ads),
+        "rel=nofollow link followed: {links:?}"
+    );
+
+    let html =
+        br#"<html><head><meta name="robots" content="noindex, nofollow, noarchive"></head><body>
+        <a href="/about">About</a>
+    </body></html>"#;
+    let mut page = build_with_parse(
+        "https://www.example.com/page",
+        PageResponse {
+            content: Some(html.to_vec()),
+            status_code: reqwest::StatusCode::OK,
+            ..Default::default()
+        },
+    );
+    assert!(page.robots.is_empty());
+    assert!(page.is_noindex() && page.is_nofollow() && page.is_noarchive());
+    assert!(!page.is_robots_withheld());
+    page.set_nofollow_policy(NofollowPolicy::new(true, false));
+    assert!(page.is_robots_withheld());
+    assert!(page.links(&selectors, &page_base).await.is_empty());
+
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert("x-robots-tag", "nofollow".parse().unwrap());
+    let mut page = build_with_parse(
+        "https://www.example.com/page",
+        PageResponse {
+            headers: Some(headers),
+            ..response()
+        },
+    );
+    assert!(page.is_nofollow() && !page.is_noindex());
+    page.set_nofollow_policy(NofollowPolicy::new(true, false));
+    assert!(!page.is_robots_withheld());
+    assert!(page.links(&selectors, &page_base).await.is_empty());
+}
+
 /// DNS resolve error (525) should not be retried and needs_retry() must be false.
 #[cfg(not(feature = "decentralized"))]
 #[test]
@@ -14088,3 +14714,55 @@ mod chrome_hedge_dns_tests {
         ));
     }
 }
+
+/// Rich metadata is collected by the same parse that extracts links.
+#[tokio::test]
+#[cfg(all(
+    feature = "rich_metadata",
+    not(feature = "decentralized"),
+    not(feature = "chrome"),
+    not(feature = "cache_request")
+))]
+async fn test_rich_metadata_links() {
+    use crate::utils::PageResponse;
+
+    let selectors = get_page_selectors("https://www.example.com/", false, false);
+    let page_base = url::Url::parse("https://www.example.com/page")
+        .ok()
+        .map(Box::new);
+
+    let html = br#"<html lang="fr"><head><title>Titre</title>
+        <meta property="og:title" content="OG Titre">
+        <meta name="twitter:card" content="summary">
+        <link rel="alternate" hreflang="en" href="https://www.example.com/en/page">
+        <script type="application/ld+json">{"@type":"WebPage"}</script>
+        </head><body><a href="/about">About</a></body></html>"#;
+
+    let mut page = build_with_parse(
+        "https://www.example.com/page",
+        PageResponse {
+            content: Some(html.to_vec()),
+            status_code: reqwest::StatusCode::OK,
+            ..Default::default()
+        },
+    );
+    let about: CaseInsensitiveString = "https://www.example.com/about".into();
+    let links = page.links(&selectors, &page_base).await;
+    assert!(links.contains(&about));
+
+    let metadata = page.get_metadata().as_ref().expect("metadata");
+    assert_eq!(metadata.title.as_deref(), Some("Titre"));
+
+    let rich = metadata.rich.as_ref().expect("rich metadata");
+    assert_eq!(rich.lang.as_deref(), Some("fr"));
+    assert_eq!(
+        rich.og.as_ref().and_then(|og| og.title.as_deref()),
+        Some("OG Titre")
+    );
+    assert_eq!(
+        rich.twitter.as_ref().and_then(|t| t.card.as_deref()),
+        Some("summary")
+    );
+    assert_eq!(rich.alternates[0].hreflang, "en");
+    assert_eq!(rich.json_ld[0]["@type"], "WebPage");
+}
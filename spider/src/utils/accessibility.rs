@@ -0,0 +1,386 @@
+//! Accessibility tree extraction and automated a11y audit for Chrome pages.
+//!
+//! After the page renders the CDP `Accessibility.getFullAXTree` is pulled and
+//! checked for missing alt text, unlabeled form controls, empty links and
+//! buttons and skipped heading levels. Text contrast is checked separately from
+//! the computed styles against the WCAG 2 AA ratios. The result is stored as an
+//! [`AccessibilityReport`] on [`crate::page::Page::accessibility`].
+//!
+//! The audit is a quick automated pass and does not replace a manual review.
+
+use chromiumoxide::cdp::browser_protocol::accessibility::{
+    AxNode, AxPropertyName, AxValue, GetFullAxTreeParams,
+};
+use serde::{Deserialize, Serialize};
+
+pub use crate::features::chrome_common::AccessibilityConfig;
+
+/// Walk the visible text nodes and report the ones below the WCAG AA contrast
+/// ratio. Backgrounds are resolved through the ancestors; text over an image is
+/// skipped since the real background is unknown.
+const CONTRAST_SCRIPT: &str = r#"(() => {
+  const parse = (c) => {
+    const m = c && c.match(/rgba?\(([^)]+)\)/);
+    if (!m) return null;
+    const p = m[1].split(/[\s,\/]+/).filter(Boolean).map(parseFloat);
+    return [p[0], p[1], p[2], p.length > 3 ? p[3] : 1];
+  };
+  const lum = (c) => {
+    const f = (v) => { v /= 255; return v <= 0.03928 ? v / 12.92 : Math.pow((v + 0.055) / 1.055, 2.4); };
+    return 0.2126 * f(c[0]) + 0.7152 * f(c[1]) + 0.0722 * f(c[2]);
+  };
+  const background = (el) => {
+    for (let e = el; e; e = e.parentElement) {
+      const s = getComputedStyle(e);
+      if (s.backgroundImage && s.backgroundImage !== 'none') return null;
+      const c = parse(s.backgroundColor);
+      if (c && c[3] > 0) return c;
+    }
+    return [255, 255, 255, 1];
+  };
+  const root = document.body || document.documentElement;
+  if (!root) return '[]';
+  const out = [], seen = new Set();
+  const walker = document.createTreeWalker(root, NodeFilter.SHOW_TEXT);
+  let node, visited = 0;
+  while ((node = walker.nextNode()) && visited < 5000 && out.length < 100) {
+    visited++;
+    const el = node.parentElement;
+    const text = node.textContent.trim();
+    if (!el || !text || seen.has(el)) continue;
+    seen.add(el);
+    if (el.checkVisibility && !el.checkVisibility({ opacityProperty: true, visibilityProperty: true })) continue;
+    const s = getComputedStyle(el);
+    const fg = parse(s.color), bg = background(el);
+    if (!fg || !bg) continue;
+    const blended = [0, 1, 2].map((i) => fg[i] * fg[3] + bg[i] * (1 - fg[3]));
+    const a = lum(blended), b = lum(bg);
+    const ratio = (Math.max(a, b) + 0.05) / (Math.min(a, b) + 0.05);
+    const size = parseFloat(s.fontSize) || 16;
+    const large = size >= 24 || (parseInt(s.fontWeight, 10) >= 700 && size >= 18.66);
+    const required = large ? 3 : 4.5;
+    if (ratio < required) {
+      out.push({ tag: el.tagName.toLowerCase(), text: text.slice(0, 80), ratio: Math.round(ratio * 100) / 100, required });
+    }
+  }
+  return JSON.stringify(out);
+})()"#;
+
+/// Roles of form controls that need an accessible name.
+const FORM_CONTROL_ROLES: &[&str] = &[
+    "textbox",
+    "searchbox",
+    "combobox",
+    "listbox",
+    "checkbox",
+    "radio",
+    "slider",
+    "spinbutton",
+    "switch",
+];
+
+/// The rule an [`AccessibilityIssue`] failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessibilityRule {
+    /// An image without alternative text.
+    ImageAlt,
+    /// A form control without a label.
+    FormLabel,
+    /// A link without an accessible name.
+    LinkName,
+    /// A button without an accessible name.
+    ButtonName,
+    /// A heading that skips one or more levels.
+    HeadingOrder,
+    /// Text below the WCAG AA contrast ratio.
+    ColorContrast,
+}
+
+/// A single failed check.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessibilityIssue {
+    /// The failed rule.
+    pub rule: AccessibilityRule,
+    /// The accessibility role, or the tag name for contrast issues.
+    pub role: String,
+    /// The accessible name, or the text for contrast issues.
+    pub name: String,
+    /// A short description of the failure.
+    pub message: String,
+    /// The DOM node, when known.
+    pub backend_node_id: Option<i64>,
+}
+
+/// A node of the flattened accessibility tree. Ignored nodes are left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccessibilityNode {
+    /// The accessibility node id.
+    pub node_id: String,
+    /// The parent node id.
+    pub parent_id: Option<String>,
+    /// The child node ids.
+    pub child_ids: Vec<String>,
+    /// The role such as `link` or `heading`.
+    pub role: String,
+    /// The computed accessible name.
+    pub name: String,
+    /// The heading or tree level.
+    pub level: Option<i64>,
+    /// The DOM node.
+    pub backend_node_id: Option<i64>,
+}
+
+/// The accessibility audit of a page.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccessibilityReport {
+    /// The number of nodes in the accessibility tree that are not ignored.
+    pub nodes: usize,
+    /// The failed checks in document order, contrast issues last.
+    pub issues: Vec<AccessibilityIssue>,
+    /// The flattened accessibility tree when [`AccessibilityConfig::include_tree`] is set.
+    pub tree: Option<Vec<AccessibilityNode>>,
+}
+
+impl AccessibilityReport {
+    /// Build the report from the nodes of `Accessibility.getFullAXTree`.
+    pub fn from_nodes(nodes: &[AxNode], include_tree: bool) -> Self {
+        let mut report = Self::default();
+        let mut tree = Vec::new();
+        let mut last_heading: Option<i64> = None;
+
+        for node in nodes.iter().filter(|n| !n.ignored && !is_hidden(n)) {
+            report.nodes += 1;
+
+            let role = value_string(node.role.as_ref());
+            let name = value_string(node.name.as_ref());
+            let level = property(node, AxPropertyName::Level).and_then(|v| v.as_i64());
+            let backend_node_id = node.backend_dom_node_id.as_ref().map(|id| *id.inner());
+            let unnamed = name.trim().is_empty();
+
+            let failed = match role.as_str() {
+                "image" | "img" if unnamed => {
+                    Some((AccessibilityRule::ImageAlt, "Image has no alt text.".into()))
+                }
+                "link" if unnamed => Some((
+                    AccessibilityRule::LinkName,
+                    "Link has no discernible text.".into(),
+                )),
+                "button" if unnamed => Some((
+                    AccessibilityRule::ButtonName,
+                    "Button has no discernible text.".into(),
+                )),
+                r if unnamed && FORM_CONTROL_ROLES.contains(&r) => Some((
+                    AccessibilityRule::FormLabel,
+                    "Form control has no label.".into(),
+                )),
+                "heading" => {
+                    let skipped = match (last_heading, level) {
+                        (Some(prev), Some(level)) if level > prev + 1 => {
+                            Some(format!("Heading level {level} skips from level {prev}."))
+                        }
+                        _ => None,
+                    };
+                    if level.is_some() {
+                        last_heading = level;
+                    }
+                    skipped.map(|message| (AccessibilityRule::HeadingOrder, message))
+                }
+                _ => None,
+            };
+
+            if let Some((rule, message)) = failed {
+                report.issues.push(AccessibilityIssue {
+                    rule,
+                    role: role.clone(),
+                    name: name.clone(),
+                    message,
+                    backend_node_id,
+                });
+            }
+
+            if include_tree {
+                tree.push(AccessibilityNode {
+                    node_id: node.node_id.inner().clone(),
+                    parent_id: node.parent_id.as_ref().map(|id| id.inner().clone()),
+                    child_ids: node
+                        .child_ids
+                        .as_ref()
+                        .map(|ids| ids.iter().map(|id| id.inner().clone()).collect())
+                        .unwrap_or_default(),
+                    role,
+                    name,
+                    level,
+                    backend_node_id,
+                });
+            }
+        }
+
+        if include_tree {
+            report.tree = Some(tree);
+        }
+
+        report
+    }
+
+    /// Append the contrast issues from the output of the contrast script.
+    pub(crate) fn push_contrast(&mut self, json: &str) {
+        #[derive(Deserialize)]
+        struct Contrast {
+            tag: String,
+            text: String,
+            ratio: f64,
+            required: f64,
+        }
+
+        if let Ok(items) = serde_json::from_str::<Vec<Contrast>>(json) {
+            self.issues
+                .extend(items.into_iter().map(|c| AccessibilityIssue {
+                    rule: AccessibilityRule::ColorContrast,
+                    role: c.tag,
+                    name: c.text,
+                    message: format!(
+                        "Contrast ratio {} is below the required {}:1.",
+                        c.ratio, c.required
+                    ),
+                    backend_node_id: None,
+                }));
+        }
+    }
+
+    /// No check failed.
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// The issues that failed the rule.
+    pub fn issues_for(&self, rule: AccessibilityRule) -> impl Iterator<Item = &AccessibilityIssue> {
+        self.issues.iter().filter(move |i| i.rule == rule)
+    }
+}
+
+/// The value of an `AXValue` as a string.
+fn value_string(value: Option<&AxValue>) -> String {
+    match value.and_then(|v| v.value.as_ref()) {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Null) | None => String::new(),
+        Some(v) => v.to_string(),
+    }
+}
+
+/// The value of a node property.
+fn property(node: &AxNode, name: AxPropertyName) -> Option<&serde_json::Value> {
+    node.properties
+        .as_ref()?
+        .iter()
+        .find(|p| p.name == name)
+        .and_then(|p| p.value.value.as_ref())
+}
+
+/// The node is hidden from assistive technology.
+fn is_hidden(node: &AxNode) -> bool {
+    property(node, AxPropertyName::Hidden).is_some_and(|v| v.as_bool() == Some(true))
+}
+
+/// Pull the accessibility tree of the rendered page and audit it.
+pub(crate) async fn collect(
+    page: &chromiumoxide::Page,
+    config: &AccessibilityConfig,
+) -> Option<AccessibilityReport> {
+    let nodes = page
+        .execute(GetFullAxTreeParams::default())
+        .await
+        .ok()?
+        .result
+        .nodes;
+
+    let mut report = AccessibilityReport::from_nodes(&nodes, config.include_tree);
+
+    if config.contrast {
+        if let Some(json) = page
+            .evaluate(CONTRAST_SCRIPT)
+            .await
+            .ok()
+            .and_then(|v| v.into_value::<String>().ok())
+        {
+            report.push_contrast(&json);
+        }
+    }
+
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, role: &str, name: &str, level: Option<i64>) -> serde_json::Value {
+        let mut node = serde_json::json!({
+            "nodeId": id,
+            "ignored": false,
+            "role": { "type": "role", "value": role },
+            "name": { "type": "computedString", "value": name },
+            "backendDOMNodeId": id.parse::<i64>().unwrap(),
+        });
+        if let Some(level) = level {
+            node["properties"] = serde_json::json!([
+                { "name": "level", "value": { "type": "integer", "value": level } }
+            ]);
+        }
+        node
+    }
+
+    #[test]
+    fn test_audit_rules() {
+        let mut hidden = node("8", "image", "", None);
+        hidden["properties"] = serde_json::json!([
+            { "name": "hidden", "value": { "type": "boolean", "value": true } }
+        ]);
+        let mut ignored = node("9", "link", "", None);
+        ignored["ignored"] = serde_json::json!(true);
+
+        let nodes: Vec<AxNode> = serde_json::from_value(serde_json::json!([
+            node("1", "RootWebArea", "Home", None),
+            node("2", "heading", "Title", Some(1)),
+            node("3", "image", "", None),
+            node("4", "link", "About", None),
+            node("5", "button", " ", None),
+            node("6", "textbox", "", None),
+            node("7", "heading", "Details", Some(3)),
+            hidden,
+            ignored,
+        ]))
+        .unwrap();
+
+        let report = AccessibilityReport::from_nodes(&nodes, true);
+        let rules: Vec<_> = report.issues.iter().map(|i| i.rule).collect();
+
+        assert_eq!(report.nodes, 7);
+        assert_eq!(
+            rules,
+            [
+                AccessibilityRule::ImageAlt,
+                AccessibilityRule::ButtonName,
+                AccessibilityRule::FormLabel,
+                AccessibilityRule::HeadingOrder,
+            ]
+        );
+        assert_eq!(report.issues[0].backend_node_id, Some(3));
+        assert_eq!(report.tree.as_ref().map(|t| t.len()), Some(7));
+        assert_eq!(report.tree.unwrap()[6].level, Some(3));
+    }
+
+    #[test]
+    fn test_contrast_issues() {
+        let mut report = AccessibilityReport::default();
+        report.push_contrast(r#"[{"tag":"p","text":"faint","ratio":2.1,"required":4.5}]"#);
+        report.push_contrast("not json");
+
+        assert!(!report.is_clean());
+        assert_eq!(
+            report.issues_for(AccessibilityRule::ColorContrast).count(),
+            1
+        );
+        assert_eq!(report.issues[0].role, "p");
+    }
+}
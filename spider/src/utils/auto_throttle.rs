@@ -194,6 +194,15 @@ mod inner {
             })
         }
 
+        /// The EMA latency in milliseconds of every domain with samples.
+        pub fn domain_latencies(&self) -> Vec<(CompactString, f64)> {
+            self.domains
+                .iter()
+                .filter(|entry| entry.samples.load(Ordering::Relaxed) > 0)
+                .map(|entry| (entry.key().clone(), entry.ema_micros() / 1000.0))
+                .collect()
+        }
+
         /// Number of tracked domains.
         pub fn len(&self) -> usize {
             self.domains.len()
@@ -0,0 +1,412 @@
+//! Browser console messages, JS exceptions and failed resources for Chrome pages.
+//!
+//! Collected from `Runtime.consoleAPICalled`, `Runtime.exceptionThrown`,
+//! `Log.entryAdded` and `Network.loadingFailed` while the page renders and
+//! attached to [`crate::page::Page::browser_logs`]. Turning this on enables
+//! the CDP `Runtime` and `Log` domains, which some anti-bot scripts detect.
+
+use chromiumoxide::cdp::browser_protocol::log::{EventEntryAdded, LogEntryLevel};
+use chromiumoxide::cdp::browser_protocol::network::{EventLoadingFailed, EventRequestWillBeSent};
+use chromiumoxide::cdp::js_protocol::runtime::{
+    ConsoleApiCalledType, EventConsoleApiCalled, EventExceptionThrown, RemoteObject, StackTrace,
+};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// Browser log capture configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrowserLogsConfig {
+    /// The max entries kept per page. Defaults to 200.
+    pub max_entries: usize,
+}
+
+impl Default for BrowserLogsConfig {
+    fn default() -> Self {
+        Self { max_entries: 200 }
+    }
+}
+
+/// The severity of a browser log.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowserLogLevel {
+    /// Verbose and debug output.
+    Verbose,
+    #[default]
+    /// Informational output.
+    Info,
+    /// Warnings.
+    Warning,
+    /// Errors.
+    Error,
+}
+
+/// Where a browser log came from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowserLogSource {
+    #[default]
+    /// A `console.*` call.
+    Console,
+    /// An uncaught JS exception.
+    Exception,
+    /// A browser log entry such as a CSP or deprecation message.
+    Browser,
+    /// A resource that failed to load.
+    Network,
+}
+
+/// A browser log entry.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BrowserLog {
+    /// The severity.
+    pub level: BrowserLogLevel,
+    /// Where the log came from.
+    pub source: BrowserLogSource,
+    /// The message.
+    pub text: String,
+    /// The script or resource url.
+    pub url: Option<String>,
+    /// The 1-based line number in `url`.
+    pub line: Option<i64>,
+    /// The time of the event in milliseconds since the epoch.
+    pub timestamp: f64,
+}
+
+impl BrowserLog {
+    /// Is this an uncaught exception or an error logged by the page.
+    pub fn is_js_error(&self) -> bool {
+        self.level == BrowserLogLevel::Error
+            && matches!(
+                self.source,
+                BrowserLogSource::Exception | BrowserLogSource::Console
+            )
+    }
+}
+
+/// The top frame of a stack trace as url and 1-based line.
+fn top_frame(stack_trace: &Option<StackTrace>) -> (Option<String>, Option<i64>) {
+    match stack_trace.as_ref().and_then(|s| s.call_frames.first()) {
+        Some(frame) if !frame.url.is_empty() => {
+            (Some(frame.url.clone()), Some(frame.line_number + 1))
+        }
+        _ => (None, None),
+    }
+}
+
+/// Render a console argument the way DevTools prints it.
+fn remote_object_text(object: &RemoteObject) -> String {
+    match &object.value {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(value) => value.to_string(),
+        _ => match (&object.unserializable_value, &object.description) {
+            (Some(value), _) => value.inner().clone(),
+            (_, Some(description)) => description.clone(),
+            _ => object.r#type.as_ref().to_string(),
+        },
+    }
+}
+
+/// A console call as a log entry.
+pub fn from_console(event: &EventConsoleApiCalled) -> BrowserLog {
+    let level = match event.r#type {
+        ConsoleApiCalledType::Error | ConsoleApiCalledType::Assert => BrowserLogLevel::Error,
+        ConsoleApiCalledType::Warning => BrowserLogLevel::Warning,
+        ConsoleApiCalledType::Debug | ConsoleApiCalledType::Trace => BrowserLogLevel::Verbose,
+        _ => BrowserLogLevel::Info,
+    };
+    let (url, line) = top_frame(&event.stack_trace);
+
+    BrowserLog {
+        level,
+        source: BrowserLogSource::Console,
+        text: event
+            .args
+            .iter()
+            .map(remote_object_text)
+            .collect::<Vec<_>>()
+            .join(" "),
+        url,
+        line,
+        timestamp: *event.timestamp.inner(),
+    }
+}
+
+/// An uncaught exception as a log entry.
+pub fn from_exception(event: &EventExceptionThrown) -> BrowserLog {
+    let details = &event.exception_details;
+    // the description holds the message followed by the stack.
+    let text = details
+        .exception
+        .as_ref()
+        .and_then(|e| e.description.as_deref())
+        .and_then(|d| d.lines().next())
+        .unwrap_or(&details.text)
+        .to_string();
+    let (url, line) = match &details.url {
+        Some(url) if !url.is_empty() => (Some(url.clone()), Some(details.line_number + 1)),
+        _ => top_frame(&details.stack_trace),
+    };
+
+    BrowserLog {
+        level: BrowserLogLevel::Error,
+        source: BrowserLogSource::Exception,
+        text,
+        url,
+        line,
+        timestamp: *event.timestamp.inner(),
+    }
+}
+
+/// A browser log entry.
+pub fn from_log_entry(event: &EventEntryAdded) -> BrowserLog {
+    let entry = &event.entry;
+    let level = match entry.level {
+        LogEntryLevel::Verbose => BrowserLogLevel::Verbose,
+        LogEntryLevel::Info => BrowserLogLevel::Info,
+        LogEntryLevel::Warning => BrowserLogLevel::Warning,
+        LogEntryLevel::Error => BrowserLogLevel::Error,
+    };
+    let (url, line) = match &entry.url {
+        Some(url) if !url.is_empty() => (Some(url.clone()), entry.line_number.map(|l| l + 1)),
+        _ => top_frame(&entry.stack_trace),
+    };
+
+    BrowserLog {
+        level,
+        source: BrowserLogSource::Browser,
+        text: entry.text.clone(),
+        url,
+        line,
+        timestamp: *entry.timestamp.inner(),
+    }
+}
+
+/// Collect the browser logs of a page up to the configured cap.
+#[derive(Debug, Default)]
+pub struct BrowserLogCollector {
+    max_entries: usize,
+    logs: Vec<BrowserLog>,
+    /// The request urls by id to name failed resources.
+    requests: HashMap<String, String>,
+    /// The monotonic and wall clock of the first request to date network failures.
+    clock: Option<(f64, f64)>,
+}
+
+impl BrowserLogCollector {
+    /// A new collector keeping at most `max_entries` logs.
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            max_entries,
+            ..Default::default()
+        }
+    }
+
+    /// Add a log, dropped once the cap is reached.
+    pub fn push(&mut self, log: BrowserLog) {
+        if self.logs.len() < self.max_entries {
+            self.logs.push(log);
+        }
+    }
+
+    /// Handle `Network.requestWillBeSent`.
+    pub fn on_request(&mut self, event: &EventRequestWillBeSent) {
+        if self.clock.is_none() {
+            self.clock = Some((*event.timestamp.inner(), *event.wall_time.inner()));
+        }
+        self.requests
+            .insert(event.request_id.inner().clone(), event.request.url.clone());
+    }
+
+    /// Handle `Network.loadingFailed`. Canceled requests are skipped.
+    pub fn on_failed(&mut self, event: &EventLoadingFailed) {
+        let url = self.requests.remove(event.request_id.inner());
+
+        if event.canceled.unwrap_or_default() {
+            return;
+        }
+
+        let timestamp = match self.clock {
+            Some((started, wall_time)) => {
+                (wall_time + (*event.timestamp.inner() - started)) * 1000.0
+            }
+            _ => 0.0,
+        };
+        let text = match &event.blocked_reason {
+            Some(reason) => format!("{} ({})", event.error_text, reason.as_ref()),
+            _ => event.error_text.clone(),
+        };
+
+        self.push(BrowserLog {
+            level: BrowserLogLevel::Error,
+            source: BrowserLogSource::Network,
+            text,
+            url,
+            line: None,
+            timestamp,
+        });
+    }
+
+    /// The logs collected.
+    pub fn finish(self) -> Vec<BrowserLog> {
+        self.logs
+    }
+}
+
+/// Collect the browser logs of `page` until `shutdown` fires. The listeners
+/// are created before returning so the caller can navigate right after.
+pub(crate) async fn spawn_collector(
+    page: &chromiumoxide::Page,
+    config: BrowserLogsConfig,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) -> tokio::task::JoinHandle<Vec<BrowserLog>> {
+    use crate::utils::next_optional_event as next_event;
+
+    let (console, exceptions, entries, requests, failed) = tokio::join!(
+        page.event_listener::<EventConsoleApiCalled>(),
+        page.event_listener::<EventExceptionThrown>(),
+        page.event_listener::<EventEntryAdded>(),
+        page.event_listener::<EventRequestWillBeSent>(),
+        page.event_listener::<EventLoadingFailed>(),
+    );
+    let _ = tokio::join!(page.enable_runtime(), page.enable_log());
+
+    let (mut console, mut exceptions, mut entries, mut requests, mut failed) = (
+        console.ok(),
+        exceptions.ok(),
+        entries.ok(),
+        requests.ok(),
+        failed.ok(),
+    );
+
+    tokio::spawn(async move {
+        let mut collector = BrowserLogCollector::new(config.max_entries);
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown.changed() => break,
+                ev = next_event(&mut console) => match ev {
+                    Some(ev) => collector.push(from_console(&ev)),
+                    _ => console = None,
+                },
+                ev = next_event(&mut exceptions) => match ev {
+                    Some(ev) => collector.push(from_exception(&ev)),
+                    _ => exceptions = None,
+                },
+                ev = next_event(&mut entries) => match ev {
+                    Some(ev) => collector.push(from_log_entry(&ev)),
+                    _ => entries = None,
+                },
+                ev = next_event(&mut requests) => match ev {
+                    Some(ev) => collector.on_request(&ev),
+                    _ => requests = None,
+                },
+                ev = next_event(&mut failed) => match ev {
+                    Some(ev) => collector.on_failed(&ev),
+                    _ => failed = None,
+                },
+            }
+        }
+
+        collector.finish()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_console_and_exception_logs() {
+        let console: EventConsoleApiCalled = serde_json::from_value(serde_json::json!({
+            "type": "error",
+            "args": [
+                { "type": "string", "value": "failed" },
+                { "type": "number", "value": 3 },
+                { "type": "object", "description": "Object" }
+            ],
+            "executionContextId": 1,
+            "timestamp": 1_700_000_000_000.0,
+            "stackTrace": { "callFrames": [{
+                "functionName": "f", "scriptId": "1", "url": "https://a.com/app.js",
+                "lineNumber": 9, "columnNumber": 2
+            }]}
+        }))
+        .unwrap();
+        let log = from_console(&console);
+
+        assert_eq!(log.level, BrowserLogLevel::Error);
+        assert_eq!(log.text, "failed 3 Object");
+        assert_eq!(log.url.as_deref(), Some("https://a.com/app.js"));
+        assert_eq!(log.line, Some(10));
+        assert!(log.is_js_error());
+
+        let exception: EventExceptionThrown = serde_json::from_value(serde_json::json!({
+            "timestamp": 1.0,
+            "exceptionDetails": {
+                "exceptionId": 1,
+                "text": "Uncaught",
+                "lineNumber": 0,
+                "columnNumber": 5,
+                "url": "https://a.com/x.js",
+                "exception": {
+                    "type": "object",
+                    "description": "TypeError: x is undefined\n    at https://a.com/x.js:1:5"
+                }
+            }
+        }))
+        .unwrap();
+        let log = from_exception(&exception);
+
+        assert_eq!(log.source, BrowserLogSource::Exception);
+        assert_eq!(log.text, "TypeError: x is undefined");
+        assert_eq!(log.line, Some(1));
+    }
+
+    #[test]
+    fn test_collector_network_failures_and_cap() {
+        let mut collector = BrowserLogCollector::new(2);
+        let request = |id: &str, url: &str| -> EventRequestWillBeSent {
+            serde_json::from_value(serde_json::json!({
+                "requestId": id,
+                "loaderId": "l",
+                "documentURL": url,
+                "request": {
+                    "url": url, "method": "GET", "headers": {},
+                    "initialPriority": "High", "referrerPolicy": "no-referrer"
+                },
+                "timestamp": 10.0,
+                "wallTime": 1_700_000_000.0,
+                "initiator": { "type": "other" },
+                "redirectHasExtraInfo": false
+            }))
+            .unwrap()
+        };
+        let failed = |id: &str, canceled: bool| -> EventLoadingFailed {
+            serde_json::from_value(serde_json::json!({
+                "requestId": id, "timestamp": 11.0, "type": "Script",
+                "errorText": "net::ERR_NAME_NOT_RESOLVED", "canceled": canceled
+            }))
+            .unwrap()
+        };
+
+        collector.on_request(&request("1", "https://a.com/x.js"));
+        collector.on_request(&request("2", "https://a.com/y.js"));
+        collector.on_failed(&failed("2", true));
+        collector.on_failed(&failed("1", false));
+        collector.push(BrowserLog::default());
+        collector.push(BrowserLog::default());
+
+        let logs = collector.finish();
+
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].source, BrowserLogSource::Network);
+        assert_eq!(logs[0].url.as_deref(), Some("https://a.com/x.js"));
+        assert_eq!(logs[0].timestamp, 1_700_000_001_000.0);
+        assert!(!logs[0].is_js_error());
+    }
+}
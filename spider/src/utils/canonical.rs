@@ -0,0 +1,331 @@
+//! Canonical URL discovery and canonical-aware page deduplication.
+//!
+//! Sites often serve one document under several URLs — tracking
+//! parameters, alternate paths, print views — and point every variant at
+//! one `<link rel="canonical">` (or `Link: <...>; rel=canonical` header).
+//! [`CanonicalUrls`] keys pages by that canonical so the alternates are
+//! emitted and followed once. The page served at the canonical URL itself
+//! is preferred: an alternate is dropped and its canonical URL crawled in
+//! its place, unless the canonical lives on another host.
+use crate::page::Page;
+use crate::utils::robots_meta::{for_each_head_tag, head_slice, tag_attr};
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use std::sync::{Arc, Mutex};
+
+/// Read the canonical target from the `Link` response headers.
+pub fn canonical_from_headers(headers: &reqwest::header::HeaderMap) -> Option<String> {
+    headers
+        .get_all(reqwest::header::LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find_map(canonical_from_link_header)
+}
+
+/// Read the canonical target from a single `Link` header value, which may
+/// list several comma separated links.
+pub fn canonical_from_link_header(value: &str) -> Option<String> {
+    let mut rest = value;
+
+    while let Some(open) = rest.find('<') {
+        let close = open + rest[open..].find('>')?;
+        let target = &rest[open + 1..close];
+        let params_end = rest[close..]
+            .find('<')
+            .map_or(rest.len(), |next| close + next);
+        let params = &rest[close + 1..params_end];
+
+        let canonical = params.split(';').any(|param| {
+            param.split_once('=').is_some_and(|(key, value)| {
+                key.trim().eq_ignore_ascii_case("rel")
+                    && value
+                        .trim()
+                        .trim_end_matches(',')
+                        .trim()
+                        .trim_matches('"')
+                        .split_ascii_whitespace()
+                        .any(|rel| rel.eq_ignore_ascii_case("canonical"))
+            })
+        });
+
+        if canonical && !target.trim().is_empty() {
+            return Some(target.trim().to_string());
+        }
+
+        rest = &rest[params_end..];
+    }
+
+    None
+}
+
+/// Read `<link rel="canonical" href="...">` from the document head.
+pub fn canonical_from_html(html: &[u8]) -> Option<String> {
+    let head = head_slice(html);
+
+    if !head
+        .windows(9)
+        .any(|w| w.eq_ignore_ascii_case(b"canonical"))
+    {
+        return None;
+    }
+
+    let mut canonical = None;
+
+    for_each_head_tag(head, "link", |tag| {
+        if canonical.is_some() {
+            return;
+        }
+        let is_canonical = tag_attr(tag, "rel").is_some_and(|rel| {
+            rel.split_ascii_whitespace()
+                .any(|rel| rel.eq_ignore_ascii_case("canonical"))
+        });
+        if is_canonical {
+            if let Some(href) = tag_attr(tag, "href").map(str::trim) {
+                if !href.is_empty() {
+                    canonical = Some(href.to_string());
+                }
+            }
+        }
+    });
+
+    canonical
+}
+
+/// Resolve a canonical target against the page URL. Fragments are dropped
+/// and only `http`/`https` targets are kept.
+pub fn resolve_canonical(page_url: &str, href: &str) -> Option<String> {
+    let base = url::Url::parse(page_url).ok();
+    let mut resolved = match &base {
+        Some(base) => base.join(href).ok()?,
+        None => url::Url::parse(href).ok()?,
+    };
+
+    if !matches!(resolved.scheme(), "http" | "https") {
+        return None;
+    }
+
+    resolved.set_fragment(None);
+
+    Some(resolved.into())
+}
+
+/// Both URLs are on the same host.
+fn same_host(a: &str, b: &str) -> bool {
+    match (url::Url::parse(a), url::Url::parse(b)) {
+        (Ok(a), Ok(b)) => a.host_str() == b.host_str(),
+        _ => false,
+    }
+}
+
+/// The dedup key of a URL: parsed and serialized without the fragment so
+/// `https://a.com` and `https://a.com/#top` compare equal.
+pub fn canonical_key(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(mut parsed) => {
+            parsed.set_fragment(None);
+            parsed.into()
+        }
+        _ => url.to_string(),
+    }
+}
+
+/// What to do with a crawled page after checking its canonical URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CanonicalVerdict {
+    /// The page owns its canonical URL: emit it and follow its links.
+    Keep,
+    /// Another page already owns the canonical URL: drop the page.
+    Duplicate,
+    /// The page is an alternate of a same-host canonical URL nobody owns
+    /// yet: drop the page and crawl the canonical URL instead.
+    Follow(String),
+}
+
+/// Pages seen so far, keyed by canonical URL. Clones share the same table
+/// so concurrent crawl tasks agree on which page owns a canonical.
+#[derive(Debug, Clone, Default)]
+pub struct CanonicalUrls {
+    /// Canonical key to the URL of the page that owns it. Empty while the
+    /// canonical URL is queued in place of an alternate.
+    claimed: Arc<DashMap<String, String>>,
+    /// Cross-host canonical URLs owned by an alternate, not yet marked as visited.
+    discovered: Arc<Mutex<Vec<String>>>,
+}
+
+impl CanonicalUrls {
+    /// Claim the page's canonical URL, or its own URL when it declares
+    /// none. The page served at the canonical URL always wins: a same-host
+    /// alternate defers to it, and it takes over a claim held by an
+    /// alternate. An alternate is only kept when its canonical URL is on
+    /// another host the crawl would not reach.
+    pub fn check(&self, page: &Page) -> CanonicalVerdict {
+        let url = canonical_key(page.get_url());
+        let key = match page.canonical_url.as_deref() {
+            Some(canonical) => canonical_key(canonical),
+            None => url.clone(),
+        };
+
+        self.claim(key, url)
+    }
+
+    /// Claim `key` for `url`.
+    fn claim(&self, key: String, url: String) -> CanonicalVerdict {
+        let owns = key == url;
+
+        match self.claimed.entry(key) {
+            Entry::Occupied(mut entry) => {
+                if entry.get() == &url {
+                    CanonicalVerdict::Keep
+                } else if owns && entry.get() != entry.key() {
+                    entry.insert(url);
+                    CanonicalVerdict::Keep
+                } else {
+                    CanonicalVerdict::Duplicate
+                }
+            }
+            Entry::Vacant(entry) if owns => {
+                entry.insert(url);
+                CanonicalVerdict::Keep
+            }
+            Entry::Vacant(entry) => {
+                let canonical = entry.key().clone();
+
+                if same_host(&canonical, &url) {
+                    entry.insert(String::new());
+                    CanonicalVerdict::Follow(canonical)
+                } else {
+                    entry.insert(url);
+                    if let Ok(mut discovered) = self.discovered.lock() {
+                        discovered.push(canonical);
+                    }
+                    CanonicalVerdict::Keep
+                }
+            }
+        }
+    }
+
+    /// Take the canonical URLs owned by a kept alternate since the last
+    /// call. The crawler marks them as visited so the same document is not
+    /// fetched a second time.
+    pub fn take_discovered(&self) -> Vec<String> {
+        match self.discovered.lock() {
+            Ok(mut discovered) if !discovered.is_empty() => std::mem::take(&mut *discovered),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The URL of the page that claimed a canonical URL.
+    pub fn claimant(&self, canonical: &str) -> Option<String> {
+        self.claimed
+            .get(&canonical_key(canonical))
+            .filter(|entry| !entry.value().is_empty())
+            .map(|entry| entry.value().clone())
+    }
+
+    /// Number of canonical URLs claimed.
+    pub fn len(&self) -> usize {
+        self.claimed.len()
+    }
+
+    /// No canonical URL was claimed.
+    pub fn is_empty(&self) -> bool {
+        self.claimed.is_empty()
+    }
+
+    /// Forget every claim.
+    pub fn clear(&self) {
+        self.claimed.clear();
+        if let Ok(mut discovered) = self.discovered.lock() {
+            discovered.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_from_link_header() {
+        assert_eq!(
+            canonical_from_link_header(r#"<https://a.com/p>; rel="canonical""#).as_deref(),
+            Some("https://a.com/p")
+        );
+        assert_eq!(
+            canonical_from_link_header(
+                r#"<https://a.com/style.css>; rel=preload; as=style, <https://a.com/Doc>; rel=canonical"#
+            )
+            .as_deref(),
+            Some("https://a.com/Doc")
+        );
+        assert!(canonical_from_link_header(r#"<https://a.com/next>; rel="next""#).is_none());
+    }
+
+    #[test]
+    fn test_canonical_from_html() {
+        let html = br#"<html><head><LINK REL="Canonical" HREF="/Articles/One?x=1"><link rel="canonical" href="/ignored"></head></html>"#;
+        assert_eq!(
+            canonical_from_html(html).as_deref(),
+            Some("/Articles/One?x=1")
+        );
+
+        let html = br#"<html><head><link rel="alternate" href="/amp"></head><body><link rel="canonical" href="/late"></body></html>"#;
+        assert!(canonical_from_html(html).is_none());
+    }
+
+    #[test]
+    fn test_resolve_canonical() {
+        assert_eq!(
+            resolve_canonical("https://a.com/x/y?utm=1", "../z#frag").as_deref(),
+            Some("https://a.com/z")
+        );
+        assert!(resolve_canonical("https://a.com/", "mailto:me@a.com").is_none());
+    }
+
+    #[test]
+    fn test_canonical_urls_dedup() {
+        let seen = CanonicalUrls::default();
+        let page = |url: &str, canonical: Option<&str>| {
+            let mut page = Page::default();
+            page.set_url(url.into());
+            page.canonical_url = canonical.map(Into::into);
+            page
+        };
+
+        let alternate = page("https://a.com/p?utm=1", Some("https://a.com/p"));
+        // The alternate defers to the canonical URL, once.
+        assert_eq!(
+            seen.check(&alternate),
+            CanonicalVerdict::Follow("https://a.com/p".into())
+        );
+        assert!(seen.claimant("https://a.com/p").is_none());
+        assert_eq!(seen.check(&alternate), CanonicalVerdict::Duplicate);
+        assert_eq!(
+            seen.check(&page("https://a.com/p?ref=2", Some("https://a.com/p"))),
+            CanonicalVerdict::Duplicate
+        );
+        // The page at the canonical URL wins, and a retry is not a duplicate of itself.
+        assert_eq!(
+            seen.check(&page("https://a.com/p", None)),
+            CanonicalVerdict::Keep
+        );
+        assert_eq!(
+            seen.check(&page("https://a.com/p", Some("https://a.com/p#top"))),
+            CanonicalVerdict::Keep
+        );
+        assert_eq!(
+            seen.check(&page("https://a.com/q", None)),
+            CanonicalVerdict::Keep
+        );
+        assert_eq!(
+            seen.claimant("https://a.com/p#top").as_deref(),
+            Some("https://a.com/p")
+        );
+        // An alternate of another host is kept and its canonical marked as visited.
+        let mirror = page("https://m.a.com/r", Some("https://b.com/r"));
+        assert_eq!(seen.check(&mirror), CanonicalVerdict::Keep);
+        assert_eq!(seen.take_discovered(), ["https://b.com/r"]);
+        assert!(seen.take_discovered().is_empty());
+        assert_eq!(seen.len(), 3);
+    }
+}
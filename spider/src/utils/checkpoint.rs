@@ -0,0 +1,287 @@
+//! Crawl checkpoints for resumable crawls.
+//!
+//! Feature-gated behind `checkpoint`. A [`CrawlCheckpoint`] captures
+//! everything a `Website` needs to continue a crawl after the process dies:
+//! the visited set, the pending queue, an optional prioritized frontier,
+//! the content signatures used for dedup, the remaining budget counters and
+//! the crawl status.
+//!
+//! The on-disk format is a versioned, line-oriented text file — one tagged
+//! record per line — so multi-million URL checkpoints stream without a
+//! serializer dependency and stay inspectable with standard tools. Backslashes,
+//! tabs and line breaks inside urls are escaped so any url survives the trip.
+//! Writes go
+//! to a sibling temp file first and are renamed into place, so a crash while
+//! checkpointing never clobbers the previous good snapshot.
+
+use crate::utils::frontier::{escape, unescape, ScoredUrl};
+use crate::website::CrawlStatus;
+use crate::CaseInsensitiveString;
+use hashbrown::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Magic header identifying a checkpoint file and its format version.
+const MAGIC: &str = "SPIDER-CHECKPOINT/1";
+
+/// Checkpoint configuration for periodic snapshots during a crawl.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CheckpointConfig {
+    /// Output file path for the checkpoint.
+    pub path: String,
+    /// Minimum time between two snapshots. `None` only checkpoints when the
+    /// crawl finishes or is shut down.
+    pub interval: Option<Duration>,
+}
+
+impl CheckpointConfig {
+    /// Checkpoint to `path` every `interval`.
+    pub fn new(path: impl Into<String>, interval: Option<Duration>) -> Self {
+        Self {
+            path: path.into(),
+            interval,
+        }
+    }
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            path: "spider.checkpoint".to_string(),
+            interval: Some(Duration::from_secs(60)),
+        }
+    }
+}
+
+/// A snapshot of a crawl's progress.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CrawlCheckpoint {
+    /// The crawl target url.
+    pub url: String,
+    /// The crawl status at the time of the snapshot.
+    pub status: CrawlStatus,
+    /// URLs already visited.
+    pub visited: HashSet<CaseInsensitiveString>,
+    /// URLs discovered but not fetched yet.
+    pub pending: HashSet<CaseInsensitiveString>,
+    /// Prioritized frontier entries not popped yet.
+    pub frontier: Vec<ScoredUrl>,
+    /// Content signatures already seen.
+    pub signatures: HashSet<u64>,
+    /// The remaining crawl budget per path.
+    pub budget: Option<HashMap<CaseInsensitiveString, u32>>,
+}
+
+impl CrawlCheckpoint {
+    /// Encode the checkpoint to its on-disk representation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(
+            64 + (self.visited.len() + self.pending.len() + self.frontier.len()) * 64,
+        );
+
+        let _ = writeln!(buf, "{MAGIC}");
+        let _ = writeln!(buf, "url\t{}", escape(&self.url));
+        let _ = writeln!(buf, "status\t{}", self.status);
+
+        if let Some(ref budget) = self.budget {
+            for (path, remaining) in budget.iter() {
+                let _ = writeln!(buf, "budget\t{remaining}\t{}", escape(path.inner()));
+            }
+        }
+        for signature in self.signatures.iter() {
+            let _ = writeln!(buf, "signature\t{signature}");
+        }
+        for link in self.visited.iter() {
+            let _ = writeln!(buf, "visited\t{}", escape(link.inner()));
+        }
+        for link in self.pending.iter() {
+            let _ = writeln!(buf, "pending\t{}", escape(link.inner()));
+        }
+        for entry in self.frontier.iter() {
+            let _ = writeln!(
+                buf,
+                "frontier\t{}\t{}",
+                entry.priority,
+                escape(entry.url.inner())
+            );
+        }
+
+        buf
+    }
+
+    /// Decode a checkpoint from its on-disk representation.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let text = std::str::from_utf8(bytes).map_err(invalid_data)?;
+        let mut lines = text.lines();
+
+        if lines.next() != Some(MAGIC) {
+            return Err(invalid_data("missing checkpoint header"));
+        }
+
+        let mut checkpoint = Self::default();
+
+        for line in lines {
+            if line.is_empty() {
+                continue;
+            }
+            let (tag, rest) = line.split_once('\t').unwrap_or((line, ""));
+
+            match tag {
+                "url" => checkpoint.url = unescape(rest),
+                "status" => {
+                    checkpoint.status = CrawlStatus::from_str(rest).map_err(invalid_data)?;
+                }
+                "budget" => {
+                    let (remaining, path) = rest
+                        .split_once('\t')
+                        .ok_or_else(|| invalid_data("malformed budget record"))?;
+                    let remaining = remaining.parse::<u32>().map_err(invalid_data)?;
+                    checkpoint
+                        .budget
+                        .get_or_insert_with(HashMap::new)
+                        .insert(unescape(path).into(), remaining);
+                }
+                "signature" => {
+                    checkpoint
+                        .signatures
+                        .insert(rest.parse::<u64>().map_err(invalid_data)?);
+                }
+                "visited" => {
+                    checkpoint.visited.insert(unescape(rest).into());
+                }
+                "pending" => {
+                    checkpoint.pending.insert(unescape(rest).into());
+                }
+                "frontier" => {
+                    let (priority, url) = rest
+                        .split_once('\t')
+                        .ok_or_else(|| invalid_data("malformed frontier record"))?;
+                    checkpoint.frontier.push(ScoredUrl {
+                        priority: priority.parse::<i32>().map_err(invalid_data)?,
+                        url: unescape(url).into(),
+                    });
+                }
+                // Unknown records are skipped so newer writers stay readable.
+                _ => (),
+            }
+        }
+
+        Ok(checkpoint)
+    }
+
+    /// Atomically write the checkpoint to `path`.
+    ///
+    /// The bytes land in `<path>.tmp` first and are renamed over `path`, so a
+    /// reader never observes a partially written snapshot.
+    pub async fn write_to(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                crate::utils::uring_fs::create_dir_all(parent.to_string_lossy().into_owned())
+                    .await?;
+            }
+        }
+
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let tmp = std::path::PathBuf::from(tmp);
+
+        crate::utils::uring_fs::write_file(tmp.to_string_lossy().into_owned(), self.to_bytes())
+            .await?;
+
+        tokio::fs::rename(&tmp, path).await
+    }
+
+    /// Read a checkpoint previously written with [`CrawlCheckpoint::write_to`].
+    pub async fn read_from(path: impl AsRef<Path>) -> io::Result<Self> {
+        let bytes =
+            crate::utils::uring_fs::read_file(path.as_ref().to_string_lossy().into_owned()).await?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Build an `InvalidData` io error.
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> CrawlCheckpoint {
+        let mut checkpoint = CrawlCheckpoint {
+            url: "https://example.com".into(),
+            status: CrawlStatus::Active,
+            ..Default::default()
+        };
+        checkpoint.visited.insert("https://example.com/".into());
+        checkpoint.visited.insert("https://example.com/a".into());
+        checkpoint.pending.insert("https://example.com/b".into());
+        checkpoint.frontier.push(ScoredUrl {
+            priority: 950,
+            url: "https://example.com/c".into(),
+        });
+        checkpoint.signatures.insert(42);
+        checkpoint.budget = Some(HashMap::from([
+            (CaseInsensitiveString::from("*"), 10),
+            (CaseInsensitiveString::from("/blog"), 3),
+        ]));
+        checkpoint
+    }
+
+    #[test]
+    fn round_trip_bytes() {
+        let checkpoint = sample();
+        let decoded = CrawlCheckpoint::from_bytes(&checkpoint.to_bytes()).unwrap();
+        assert_eq!(decoded, checkpoint);
+    }
+
+    #[test]
+    fn rejects_missing_header() {
+        assert!(CrawlCheckpoint::from_bytes(b"visited\thttps://example.com\n").is_err());
+    }
+
+    #[test]
+    fn skips_unknown_records() {
+        let bytes = format!("{MAGIC}\nfuture\tvalue\nvisited\thttps://example.com/a\n");
+        let decoded = CrawlCheckpoint::from_bytes(bytes.as_bytes()).unwrap();
+        assert_eq!(decoded.visited.len(), 1);
+    }
+
+    #[test]
+    fn escapes_delimiters() {
+        let mut checkpoint = sample();
+        checkpoint
+            .pending
+            .insert("https://example.com/x\nvisited\tinjected".into());
+        checkpoint.frontier.push(ScoredUrl {
+            priority: 5,
+            url: "https://example.com/a\\tb\r".into(),
+        });
+        let decoded = CrawlCheckpoint::from_bytes(&checkpoint.to_bytes()).unwrap();
+        assert_eq!(decoded, checkpoint);
+        assert!(!decoded
+            .visited
+            .contains(&CaseInsensitiveString::from("injected")));
+    }
+
+    #[tokio::test]
+    async fn write_and_read_file() {
+        let dir = std::env::temp_dir().join("spider_checkpoint_test");
+        let path = dir.join("crawl.checkpoint");
+        let checkpoint = sample();
+
+        checkpoint.write_to(&path).await.unwrap();
+        let decoded = CrawlCheckpoint::read_from(&path).await.unwrap();
+
+        assert_eq!(decoded, checkpoint);
+        assert!(!dir.join("crawl.checkpoint.tmp").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
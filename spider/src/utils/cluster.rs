@@ -0,0 +1,787 @@
+//! Distributed crawl coordination across `spider_worker` instances.
+//!
+//! Feature-gated behind `cluster`. One logical crawl runs on several nodes,
+//! each owning the hosts that a consistent [`HashRing`] assigns to it. A
+//! node crawls with a [`ClusterConfig`] set: links it discovers for hosts it
+//! owns stay in its local frontier, links for other hosts are batched and
+//! sent to their owner as a flexbuffers encoded [`LinkBatch`]. Every host is
+//! fetched from a single node, so the per-host delay and concurrency limits
+//! keep applying as they would on one machine.
+//!
+//! The [`ClusterCoordinator`] starts the crawl on every node, polls their
+//! [`NodeStatus`] until all of them are idle with every sent link received,
+//! then merges the visited sets of the nodes and stops them. A crawl where a
+//! node gave up delivering links fails instead, since the hosts of those
+//! links were never crawled.
+//!
+//! With a token set, every request between nodes carries it as a bearer
+//! token.
+//!
+//! Node endpoints, relative to the node base url:
+//!
+//! ```text
+//! POST   /cluster/{crawl_id}          start the crawl, JSON ClusterStart
+//! POST   /cluster/{crawl_id}/links    links discovered by a peer, flexbuffers LinkBatch
+//! GET    /cluster/{crawl_id}          JSON NodeStatus
+//! GET    /cluster/{crawl_id}/visited  visited links, flexbuffers LinkBatch
+//! DELETE /cluster/{crawl_id}          stop the crawl
+//! ```
+
+use crate::configuration::Configuration;
+use crate::CaseInsensitiveString;
+use hashbrown::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Default number of points each node takes on the ring.
+pub const DEFAULT_VIRTUAL_NODES: usize = 64;
+
+/// Links sent to a peer in one request.
+const MAX_BATCH_LINKS: usize = 512;
+
+/// How often partial batches are sent.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Attempts to deliver a batch before its links are dropped.
+const DELIVERY_ATTEMPTS: u32 = 3;
+
+/// Content type of flexbuffers bodies.
+pub const FLEXBUFFERS_CONTENT_TYPE: &str = "application/x-flexbuffers";
+
+/// Hash a key onto the ring. FNV alone clusters similar keys, so the result
+/// goes through the splitmix64 finalizer.
+fn ring_hash(key: &str) -> u64 {
+    let mut h = spider_agent_types::fnv1a64(key.as_bytes());
+    h = (h ^ (h >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h = (h ^ (h >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^ (h >> 31)
+}
+
+/// The lowercase host of a link.
+fn link_host(link: &str) -> Option<String> {
+    url::Url::parse(link)
+        .ok()?
+        .host_str()
+        .map(|h| h.to_ascii_lowercase())
+}
+
+/// A consistent hash ring assigning hosts to nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashRing {
+    /// Ring points sorted by hash, with the node index owning each.
+    points: Vec<(u64, usize)>,
+}
+
+impl HashRing {
+    /// A ring over `nodes`, identified by their urls, with `virtual_nodes`
+    /// points per node. Points are placed by node url, so adding or removing
+    /// a node only moves the hosts next to its points.
+    pub fn new(nodes: &[String], virtual_nodes: usize) -> Self {
+        let virtual_nodes = virtual_nodes.max(1);
+        let mut points = Vec::with_capacity(nodes.len() * virtual_nodes);
+
+        for (index, node) in nodes.iter().enumerate() {
+            for v in 0..virtual_nodes {
+                points.push((ring_hash(&format!("{node}#{v}")), index));
+            }
+        }
+        points.sort_unstable();
+
+        Self { points }
+    }
+
+    /// The node owning `host`, `None` when the ring is empty.
+    pub fn owner(&self, host: &str) -> Option<usize> {
+        if self.points.is_empty() {
+            return None;
+        }
+        let hash = ring_hash(host);
+        let index = self.points.partition_point(|(h, _)| *h < hash);
+        Some(self.points[index % self.points.len()].1)
+    }
+}
+
+/// The place of a node in a cluster crawl.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ClusterConfig {
+    /// Identifies the crawl on every node.
+    pub crawl_id: String,
+    /// Base urls of every node in the cluster, this one included.
+    pub nodes: Vec<String>,
+    /// Index of this node in `nodes`.
+    pub node: usize,
+    /// Points each node takes on the ring.
+    pub virtual_nodes: usize,
+    /// Bearer token sent with link batches. Set by each node, never sent.
+    #[serde(skip)]
+    pub token: Option<String>,
+}
+
+impl ClusterConfig {
+    /// Node `node` of a crawl spread over `nodes`.
+    pub fn new(crawl_id: impl Into<String>, nodes: Vec<String>, node: usize) -> Self {
+        Self {
+            crawl_id: crawl_id.into(),
+            nodes,
+            node,
+            virtual_nodes: DEFAULT_VIRTUAL_NODES,
+            token: None,
+        }
+    }
+
+    /// Set the bearer token sent with link batches.
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Set the points each node takes on the ring.
+    pub fn with_virtual_nodes(mut self, virtual_nodes: usize) -> Self {
+        self.virtual_nodes = virtual_nodes;
+        self
+    }
+
+    /// The ring of the cluster.
+    pub fn ring(&self) -> HashRing {
+        HashRing::new(&self.nodes, self.virtual_nodes)
+    }
+
+    /// The url of a cluster endpoint on `node`.
+    pub fn endpoint(&self, node: usize, path: &str) -> Option<String> {
+        self.nodes
+            .get(node)
+            .map(|base| endpoint(base, &self.crawl_id, path))
+    }
+}
+
+/// The url of a cluster endpoint under a node base url.
+fn endpoint(base: &str, crawl_id: &str, path: &str) -> String {
+    format!("{}/cluster/{crawl_id}{path}", base.trim_end_matches('/'))
+}
+
+/// Links exchanged between nodes, also used to collect visited sets.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LinkBatch {
+    /// The crawl the links belong to.
+    pub crawl_id: String,
+    /// Absolute urls.
+    pub links: Vec<String>,
+}
+
+impl LinkBatch {
+    /// Encode the batch with flexbuffers.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        flexbuffers::to_vec(self).unwrap_or_default()
+    }
+
+    /// Decode a flexbuffers encoded batch.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        flexbuffers::from_slice(bytes).ok()
+    }
+}
+
+/// The request starting a crawl on a node, with the crawl settings as `C`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ClusterStart<C = Configuration> {
+    /// The seed url of the crawl.
+    pub url: String,
+    /// The crawl configuration shared by every node.
+    pub configuration: C,
+    /// The place of the receiving node.
+    pub cluster: ClusterConfig,
+}
+
+/// Progress of a node, used to detect the end of the crawl.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct NodeStatus {
+    /// Not crawling, with no link waiting to be crawled or sent.
+    pub idle: bool,
+    /// Links handed to peers.
+    pub sent: u64,
+    /// Links sent that could not be delivered.
+    pub dropped: u64,
+    /// Links received from peers.
+    pub received: u64,
+    /// Links visited by the node.
+    pub visited: u64,
+}
+
+/// Counters shared with the outbox task.
+#[derive(Debug, Default)]
+struct Counters {
+    /// Links handed to the outbox.
+    sent: AtomicU64,
+    /// Links the outbox gave up on.
+    dropped: AtomicU64,
+    /// Links received from peers.
+    received: AtomicU64,
+    /// Links in the outbox not delivered yet.
+    pending: AtomicU64,
+}
+
+/// A node of a cluster crawl: routes links to their owner and keeps the
+/// counters reported in its [`NodeStatus`].
+#[derive(Debug)]
+pub struct ClusterNode {
+    /// The place of the node.
+    config: ClusterConfig,
+    /// Host ownership.
+    ring: HashRing,
+    /// Exchange counters.
+    counters: Arc<Counters>,
+    /// Links already handed to a peer.
+    forwarded: Mutex<HashSet<CaseInsensitiveString>>,
+    /// Links to send, with the index of their owner.
+    outbox: mpsc::UnboundedSender<(usize, String)>,
+}
+
+impl ClusterNode {
+    /// Join the cluster and start sending links to peers.
+    pub fn start(config: ClusterConfig) -> Arc<Self> {
+        let (outbox, rx) = mpsc::unbounded_channel();
+        let counters = Arc::new(Counters::default());
+
+        crate::utils::spawn_task(
+            "cluster_outbox",
+            run_outbox(config.clone(), counters.clone(), rx),
+        );
+
+        Arc::new(Self {
+            ring: config.ring(),
+            config,
+            counters,
+            forwarded: Default::default(),
+            outbox,
+        })
+    }
+
+    /// The place of the node.
+    pub fn config(&self) -> &ClusterConfig {
+        &self.config
+    }
+
+    /// Whether the host of `link` belongs to this node. Links without a
+    /// host are kept local.
+    pub fn owns(&self, link: &str) -> bool {
+        self.remote_owner(link).is_none()
+    }
+
+    /// The peer owning the host of `link`, `None` when this node owns it.
+    fn remote_owner(&self, link: &str) -> Option<usize> {
+        let owner = self.ring.owner(&link_host(link)?)?;
+        (owner != self.config.node).then_some(owner)
+    }
+
+    /// Hand `link` to its owner when another node owns its host. Returns
+    /// whether the link was routed away and must not be crawled here. Call
+    /// it where links are queued, not from allow checks.
+    pub fn route(&self, link: &CaseInsensitiveString) -> bool {
+        let Some(owner) = self.remote_owner(link.inner()) else {
+            return false;
+        };
+
+        let first = self
+            .forwarded
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(link.clone());
+
+        if first {
+            self.counters.pending.fetch_add(1, Ordering::SeqCst);
+            self.counters.sent.fetch_add(1, Ordering::SeqCst);
+            if self.outbox.send((owner, link.inner().to_string())).is_err() {
+                self.counters.pending.fetch_sub(1, Ordering::SeqCst);
+                self.counters.dropped.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        true
+    }
+
+    /// Hand the links of hosts owned by peers to their owner, keeping the
+    /// local ones in `links`.
+    pub fn forward(&self, links: &mut HashSet<CaseInsensitiveString>) {
+        links.retain(|link| !self.route(link));
+    }
+
+    /// Count links received from a peer. Call it once the links are queued
+    /// so the node no longer reports itself idle.
+    pub fn record_received(&self, links: usize) {
+        self.counters
+            .received
+            .fetch_add(links as u64, Ordering::SeqCst);
+    }
+
+    /// The status of the node. `idle` is whether the caller has nothing left
+    /// to crawl; the node adds whether links are still waiting to be sent.
+    pub fn status(&self, idle: bool, visited: usize) -> NodeStatus {
+        NodeStatus {
+            idle: idle && self.counters.pending.load(Ordering::SeqCst) == 0,
+            sent: self.counters.sent.load(Ordering::SeqCst),
+            dropped: self.counters.dropped.load(Ordering::SeqCst),
+            received: self.counters.received.load(Ordering::SeqCst),
+            visited: visited as u64,
+        }
+    }
+}
+
+/// Batch outgoing links per owner and post them until the node is dropped.
+async fn run_outbox(
+    config: ClusterConfig,
+    counters: Arc<Counters>,
+    mut rx: mpsc::UnboundedReceiver<(usize, String)>,
+) {
+    let client = reqwest::Client::new();
+    let mut batches: Vec<Vec<String>> = vec![Vec::new(); config.nodes.len()];
+    let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+
+    loop {
+        tokio::select! {
+            message = rx.recv() => match message {
+                Some((owner, link)) => {
+                    if let Some(batch) = batches.get_mut(owner) {
+                        batch.push(link);
+                        if batch.len() >= MAX_BATCH_LINKS {
+                            let links = std::mem::take(batch);
+                            deliver(&client, &config, &counters, owner, links).await;
+                        }
+                    } else {
+                        counters.pending.fetch_sub(1, Ordering::SeqCst);
+                        counters.dropped.fetch_add(1, Ordering::SeqCst);
+                    }
+                }
+                None => break,
+            },
+            _ = interval.tick() => {
+                for (owner, batch) in batches.iter_mut().enumerate() {
+                    if !batch.is_empty() {
+                        let links = std::mem::take(batch);
+                        deliver(&client, &config, &counters, owner, links).await;
+                    }
+                }
+            }
+        }
+    }
+
+    for (owner, links) in batches.into_iter().enumerate() {
+        if !links.is_empty() {
+            deliver(&client, &config, &counters, owner, links).await;
+        }
+    }
+}
+
+/// Post a batch to its owner, retrying before giving the links up.
+async fn deliver(
+    client: &reqwest::Client,
+    config: &ClusterConfig,
+    counters: &Counters,
+    owner: usize,
+    links: Vec<String>,
+) {
+    let count = links.len() as u64;
+    let delivered = match config.endpoint(owner, "/links") {
+        Some(url) => {
+            let body = LinkBatch {
+                crawl_id: config.crawl_id.clone(),
+                links,
+            }
+            .to_bytes();
+            let mut delivered = false;
+
+            for attempt in 1..=DELIVERY_ATTEMPTS {
+                let mut request = client
+                    .post(&url)
+                    .header(reqwest::header::CONTENT_TYPE, FLEXBUFFERS_CONTENT_TYPE)
+                    .body(body.clone());
+                if let Some(token) = config.token.as_deref() {
+                    request = request.bearer_auth(token);
+                }
+                let response = request.send().await;
+
+                match response.and_then(|r| r.error_for_status()) {
+                    Ok(_) => {
+                        delivered = true;
+                        break;
+                    }
+                    Err(e) if attempt == DELIVERY_ATTEMPTS => {
+                        log::warn!("failed to send {count} links to {url}: {e}");
+                    }
+                    Err(_) => {
+                        tokio::time::sleep(Duration::from_millis(250 * attempt as u64)).await;
+                    }
+                }
+            }
+
+            delivered
+        }
+        _ => false,
+    };
+
+    if !delivered {
+        counters.dropped.fetch_add(count, Ordering::SeqCst);
+    }
+    counters.pending.fetch_sub(count, Ordering::SeqCst);
+}
+
+/// An error while coordinating a cluster crawl.
+#[derive(Debug)]
+pub enum ClusterError {
+    /// A node could not be reached or answered with an error.
+    Request(reqwest::Error),
+    /// A node answered with a body that could not be decoded.
+    Decode(String),
+    /// Nodes gave up delivering links, leaving their hosts uncrawled.
+    Dropped(u64),
+}
+
+impl std::fmt::Display for ClusterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Request(e) => write!(f, "cluster request failed: {e}"),
+            Self::Decode(url) => write!(f, "invalid cluster response from {url}"),
+            Self::Dropped(links) => write!(f, "cluster nodes dropped {links} links"),
+        }
+    }
+}
+
+impl std::error::Error for ClusterError {}
+
+impl From<reqwest::Error> for ClusterError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Request(e)
+    }
+}
+
+/// Whether the cluster finished, given the statuses of every node: all idle
+/// with every delivered link received.
+pub fn is_quiescent(statuses: &[NodeStatus]) -> bool {
+    let sent: u64 = statuses
+        .iter()
+        .map(|s| s.sent.saturating_sub(s.dropped))
+        .sum();
+    let received: u64 = statuses.iter().map(|s| s.received).sum();
+
+    statuses.iter().all(|s| s.idle) && sent == received
+}
+
+/// Drives a crawl across the nodes of a cluster.
+#[derive(Debug, Clone)]
+pub struct ClusterCoordinator {
+    /// Base urls of the nodes.
+    nodes: Vec<String>,
+    /// Points each node takes on the ring.
+    virtual_nodes: usize,
+    /// How often node statuses are polled.
+    poll_interval: Duration,
+    /// Client used to reach the nodes.
+    client: reqwest::Client,
+    /// Bearer token sent to the nodes.
+    token: Option<String>,
+}
+
+impl ClusterCoordinator {
+    /// A coordinator for the nodes at the base urls.
+    pub fn new(nodes: Vec<String>) -> Self {
+        Self {
+            nodes,
+            virtual_nodes: DEFAULT_VIRTUAL_NODES,
+            poll_interval: Duration::from_secs(1),
+            client: reqwest::Client::new(),
+            token: None,
+        }
+    }
+
+    /// Set the bearer token sent to the nodes.
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// A request to a node, with the bearer token when set.
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match self.token.as_deref() {
+            Some(token) => request.bearer_auth(token),
+            _ => request,
+        }
+    }
+
+    /// Set the points each node takes on the ring.
+    pub fn with_virtual_nodes(mut self, virtual_nodes: usize) -> Self {
+        self.virtual_nodes = virtual_nodes;
+        self
+    }
+
+    /// Set how often node statuses are polled.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// The config of node `node` for the crawl.
+    fn node_config(&self, crawl_id: &str, node: usize) -> ClusterConfig {
+        ClusterConfig::new(crawl_id, self.nodes.clone(), node)
+            .with_virtual_nodes(self.virtual_nodes)
+    }
+
+    /// Crawl `url` across the cluster and return the merged visited set.
+    /// `configuration` is sent as the [`ClusterStart`] settings of every
+    /// node. The nodes are stopped once the crawl ends or fails.
+    pub async fn crawl<C: serde::Serialize>(
+        &self,
+        crawl_id: &str,
+        url: &str,
+        configuration: &C,
+    ) -> Result<HashSet<CaseInsensitiveString>, ClusterError> {
+        let result = self.run(crawl_id, url, configuration).await;
+        self.stop(crawl_id).await;
+        result
+    }
+
+    /// Start, await and collect the crawl.
+    async fn run<C: serde::Serialize>(
+        &self,
+        crawl_id: &str,
+        url: &str,
+        configuration: &C,
+    ) -> Result<HashSet<CaseInsensitiveString>, ClusterError> {
+        // Only the owner of the seed crawls right away, so starting it last
+        // registers the crawl on every peer before links flow.
+        let ring = HashRing::new(&self.nodes, self.virtual_nodes);
+        let seed_owner = link_host(url).and_then(|host| ring.owner(&host));
+        let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+        order.sort_by_key(|node| Some(*node) == seed_owner);
+
+        for node in order {
+            let base = &self.nodes[node];
+            let start = ClusterStart {
+                url: url.to_string(),
+                configuration,
+                cluster: self.node_config(crawl_id, node),
+            };
+            let body = serde_json::to_vec(&start)
+                .map_err(|_| ClusterError::Decode(endpoint(base, crawl_id, "")))?;
+
+            self.request(reqwest::Method::POST, &endpoint(base, crawl_id, ""))
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body)
+                .send()
+                .await?
+                .error_for_status()?;
+        }
+
+        let mut previous: Option<Vec<NodeStatus>> = None;
+
+        loop {
+            tokio::time::sleep(self.poll_interval).await;
+
+            let mut statuses = Vec::with_capacity(self.nodes.len());
+            for base in self.nodes.iter() {
+                statuses.push(self.status(base, crawl_id).await?);
+            }
+
+            let dropped: u64 = statuses.iter().map(|s| s.dropped).sum();
+            if dropped > 0 {
+                return Err(ClusterError::Dropped(dropped));
+            }
+
+            // A quiescent snapshot can race a link in flight, so the crawl
+            // ends only when two consecutive snapshots agree.
+            if is_quiescent(&statuses) {
+                if previous.as_ref() == Some(&statuses) {
+                    break;
+                }
+                previous = Some(statuses);
+            } else {
+                previous = None;
+            }
+        }
+
+        let mut visited = HashSet::new();
+        for base in self.nodes.iter() {
+            let url = endpoint(base, crawl_id, "/visited");
+            let bytes = self
+                .request(reqwest::Method::GET, &url)
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await?;
+            let batch = LinkBatch::from_bytes(&bytes).ok_or(ClusterError::Decode(url))?;
+            visited.extend(batch.links.into_iter().map(CaseInsensitiveString::from));
+        }
+
+        Ok(visited)
+    }
+
+    /// The status of a node.
+    async fn status(&self, base: &str, crawl_id: &str) -> Result<NodeStatus, ClusterError> {
+        let url = endpoint(base, crawl_id, "");
+        let bytes = self
+            .request(reqwest::Method::GET, &url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        serde_json::from_slice(&bytes).map_err(|_| ClusterError::Decode(url))
+    }
+
+    /// Stop the crawl on every node, ignoring nodes that already dropped it.
+    async fn stop(&self, crawl_id: &str) {
+        for base in self.nodes.iter() {
+            let url = endpoint(base, crawl_id, "");
+            if let Err(e) = self.request(reqwest::Method::DELETE, &url).send().await {
+                log::warn!("failed to stop {url}: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes() -> Vec<String> {
+        vec![
+            "http://10.0.0.1:3033".into(),
+            "http://10.0.0.2:3033".into(),
+            "http://10.0.0.3:3033".into(),
+        ]
+    }
+
+    #[test]
+    fn test_ring_is_consistent() {
+        let ring = HashRing::new(&nodes(), DEFAULT_VIRTUAL_NODES);
+        let hosts: Vec<String> = (0..300).map(|i| format!("host{i}.example.com")).collect();
+
+        let mut counts = [0usize; 3];
+        for host in hosts.iter() {
+            let owner = ring.owner(host).unwrap();
+            assert_eq!(ring.owner(host), Some(owner));
+            counts[owner] += 1;
+        }
+        assert!(counts.iter().all(|c| *c > 50), "{counts:?}");
+
+        // Removing a node only moves the hosts it owned.
+        let smaller = HashRing::new(&nodes()[..2], DEFAULT_VIRTUAL_NODES);
+        for host in hosts.iter() {
+            let owner = ring.owner(host).unwrap();
+            if owner < 2 {
+                assert_eq!(smaller.owner(host), Some(owner));
+            }
+        }
+
+        assert_eq!(HashRing::new(&[], 8).owner("example.com"), None);
+    }
+
+    #[test]
+    fn test_link_batch_roundtrip() {
+        let batch = LinkBatch {
+            crawl_id: "crawl".into(),
+            links: vec![
+                "https://example.com/a".into(),
+                "https://example.org/".into(),
+            ],
+        };
+        assert_eq!(LinkBatch::from_bytes(&batch.to_bytes()), Some(batch));
+        assert_eq!(LinkBatch::from_bytes(b"not flexbuffers"), None);
+    }
+
+    #[tokio::test]
+    async fn test_route_forwards_remote_hosts_once() {
+        let config = ClusterConfig::new(
+            "crawl",
+            vec!["http://127.0.0.1:1".into(), "http://127.0.0.1:2".into()],
+            0,
+        );
+        let ring = config.ring();
+        let host = (0..)
+            .map(|i| format!("host{i}.example.com"))
+            .find(|h| ring.owner(h) == Some(1))
+            .unwrap();
+
+        let node = ClusterNode::start(config);
+        let remote = CaseInsensitiveString::from(format!("https://{host}/"));
+
+        assert!(node.route(&remote));
+        assert!(node.route(&remote));
+        assert_eq!(node.status(true, 0).sent, 1);
+        assert!(!node.owns(remote.inner()));
+        assert!(!node.route(&CaseInsensitiveString::from("not a url")));
+
+        let local = CaseInsensitiveString::from("not a url");
+        let mut links: HashSet<CaseInsensitiveString> = [remote, local.clone()].into();
+        node.forward(&mut links);
+        assert_eq!(links, [local].into());
+        assert_eq!(node.status(true, 0).sent, 1);
+    }
+
+    #[tokio::test]
+    async fn test_crawl_fails_on_dropped_links() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Every node endpoint answers with a status that dropped a link.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let authorized = tokio::spawn(async move {
+            let mut authorized = true;
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 64 * 1024];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase();
+                authorized &= request.contains("authorization: bearer secret");
+                let body = r#"{"idle":true,"sent":1,"dropped":1,"received":0,"visited":0}"#;
+                let _ = stream
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                            body.len()
+                        )
+                        .as_bytes(),
+                    )
+                    .await;
+                if request.starts_with("delete") {
+                    break;
+                }
+            }
+            authorized
+        });
+
+        let result = ClusterCoordinator::new(vec![format!("http://{addr}")])
+            .with_token(Some("secret".into()))
+            .with_poll_interval(Duration::from_millis(10))
+            .crawl("crawl", "https://example.com", &Configuration::default())
+            .await;
+
+        assert!(
+            matches!(result, Err(ClusterError::Dropped(1))),
+            "{result:?}"
+        );
+        assert!(authorized.await.unwrap());
+    }
+
+    #[test]
+    fn test_quiescence() {
+        let idle = |sent, dropped, received| NodeStatus {
+            idle: true,
+            sent,
+            dropped,
+            received,
+            visited: 0,
+        };
+
+        assert!(is_quiescent(&[idle(2, 0, 1), idle(1, 0, 2)]));
+        assert!(is_quiescent(&[idle(3, 1, 1), idle(1, 0, 2)]));
+        assert!(!is_quiescent(&[idle(2, 0, 0), idle(0, 0, 1)]));
+        assert!(!is_quiescent(&[
+            idle(0, 0, 0),
+            NodeStatus {
+                idle: false,
+                ..Default::default()
+            }
+        ]));
+    }
+}
@@ -0,0 +1,500 @@
+//! RSS 2.0, Atom and JSON Feed parsing and feed-driven crawl seeding.
+//!
+//! Pages expose the feeds they advertise through `<link rel="alternate">` as
+//! [`RichMetadata::feeds`](crate::page::RichMetadata::feeds). With a
+//! [`FeedConfig`] set, the crawl fetches the configured feeds — and, with
+//! [`FeedConfig::with_discover`], the feeds the start page links to, read
+//! from the start page the crawl already fetched — and seeds the queue with
+//! the item URLs, keeping each [`FeedItem`] and its published date.
+//!
+//! Reference: <https://www.rssboard.org/rss-specification>,
+//! <https://www.rfc-editor.org/rfc/rfc4287>, <https://www.jsonfeed.org/version/1.1/>
+
+pub use crate::utils::rich_metadata::{FeedKind, FeedLink};
+
+use crate::page::Page;
+use std::time::SystemTime;
+use url::Url;
+
+/// Largest feed body read, larger feeds are skipped.
+pub const MAX_FEED_BYTES: usize = 10 * 1024 * 1024;
+
+/// An item read from a feed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeedItem {
+    /// The absolute item URL.
+    pub url: String,
+    /// The item title.
+    pub title: Option<String>,
+    /// When the item was published, or last updated when no publish date is given.
+    pub published: Option<SystemTime>,
+    /// The URL of the feed the item was read from.
+    pub feed: String,
+}
+
+/// A parsed feed document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feed {
+    /// The feed format.
+    pub kind: FeedKind,
+    /// The feed title.
+    pub title: Option<String>,
+    /// The items in document order. Items without a URL are skipped.
+    pub items: Vec<FeedItem>,
+}
+
+/// Feed-driven crawl settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeedConfig {
+    /// Feed URLs to read, absolute or relative to the crawl URL.
+    pub urls: Vec<String>,
+    /// Also read the feeds the start page links to.
+    pub discover: bool,
+    /// Skip items published before this time.
+    pub published_since: Option<SystemTime>,
+    /// Most items to take from each feed.
+    pub max_items: Option<usize>,
+}
+
+impl FeedConfig {
+    /// Read the given feed URLs.
+    pub fn new<I, S>(urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            urls: urls.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Also read the feeds the start page links to.
+    pub fn with_discover(mut self, discover: bool) -> Self {
+        self.discover = discover;
+        self
+    }
+
+    /// Skip items published before `since`. Items without a date are kept.
+    pub fn with_published_since(mut self, since: Option<SystemTime>) -> Self {
+        self.published_since = since;
+        self
+    }
+
+    /// Take at most `max_items` items from each feed.
+    pub fn with_max_items(mut self, max_items: Option<usize>) -> Self {
+        self.max_items = max_items;
+        self
+    }
+
+    /// The items of one feed to seed the crawl with.
+    pub fn select(&self, items: Vec<FeedItem>) -> impl Iterator<Item = FeedItem> + '_ {
+        items
+            .into_iter()
+            .filter(|item| {
+                !matches!((self.published_since, item.published), (Some(since), Some(published)) if published < since)
+            })
+            .take(self.max_items.unwrap_or(usize::MAX))
+    }
+}
+
+/// The absolute URLs of the feeds a page links to.
+pub fn discovered_feeds(page: &Page) -> Vec<Url> {
+    let feeds = match page
+        .get_metadata()
+        .as_ref()
+        .and_then(|metadata| metadata.rich.as_ref())
+    {
+        Some(rich) if !rich.feeds.is_empty() => &rich.feeds,
+        _ => return Vec::new(),
+    };
+    let base = match Url::parse(page.get_url()) {
+        Ok(base) => base,
+        _ => return Vec::new(),
+    };
+
+    feeds
+        .iter()
+        .filter_map(|feed| base.join(&feed.href).ok())
+        .collect()
+}
+
+/// Read a feed response body, giving up once it grows past [`MAX_FEED_BYTES`].
+pub(crate) async fn read_feed_body(mut response: reqwest::Response) -> Option<Vec<u8>> {
+    let url = response.url().to_string();
+
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_FEED_BYTES as u64)
+    {
+        log::info!("feed {url} skipped: larger than {MAX_FEED_BYTES} bytes");
+        return None;
+    }
+
+    let mut body = Vec::new();
+
+    loop {
+        match response.chunk().await {
+            Ok(Some(chunk)) => {
+                if body.len() + chunk.len() > MAX_FEED_BYTES {
+                    log::info!("feed {url} skipped: larger than {MAX_FEED_BYTES} bytes");
+                    return None;
+                }
+                body.extend_from_slice(&chunk);
+            }
+            Ok(None) => return Some(body),
+            Err(err) => {
+                log::info!("feed read error: {:?}", err.to_string());
+                return None;
+            }
+        }
+    }
+}
+
+/// Parse an RSS 2.0 (or RSS 1.0), Atom or JSON Feed document fetched from
+/// `feed_url`. Relative item links are resolved against `feed_url`. Returns
+/// `None` when the body is not a feed.
+pub fn parse_feed(body: &[u8], feed_url: &Url) -> Option<Feed> {
+    let body = body.strip_prefix(b"\xef\xbb\xbf").unwrap_or(body);
+    let start = body.iter().position(|b| !b.is_ascii_whitespace())?;
+    let body = &body[start..];
+
+    if body.starts_with(b"{") {
+        parse_json_feed(body, feed_url)
+    } else {
+        parse_xml_feed(body, feed_url)
+    }
+}
+
+/// Parse an RFC 3339 (Atom, JSON Feed, `dc:date`) or RFC 2822 (RSS) date.
+fn parse_date(value: &str) -> Option<SystemTime> {
+    let value = value.trim();
+    chrono::DateTime::parse_from_rfc3339(value)
+        .or_else(|_| chrono::DateTime::parse_from_rfc2822(value))
+        .ok()
+        .map(SystemTime::from)
+}
+
+/// Build an item from its raw parts.
+fn feed_item(
+    feed_url: &Url,
+    link: Option<&str>,
+    title: Option<String>,
+    published: Option<&str>,
+) -> Option<FeedItem> {
+    let url = feed_url.join(link?.trim()).ok()?;
+
+    Some(FeedItem {
+        url: url.into(),
+        title: title
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty()),
+        published: published.and_then(parse_date),
+        feed: feed_url.to_string(),
+    })
+}
+
+/// Parse a JSON Feed document.
+fn parse_json_feed(body: &[u8], feed_url: &Url) -> Option<Feed> {
+    let value: serde_json::Value = serde_json::from_slice(body).ok()?;
+
+    if !value
+        .get("version")?
+        .as_str()?
+        .starts_with("https://jsonfeed.org/version/")
+    {
+        return None;
+    }
+
+    fn str_field<'a>(value: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+        value.get(key).and_then(|v| v.as_str())
+    }
+
+    let items = value
+        .get("items")
+        .and_then(|items| items.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    feed_item(
+                        feed_url,
+                        str_field(item, "url").or_else(|| str_field(item, "external_url")),
+                        str_field(item, "title").map(Into::into),
+                        str_field(item, "date_published")
+                            .or_else(|| str_field(item, "date_modified")),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(Feed {
+        kind: FeedKind::JsonFeed,
+        title: str_field(&value, "title").map(Into::into),
+        items,
+    })
+}
+
+/// The text fields read from an RSS `<item>` or Atom `<entry>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Title,
+    Link,
+    Guid,
+    Published,
+    Updated,
+}
+
+/// An item or entry being read.
+#[derive(Debug, Default)]
+struct RawItem {
+    title: Option<String>,
+    link: Option<String>,
+    guid: Option<String>,
+    published: Option<String>,
+    updated: Option<String>,
+}
+
+/// Parse an RSS or Atom document.
+fn parse_xml_feed(body: &[u8], feed_url: &Url) -> Option<Feed> {
+    use quick_xml::events::Event;
+    use quick_xml::XmlVersion;
+
+    // Values are trimmed once complete: trimming each text event would
+    // drop the spaces around entity references such as `&amp;`.
+    let mut reader = quick_xml::Reader::from_reader(body);
+
+    let mut buf = Vec::new();
+    let mut kind = None;
+    let mut title = None;
+    let mut items = Vec::new();
+    let mut item: Option<RawItem> = None;
+    let mut field: Option<Field> = None;
+    let mut text = String::new();
+
+    loop {
+        let event = match reader.read_event_into(&mut buf) {
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(event) => event,
+        };
+
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let is_empty = matches!(event, Event::Empty(_));
+                let name = e.local_name();
+
+                match (kind, name.as_ref()) {
+                    (None, b"rss" | b"RDF") => kind = Some(FeedKind::Rss),
+                    (None, b"feed") => kind = Some(FeedKind::Atom),
+                    (None, _) => return None,
+                    (Some(FeedKind::Rss), b"item") | (Some(FeedKind::Atom), b"entry") => {
+                        item = Some(RawItem::default());
+                    }
+                    (Some(FeedKind::Atom), b"link") => {
+                        let rel = e.try_get_attribute("rel").ok().flatten().and_then(|a| {
+                            a.normalized_value(XmlVersion::Implicit1_0)
+                                .ok()
+                                .map(|v| v.into_owned())
+                        });
+                        let href = e.try_get_attribute("href").ok().flatten().and_then(|a| {
+                            a.normalized_value(XmlVersion::Implicit1_0)
+                                .ok()
+                                .map(|v| v.into_owned())
+                        });
+
+                        if let (Some(item), Some(href)) = (item.as_mut(), href) {
+                            if item.link.is_none()
+                                && rel.as_deref().is_none_or(|rel| rel == "alternate")
+                            {
+                                item.link = Some(href);
+                            }
+                        }
+                    }
+                    (_, name) if !is_empty => {
+                        field = match name {
+                            b"title" => Some(Field::Title),
+                            b"link" if item.is_some() => Some(Field::Link),
+                            b"guid"
+                                if e.try_get_attribute("isPermaLink")
+                                    .ok()
+                                    .flatten()
+                                    .is_none_or(|a| a.value.as_ref() != b"false") =>
+                            {
+                                Some(Field::Guid)
+                            }
+                            b"pubDate" | b"published" | b"issued" | b"date" => {
+                                Some(Field::Published)
+                            }
+                            b"updated" | b"modified" => Some(Field::Updated),
+                            _ => None,
+                        };
+                        text.clear();
+                    }
+                    _ => (),
+                }
+            }
+            Event::Text(e) if field.is_some() => {
+                if let Ok(value) = e.decode() {
+                    text.push_str(&value);
+                }
+            }
+            Event::CData(e) if field.is_some() => {
+                text.push_str(&String::from_utf8_lossy(&e));
+            }
+            Event::GeneralRef(e) if field.is_some() => {
+                if let Ok(name) = e.decode() {
+                    let raw = format!("&{name};");
+                    match quick_xml::escape::unescape(&raw) {
+                        Ok(value) => text.push_str(&value),
+                        _ => text.push_str(&raw),
+                    }
+                }
+            }
+            Event::End(e) => {
+                let name = e.local_name();
+
+                if matches!(name.as_ref(), b"item" | b"entry") {
+                    if let Some(raw) = item.take() {
+                        if let Some(parsed) = feed_item(
+                            feed_url,
+                            raw.link.as_deref().or(raw.guid.as_deref()),
+                            raw.title,
+                            raw.published.as_deref().or(raw.updated.as_deref()),
+                        ) {
+                            items.push(parsed);
+                        }
+                    }
+                } else if let Some(current) = field.take() {
+                    let value = std::mem::take(&mut text);
+
+                    match item.as_mut() {
+                        Some(item) => {
+                            let slot = match current {
+                                Field::Title => &mut item.title,
+                                Field::Link => &mut item.link,
+                                Field::Guid => &mut item.guid,
+                                Field::Published => &mut item.published,
+                                Field::Updated => &mut item.updated,
+                            };
+                            if slot.is_none() {
+                                *slot = Some(value);
+                            }
+                        }
+                        _ if current == Field::Title && title.is_none() => {
+                            title = Some(value.trim().to_string());
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        buf.clear();
+    }
+
+    Some(Feed {
+        kind: kind?,
+        title: title.filter(|t| !t.is_empty()),
+        items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn feed_url() -> Url {
+        Url::parse("https://example.com/blog/feed.xml").unwrap()
+    }
+
+    #[test]
+    fn test_parse_rss() {
+        let body = br#"<?xml version="1.0"?>
+            <rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom"><channel>
+            <title>Blog &amp; News</title>
+            <atom:link href="https://example.com/blog/feed.xml" rel="self" type="application/rss+xml"/>
+            <item><title><![CDATA[First <post>]]></title><link>/blog/first</link>
+                <pubDate>Tue, 14 Nov 2023 22:13:20 GMT</pubDate></item>
+            <item><title>Second</title><guid>https://example.com/blog/second</guid></item>
+            <item><title>No link</title><guid isPermaLink="false">abc-123</guid></item>
+            </channel></rss>"#;
+
+        let feed = parse_feed(body, &feed_url()).unwrap();
+        assert_eq!(feed.kind, FeedKind::Rss);
+        assert_eq!(feed.title.as_deref(), Some("Blog & News"));
+        assert_eq!(feed.items.len(), 2);
+        assert_eq!(feed.items[0].url, "https://example.com/blog/first");
+        assert_eq!(feed.items[0].title.as_deref(), Some("First <post>"));
+        assert_eq!(
+            feed.items[0].published,
+            Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        assert_eq!(feed.items[1].url, "https://example.com/blog/second");
+        assert_eq!(feed.items[1].published, None);
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let body = br#"<?xml version="1.0" encoding="utf-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom"><title>Atom</title>
+            <link href="https://example.com/" />
+            <entry><title type="html">Entry</title>
+                <link rel="edit" href="/edit/1"/><link href="entries/1"/>
+                <updated>2023-11-14T22:13:20Z</updated></entry>
+            </feed>"#;
+
+        let feed = parse_feed(body, &feed_url()).unwrap();
+        assert_eq!(feed.kind, FeedKind::Atom);
+        assert_eq!(feed.title.as_deref(), Some("Atom"));
+        assert_eq!(feed.items.len(), 1);
+        assert_eq!(feed.items[0].url, "https://example.com/blog/entries/1");
+        assert_eq!(
+            feed.items[0].published,
+            Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+    }
+
+    #[test]
+    fn test_parse_json_feed() {
+        let body = br#"{"version":"https://jsonfeed.org/version/1.1","title":"JSON",
+            "items":[{"id":"1","url":"https://example.com/a","title":"A","date_published":"2023-11-14T22:13:20+00:00"},
+                     {"id":"2","external_url":"/b"},{"id":"3"}]}"#;
+
+        let feed = parse_feed(body, &feed_url()).unwrap();
+        assert_eq!(feed.kind, FeedKind::JsonFeed);
+        assert_eq!(feed.items.len(), 2);
+        assert_eq!(feed.items[1].url, "https://example.com/b");
+        assert!(parse_feed(br#"{"items":[]}"#, &feed_url()).is_none());
+        assert!(parse_feed(b"<!doctype html><html></html>", &feed_url()).is_none());
+    }
+
+    #[test]
+    fn test_feed_config_select() {
+        let since = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let item = |secs: Option<u64>| FeedItem {
+            url: "https://example.com/".into(),
+            title: None,
+            published: secs.map(|s| UNIX_EPOCH + Duration::from_secs(s)),
+            feed: "https://example.com/feed.xml".into(),
+        };
+        let config = FeedConfig::new(["/feed.xml"])
+            .with_published_since(Some(since))
+            .with_max_items(Some(2));
+
+        let selected: Vec<_> = config
+            .select(vec![
+                item(Some(1)),
+                item(None),
+                item(Some(1_800_000_000)),
+                item(Some(1_900_000_000)),
+            ])
+            .collect();
+        assert_eq!(selected.len(), 2);
+        assert_eq!(selected[0].published, None);
+    }
+}
@@ -70,6 +70,18 @@ impl UrlFrontier {
         true
     }
 
+    /// Record a URL as visited without queuing it. Returns `false` if the
+    /// URL was already visited/enqueued.
+    pub fn mark_visited(&mut self, url: &str) -> bool {
+        self.visited.insert(CompactString::new(url))
+    }
+
+    /// Whether the URL was ever enqueued or marked visited.
+    #[inline]
+    pub fn contains(&self, url: &str) -> bool {
+        self.visited.contains(url)
+    }
+
     /// Pop the highest-priority URL.
     ///
     /// When `round_robin` is enabled, this will skip URLs from the same domain
@@ -142,6 +154,36 @@ impl UrlFrontier {
     pub fn is_empty(&self) -> bool {
         self.heap.is_empty()
     }
+
+    /// Whether the frontier uses domain round-robin.
+    #[inline]
+    pub fn round_robin(&self) -> bool {
+        self.round_robin
+    }
+
+    /// Snapshot the URLs not yet popped, in no particular order.
+    pub fn entries(&self) -> Vec<ScoredUrl> {
+        self.heap.iter().cloned().collect()
+    }
+
+    /// Snapshot the URLs popped or marked visited, in no particular order.
+    pub fn visited(&self) -> Vec<CaseInsensitiveString> {
+        let queued: HashSet<&str> = self.heap.iter().map(|e| e.url.inner().as_str()).collect();
+        self.visited
+            .iter()
+            .filter(|url| !queued.contains(url.as_str()))
+            .map(|url| url.as_str().into())
+            .collect()
+    }
+
+    /// Rebuild a frontier from a previous [`entries`](Self::entries) snapshot.
+    pub fn from_entries(round_robin: bool, entries: impl IntoIterator<Item = ScoredUrl>) -> Self {
+        let mut frontier = Self::new(round_robin);
+        for entry in entries {
+            frontier.push(entry.url, entry.priority);
+        }
+        frontier
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -184,6 +226,22 @@ pub fn score_url(url: &str, depth: u32) -> i32 {
     score.clamp(0, 2000)
 }
 
+/// Score a discovered link, using its path depth as the crawl depth.
+pub fn score_link(url: &str) -> i32 {
+    let depth = url_path(url).split('/').filter(|s| !s.is_empty()).count();
+    score_url(url, depth as u32)
+}
+
+/// Score a URL listed in a sitemap.
+///
+/// [`score_url`] shifted by the sitemap `<priority>`: the protocol default
+/// `0.5` leaves the score unchanged and every `0.1` above or below moves it
+/// by 60, so a `1.0` entry outranks an unprioritized page one level up.
+pub fn score_sitemap_url(url: &str, depth: u32, priority: Option<f32>) -> i32 {
+    let bonus = priority.map_or(0, |p| ((p.clamp(0.0, 1.0) - 0.5) * 600.0).round() as i32);
+    score_url(url, depth).saturating_add(bonus).clamp(0, 2000)
+}
+
 /// Extract the domain (host) from a URL string. Returns empty string on parse
 /// failure.
 fn extract_domain(url: &str) -> CompactString {
@@ -241,6 +299,64 @@ fn contains_ignore_ascii_case(haystack: &str, needle: &str) -> bool {
     false
 }
 
+// ---------------------------------------------------------------------------
+// Line records
+// ---------------------------------------------------------------------------
+
+/// Escape backslashes, tabs and line breaks so a url always fits in one
+/// field of a tab separated, line delimited record.
+#[cfg(any(feature = "checkpoint", feature = "frontier_store"))]
+pub(crate) fn escape(value: &str) -> std::borrow::Cow<'_, str> {
+    if !value.contains(['\\', '\t', '\n', '\r']) {
+        return value.into();
+    }
+
+    let mut escaped = String::with_capacity(value.len() + 8);
+
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.into()
+}
+
+/// Reverse [`escape`]. Unknown sequences are kept as written.
+#[cfg(any(feature = "checkpoint", feature = "frontier_store"))]
+pub(crate) fn unescape(value: &str) -> String {
+    if !value.contains('\\') {
+        return value.to_string();
+    }
+
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -257,6 +373,15 @@ mod tests {
         assert_eq!(f.len(), 1);
     }
 
+    #[test]
+    fn mark_visited_blocks_push() {
+        let mut f = UrlFrontier::new(false);
+        assert!(f.mark_visited("https://example.com/seen"));
+        assert!(!f.push(cis("https://example.com/seen"), 100));
+        assert!(f.contains("https://example.com/seen"));
+        assert!(f.is_empty());
+    }
+
     #[test]
     fn pop_highest_priority_first() {
         let mut f = UrlFrontier::new(false);
@@ -320,6 +445,34 @@ mod tests {
         assert_eq!(third, cis("https://a.com/2"));
     }
 
+    #[test]
+    fn entries_round_trip() {
+        let mut f = UrlFrontier::new(true);
+        f.push(cis("https://a.com/1"), 100);
+        f.push(cis("https://b.com/1"), 300);
+        f.push(cis("https://a.com/2"), 200);
+        f.pop();
+
+        let mut restored = UrlFrontier::from_entries(f.round_robin(), f.entries());
+        assert_eq!(restored.len(), 2);
+        assert!(restored.round_robin());
+        assert_eq!(restored.pop().unwrap(), cis("https://a.com/2"));
+        assert_eq!(restored.pop().unwrap(), cis("https://a.com/1"));
+    }
+
+    #[test]
+    fn visited_excludes_queued() {
+        let mut f = UrlFrontier::new(false);
+        f.push(cis("https://a.com/1"), 100);
+        f.push(cis("https://a.com/2"), 200);
+        f.mark_visited("https://a.com/seen");
+        f.pop();
+
+        let mut visited = f.visited();
+        visited.sort_by(|a, b| a.inner().cmp(b.inner()));
+        assert_eq!(visited, [cis("https://a.com/2"), cis("https://a.com/seen")]);
+    }
+
     #[test]
     fn pop_empty_returns_none() {
         let mut f = UrlFrontier::new(false);
@@ -351,4 +504,20 @@ mod tests {
         let s = score_url("https://x.com/legal", 20);
         assert_eq!(s, 0);
     }
+
+    #[test]
+    fn score_sitemap_url_priority() {
+        let base = score_url("https://x.com/a", 0);
+        assert_eq!(score_sitemap_url("https://x.com/a", 0, None), base);
+        assert_eq!(score_sitemap_url("https://x.com/a", 0, Some(0.5)), base);
+        assert_eq!(
+            score_sitemap_url("https://x.com/a", 0, Some(1.0)),
+            base + 300
+        );
+        assert_eq!(
+            score_sitemap_url("https://x.com/a", 0, Some(0.0)),
+            base - 300
+        );
+        assert!(score_sitemap_url("https://x.com/a", 1, Some(1.0)) > base);
+    }
 }
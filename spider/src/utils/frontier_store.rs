@@ -0,0 +1,1159 @@
+//! Pluggable storage for the crawl frontier.
+//!
+//! Feature-gated behind `frontier_store`. A [`FrontierStore`] keeps the URLs
+//! waiting to be crawled outside of the crawl loop. With one installed via
+//! [`Website::with_frontier_store`](crate::website::Website::with_frontier_store),
+//! the crawl keeps at most [`MEMORY_LINKS`] discovered links in memory,
+//! spills the rest into the store scored with
+//! [`score_link`](crate::utils::frontier::score_link), and refills from the
+//! store, highest priority first, as the in-memory batch drains.
+//!
+//! Three stores are provided:
+//!
+//! * [`MemoryFrontierStore`] wraps a [`UrlFrontier`].
+//! * [`SqliteFrontierStore`] keeps the queue in a SQLite table with the
+//!   `disk` feature, so it survives restarts.
+//! * [`FileFrontierStore`] is an embedded log-structured store: pushes land
+//!   in a memtable that is flushed to sorted run files, and pops merge the
+//!   runs. The seen URLs are logged to disk with only a fingerprint and log
+//!   offset per URL kept in memory.
+
+use crate::utils::frontier::{escape, unescape, ScoredUrl, UrlFrontier};
+use case_insensitive_string::CaseInsensitiveString;
+use hashbrown::hash_map::Entry;
+use hashbrown::{HashMap, HashSet};
+use std::collections::BinaryHeap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Discovered links the crawl keeps in memory before spilling to the store.
+pub const MEMORY_LINKS: usize = 10_000;
+
+/// Storage for URLs waiting to be crawled.
+///
+/// Every URL is queued at most once: pushing a URL that was queued or
+/// marked visited before is ignored.
+#[async_trait::async_trait]
+pub trait FrontierStore: Send + Sync + 'static {
+    /// Queue `url` with `priority`, higher popped first. Returns `false`
+    /// when the URL was seen before.
+    async fn push(&self, url: &str, priority: i32) -> bool;
+
+    /// Pop the highest priority URL.
+    async fn pop(&self) -> Option<CaseInsensitiveString>;
+
+    /// Record `url` as visited so it is never queued.
+    async fn mark_visited(&self, url: &str);
+
+    /// Whether `url` was queued or marked visited.
+    async fn contains(&self, url: &str) -> bool;
+
+    /// Number of URLs queued and not popped yet.
+    async fn len(&self) -> usize;
+
+    /// Snapshot the URLs queued and not popped yet with their priorities,
+    /// in no particular order. Used to checkpoint the crawl.
+    async fn entries(&self) -> Vec<ScoredUrl>;
+
+    /// Snapshot the URLs popped or marked visited, in no particular order.
+    /// Used to checkpoint the crawl.
+    async fn visited(&self) -> Vec<CaseInsensitiveString>;
+
+    /// Whether no URL is queued.
+    async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
+    /// Queue many URLs. Returns how many were new.
+    async fn push_batch(&self, urls: Vec<(String, i32)>) -> usize {
+        let mut pushed = 0;
+        for (url, priority) in urls {
+            if self.push(&url, priority).await {
+                pushed += 1;
+            }
+        }
+        pushed
+    }
+
+    /// Pop up to `max` URLs, highest priority first.
+    async fn pop_batch(&self, max: usize) -> Vec<CaseInsensitiveString> {
+        let mut urls = Vec::new();
+        while urls.len() < max {
+            match self.pop().await {
+                Some(url) => urls.push(url),
+                _ => break,
+            }
+        }
+        urls
+    }
+}
+
+/// A frontier store shared by a [`Website`](crate::website::Website).
+pub type SharedFrontierStore = Arc<dyn FrontierStore>;
+
+/// Lock a mutex, recovering from a poisoned lock.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// An in-memory store backed by a [`UrlFrontier`].
+pub struct MemoryFrontierStore {
+    /// The frontier.
+    frontier: Mutex<UrlFrontier>,
+}
+
+impl Default for MemoryFrontierStore {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl MemoryFrontierStore {
+    /// An empty store. With `round_robin`, consecutive pops prefer different domains.
+    pub fn new(round_robin: bool) -> Self {
+        Self {
+            frontier: Mutex::new(UrlFrontier::new(round_robin)),
+        }
+    }
+
+    /// A store queueing `entries`, e.g. a checkpointed frontier.
+    pub fn from_entries(round_robin: bool, entries: impl IntoIterator<Item = ScoredUrl>) -> Self {
+        Self {
+            frontier: Mutex::new(UrlFrontier::from_entries(round_robin, entries)),
+        }
+    }
+
+    /// Run `f` on the frontier.
+    fn with<R>(&self, f: impl FnOnce(&mut UrlFrontier) -> R) -> R {
+        f(&mut lock(&self.frontier))
+    }
+}
+
+#[async_trait::async_trait]
+impl FrontierStore for MemoryFrontierStore {
+    async fn push(&self, url: &str, priority: i32) -> bool {
+        self.with(|f| f.push(url.into(), priority))
+    }
+
+    async fn pop(&self) -> Option<CaseInsensitiveString> {
+        self.with(|f| f.pop())
+    }
+
+    async fn mark_visited(&self, url: &str) {
+        self.with(|f| f.mark_visited(url));
+    }
+
+    async fn contains(&self, url: &str) -> bool {
+        self.with(|f| f.contains(url))
+    }
+
+    async fn len(&self) -> usize {
+        self.with(|f| f.len())
+    }
+
+    async fn entries(&self) -> Vec<ScoredUrl> {
+        self.with(|f| f.entries())
+    }
+
+    async fn visited(&self) -> Vec<CaseInsensitiveString> {
+        self.with(|f| f.visited())
+    }
+}
+
+#[cfg(feature = "disk")]
+pub use sqlite::SqliteFrontierStore;
+
+#[cfg(feature = "disk")]
+mod sqlite {
+    use super::FrontierStore;
+    use crate::utils::frontier::ScoredUrl;
+    use case_insensitive_string::CaseInsensitiveString;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A store keeping the queue in a SQLite database.
+    ///
+    /// Rows stay after they are popped, marking the URL as seen, so a crawl
+    /// reopening the same database resumes where it stopped.
+    #[derive(Debug)]
+    pub struct SqliteFrontierStore {
+        /// The connection pool.
+        pool: SqlitePool,
+        /// Queued rows, tracked to avoid counting on every call.
+        len: AtomicUsize,
+    }
+
+    impl SqliteFrontierStore {
+        /// Open the database at `path`, creating it when missing.
+        pub async fn open(path: &str) -> Result<Self, sqlx::Error> {
+            let pool = SqlitePool::connect_with(
+                SqliteConnectOptions::new()
+                    .filename(path)
+                    .create_if_missing(true),
+            )
+            .await?;
+
+            sqlx::query(
+                r#"CREATE TABLE IF NOT EXISTS frontier (
+                    url TEXT PRIMARY KEY COLLATE NOCASE,
+                    priority INTEGER NOT NULL,
+                    queued INTEGER NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS idx_frontier_queue ON frontier (queued, priority DESC);"#,
+            )
+            .execute(&pool)
+            .await?;
+
+            let (len,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM frontier WHERE queued = 1")
+                .fetch_one(&pool)
+                .await?;
+
+            Ok(Self {
+                pool,
+                len: AtomicUsize::new(len as usize),
+            })
+        }
+
+        /// Log a failed query.
+        fn log_error(e: sqlx::Error) {
+            log::warn!("frontier store query failed: {e}");
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FrontierStore for SqliteFrontierStore {
+        async fn push(&self, url: &str, priority: i32) -> bool {
+            self.push_batch(vec![(url.to_string(), priority)]).await > 0
+        }
+
+        async fn pop(&self) -> Option<CaseInsensitiveString> {
+            self.pop_batch(1).await.pop()
+        }
+
+        async fn mark_visited(&self, url: &str) {
+            let dequeued =
+                sqlx::query("UPDATE frontier SET queued = 0 WHERE url = ? AND queued = 1")
+                    .bind(url)
+                    .execute(&self.pool)
+                    .await;
+            let inserted = sqlx::query(
+                "INSERT OR IGNORE INTO frontier (url, priority, queued) VALUES (?, 0, 0)",
+            )
+            .bind(url)
+            .execute(&self.pool)
+            .await;
+
+            match dequeued {
+                Ok(result) if result.rows_affected() > 0 => {
+                    self.len.fetch_sub(1, Ordering::SeqCst);
+                }
+                Err(e) => Self::log_error(e),
+                _ => (),
+            }
+            if let Err(e) = inserted {
+                Self::log_error(e);
+            }
+        }
+
+        async fn contains(&self, url: &str) -> bool {
+            match sqlx::query("SELECT 1 FROM frontier WHERE url = ? LIMIT 1")
+                .bind(url)
+                .fetch_optional(&self.pool)
+                .await
+            {
+                Ok(row) => row.is_some(),
+                Err(e) => {
+                    Self::log_error(e);
+                    false
+                }
+            }
+        }
+
+        async fn len(&self) -> usize {
+            self.len.load(Ordering::SeqCst)
+        }
+
+        async fn entries(&self) -> Vec<ScoredUrl> {
+            let rows: Result<Vec<(String, i32)>, _> =
+                sqlx::query_as("SELECT url, priority FROM frontier WHERE queued = 1")
+                    .fetch_all(&self.pool)
+                    .await;
+
+            match rows {
+                Ok(rows) => rows
+                    .into_iter()
+                    .map(|(url, priority)| ScoredUrl {
+                        priority,
+                        url: url.into(),
+                    })
+                    .collect(),
+                Err(e) => {
+                    Self::log_error(e);
+                    Vec::new()
+                }
+            }
+        }
+
+        async fn visited(&self) -> Vec<CaseInsensitiveString> {
+            let rows: Result<Vec<(String,)>, _> =
+                sqlx::query_as("SELECT url FROM frontier WHERE queued = 0")
+                    .fetch_all(&self.pool)
+                    .await;
+
+            match rows {
+                Ok(rows) => rows.into_iter().map(|(url,)| url.into()).collect(),
+                Err(e) => {
+                    Self::log_error(e);
+                    Vec::new()
+                }
+            }
+        }
+
+        async fn push_batch(&self, urls: Vec<(String, i32)>) -> usize {
+            let mut tx = match self.pool.begin().await {
+                Ok(tx) => tx,
+                Err(e) => {
+                    Self::log_error(e);
+                    return 0;
+                }
+            };
+            let mut pushed = 0;
+
+            for (url, priority) in urls {
+                match sqlx::query(
+                    "INSERT OR IGNORE INTO frontier (url, priority, queued) VALUES (?, ?, 1)",
+                )
+                .bind(url)
+                .bind(priority)
+                .execute(&mut *tx)
+                .await
+                {
+                    Ok(result) => pushed += result.rows_affected() as usize,
+                    Err(e) => Self::log_error(e),
+                }
+            }
+
+            match tx.commit().await {
+                Ok(_) => {
+                    self.len.fetch_add(pushed, Ordering::SeqCst);
+                    pushed
+                }
+                Err(e) => {
+                    Self::log_error(e);
+                    0
+                }
+            }
+        }
+
+        async fn pop_batch(&self, max: usize) -> Vec<CaseInsensitiveString> {
+            if max == 0 || self.len.load(Ordering::SeqCst) == 0 {
+                return Vec::new();
+            }
+
+            // RETURNING does not keep the subquery order.
+            let rows: Result<Vec<(String, i32)>, _> = sqlx::query_as(
+                "UPDATE frontier SET queued = 0 WHERE url IN (
+                    SELECT url FROM frontier WHERE queued = 1 ORDER BY priority DESC LIMIT ?
+                 ) RETURNING url, priority",
+            )
+            .bind(max as i64)
+            .fetch_all(&self.pool)
+            .await;
+
+            match rows {
+                Ok(mut rows) => {
+                    rows.sort_by_key(|row| std::cmp::Reverse(row.1));
+                    let _ = self
+                        .len
+                        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |len| {
+                            Some(len.saturating_sub(rows.len()))
+                        });
+                    rows.into_iter().map(|(url, _)| url.into()).collect()
+                }
+                Err(e) => {
+                    Self::log_error(e);
+                    Vec::new()
+                }
+            }
+        }
+    }
+}
+
+/// Memtable entries flushed to a run file at once.
+const DEFAULT_MEMTABLE_LINKS: usize = 50_000;
+
+/// Run files merged into one once there are more.
+const MAX_RUNS: usize = 8;
+
+/// The log of every URL queued or visited.
+const SEEN_FILE: &str = "seen.log";
+
+/// The read positions of the run files.
+const POSITIONS_FILE: &str = "runs.pos";
+
+/// A case-insensitive fingerprint of a URL.
+fn fingerprint(url: &str) -> u64 {
+    spider_agent_types::fnv1a64(url.to_ascii_lowercase().as_bytes())
+}
+
+/// A run file sorted by descending priority, read front to back.
+struct Run {
+    /// The file path.
+    path: PathBuf,
+    /// Reader positioned at `next`.
+    reader: BufReader<File>,
+    /// The next entry of the run.
+    head: Option<ScoredUrl>,
+    /// Offset of the `head` line.
+    offset: u64,
+    /// Offset after the `head` line.
+    next: u64,
+}
+
+impl Run {
+    /// Open a run at `offset` and read the entry there.
+    fn open(path: PathBuf, offset: u64) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(&path)?);
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut run = Self {
+            reader,
+            path,
+            head: None,
+            offset,
+            next: offset,
+        };
+        run.advance()?;
+        Ok(run)
+    }
+
+    /// Read the next entry into `head`.
+    fn advance(&mut self) -> io::Result<()> {
+        let mut line = String::new();
+        self.head = None;
+
+        loop {
+            self.offset = self.next;
+            let read = self.reader.read_line(&mut line)?;
+            if read == 0 {
+                break;
+            }
+            self.next += read as u64;
+            if let Some(entry) = parse_entry(&line) {
+                self.head = Some(entry);
+                break;
+            }
+            line.clear();
+        }
+
+        Ok(())
+    }
+
+    /// The head and every entry after it, read without moving the run.
+    fn remaining(&self) -> io::Result<Vec<ScoredUrl>> {
+        let mut entries: Vec<ScoredUrl> = self.head.iter().cloned().collect();
+
+        if self.head.is_some() {
+            let mut reader = BufReader::new(File::open(&self.path)?);
+            reader.seek(SeekFrom::Start(self.next))?;
+            for line in reader.lines() {
+                entries.extend(parse_entry(&line?));
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Count the head and every entry after it.
+    fn count(&self) -> io::Result<usize> {
+        let mut count = usize::from(self.head.is_some());
+
+        if self.head.is_some() {
+            let mut reader = BufReader::new(File::open(&self.path)?);
+            reader.seek(SeekFrom::Start(self.next))?;
+            for line in reader.lines() {
+                count += usize::from(parse_entry(&line?).is_some());
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Take the head and move to the next entry.
+    fn take(&mut self) -> io::Result<Option<ScoredUrl>> {
+        let head = self.head.take();
+        if head.is_some() {
+            self.advance()?;
+        }
+        Ok(head)
+    }
+}
+
+/// Parse a `priority\turl` run line, the url escaped.
+fn parse_entry(line: &str) -> Option<ScoredUrl> {
+    let (priority, url) = line.trim_end_matches('\n').split_once('\t')?;
+    Some(ScoredUrl {
+        priority: priority.parse().ok()?,
+        url: unescape(url).into(),
+    })
+}
+
+/// Write entries, already in descending order, to a new run file.
+fn write_run(path: &Path, entries: impl Iterator<Item = ScoredUrl>) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for entry in entries {
+        writeln!(writer, "{}\t{}", entry.priority, escape(entry.url.inner()))?;
+    }
+    writer.flush()
+}
+
+/// The URLs queued or visited, appended to a log file.
+///
+/// Memory holds the fingerprint and log offset of each URL. A fingerprint
+/// hit is confirmed against the full URL read back from the log, and the
+/// rare URLs colliding with another URL are kept in memory.
+struct SeenLog {
+    /// The log appended to.
+    writer: BufWriter<File>,
+    /// The log read back on fingerprint hits.
+    reader: File,
+    /// Length of the log including the buffered writes.
+    end: u64,
+    /// Log offset of the first URL with each fingerprint.
+    index: HashMap<u64, u64>,
+    /// URLs sharing a fingerprint with a different URL.
+    collided: HashSet<CaseInsensitiveString>,
+}
+
+impl SeenLog {
+    /// Open the log at `path` and index the URLs in it.
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        let mut seen = Self {
+            writer: BufWriter::new(file.try_clone()?),
+            reader: file,
+            end: 0,
+            index: HashMap::new(),
+            collided: HashSet::new(),
+        };
+
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut line = String::new();
+        loop {
+            let read = reader.read_line(&mut line)?;
+            if read == 0 {
+                break;
+            }
+            let url = unescape(line.trim_end_matches('\n'));
+            if !seen.contains(&url)? {
+                seen.record(url);
+            }
+            seen.end += read as u64;
+            line.clear();
+        }
+
+        Ok(seen)
+    }
+
+    /// The URL logged at `offset`.
+    fn read_at(&mut self, offset: u64) -> io::Result<String> {
+        self.writer.flush()?;
+        let mut reader = BufReader::new(&self.reader);
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        Ok(unescape(line.trim_end_matches('\n')))
+    }
+
+    /// Whether `url` was logged.
+    fn contains(&mut self, url: &str) -> io::Result<bool> {
+        match self.index.get(&fingerprint(url)) {
+            Some(&offset) => Ok(self.read_at(offset)?.eq_ignore_ascii_case(url)
+                || self.collided.contains(&CaseInsensitiveString::from(url))),
+            _ => Ok(false),
+        }
+    }
+
+    /// Index `url`, logged at the end of the log.
+    fn record(&mut self, url: String) {
+        match self.index.entry(fingerprint(&url)) {
+            Entry::Vacant(entry) => {
+                entry.insert(self.end);
+            }
+            Entry::Occupied(_) => {
+                self.collided.insert(url.into());
+            }
+        }
+    }
+
+    /// Log `url`. Returns `false` when it was logged before.
+    fn insert(&mut self, url: &str) -> io::Result<bool> {
+        if self.contains(url)? {
+            return Ok(false);
+        }
+        self.record(url.to_string());
+        let line = escape(url);
+        writeln!(self.writer, "{line}")?;
+        self.end += line.len() as u64 + 1;
+        Ok(true)
+    }
+}
+
+/// The state of a [`FileFrontierStore`].
+struct FileState {
+    /// Directory holding the run files.
+    dir: PathBuf,
+    /// Entries not flushed yet.
+    memtable: BinaryHeap<ScoredUrl>,
+    /// Memtable size that triggers a flush.
+    memtable_links: usize,
+    /// Flushed runs.
+    runs: Vec<Run>,
+    /// Every URL queued or visited.
+    seen: SeenLog,
+    /// Entries queued in the memtable and runs.
+    len: Arc<AtomicUsize>,
+    /// Number of the next run file.
+    next_run: u64,
+}
+
+impl FileState {
+    /// Open the state in `dir`, reloading the runs and the seen URLs left there.
+    fn open(dir: PathBuf) -> io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+
+        let mut positions = HashMap::new();
+        if let Ok(file) = File::open(dir.join(POSITIONS_FILE)) {
+            for line in BufReader::new(file).lines() {
+                if let Some((name, offset)) = line?.split_once('\t') {
+                    if let Ok(offset) = offset.parse::<u64>() {
+                        positions.insert(name.to_string(), offset);
+                    }
+                }
+            }
+        }
+
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "run") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut runs = Vec::new();
+        let mut next_run = 0;
+        let mut len = 0;
+        for path in paths {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if let Some(number) = name.strip_suffix(".run").and_then(|n| n.parse().ok()) {
+                next_run = next_run.max(number);
+            }
+            let offset = positions.get(name.as_ref()).copied().unwrap_or(0);
+            let run = Run::open(path, offset)?;
+            if run.head.is_some() {
+                len += run.count()?;
+                runs.push(run);
+            } else {
+                std::fs::remove_file(&run.path)?;
+            }
+        }
+
+        let state = Self {
+            seen: SeenLog::open(&dir.join(SEEN_FILE))?,
+            dir,
+            memtable: BinaryHeap::new(),
+            memtable_links: DEFAULT_MEMTABLE_LINKS,
+            runs,
+            len: Arc::new(AtomicUsize::new(len)),
+            next_run,
+        };
+        // Forget the positions of the runs removed since they were saved.
+        state.save_positions()?;
+        Ok(state)
+    }
+
+    /// Queue an entry, flushing the memtable when full.
+    fn push(&mut self, url: &str, priority: i32) -> io::Result<bool> {
+        if !self.seen.insert(url)? {
+            return Ok(false);
+        }
+        self.memtable.push(ScoredUrl {
+            priority,
+            url: url.into(),
+        });
+        self.len.fetch_add(1, Ordering::Relaxed);
+
+        if self.memtable.len() >= self.memtable_links {
+            self.flush()?;
+        }
+        Ok(true)
+    }
+
+    /// The path of a new run file.
+    fn run_path(&mut self) -> PathBuf {
+        self.next_run += 1;
+        self.dir.join(format!("{:010}.run", self.next_run))
+    }
+
+    /// Write the memtable to a run, merging the runs when there are too many.
+    fn flush(&mut self) -> io::Result<()> {
+        if self.memtable.is_empty() {
+            return Ok(());
+        }
+        let path = self.run_path();
+        let entries = std::mem::take(&mut self.memtable).into_sorted_vec();
+        write_run(&path, entries.into_iter().rev())?;
+        self.runs.push(Run::open(path, 0)?);
+
+        if self.runs.len() > MAX_RUNS {
+            self.compact()?;
+        }
+        self.seen.writer.flush()?;
+        self.save_positions()
+    }
+
+    /// Merge every run into one.
+    fn compact(&mut self) -> io::Result<()> {
+        let path = self.run_path();
+        let mut runs = std::mem::take(&mut self.runs);
+        let mut writer = BufWriter::new(File::create(&path)?);
+
+        while let Some(index) = max_run(&runs) {
+            if let Some(entry) = runs[index].take()? {
+                writeln!(writer, "{}\t{}", entry.priority, escape(entry.url.inner()))?;
+            }
+        }
+        writer.flush()?;
+        self.runs.push(Run::open(path, 0)?);
+        // The merged run is read from the start, forget the old positions first.
+        self.save_positions()?;
+
+        for run in runs {
+            let _ = std::fs::remove_file(&run.path);
+        }
+        Ok(())
+    }
+
+    /// Record where each run is read from, so a reopened store skips the popped entries.
+    fn save_positions(&self) -> io::Result<()> {
+        let path = self.dir.join(POSITIONS_FILE);
+        let tmp = path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        for run in self.runs.iter() {
+            if let Some(name) = run.path.file_name() {
+                writeln!(writer, "{}\t{}", name.to_string_lossy(), run.offset)?;
+            }
+        }
+        writer.flush()?;
+        drop(writer);
+        std::fs::rename(tmp, path)
+    }
+
+    /// Every queued entry across the memtable and the runs.
+    fn entries(&self) -> io::Result<Vec<ScoredUrl>> {
+        let mut entries: Vec<ScoredUrl> = self.memtable.iter().cloned().collect();
+        for run in self.runs.iter() {
+            entries.extend(run.remaining()?);
+        }
+        Ok(entries)
+    }
+
+    /// Every URL popped or marked visited, read back from the seen log.
+    fn visited(&mut self) -> io::Result<Vec<CaseInsensitiveString>> {
+        let queued: HashSet<CaseInsensitiveString> =
+            self.entries()?.into_iter().map(|e| e.url).collect();
+        self.seen.writer.flush()?;
+
+        let mut urls = Vec::new();
+        for line in BufReader::new(File::open(self.dir.join(SEEN_FILE))?).lines() {
+            let url = CaseInsensitiveString::from(unescape(&line?));
+            if !queued.contains(&url) {
+                urls.push(url);
+            }
+        }
+        Ok(urls)
+    }
+
+    /// Pop the highest entry across the memtable and the runs.
+    fn pop(&mut self) -> io::Result<Option<CaseInsensitiveString>> {
+        let run = max_run(&self.runs);
+        let from_memtable = match (
+            self.memtable.peek(),
+            run.and_then(|i| self.runs[i].head.as_ref()),
+        ) {
+            (Some(mem), Some(head)) => mem >= head,
+            (Some(_), None) => true,
+            _ => false,
+        };
+
+        let entry = if from_memtable {
+            self.memtable.pop()
+        } else if let Some(index) = run {
+            let entry = self.runs[index].take()?;
+            if self.runs[index].head.is_none() {
+                let run = self.runs.swap_remove(index);
+                let _ = std::fs::remove_file(&run.path);
+            }
+            entry
+        } else {
+            None
+        };
+
+        if entry.is_some() {
+            let _ = self
+                .len
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |len| {
+                    Some(len.saturating_sub(1))
+                });
+        }
+        Ok(entry.map(|e| e.url))
+    }
+
+    /// Pop up to `max` entries and record the new run positions.
+    fn pop_batch(&mut self, max: usize) -> io::Result<Vec<CaseInsensitiveString>> {
+        let mut urls = Vec::new();
+        while urls.len() < max {
+            match self.pop()? {
+                Some(url) => urls.push(url),
+                _ => break,
+            }
+        }
+        self.save_positions()?;
+        Ok(urls)
+    }
+}
+
+impl Drop for FileState {
+    /// Flush the memtable so a reopened store finds every queued entry.
+    fn drop(&mut self) {
+        let flushed = self
+            .flush()
+            .and_then(|_| self.seen.writer.flush())
+            .and_then(|_| self.save_positions());
+        if let Err(e) = flushed {
+            log::warn!("frontier store write failed: {e}");
+        }
+    }
+}
+
+/// The index of the run with the highest head.
+fn max_run(runs: &[Run]) -> Option<usize> {
+    runs.iter()
+        .enumerate()
+        .filter_map(|(i, run)| run.head.as_ref().map(|head| (i, head)))
+        .max_by(|a, b| a.1.cmp(b.1))
+        .map(|(i, _)| i)
+}
+
+/// An embedded on-disk store with the queue in sorted run files.
+///
+/// The seen URLs are appended to a log in the same directory, and the
+/// memtable is flushed to a run when the store is dropped, so
+/// [`open`](Self::open) continues the queue left by a previous store.
+/// Use [`create`](Self::create) to start from an empty directory. The file
+/// I/O runs on the blocking thread pool.
+pub struct FileFrontierStore {
+    /// The store state.
+    state: Arc<Mutex<FileState>>,
+    /// Entries queued, readable without waiting on the state.
+    len: Arc<AtomicUsize>,
+}
+
+impl FileFrontierStore {
+    /// Open the store in `dir`, creating the directory when missing and
+    /// reloading the queue and the seen URLs left there.
+    pub fn open(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let state = FileState::open(dir.into())?;
+        Ok(Self {
+            len: state.len.clone(),
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    /// Open an empty store in `dir`, removing the queue and the seen URLs left there.
+    pub fn create(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+
+        if dir.is_dir() {
+            for entry in std::fs::read_dir(&dir)? {
+                let path = entry?.path();
+                let name = path.file_name().unwrap_or_default();
+                if path.extension().is_some_and(|e| e == "run")
+                    || name == SEEN_FILE
+                    || name == POSITIONS_FILE
+                {
+                    std::fs::remove_file(path)?;
+                }
+            }
+        }
+
+        Self::open(dir)
+    }
+
+    /// Set how many entries the memtable holds before it is flushed to a run.
+    pub fn with_memtable_links(self, memtable_links: usize) -> Self {
+        lock(&self.state).memtable_links = memtable_links.max(1);
+        self
+    }
+
+    /// Run `f` on the state on the blocking thread pool.
+    async fn with<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut FileState) -> io::Result<R> + Send + 'static,
+    ) -> io::Result<R> {
+        let state = self.state.clone();
+        tokio::task::spawn_blocking(move || f(&mut lock(&state)))
+            .await
+            .map_err(io::Error::other)?
+    }
+}
+
+#[async_trait::async_trait]
+impl FrontierStore for FileFrontierStore {
+    async fn push(&self, url: &str, priority: i32) -> bool {
+        self.push_batch(vec![(url.to_string(), priority)]).await > 0
+    }
+
+    async fn pop(&self) -> Option<CaseInsensitiveString> {
+        self.pop_batch(1).await.pop()
+    }
+
+    async fn mark_visited(&self, url: &str) {
+        let url = url.to_string();
+        if let Err(e) = self.with(move |state| state.seen.insert(&url)).await {
+            log::warn!("frontier store write failed: {e}");
+        }
+    }
+
+    async fn contains(&self, url: &str) -> bool {
+        let url = url.to_string();
+        self.with(move |state| state.seen.contains(&url))
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("frontier store read failed: {e}");
+                false
+            })
+    }
+
+    async fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    async fn entries(&self) -> Vec<ScoredUrl> {
+        self.with(|state| state.entries())
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("frontier store read failed: {e}");
+                Vec::new()
+            })
+    }
+
+    async fn visited(&self) -> Vec<CaseInsensitiveString> {
+        self.with(|state| state.visited())
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("frontier store read failed: {e}");
+                Vec::new()
+            })
+    }
+
+    async fn push_batch(&self, urls: Vec<(String, i32)>) -> usize {
+        self.with(move |state| {
+            let mut pushed = 0;
+            for (url, priority) in urls {
+                if state.push(&url, priority)? {
+                    pushed += 1;
+                }
+            }
+            Ok(pushed)
+        })
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("frontier store write failed: {e}");
+            0
+        })
+    }
+
+    async fn pop_batch(&self, max: usize) -> Vec<CaseInsensitiveString> {
+        self.with(move |state| state.pop_batch(max))
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("frontier store read failed: {e}");
+                Vec::new()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn exercise(store: &dyn FrontierStore) {
+        assert!(store.is_empty().await);
+        assert!(store.push("https://example.com/low", 10).await);
+        assert!(store.push("https://example.com/high", 900).await);
+        assert!(!store.push("https://example.com/high", 5).await);
+        store.mark_visited("https://example.com/seen").await;
+        assert!(!store.push("https://example.com/seen", 1000).await);
+        assert_eq!(
+            store.visited().await,
+            [CaseInsensitiveString::from("https://example.com/seen")]
+        );
+        assert!(store.contains("https://example.com/low").await);
+
+        let pushed = store
+            .push_batch(
+                (0..20)
+                    .map(|i| (format!("https://example.com/{i}"), i * 10))
+                    .collect(),
+            )
+            .await;
+        assert_eq!(pushed, 20);
+        assert_eq!(store.len().await, 22);
+
+        let mut entries = store.entries().await;
+        entries.sort();
+        assert_eq!(entries.len(), 22);
+        assert_eq!(entries[21].url.inner(), "https://example.com/high");
+        assert_eq!(entries[21].priority, 900);
+
+        assert_eq!(
+            store.pop().await.unwrap().inner(),
+            "https://example.com/high"
+        );
+        let batch = store.pop_batch(3).await;
+        let batch: Vec<&str> = batch.iter().map(|u| u.inner().as_str()).collect();
+        assert_eq!(
+            batch,
+            [
+                "https://example.com/19",
+                "https://example.com/18",
+                "https://example.com/17"
+            ]
+        );
+        assert_eq!(store.pop_batch(100).await.len(), 18);
+        assert!(store.pop().await.is_none());
+        assert!(store.is_empty().await);
+        assert_eq!(store.visited().await.len(), 23);
+    }
+
+    #[tokio::test]
+    async fn test_memory_store() {
+        exercise(&MemoryFrontierStore::new(false)).await;
+    }
+
+    #[tokio::test]
+    async fn test_file_store_merges_runs() {
+        let dir = std::env::temp_dir().join(format!("spider-frontier-{}", std::process::id()));
+        // A memtable of 2 forces flushes and compactions.
+        let store = FileFrontierStore::create(&dir)
+            .unwrap()
+            .with_memtable_links(2);
+        exercise(&store).await;
+        drop(store);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_file_store_reopens_queue() {
+        let dir =
+            std::env::temp_dir().join(format!("spider-frontier-reopen-{}", std::process::id()));
+        let store = FileFrontierStore::create(&dir)
+            .unwrap()
+            .with_memtable_links(2);
+        for i in 0..5 {
+            assert!(store.push(&format!("https://example.com/{i}"), i).await);
+        }
+        assert_eq!(store.pop().await.unwrap().inner(), "https://example.com/4");
+        store.mark_visited("https://example.com/seen").await;
+        drop(store);
+
+        let store = FileFrontierStore::open(&dir).unwrap();
+        assert_eq!(store.len().await, 4);
+        assert!(store.contains("https://example.com/seen").await);
+        assert!(!store.push("https://example.com/4", 10).await);
+        assert_eq!(store.pop().await.unwrap().inner(), "https://example.com/3");
+        drop(store);
+
+        let store = FileFrontierStore::open(&dir).unwrap();
+        let urls = store.pop_batch(10).await;
+        let urls: Vec<&str> = urls.iter().map(|u| u.inner().as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/2",
+                "https://example.com/1",
+                "https://example.com/0"
+            ]
+        );
+        drop(store);
+
+        let store = FileFrontierStore::create(&dir).unwrap();
+        assert!(store.is_empty().await);
+        assert!(!store.contains("https://example.com/seen").await);
+        drop(store);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_seen_log_compares_urls() {
+        let dir = std::env::temp_dir().join(format!("spider-frontier-seen-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SEEN_FILE);
+        let _ = std::fs::remove_file(&path);
+
+        let mut seen = SeenLog::open(&path).unwrap();
+        assert!(seen.insert("https://example.com/a").unwrap());
+        assert!(!seen.insert("https://EXAMPLE.com/a").unwrap());
+
+        // A URL colliding with the fingerprint of another is still new.
+        let b = "https://example.com/b";
+        seen.index.insert(fingerprint(b), 0);
+        assert!(seen.insert(b).unwrap());
+        assert!(seen.contains(b).unwrap());
+        assert!(!seen.insert(b).unwrap());
+        drop(seen);
+
+        let mut seen = SeenLog::open(&path).unwrap();
+        assert!(seen.contains("https://example.com/a").unwrap());
+        assert!(seen.contains(b).unwrap());
+        assert!(!seen.contains("https://example.com/c").unwrap());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_file_store_escapes_urls() {
+        let dir =
+            std::env::temp_dir().join(format!("spider-frontier-escape-{}", std::process::id()));
+        let store = FileFrontierStore::create(&dir)
+            .unwrap()
+            .with_memtable_links(1);
+        let url = "https://example.com/a\tb\nc\\d";
+        assert!(store.push(url, 1).await);
+        assert_eq!(store.entries().await[0].url.inner(), url);
+        assert_eq!(store.pop().await.unwrap().inner(), url);
+        assert!(store.contains(url).await);
+        drop(store);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[cfg(feature = "disk")]
+    #[tokio::test]
+    async fn test_sqlite_store() {
+        let path = std::env::temp_dir().join(format!("spider-frontier-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let store = SqliteFrontierStore::open(&path.display().to_string())
+            .await
+            .unwrap();
+        exercise(&store).await;
+        let _ = std::fs::remove_file(path);
+    }
+}
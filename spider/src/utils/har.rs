@@ -0,0 +1,941 @@
+//! HAR 1.2 capture for Chrome pages.
+//!
+//! The recorder listens to the CDP `Network` and `Page` domains while a page
+//! is loaded and assembles one [`Har`] log per page: request and response
+//! headers, the timings breakdown, sizes, status and (optionally) bodies.
+//! The result is attached to [`crate::page::Page::har`] and can be written
+//! to disk with [`Har::write_to`] for use in any standard HAR viewer.
+//!
+//! See <http://www.softwareishard.com/blog/har-12-spec/> for the format.
+
+use chromiumoxide::cdp::browser_protocol::network::{
+    EventLoadingFailed, EventLoadingFinished, EventRequestWillBeSent, EventResponseReceived,
+    GetResponseBodyParams, Headers, Request, ResourceTiming, Response,
+};
+use chromiumoxide::cdp::browser_protocol::page::{EventDomContentEventFired, EventLoadEventFired};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// The id used for the single page in every log.
+const PAGE_REF: &str = "page_1";
+
+/// HAR capture configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HarConfig {
+    /// Store the response bodies in `content.text`. Defaults to false.
+    pub bodies: bool,
+    /// Skip bodies larger than this many bytes. Defaults to 1 MiB.
+    pub max_body_bytes: usize,
+}
+
+impl Default for HarConfig {
+    fn default() -> Self {
+        Self {
+            bodies: false,
+            max_body_bytes: 1024 * 1024,
+        }
+    }
+}
+
+impl HarConfig {
+    /// Capture the network log including response bodies.
+    pub fn with_bodies() -> Self {
+        Self {
+            bodies: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// The HAR root object.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Har {
+    /// The log.
+    pub log: HarLog,
+}
+
+impl Har {
+    /// Write the log as JSON to `path`. This is a blocking call.
+    pub fn write_to(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let file = std::fs::File::create(path)?;
+        let mut writer = std::io::BufWriter::new(file);
+        serde_json::to_writer(&mut writer, self)?;
+        std::io::Write::flush(&mut writer)
+    }
+}
+
+/// The HAR log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarLog {
+    /// The HAR format version.
+    pub version: String,
+    /// The application that created the log.
+    pub creator: HarCreator,
+    /// The pages in the log.
+    pub pages: Vec<HarPage>,
+    /// The requests made.
+    pub entries: Vec<HarEntry>,
+}
+
+impl Default for HarLog {
+    fn default() -> Self {
+        Self {
+            version: "1.2".into(),
+            creator: HarCreator::default(),
+            pages: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+}
+
+/// The log creator.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HarCreator {
+    /// The application name.
+    pub name: String,
+    /// The application version.
+    pub version: String,
+}
+
+impl Default for HarCreator {
+    fn default() -> Self {
+        Self {
+            name: "spider".into(),
+            version: env!("CARGO_PKG_VERSION").into(),
+        }
+    }
+}
+
+/// A page in the log.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPage {
+    /// The ISO 8601 start of the page load.
+    pub started_date_time: String,
+    /// The page id referenced by the entries.
+    pub id: String,
+    /// The page title.
+    pub title: String,
+    /// The page load timings.
+    pub page_timings: HarPageTimings,
+}
+
+/// The page load timings in milliseconds since the page started, `-1` when not fired.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPageTimings {
+    /// The `DOMContentLoaded` event.
+    pub on_content_load: f64,
+    /// The `load` event.
+    pub on_load: f64,
+}
+
+impl Default for HarPageTimings {
+    fn default() -> Self {
+        Self {
+            on_content_load: -1.0,
+            on_load: -1.0,
+        }
+    }
+}
+
+/// A single request and response.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    /// The page the entry belongs to.
+    pub pageref: String,
+    /// The ISO 8601 start of the request.
+    pub started_date_time: String,
+    /// The total time of the request in milliseconds.
+    pub time: f64,
+    /// The request.
+    pub request: HarRequest,
+    /// The response.
+    pub response: HarResponse,
+    /// The cache usage, not tracked.
+    pub cache: HarCache,
+    /// The timings breakdown.
+    pub timings: HarTimings,
+    /// The IP address of the server.
+    #[serde(rename = "serverIPAddress", skip_serializing_if = "Option::is_none")]
+    pub server_ip_address: Option<String>,
+    /// The connection id.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection: Option<String>,
+    /// The bytes transferred over the network, as in Chrome DevTools exports.
+    #[serde(rename = "_transferSize")]
+    pub transfer_size: f64,
+    /// The network error when the request failed.
+    #[serde(rename = "_error", skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The request.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    /// The request method.
+    pub method: String,
+    /// The absolute url.
+    pub url: String,
+    /// The HTTP version.
+    pub http_version: String,
+    /// The request cookies.
+    pub cookies: Vec<HarNameValue>,
+    /// The request headers.
+    pub headers: Vec<HarNameValue>,
+    /// The query string parameters.
+    pub query_string: Vec<HarNameValue>,
+    /// The posted data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    /// The size of the headers, `-1` when unknown.
+    pub headers_size: i64,
+    /// The size of the body, `-1` when unknown.
+    pub body_size: i64,
+}
+
+/// The response.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    /// The status code, `0` when the request failed.
+    pub status: i64,
+    /// The status text.
+    pub status_text: String,
+    /// The HTTP version.
+    pub http_version: String,
+    /// The response cookies.
+    pub cookies: Vec<HarNameValue>,
+    /// The response headers.
+    pub headers: Vec<HarNameValue>,
+    /// The response content.
+    pub content: HarContent,
+    /// The redirect target from the `Location` header.
+    #[serde(rename = "redirectURL")]
+    pub redirect_url: String,
+    /// The size of the headers, `-1` when unknown.
+    pub headers_size: i64,
+    /// The size of the body, `-1` when unknown.
+    pub body_size: i64,
+}
+
+/// A name and value pair used for headers, cookies and query strings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HarNameValue {
+    /// The name.
+    pub name: String,
+    /// The value.
+    pub value: String,
+}
+
+/// The posted data.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    /// The mime type of the body.
+    pub mime_type: String,
+    /// The body.
+    pub text: String,
+}
+
+/// The response content.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    /// The decoded body size in bytes.
+    pub size: i64,
+    /// The mime type of the body.
+    pub mime_type: String,
+    /// The body when captured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// `base64` when `text` is base64 encoded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+/// The cache usage.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HarCache {}
+
+/// The timings breakdown in milliseconds, `-1` when not applicable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HarTimings {
+    /// Time spent queued before the connection was available.
+    pub blocked: f64,
+    /// DNS resolution.
+    pub dns: f64,
+    /// Creating the connection, including `ssl`.
+    pub connect: f64,
+    /// Sending the request.
+    pub send: f64,
+    /// Waiting for the first byte of the response.
+    pub wait: f64,
+    /// Reading the response.
+    pub receive: f64,
+    /// The TLS handshake.
+    pub ssl: f64,
+}
+
+impl Default for HarTimings {
+    fn default() -> Self {
+        Self {
+            blocked: -1.0,
+            dns: -1.0,
+            connect: -1.0,
+            send: 0.0,
+            wait: 0.0,
+            receive: 0.0,
+            ssl: -1.0,
+        }
+    }
+}
+
+impl HarTimings {
+    /// Build the breakdown from the CDP resource timing. `finished` is the
+    /// monotonic time in seconds the response was fully read.
+    pub fn from_resource_timing(timing: &ResourceTiming, finished: Option<f64>) -> Self {
+        let span = |start: f64, end: f64| {
+            if start >= 0.0 && end >= start {
+                end - start
+            } else {
+                -1.0
+            }
+        };
+        let blocked = [timing.dns_start, timing.connect_start, timing.send_start]
+            .into_iter()
+            .find(|t| *t >= 0.0)
+            .unwrap_or(-1.0);
+        let receive = finished
+            .map(|f| ((f - timing.request_time) * 1000.0 - timing.receive_headers_end).max(0.0))
+            .unwrap_or(0.0);
+
+        Self {
+            blocked,
+            dns: span(timing.dns_start, timing.dns_end),
+            connect: span(timing.connect_start, timing.connect_end),
+            send: span(timing.send_start, timing.send_end).max(0.0),
+            wait: span(timing.send_end, timing.receive_headers_end).max(0.0),
+            receive,
+            ssl: span(timing.ssl_start, timing.ssl_end),
+        }
+    }
+
+    /// The total time of the request. `ssl` is part of `connect`.
+    pub fn total(&self) -> f64 {
+        [
+            self.blocked,
+            self.dns,
+            self.connect,
+            self.send,
+            self.wait,
+            self.receive,
+        ]
+        .into_iter()
+        .filter(|t| *t > 0.0)
+        .sum()
+    }
+}
+
+/// Format seconds since the epoch as an ISO 8601 timestamp.
+fn iso_date(wall_time: f64) -> String {
+    chrono::DateTime::from_timestamp_millis((wall_time * 1000.0) as i64)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+/// Flatten the CDP headers object. Repeated headers are joined with `\n`.
+fn har_headers(headers: &Headers) -> Vec<HarNameValue> {
+    let mut out = Vec::new();
+
+    if let Some(map) = headers.inner().as_object() {
+        for (name, value) in map {
+            if let Some(value) = value.as_str() {
+                for value in value.split('\n') {
+                    out.push(HarNameValue {
+                        name: name.clone(),
+                        value: value.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    out
+}
+
+/// Find a header value case-insensitively.
+fn header_value<'a>(headers: &'a [HarNameValue], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|h| h.name.eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str())
+}
+
+/// Cookies from the `Cookie` request header.
+fn request_cookies(headers: &[HarNameValue]) -> Vec<HarNameValue> {
+    headers
+        .iter()
+        .filter(|h| h.name.eq_ignore_ascii_case("cookie"))
+        .flat_map(|h| h.value.split(';'))
+        .filter_map(cookie_pair)
+        .collect()
+}
+
+/// Cookies from the `Set-Cookie` response headers.
+fn response_cookies(headers: &[HarNameValue]) -> Vec<HarNameValue> {
+    headers
+        .iter()
+        .filter(|h| h.name.eq_ignore_ascii_case("set-cookie"))
+        .filter_map(|h| h.value.split(';').next().and_then(cookie_pair))
+        .collect()
+}
+
+fn cookie_pair(pair: &str) -> Option<HarNameValue> {
+    let (name, value) = pair.trim().split_once('=')?;
+
+    Some(HarNameValue {
+        name: name.trim().to_string(),
+        value: value.trim().to_string(),
+    })
+}
+
+fn query_string(url: &str) -> Vec<HarNameValue> {
+    match url::Url::parse(url) {
+        Ok(u) => u
+            .query_pairs()
+            .map(|(name, value)| HarNameValue {
+                name: name.into_owned(),
+                value: value.into_owned(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn har_request(request: &Request, http_version: &str) -> HarRequest {
+    let headers = har_headers(&request.headers);
+    let post_data = request.post_data_entries.as_ref().map(|entries| {
+        let mut body = Vec::new();
+
+        for entry in entries {
+            if let Some(bytes) = &entry.bytes {
+                if let Ok(b) = chromiumoxide::utils::base64::decode(&bytes.0) {
+                    body.extend(b);
+                }
+            }
+        }
+
+        HarPostData {
+            mime_type: header_value(&headers, "content-type")
+                .unwrap_or_default()
+                .to_string(),
+            text: String::from_utf8_lossy(&body).into_owned(),
+        }
+    });
+    let url = match &request.url_fragment {
+        Some(fragment) => format!("{}{}", request.url, fragment),
+        _ => request.url.clone(),
+    };
+
+    HarRequest {
+        method: request.method.clone(),
+        query_string: query_string(&url),
+        url,
+        http_version: http_version.to_string(),
+        cookies: request_cookies(&headers),
+        body_size: post_data.as_ref().map_or(0, |p| p.text.len() as i64),
+        post_data,
+        headers,
+        headers_size: -1,
+    }
+}
+
+fn har_response(response: &Response) -> HarResponse {
+    let headers = har_headers(&response.headers);
+
+    HarResponse {
+        status: response.status,
+        status_text: response.status_text.clone(),
+        http_version: response.protocol.clone().unwrap_or_default(),
+        cookies: response_cookies(&headers),
+        redirect_url: header_value(&headers, "location")
+            .unwrap_or_default()
+            .to_string(),
+        headers,
+        content: HarContent {
+            size: 0,
+            mime_type: response.mime_type.clone(),
+            text: None,
+            encoding: None,
+        },
+        headers_size: -1,
+        body_size: -1,
+    }
+}
+
+/// A request that has not finished loading yet.
+#[derive(Debug)]
+struct Pending {
+    /// The monotonic start in seconds.
+    started: f64,
+    /// The start in seconds since the epoch.
+    wall_time: f64,
+    request: Request,
+    response: Option<Response>,
+}
+
+/// Assemble HAR entries from the CDP network events of a page.
+#[derive(Debug, Default)]
+pub struct HarRecorder {
+    pending: HashMap<String, Pending>,
+    entries: Vec<HarEntry>,
+    /// The monotonic and wall start of the first request.
+    started: Option<(f64, f64)>,
+    dom_content_loaded: Option<f64>,
+    loaded: Option<f64>,
+}
+
+impl HarRecorder {
+    /// A new empty recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handle `Network.requestWillBeSent`. A redirect completes the previous
+    /// hop of the same request id.
+    pub fn on_request(&mut self, event: &EventRequestWillBeSent) {
+        let id = event.request_id.inner();
+        let timestamp = *event.timestamp.inner();
+
+        if let Some(redirect) = &event.redirect_response {
+            if let Some(mut pending) = self.pending.remove(id) {
+                pending.response = Some(redirect.clone());
+                let mut entry = self.complete(pending, Some(timestamp), 0.0);
+                entry.response.redirect_url = event.request.url.clone();
+                self.entries.push(entry);
+            }
+        }
+
+        let wall_time = *event.wall_time.inner();
+
+        if self.started.is_none() {
+            self.started = Some((timestamp, wall_time));
+        }
+
+        self.pending.insert(
+            id.clone(),
+            Pending {
+                started: timestamp,
+                wall_time,
+                request: event.request.clone(),
+                response: None,
+            },
+        );
+    }
+
+    /// Handle `Network.responseReceived`.
+    pub fn on_response(&mut self, event: &EventResponseReceived) {
+        if let Some(pending) = self.pending.get_mut(event.request_id.inner()) {
+            pending.response = Some(event.response.clone());
+        }
+    }
+
+    /// Handle `Network.loadingFinished`. Returns the index of the entry to
+    /// attach the body with [`HarRecorder::set_body`].
+    pub fn on_finished(&mut self, event: &EventLoadingFinished) -> Option<usize> {
+        let pending = self.pending.remove(event.request_id.inner())?;
+        let entry = self.complete(
+            pending,
+            Some(*event.timestamp.inner()),
+            event.encoded_data_length,
+        );
+        self.entries.push(entry);
+        Some(self.entries.len() - 1)
+    }
+
+    /// Handle `Network.loadingFailed`.
+    pub fn on_failed(&mut self, event: &EventLoadingFailed) {
+        if let Some(mut pending) = self.pending.remove(event.request_id.inner()) {
+            // the response is incomplete, report the request as failed.
+            pending.response = None;
+            let mut entry = self.complete(pending, Some(*event.timestamp.inner()), 0.0);
+            entry.error = Some(event.error_text.clone());
+            entry.response.status_text = event.error_text.clone();
+            self.entries.push(entry);
+        }
+    }
+
+    /// Handle `Page.domContentEventFired`.
+    pub fn on_dom_content_loaded(&mut self, event: &EventDomContentEventFired) {
+        self.dom_content_loaded = Some(*event.timestamp.inner());
+    }
+
+    /// Handle `Page.loadEventFired`.
+    pub fn on_load(&mut self, event: &EventLoadEventFired) {
+        self.loaded = Some(*event.timestamp.inner());
+    }
+
+    /// A finished entry.
+    pub fn entry(&self, index: usize) -> Option<&HarEntry> {
+        self.entries.get(index)
+    }
+
+    /// Attach the body returned by `Network.getResponseBody` to an entry.
+    pub fn set_body(&mut self, index: usize, body: String, base64_encoded: bool) {
+        if let Some(entry) = self.entries.get_mut(index) {
+            let content = &mut entry.response.content;
+
+            if base64_encoded {
+                content.size =
+                    chromiumoxide::utils::base64::decode(&body).map_or(0, |b| b.len() as i64);
+                content.encoding = Some("base64".into());
+            } else {
+                content.size = body.len() as i64;
+            }
+
+            content.text = Some(body);
+        }
+    }
+
+    fn complete(&self, pending: Pending, finished: Option<f64>, transfer_size: f64) -> HarEntry {
+        let http_version = pending
+            .response
+            .as_ref()
+            .and_then(|r| r.protocol.clone())
+            .unwrap_or_default();
+        let request = har_request(&pending.request, &http_version);
+        let elapsed = finished.map_or(0.0, |f| ((f - pending.started) * 1000.0).max(0.0));
+
+        let (response, timings, server_ip_address, connection) = match &pending.response {
+            Some(response) => {
+                let timings = match &response.timing {
+                    Some(timing) => HarTimings::from_resource_timing(timing, finished),
+                    _ => HarTimings {
+                        wait: elapsed,
+                        ..Default::default()
+                    },
+                };
+                let mut har = har_response(response);
+
+                if har.content.size == 0 {
+                    har.content.size = transfer_size as i64;
+                }
+
+                (
+                    har,
+                    timings,
+                    response.remote_ip_address.clone(),
+                    Some(response.connection_id.to_string()),
+                )
+            }
+            _ => (
+                HarResponse {
+                    headers_size: -1,
+                    body_size: -1,
+                    ..Default::default()
+                },
+                HarTimings {
+                    wait: elapsed,
+                    ..Default::default()
+                },
+                None,
+                None,
+            ),
+        };
+
+        HarEntry {
+            pageref: PAGE_REF.into(),
+            started_date_time: iso_date(pending.wall_time),
+            time: timings.total(),
+            request,
+            response,
+            cache: HarCache::default(),
+            timings,
+            server_ip_address,
+            connection,
+            transfer_size,
+            error: None,
+        }
+    }
+
+    /// Finish the log. Requests still in flight are recorded without a
+    /// receive time.
+    pub fn finish(mut self, title: &str) -> Har {
+        let pending: Vec<Pending> = self.pending.drain().map(|(_, p)| p).collect();
+
+        for pending in pending {
+            let entry = self.complete(pending, None, 0.0);
+            self.entries.push(entry);
+        }
+
+        self.entries
+            .sort_by(|a, b| a.started_date_time.cmp(&b.started_date_time));
+
+        let mut log = HarLog::default();
+
+        if let Some((started, wall_time)) = self.started {
+            let since = |t: Option<f64>| t.map_or(-1.0, |t| ((t - started) * 1000.0).max(0.0));
+
+            log.pages.push(HarPage {
+                started_date_time: iso_date(wall_time),
+                id: PAGE_REF.into(),
+                title: title.to_string(),
+                page_timings: HarPageTimings {
+                    on_content_load: since(self.dom_content_loaded),
+                    on_load: since(self.loaded),
+                },
+            });
+        }
+
+        log.entries = self.entries;
+
+        Har { log }
+    }
+}
+
+/// Record the network log of `page` until `shutdown` fires. The listeners
+/// are created before returning so the caller can navigate right after.
+pub(crate) async fn spawn_recorder(
+    page: &chromiumoxide::Page,
+    config: HarConfig,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) -> tokio::task::JoinHandle<HarRecorder> {
+    use crate::utils::next_optional_event as next_event;
+
+    let (requests, responses, finished, failed, dom_content, loaded) = tokio::join!(
+        page.event_listener::<EventRequestWillBeSent>(),
+        page.event_listener::<EventResponseReceived>(),
+        page.event_listener::<EventLoadingFinished>(),
+        page.event_listener::<EventLoadingFailed>(),
+        page.event_listener::<EventDomContentEventFired>(),
+        page.event_listener::<EventLoadEventFired>(),
+    );
+    let (mut requests, mut responses, mut finished, mut failed, mut dom_content, mut loaded) = (
+        requests.ok(),
+        responses.ok(),
+        finished.ok(),
+        failed.ok(),
+        dom_content.ok(),
+        loaded.ok(),
+    );
+    let page = page.clone();
+
+    tokio::spawn(async move {
+        let mut recorder = HarRecorder::new();
+
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown.changed() => break,
+                ev = next_event(&mut requests) => match ev {
+                    Some(ev) => recorder.on_request(&ev),
+                    _ => requests = None,
+                },
+                ev = next_event(&mut responses) => match ev {
+                    Some(ev) => recorder.on_response(&ev),
+                    _ => responses = None,
+                },
+                ev = next_event(&mut finished) => match ev {
+                    Some(ev) => {
+                        let index = recorder.on_finished(&ev);
+
+                        if config.bodies {
+                            if let Some(index) = index {
+                                let fits = recorder.entry(index).is_some_and(|e| {
+                                    e.transfer_size <= config.max_body_bytes as f64
+                                });
+
+                                if fits {
+                                    let params = GetResponseBodyParams::new(ev.request_id.clone());
+
+                                    if let Ok(Ok(body)) = tokio::time::timeout(
+                                        std::time::Duration::from_secs(10),
+                                        page.execute(params),
+                                    )
+                                    .await
+                                    {
+                                        if body.result.body.len() <= config.max_body_bytes {
+                                            recorder.set_body(
+                                                index,
+                                                body.result.body,
+                                                body.result.base64_encoded,
+                                            );
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    _ => finished = None,
+                },
+                ev = next_event(&mut failed) => match ev {
+                    Some(ev) => recorder.on_failed(&ev),
+                    _ => failed = None,
+                },
+                ev = next_event(&mut dom_content) => match ev {
+                    Some(ev) => recorder.on_dom_content_loaded(&ev),
+                    _ => dom_content = None,
+                },
+                ev = next_event(&mut loaded) => match ev {
+                    Some(ev) => recorder.on_load(&ev),
+                    _ => loaded = None,
+                },
+            }
+        }
+
+        recorder
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_event(id: &str, url: &str, timestamp: f64) -> EventRequestWillBeSent {
+        serde_json::from_value(serde_json::json!({
+            "requestId": id,
+            "loaderId": "l",
+            "documentURL": url,
+            "request": {
+                "url": url,
+                "method": "GET",
+                "headers": { "Cookie": "a=1; b=2", "Accept": "*/*" },
+                "initialPriority": "High",
+                "referrerPolicy": "no-referrer"
+            },
+            "timestamp": timestamp,
+            "wallTime": 1_700_000_000.0 + timestamp,
+            "initiator": { "type": "other" },
+            "redirectHasExtraInfo": false
+        }))
+        .unwrap()
+    }
+
+    fn response(url: &str, status: i64) -> serde_json::Value {
+        serde_json::json!({
+            "url": url,
+            "status": status,
+            "statusText": "OK",
+            "headers": { "Content-Type": "text/html", "Set-Cookie": "s=x; Path=/\nt=y" },
+            "mimeType": "text/html",
+            "charset": "",
+            "connectionReused": false,
+            "connectionId": 7.0,
+            "remoteIPAddress": "127.0.0.1",
+            "encodedDataLength": 100.0,
+            "protocol": "http/1.1",
+            "securityState": "neutral",
+            "timing": {
+                "requestTime": 10.0,
+                "proxyStart": -1.0, "proxyEnd": -1.0,
+                "dnsStart": 1.0, "dnsEnd": 3.0,
+                "connectStart": 3.0, "connectEnd": 10.0,
+                "sslStart": 5.0, "sslEnd": 10.0,
+                "workerStart": -1.0, "workerReady": -1.0,
+                "workerFetchStart": -1.0, "workerRespondWithSettled": -1.0,
+                "sendStart": 10.0, "sendEnd": 11.0,
+                "pushStart": 0.0, "pushEnd": 0.0,
+                "receiveHeadersStart": 30.0, "receiveHeadersEnd": 31.0
+            }
+        })
+    }
+
+    #[test]
+    fn test_timings_from_resource_timing() {
+        let timing: ResourceTiming =
+            serde_json::from_value(response("https://a.com/", 200)["timing"].clone()).unwrap();
+        let timings = HarTimings::from_resource_timing(&timing, Some(10.05));
+
+        assert_eq!(timings.blocked, 1.0);
+        assert_eq!(timings.dns, 2.0);
+        assert_eq!(timings.connect, 7.0);
+        assert_eq!(timings.ssl, 5.0);
+        assert_eq!(timings.send, 1.0);
+        assert_eq!(timings.wait, 20.0);
+        assert!((timings.receive - 19.0).abs() < 1e-6);
+        assert!((timings.total() - 50.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_recorder_entries_and_redirects() {
+        let mut recorder = HarRecorder::new();
+
+        recorder.on_request(&request_event("1", "http://a.com/?q=1", 10.0));
+
+        let mut redirect = request_event("1", "https://a.com/", 10.1);
+        let mut hop = response("http://a.com/?q=1", 301);
+        hop["headers"] = serde_json::json!({ "Location": "https://a.com/" });
+        redirect.redirect_response = Some(serde_json::from_value(hop).unwrap());
+        recorder.on_request(&redirect);
+
+        let received: EventResponseReceived = serde_json::from_value(serde_json::json!({
+            "requestId": "1",
+            "loaderId": "l",
+            "timestamp": 10.2,
+            "type": "Document",
+            "response": response("https://a.com/", 200),
+            "hasExtraInfo": false
+        }))
+        .unwrap();
+        recorder.on_response(&received);
+
+        let finished: EventLoadingFinished = serde_json::from_value(serde_json::json!({
+            "requestId": "1", "timestamp": 10.3, "encodedDataLength": 512.0
+        }))
+        .unwrap();
+        let index = recorder.on_finished(&finished).unwrap();
+        recorder.set_body(index, "<html></html>".into(), false);
+
+        recorder.on_request(&request_event("2", "https://a.com/x.js", 10.4));
+        let failed: EventLoadingFailed = serde_json::from_value(serde_json::json!({
+            "requestId": "2", "timestamp": 10.5, "type": "Script",
+            "errorText": "net::ERR_FAILED"
+        }))
+        .unwrap();
+        recorder.on_failed(&failed);
+
+        let har = recorder.finish("https://a.com/");
+        let entries = &har.log.entries;
+
+        assert_eq!(har.log.pages.len(), 1);
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].response.status, 301);
+        assert_eq!(entries[0].response.redirect_url, "https://a.com/");
+        assert_eq!(entries[0].request.query_string[0].name, "q");
+        assert_eq!(entries[0].request.cookies.len(), 2);
+        assert_eq!(entries[1].response.status, 200);
+        assert_eq!(entries[1].response.cookies.len(), 2);
+        assert_eq!(entries[1].response.content.size, 13);
+        assert_eq!(entries[1].transfer_size, 512.0);
+        assert_eq!(entries[1].server_ip_address.as_deref(), Some("127.0.0.1"));
+        assert_eq!(entries[2].response.status, 0);
+        assert_eq!(entries[2].error.as_deref(), Some("net::ERR_FAILED"));
+    }
+
+    #[test]
+    fn test_har_serializes_spec_names() {
+        let mut recorder = HarRecorder::new();
+        recorder.on_request(&request_event("1", "https://a.com/", 1.0));
+        let har = recorder.finish("a");
+
+        let value = serde_json::to_value(&har).unwrap();
+        let log = &value["log"];
+
+        assert_eq!(log["version"], "1.2");
+        assert_eq!(log["creator"]["name"], "spider");
+        assert_eq!(log["pages"][0]["pageTimings"]["onLoad"], -1.0);
+        assert!(log["entries"][0]["startedDateTime"]
+            .as_str()
+            .unwrap()
+            .starts_with("2023-11-14T"));
+        assert!(log["entries"][0]["response"].get("redirectURL").is_some());
+        assert!(log["entries"][0]["request"].get("queryString").is_some());
+
+        let back: Har = serde_json::from_value(value).unwrap();
+        assert_eq!(back, har);
+    }
+}
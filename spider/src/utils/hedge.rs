@@ -206,6 +206,11 @@ impl HedgeTracker {
         self.hedge_fires.load(Ordering::Relaxed)
     }
 
+    /// Get the number of hedge races the hedge won.
+    pub fn hedge_wins(&self) -> u64 {
+        self.hedge_wins.load(Ordering::Relaxed)
+    }
+
     /// Get the hedge win rate as a percentage (0–100). Returns 0 if no hedges fired.
     pub fn hedge_win_rate_pct(&self) -> u64 {
         let fires = self.hedge_fires.load(Ordering::Relaxed);
@@ -261,6 +266,12 @@ pub async fn hedge_race<T, E>(
     let delay = config.delay;
     let max_hedges = config.max_hedges.min(hedge_factories.len());
 
+    #[cfg(feature = "tracing")]
+    let primary = tracing::Instrument::instrument(
+        primary,
+        crate::utils::trace::backend_span("hedge.primary"),
+    );
+
     tokio::pin!(primary);
 
     // Phase 1: race primary against the delay timer
@@ -279,6 +290,11 @@ pub async fn hedge_race<T, E>(
     let mut hedge_iter = hedge_futs.into_iter().take(max_hedges);
 
     if let Some(first_hedge) = hedge_iter.next() {
+        #[cfg(feature = "tracing")]
+        let first_hedge = tracing::Instrument::instrument(
+            first_hedge,
+            crate::utils::trace::backend_span("hedge.secondary"),
+        );
         tokio::pin!(first_hedge);
 
         return tokio::select! {
@@ -320,6 +336,12 @@ where
     let delay = config.delay;
     let max_hedges = config.max_hedges.min(hedge_factories.len());
 
+    #[cfg(feature = "tracing")]
+    let primary = tracing::Instrument::instrument(
+        primary,
+        crate::utils::trace::backend_span("hedge.primary"),
+    );
+
     tokio::pin!(primary);
 
     // Phase 1: race primary against the delay timer
@@ -337,6 +359,11 @@ where
     let mut hedge_iter = hedge_futs.into_iter().take(max_hedges);
 
     if let Some(first_hedge) = hedge_iter.next() {
+        #[cfg(feature = "tracing")]
+        let first_hedge = tracing::Instrument::instrument(
+            first_hedge,
+            crate::utils::trace::backend_span("hedge.secondary"),
+        );
         tokio::pin!(first_hedge);
 
         tokio::select! {
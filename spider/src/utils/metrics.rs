@@ -0,0 +1,466 @@
+//! OpenMetrics (Prometheus) text exposition of crawl health.
+//!
+//! [`MetricsRegistry::render`] writes the process-wide vitals and spool
+//! counters followed by every registered collector: crawls registered with
+//! [`crate::website::Website::with_metrics`] report their hedge tracker,
+//! auto-throttle (labelled per domain) and concurrency permits, and
+//! adaptive concurrency controllers can be registered directly. Serve the
+//! output with [`CONTENT_TYPE`] from any HTTP endpoint.
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// The OpenMetrics text format content type.
+pub const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// A metric family type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricType {
+    /// A monotonically increasing total. Samples get the `_total` suffix.
+    Counter,
+    /// A value that can go up and down.
+    Gauge,
+}
+
+impl MetricType {
+    /// The `# TYPE` name.
+    fn as_str(&self) -> &'static str {
+        match self {
+            MetricType::Counter => "counter",
+            MetricType::Gauge => "gauge",
+        }
+    }
+}
+
+/// One metric family and its samples.
+#[derive(Debug)]
+struct Family {
+    /// The family name.
+    name: &'static str,
+    /// The family type.
+    kind: MetricType,
+    /// The `# HELP` text.
+    help: &'static str,
+    /// Rendered label sets and values.
+    samples: Vec<(String, String)>,
+}
+
+/// Collects samples grouped by family, since OpenMetrics requires all
+/// samples of a family to be contiguous.
+#[derive(Debug, Default)]
+pub struct MetricsEncoder {
+    /// Families in order of first use.
+    families: Vec<Family>,
+}
+
+impl MetricsEncoder {
+    /// A new empty encoder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a counter sample.
+    pub fn counter(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        value: u64,
+    ) {
+        self.sample(name, MetricType::Counter, help, labels, value.to_string());
+    }
+
+    /// Record a gauge sample.
+    pub fn gauge(
+        &mut self,
+        name: &'static str,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        value: f64,
+    ) {
+        self.sample(name, MetricType::Gauge, help, labels, value.to_string());
+    }
+
+    /// Record a sample under its family.
+    fn sample(
+        &mut self,
+        name: &'static str,
+        kind: MetricType,
+        help: &'static str,
+        labels: &[(&str, &str)],
+        value: String,
+    ) {
+        let family = match self.families.iter().position(|f| f.name == name) {
+            Some(index) => &mut self.families[index],
+            None => {
+                self.families.push(Family {
+                    name,
+                    kind,
+                    help,
+                    samples: Vec::new(),
+                });
+                self.families.last_mut().expect("family was just pushed")
+            }
+        };
+
+        family.samples.push((encode_labels(labels), value));
+    }
+
+    /// Render the exposition, terminated by `# EOF`.
+    pub fn finish(self) -> String {
+        let mut out = String::new();
+
+        for family in self.families {
+            let suffix = match family.kind {
+                MetricType::Counter => "_total",
+                MetricType::Gauge => "",
+            };
+            out.push_str(&format!(
+                "# TYPE {name} {kind}\n# HELP {name} {help}\n",
+                name = family.name,
+                kind = family.kind.as_str(),
+                help = family.help,
+            ));
+            for (labels, value) in family.samples {
+                out.push_str(&format!("{}{suffix}{labels} {value}\n", family.name));
+            }
+        }
+
+        out.push_str("# EOF\n");
+        out
+    }
+}
+
+/// Render a label set as `{a="x",b="y"}`, or nothing when empty.
+fn encode_labels(labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{key}=\"{value}\"")
+        })
+        .collect();
+
+    format!("{{{}}}", pairs.join(","))
+}
+
+/// Process-wide request, byte, page and tab counters.
+#[cfg(feature = "balance")]
+pub fn encode_vitals(encoder: &mut MetricsEncoder) {
+    let vitals = crate::utils::vitals::snapshot();
+
+    encoder.gauge(
+        "spider_requests_in_flight",
+        "HTTP and Chrome requests currently in flight.",
+        &[],
+        vitals.requests_in_flight as f64,
+    );
+    encoder.counter(
+        "spider_requests_completed",
+        "Requests completed since process start.",
+        &[],
+        vitals.requests_completed,
+    );
+    encoder.counter(
+        "spider_request_errors",
+        "Request errors since process start.",
+        &[],
+        vitals.request_errors,
+    );
+    encoder.gauge(
+        "spider_network_bytes_in_flight",
+        "Response bytes currently being streamed.",
+        &[],
+        vitals.network_bytes_in_flight as f64,
+    );
+    encoder.counter(
+        "spider_network_bytes_received",
+        "Response bytes received since process start.",
+        &[],
+        vitals.network_bytes_received,
+    );
+    encoder.gauge(
+        "spider_pages_in_memory",
+        "Pages currently held in memory.",
+        &[],
+        vitals.pages_in_memory as f64,
+    );
+    encoder.gauge(
+        "spider_chrome_tabs_active",
+        "Chrome tabs currently navigating or extracting.",
+        &[],
+        vitals.chrome_tabs_active as f64,
+    );
+}
+
+/// HTML spool memory and disk usage.
+#[cfg(feature = "balance")]
+pub fn encode_spool(encoder: &mut MetricsEncoder) {
+    encoder.gauge(
+        "spider_spool_memory_bytes",
+        "HTML bytes held in memory across pages.",
+        &[],
+        crate::utils::html_spool::total_bytes_in_memory() as f64,
+    );
+    encoder.gauge(
+        "spider_spool_pages_on_disk",
+        "Pages whose HTML is spooled to disk.",
+        &[],
+        crate::utils::html_spool::pages_on_disk() as f64,
+    );
+}
+
+/// Hedged request statistics of one crawl.
+#[cfg(feature = "hedge")]
+pub fn encode_hedge(
+    encoder: &mut MetricsEncoder,
+    domain: &str,
+    tracker: &crate::utils::hedge::HedgeTracker,
+) {
+    let labels = [("domain", domain)];
+
+    encoder.gauge(
+        "spider_hedge_latency_ema_milliseconds",
+        "EMA of page fetch durations.",
+        &labels,
+        tracker.ema_ms() as f64,
+    );
+    encoder.counter(
+        "spider_hedge_samples",
+        "Fetch durations recorded by the hedge tracker.",
+        &labels,
+        tracker.sample_count(),
+    );
+    encoder.counter(
+        "spider_hedge_fires",
+        "Hedge requests fired.",
+        &labels,
+        tracker.hedge_fires(),
+    );
+    encoder.counter(
+        "spider_hedge_wins",
+        "Hedge requests that finished first.",
+        &labels,
+        tracker.hedge_wins(),
+    );
+    encoder.gauge(
+        "spider_hedge_consecutive_errors",
+        "Consecutive retryable errors.",
+        &labels,
+        tracker.consecutive_errors() as f64,
+    );
+}
+
+/// Per-domain latency and delay of an auto-throttle.
+#[cfg(feature = "auto_throttle")]
+pub fn encode_auto_throttle(
+    encoder: &mut MetricsEncoder,
+    throttle: &crate::utils::auto_throttle::AutoThrottle,
+) {
+    for (domain, latency_ms) in throttle.domain_latencies() {
+        let labels = [("domain", domain.as_str())];
+
+        encoder.gauge(
+            "spider_throttle_latency_milliseconds",
+            "EMA response latency per domain.",
+            &labels,
+            latency_ms,
+        );
+        encoder.gauge(
+            "spider_throttle_delay_milliseconds",
+            "Crawl delay computed per domain.",
+            &labels,
+            throttle.delay_for(&domain).as_millis() as f64,
+        );
+    }
+}
+
+/// Limits of an AIMD concurrency controller.
+#[cfg(feature = "adaptive_concurrency")]
+pub fn encode_adaptive_concurrency(
+    encoder: &mut MetricsEncoder,
+    name: &str,
+    controller: &crate::utils::adaptive_concurrency::AIMDController,
+) {
+    let labels = [("controller", name)];
+
+    encoder.gauge(
+        "spider_adaptive_concurrency_limit",
+        "Current AIMD concurrency limit.",
+        &labels,
+        controller.current_limit() as f64,
+    );
+    encoder.gauge(
+        "spider_adaptive_concurrency_min_limit",
+        "Minimum AIMD concurrency limit.",
+        &labels,
+        controller.min_limit() as f64,
+    );
+    encoder.gauge(
+        "spider_adaptive_concurrency_max_limit",
+        "Maximum AIMD concurrency limit.",
+        &labels,
+        controller.max_limit() as f64,
+    );
+}
+
+/// A registered collector.
+type Collector = Arc<dyn Fn(&mut MetricsEncoder) + Send + Sync>;
+
+/// Named collectors rendered after the process-wide metrics. Clones share
+/// the same collectors.
+#[derive(Clone, Default)]
+pub struct MetricsRegistry {
+    /// Collectors by key; registering a key again replaces it.
+    collectors: Arc<RwLock<Vec<(String, Collector)>>>,
+}
+
+impl std::fmt::Debug for MetricsRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetricsRegistry")
+            .field("collectors", &self.len())
+            .finish()
+    }
+}
+
+impl MetricsRegistry {
+    /// A new empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a collector under `key`, replacing an earlier one.
+    pub fn register<F>(&self, key: impl Into<String>, collector: F)
+    where
+        F: Fn(&mut MetricsEncoder) + Send + Sync + 'static,
+    {
+        let key = key.into();
+        let collector: Collector = Arc::new(collector);
+
+        if let Ok(mut collectors) = self.collectors.write() {
+            match collectors.iter_mut().find(|(k, _)| *k == key) {
+                Some(entry) => entry.1 = collector,
+                None => collectors.push((key, collector)),
+            }
+        }
+    }
+
+    /// Remove the collector registered under `key`.
+    pub fn unregister(&self, key: &str) {
+        if let Ok(mut collectors) = self.collectors.write() {
+            collectors.retain(|(k, _)| k != key);
+        }
+    }
+
+    /// Register an AIMD controller, labelled `controller="<name>"`.
+    #[cfg(feature = "adaptive_concurrency")]
+    pub fn register_adaptive_concurrency(
+        &self,
+        name: impl Into<String>,
+        controller: Arc<crate::utils::adaptive_concurrency::AIMDController>,
+    ) {
+        let name = name.into();
+        self.register(format!("adaptive_concurrency:{name}"), move |encoder| {
+            encode_adaptive_concurrency(encoder, &name, &controller)
+        });
+    }
+
+    /// Number of registered collectors.
+    pub fn len(&self) -> usize {
+        self.collectors.read().map(|c| c.len()).unwrap_or_default()
+    }
+
+    /// No collector is registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Render the process-wide metrics and every collector.
+    pub fn render(&self) -> String {
+        let mut encoder = MetricsEncoder::new();
+
+        #[cfg(feature = "balance")]
+        {
+            encode_vitals(&mut encoder);
+            encode_spool(&mut encoder);
+        }
+
+        let collectors: Vec<Collector> = self
+            .collectors
+            .read()
+            .map(|c| c.iter().map(|(_, collector)| collector.clone()).collect())
+            .unwrap_or_default();
+
+        for collector in collectors {
+            collector(&mut encoder);
+        }
+
+        encoder.finish()
+    }
+}
+
+/// The process-wide registry, e.g. for a `/metrics` endpoint.
+pub fn global() -> &'static MetricsRegistry {
+    static GLOBAL: OnceLock<MetricsRegistry> = OnceLock::new();
+    GLOBAL.get_or_init(MetricsRegistry::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoder_groups_families() {
+        let mut encoder = MetricsEncoder::new();
+        encoder.counter("spider_hits", "Hits.", &[("domain", "a.com")], 3);
+        encoder.gauge("spider_depth", "Depth.", &[], 1.5);
+        encoder.counter("spider_hits", "Hits.", &[("domain", "b\"c.com")], 4);
+
+        assert_eq!(
+            encoder.finish(),
+            "# TYPE spider_hits counter\n\
+             # HELP spider_hits Hits.\n\
+             spider_hits_total{domain=\"a.com\"} 3\n\
+             spider_hits_total{domain=\"b\\\"c.com\"} 4\n\
+             # TYPE spider_depth gauge\n\
+             # HELP spider_depth Depth.\n\
+             spider_depth 1.5\n\
+             # EOF\n"
+        );
+    }
+
+    #[test]
+    fn test_registry_replaces_by_key() {
+        let registry = MetricsRegistry::new();
+        registry.register("crawl", |e| e.gauge("spider_test_a", "A.", &[], 1.0));
+        registry.register("crawl", |e| e.gauge("spider_test_b", "B.", &[], 2.0));
+
+        let out = registry.render();
+        assert_eq!(registry.len(), 1);
+        assert!(!out.contains("spider_test_a"));
+        assert!(out.contains("spider_test_b 2\n"));
+        assert!(out.ends_with("# EOF\n"));
+
+        registry.unregister("crawl");
+        assert!(registry.is_empty());
+    }
+
+    #[cfg(feature = "adaptive_concurrency")]
+    #[test]
+    fn test_adaptive_concurrency_metrics() {
+        let registry = MetricsRegistry::new();
+        registry.register_adaptive_concurrency(
+            "main",
+            Arc::new(crate::utils::adaptive_concurrency::AIMDController::with_defaults(8, 64)),
+        );
+
+        assert!(registry
+            .render()
+            .contains("spider_adaptive_concurrency_limit{controller=\"main\"} 8\n"));
+    }
+}
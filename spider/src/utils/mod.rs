@@ -7,6 +7,9 @@ pub mod css_selectors;
 /// Fragment templates.
 pub mod templates;
 
+#[cfg(feature = "accessibility")]
+/// Accessibility tree extraction and automated a11y audit for Chrome pages.
+pub mod accessibility;
 #[cfg(feature = "adaptive_concurrency")]
 /// AIMD-based adaptive concurrency controller.
 pub mod adaptive_concurrency;
@@ -18,6 +21,17 @@ pub mod backoff;
 #[cfg(feature = "bloom")]
 /// mmap-backed bloom filter with hugepage support for URL deduplication.
 pub mod bloom;
+#[cfg(feature = "browser_logs")]
+/// Browser console messages, JS exceptions and failed resources for Chrome pages.
+pub mod browser_logs;
+/// Canonical URL discovery and canonical-aware page deduplication.
+pub mod canonical;
+#[cfg(feature = "checkpoint")]
+/// Crawl checkpoints for persisting and resuming crawl state.
+pub mod checkpoint;
+#[cfg(feature = "cluster")]
+/// Distributed crawl coordination across worker nodes.
+pub mod cluster;
 #[cfg(feature = "request_coalesce")]
 /// Request coalescing to dedup concurrent in-flight requests.
 pub mod coalesce;
@@ -32,12 +46,21 @@ pub mod dns_cache;
 #[cfg(feature = "etag_cache")]
 /// ETag / conditional-request cache for bandwidth-efficient re-crawls.
 pub mod etag_cache;
+#[cfg(feature = "feeds")]
+/// RSS, Atom and JSON Feed parsing and feed-driven crawl seeding.
+pub mod feeds;
 #[cfg(feature = "priority_frontier")]
 /// Prioritized URL frontier with dedup and optional domain round-robin.
 pub mod frontier;
+#[cfg(feature = "frontier_store")]
+/// Pluggable frontier storage backends.
+pub mod frontier_store;
 #[cfg(feature = "h2_multiplex")]
 /// HTTP/2 multiplexing tracker for per-origin stream management.
 pub mod h2_tracker;
+#[cfg(feature = "har")]
+/// HAR 1.2 capture for Chrome pages.
+pub mod har;
 /// Utils to modify the HTTP header.
 pub mod header_utils;
 #[cfg(feature = "hedge")]
@@ -52,21 +75,52 @@ pub mod interner;
 pub mod lazy_arc;
 /// Media-asset URL classification (no policy / no business semantics).
 pub mod media_asset;
+#[cfg(feature = "metrics")]
+/// OpenMetrics (Prometheus) exposition of crawl vitals.
+pub mod metrics;
+#[cfg(feature = "monitor")]
+/// Change detection with structured page diffs across crawl runs.
+pub mod monitor;
+/// SimHash near-duplicate page detection.
+pub mod near_duplicate;
 #[cfg(feature = "numa")]
 /// NUMA-aware thread pinning for multi-socket servers.
 pub mod numa;
+#[cfg(feature = "otel")]
+/// OTLP/HTTP exporter and W3C `traceparent` propagation for crawl spans.
+pub mod otlp;
 #[cfg(feature = "parallel_backends")]
 /// Parallel crawl backends — race alternative engines alongside the primary crawl.
 pub mod parallel_backends;
+#[cfg(feature = "politeness")]
+/// Per-host politeness scheduler for robots.txt delays, concurrency caps and `Retry-After`.
+pub mod politeness;
 #[cfg(feature = "rate_limit")]
 /// Per-domain token bucket rate limiter.
 pub mod rate_limiter;
+#[cfg(feature = "rich_metadata")]
+/// Open Graph, Twitter card, `hreflang` and JSON-LD page metadata.
+pub mod rich_metadata;
 #[cfg(feature = "robots_cache")]
 /// Cross-crawl robots.txt cache with TTL-based expiry.
 pub mod robots_cache;
+/// Robots meta tag and `X-Robots-Tag` directives.
+pub mod robots_meta;
+#[cfg(feature = "site_export")]
+/// `sitemap.xml` and `llms.txt` generation from a completed crawl.
+pub mod site_export;
+#[cfg(feature = "sitemap")]
+/// Sitemap entry metadata, index depth limits and `.xml.gz` decoding.
+pub mod sitemap_meta;
 #[cfg(feature = "chrome")]
 /// Chrome tab pooling for reusing CDP tabs across page visits.
 pub mod tab_pool;
+#[cfg(any(feature = "monitor", feature = "site_export"))]
+/// Visible text extraction from HTML documents.
+pub mod text;
+#[cfg(feature = "tracing")]
+/// Structured crawl, page, attempt and backend spans.
+pub mod trace;
 /// A trie struct.
 pub mod trie;
 /// Async file I/O with optional io_uring acceleration.
@@ -82,6 +136,9 @@ pub mod wait_guard;
 #[cfg(feature = "warc")]
 /// WARC 1.1 file writer for web archive output.
 pub mod warc;
+#[cfg(feature = "web_vitals")]
+/// Core Web Vitals and performance metrics for Chrome pages.
+pub mod web_vitals;
 #[cfg(feature = "zero_copy")]
 /// Zero-copy byte-level parsing for HTTP wire formats and protocol structures.
 pub mod zero_copy;
@@ -576,6 +633,32 @@ where
     }
 }
 
+/// Send an HTTP request. With `tracing` the send runs inside an `http`
+/// backend span, and with `otel` the span's `traceparent` is stamped on
+/// the request when the exporter asks for propagation.
+#[inline]
+pub(crate) fn send_request(
+    req: crate::client::request_client::RequestBuilder,
+) -> impl std::future::Future<Output = Result<Response, RequestError>> {
+    #[cfg(feature = "tracing")]
+    {
+        use tracing::Instrument;
+        let span = trace::backend_span("http");
+        #[cfg(feature = "otel")]
+        let req = otlp::inject_traceparent(req, &span);
+        async move {
+            let res = req.send().await;
+            if let Ok(res) = &res {
+                tracing::Span::current().record("http.response.status_code", res.status().as_u16());
+            }
+            res
+        }
+        .instrument(span)
+    }
+    #[cfg(not(feature = "tracing"))]
+    req.send()
+}
+
 /// Build a synthetic `524 GATEWAY_TIMEOUT` PageResponse for an HTTP
 /// fetch that exceeded its first-byte budget. Mirrors the chrome-side
 /// timeout handling so the caller's retry path (`should_retry =
@@ -623,6 +706,12 @@ pub struct PageResponse {
     #[cfg(feature = "chrome")]
     /// The screenshot bytes of the page. The ScreenShotConfig bytes boolean needs to be set to true.
     pub screenshot_bytes: Option<Vec<u8>>,
+    #[cfg(feature = "chrome")]
+    /// The PDF bytes of the page. The PdfConfig bytes boolean needs to be set to true.
+    pub pdf_bytes: Option<Vec<u8>>,
+    #[cfg(feature = "chrome")]
+    /// The MHTML snapshot bytes of the page. The MhtmlConfig bytes boolean needs to be set to true.
+    pub mhtml_bytes: Option<Vec<u8>>,
     #[cfg(feature = "openai")]
     /// The credits used from OpenAI in order.
     pub openai_credits_used: Option<Vec<crate::features::openai_common::OpenAIUsage>>,
@@ -653,6 +742,18 @@ pub struct PageResponse {
     #[cfg(feature = "chrome")]
     /// All of the request events mapped with the time period of the event sent.
     pub request_map: Option<HashMap<String, f64>>,
+    #[cfg(feature = "har")]
+    /// The HAR 1.2 network log of the page.
+    pub har: Option<Box<crate::utils::har::Har>>,
+    #[cfg(feature = "browser_logs")]
+    /// The browser console messages, JS exceptions and failed resources of the page.
+    pub browser_logs: Option<Vec<crate::utils::browser_logs::BrowserLog>>,
+    #[cfg(feature = "web_vitals")]
+    /// The Core Web Vitals and performance metrics of the page.
+    pub web_vitals: Option<Box<crate::utils::web_vitals::WebVitals>>,
+    #[cfg(feature = "accessibility")]
+    /// The accessibility audit of the page.
+    pub accessibility: Option<Box<crate::utils::accessibility::AccessibilityReport>>,
     /// The anti-bot tech used.
     pub anti_bot_tech: crate::page::AntiBotTech,
     /// The metadata of the page.
@@ -3765,6 +3866,10 @@ pub struct ChromeFetchParams<'a> {
     pub wait_for: &'a Option<crate::configuration::WaitFor>,
     /// Screenshot configuration, if any.
     pub screenshot: &'a Option<crate::configuration::ScreenShotConfig>,
+    /// PDF configuration, if any.
+    pub pdf: &'a Option<crate::configuration::PdfConfig>,
+    /// MHTML snapshot configuration, if any.
+    pub mhtml: &'a Option<crate::configuration::MhtmlConfig>,
     /// OpenAI configuration for content extraction.
     pub openai_config: &'a Option<Box<crate::configuration::GPTConfigs>>,
     /// Per-URL execution scripts trie.
@@ -3829,6 +3934,8 @@ pub struct ChromeFetchParams<'a> {
     /// first-byte watchdog passes this URL to
     /// `LazyChromeFailover::mark_url_bad` on timeout.
     pub chrome_endpoint_url: Option<&'a str>,
+    /// The `nofollow` signals honored by the streaming link extractor.
+    pub nofollow: crate::utils::robots_meta::NofollowPolicy,
 }
 
 #[cfg(feature = "chrome")]
@@ -3855,6 +3962,17 @@ impl<'a> ChromeFetchParams<'a> {
     }
 }
 
+/// The next event of an optional CDP listener, pending forever once closed.
+#[cfg(any(feature = "har", feature = "browser_logs"))]
+pub(crate) async fn next_optional_event<S: tokio_stream::Stream + Unpin>(
+    stream: &mut Option<S>,
+) -> Option<S::Item> {
+    match stream {
+        Some(s) => s.next().await,
+        _ => std::future::pending().await,
+    }
+}
+
 #[cfg(feature = "chrome")]
 /// Perform a network request to a resource extracting all content as text streaming via chrome.
 ///
@@ -3870,6 +3988,10 @@ impl<'a> ChromeFetchParams<'a> {
 /// before; the streaming hook is a crate-internal optimisation surfaced
 /// only to `chrome_page_fetch!` macro plumbing.
 #[allow(private_interfaces)]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "backend", skip_all, fields(spider.backend = "chrome"))
+)]
 pub async fn fetch_page_html_chrome_base<'h>(
     source: &[u8],
     page: &chromiumoxide::Page,
@@ -3889,6 +4011,8 @@ pub async fn fetch_page_html_chrome_base<'h>(
 ) -> Result<PageResponse, chromiumoxide::error::CdpError> {
     let wait_for = params.wait_for;
     let screenshot = params.screenshot;
+    let pdf = params.pdf;
+    let mhtml = params.mhtml;
     let openai_config = params.openai_config;
     let execution_scripts = params.execution_scripts;
     let automation_scripts = params.automation_scripts;
@@ -4087,6 +4211,34 @@ pub async fn fetch_page_html_chrome_base<'h>(
     // so listeners exit deterministically instead of relying on stream closure.
     let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
 
+    // Record the HAR network log alongside the metric listeners.
+    #[cfg(feature = "har")]
+    let har_handle = match track_events.as_ref().and_then(|t| t.har.clone()) {
+        Some(config) => {
+            Some(crate::utils::har::spawn_recorder(page, config, shutdown_rx.clone()).await)
+        }
+        _ => None,
+    };
+
+    #[cfg(feature = "browser_logs")]
+    let browser_logs_handle = match track_events.as_ref().and_then(|t| t.browser_logs.clone()) {
+        Some(config) => Some(
+            crate::utils::browser_logs::spawn_collector(page, config, shutdown_rx.clone()).await,
+        ),
+        _ => None,
+    };
+
+    #[cfg(feature = "web_vitals")]
+    let track_web_vitals = track_events.as_ref().is_some_and(|t| t.web_vitals);
+
+    #[cfg(feature = "web_vitals")]
+    if track_web_vitals {
+        crate::utils::web_vitals::install(page).await;
+    }
+
+    #[cfg(feature = "accessibility")]
+    let accessibility_config = track_events.as_ref().and_then(|t| t.accessibility.clone());
+
     // Listen for network events to track data transfer.
     // Spawning is always required here to collect network metrics in real-time.
     let first_byte_signal_for_spawn = first_byte_signal.clone();
@@ -5207,6 +5359,46 @@ pub async fn fetch_page_html_chrome_base<'h>(
                 .await;
             }
 
+            if pdf.is_some() {
+                let _ = tokio::time::timeout(
+                    base_timeout + tokio::time::Duration::from_secs(30),
+                    perform_pdf(target_url, page, pdf, &mut page_response),
+                )
+                .await;
+            }
+
+            if mhtml.is_some() {
+                let _ = tokio::time::timeout(
+                    base_timeout + tokio::time::Duration::from_secs(30),
+                    perform_mhtml(target_url, page, mhtml, &mut page_response),
+                )
+                .await;
+            }
+
+            #[cfg(feature = "web_vitals")]
+            if track_web_vitals {
+                if let Ok(vitals) = tokio::time::timeout(
+                    base_timeout.min(Duration::from_secs(10)),
+                    crate::utils::web_vitals::collect(page),
+                )
+                .await
+                {
+                    page_response.web_vitals = vitals.map(Box::new);
+                }
+            }
+
+            #[cfg(feature = "accessibility")]
+            if let Some(config) = accessibility_config.as_ref() {
+                if let Ok(report) = tokio::time::timeout(
+                    base_timeout.min(Duration::from_secs(20)),
+                    crate::utils::accessibility::collect(page, config),
+                )
+                .await
+                {
+                    page_response.accessibility = report.map(Box::new);
+                }
+            }
+
             if metadata.is_some() {
                 let mut default_metadata = Metadata::default();
                 default_metadata.automation = metadata;
@@ -5703,6 +5895,36 @@ pub async fn fetch_page_html_chrome_base<'h>(
         }
     }
 
+    #[cfg(feature = "har")]
+    if let Some(har_handle) = har_handle {
+        // the page may be kept open with `chrome_store_page`, stop the recorder either way.
+        let _ = shutdown_tx.send(true);
+
+        if let Ok(Ok(recorder)) =
+            tokio::time::timeout(base_timeout.min(Duration::from_secs(30)), har_handle).await
+        {
+            let title = match &page_response.final_url {
+                Some(final_url) if !final_url.is_empty() => final_url,
+                _ => target_url,
+            };
+            page_response.har = Some(Box::new(recorder.finish(title)));
+        }
+    }
+
+    #[cfg(feature = "browser_logs")]
+    if let Some(browser_logs_handle) = browser_logs_handle {
+        let _ = shutdown_tx.send(true);
+
+        if let Ok(Ok(logs)) = tokio::time::timeout(
+            base_timeout.min(Duration::from_secs(30)),
+            browser_logs_handle,
+        )
+        .await
+        {
+            page_response.browser_logs = Some(logs);
+        }
+    }
+
     // Fallback body classification — parity with HTTP path
     // (`handle_response_bytes`).  The block at 5550 only runs when CDP
     // populated `response_map`; when that arm is empty (no
@@ -5978,6 +6200,86 @@ pub async fn perform_screenshot(
     }
 }
 
+/// Print the page as PDF.
+#[cfg(feature = "chrome")]
+pub async fn perform_pdf(
+    target_url: &str,
+    page: &chromiumoxide::Page,
+    pdf: &Option<crate::configuration::PdfConfig>,
+    page_response: &mut PageResponse,
+) {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+
+    if let Some(pdf) = pdf {
+        let params =
+            chromiumoxide::cdp::browser_protocol::page::PrintToPdfParams::from(pdf.params.clone());
+
+        match page.execute(params).await {
+            Ok(b) => {
+                if let Ok(b) = STANDARD.decode(&b.data) {
+                    if pdf.save {
+                        let output_path = create_output_path(
+                            &pdf.output_dir
+                                .clone()
+                                .unwrap_or_else(|| "./storage/".into()),
+                            target_url,
+                            ".pdf",
+                        )
+                        .await;
+                        let _ = uring_fs::write_file(output_path, b.to_vec()).await;
+                    }
+                    if pdf.bytes {
+                        page_response.pdf_bytes = Some(b);
+                    }
+                }
+            }
+            Err(e) => {
+                log::error!("failed to print pdf: {:?} - {:?}", e, target_url)
+            }
+        }
+    }
+}
+
+/// Capture an MHTML snapshot of the page.
+#[cfg(feature = "chrome")]
+pub async fn perform_mhtml(
+    target_url: &str,
+    page: &chromiumoxide::Page,
+    mhtml: &Option<crate::configuration::MhtmlConfig>,
+    page_response: &mut PageResponse,
+) {
+    if let Some(mhtml) = mhtml {
+        match page
+            .execute(chromiumoxide::cdp::browser_protocol::page::CaptureSnapshotParams::default())
+            .await
+        {
+            Ok(snapshot) => {
+                let b = snapshot.result.data.into_bytes();
+
+                if mhtml.save {
+                    let output_path = create_output_path(
+                        &mhtml
+                            .output_dir
+                            .clone()
+                            .unwrap_or_else(|| "./storage/".into()),
+                        target_url,
+                        ".mhtml",
+                    )
+                    .await;
+                    let _ = uring_fs::write_file(output_path, b.clone()).await;
+                }
+                if mhtml.bytes {
+                    page_response.mhtml_bytes = Some(b);
+                }
+            }
+            Err(e) => {
+                log::error!("failed to capture mhtml: {:?} - {:?}", e, target_url)
+            }
+        }
+    }
+}
+
 #[cfg(feature = "chrome")]
 /// Check if url matches the last item in a redirect chain for chrome CDP
 pub async fn get_last_redirect(
@@ -6599,7 +6901,7 @@ async fn fetch_page_html_raw_base(
         // reqwest_middleware backends because the inner `?` propagates
         // each variant's own `RequestError` type alias.
         match timeout_first_byte(
-            client.get(url).send(),
+            send_request(client.get(url)),
             first_byte_timeout,
             first_byte_jitter,
         )
@@ -6764,7 +7066,7 @@ pub async fn fetch_page_html_raw_conditional(
         }
     }
 
-    let mut page_response = match req.send().await {
+    let mut page_response = match send_request(req).await {
         Ok(res) => {
             if res.status() == StatusCode::NOT_MODIFIED {
                 // 304 — content unchanged, no body to process.
@@ -9495,6 +9797,8 @@ where
     F: std::future::Future + Send + 'static,
     F::Output: Send + 'static,
 {
+    #[cfg(feature = "tracing")]
+    let future = trace::instrument_task(_task_name, future);
     tokio::task::spawn(future)
 }
 
@@ -9516,6 +9820,8 @@ where
     // paths, `Page::build`) all see the same handle.  A single atomic
     // `Arc` clone — no mutex, no alloc.  When the caller is not inside
     // a website scope (tests, ad-hoc uses) this is a no-op.
+    #[cfg(feature = "tracing")]
+    let future = trace::instrument_task(_task_name, future);
     #[cfg(feature = "balance")]
     {
         if let Some(dir) = crate::utils::html_spool::current_website_spool_dir() {
@@ -0,0 +1,805 @@
+//! Change detection across crawl runs.
+//!
+//! Feature-gated behind `monitor`. With a [`MonitorConfig`] set, every crawl
+//! loads the page snapshots stored by the previous run, compares each page
+//! it fetches against them and broadcasts a [`PageChange`] for pages that
+//! were added, modified or answered with a different status. Once the crawl
+//! finishes, pages of the previous run that were not crawled again are
+//! reported as removed and the new snapshots replace the stored ones, so a
+//! `with_cron` crawl diffs each run against the one before it.
+//!
+//! A [`PageSnapshot`] keeps the status code, the visible text of the page
+//! and its fingerprint. Only the fingerprints are compared, so markup churn
+//! such as rotating nonces or reordered attributes does not count as a
+//! change; the lines added and removed on a modified page are reported as a
+//! [`TextDiff`].
+//!
+//! Pages are snapshotted as they are taken off the crawl channel and only
+//! the snapshots are queued, on a bounded queue, for diffing.
+//!
+//! Snapshots are stored in a line-oriented text file, or in a SQLite
+//! database with the `disk` feature.
+
+use crate::page::Page;
+use hashbrown::HashMap;
+use std::io::{self, Write};
+use std::path::Path;
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+pub use crate::utils::text::extract_text;
+
+/// Magic header identifying a snapshot file and its format version.
+const MAGIC: &str = "SPIDER-MONITOR/3";
+
+/// Default cap on the text kept per page.
+pub const DEFAULT_MAX_TEXT_BYTES: usize = 64 * 1024;
+
+/// Snapshots waiting to be diffed. Past it the channel receiver waits, and
+/// pages it falls behind on are counted as missed.
+const QUEUE_CAPACITY: usize = 64;
+
+/// Where page snapshots are kept between runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MonitorStore {
+    /// A snapshot file at the path.
+    File(String),
+    #[cfg(feature = "disk")]
+    /// A SQLite database at the path.
+    Sqlite(String),
+}
+
+impl MonitorStore {
+    /// Load the snapshots of the previous run. A missing store is empty.
+    pub async fn load(&self) -> io::Result<HashMap<String, PageSnapshot>> {
+        match self {
+            Self::File(path) => match crate::utils::uring_fs::read_file(path.clone()).await {
+                Ok(bytes) => decode_snapshots(&bytes),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(HashMap::new()),
+                Err(e) => Err(e),
+            },
+            #[cfg(feature = "disk")]
+            Self::Sqlite(path) => sqlite::load(path).await.map_err(io::Error::other),
+        }
+    }
+
+    /// Replace the stored snapshots.
+    pub async fn save(&self, snapshots: &HashMap<String, PageSnapshot>) -> io::Result<()> {
+        match self {
+            Self::File(path) => write_atomic(Path::new(path), encode_snapshots(snapshots)).await,
+            #[cfg(feature = "disk")]
+            Self::Sqlite(path) => sqlite::save(path, snapshots)
+                .await
+                .map_err(io::Error::other),
+        }
+    }
+}
+
+/// Change detection settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonitorConfig {
+    /// Where snapshots are kept between runs.
+    pub store: MonitorStore,
+    /// Cap on the text kept per page, in bytes.
+    pub max_text_bytes: usize,
+}
+
+impl MonitorConfig {
+    /// Keep snapshots in a file at `path`.
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            store: MonitorStore::File(path.into()),
+            max_text_bytes: DEFAULT_MAX_TEXT_BYTES,
+        }
+    }
+
+    #[cfg(feature = "disk")]
+    /// Keep snapshots in a SQLite database at `path`.
+    pub fn sqlite(path: impl Into<String>) -> Self {
+        Self {
+            store: MonitorStore::Sqlite(path.into()),
+            max_text_bytes: DEFAULT_MAX_TEXT_BYTES,
+        }
+    }
+
+    /// Set the cap on the text kept per page.
+    pub fn with_max_text_bytes(mut self, max_text_bytes: usize) -> Self {
+        self.max_text_bytes = max_text_bytes;
+        self
+    }
+}
+
+/// What a crawl saw of a page.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PageSnapshot {
+    /// The page url.
+    pub url: String,
+    /// The HTTP status code.
+    pub status: u16,
+    /// Fingerprint of the visible text, or of the body when it has none.
+    pub fingerprint: u64,
+    /// The visible text of the page, capped at [`MonitorConfig::max_text_bytes`].
+    pub text: String,
+}
+
+impl PageSnapshot {
+    /// Snapshot a response body.
+    pub fn new(url: impl Into<String>, status: u16, body: &str, max_text_bytes: usize) -> Self {
+        let mut text = extract_text(body);
+        let fingerprint = if text.is_empty() {
+            spider_agent_types::fnv1a64(body.as_bytes())
+        } else {
+            spider_agent_types::fnv1a64(text.as_bytes())
+        };
+
+        let mut end = text.len().min(max_text_bytes);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.shrink_to_fit();
+
+        Self {
+            url: url.into(),
+            status,
+            fingerprint,
+            text,
+        }
+    }
+
+    /// The lines of `current` added and removed against this snapshot.
+    pub fn diff(&self, current: &PageSnapshot) -> TextDiff {
+        TextDiff::new(&self.text, &current.text)
+    }
+}
+
+/// The lines of text added and removed between two crawls of a page, in
+/// page order. Moved lines are not reported.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextDiff {
+    /// Lines of the current text missing from the previous one.
+    pub added: Vec<String>,
+    /// Lines of the previous text missing from the current one.
+    pub removed: Vec<String>,
+}
+
+impl TextDiff {
+    /// Diff two texts line by line.
+    pub fn new(previous: &str, current: &str) -> Self {
+        let lines = |text: &str| -> Vec<String> {
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+
+        let previous = lines(previous);
+        let current = lines(current);
+
+        Self {
+            added: unmatched(&current, &previous),
+            removed: unmatched(&previous, &current),
+        }
+    }
+
+    /// Neither line was added nor removed.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// The lines of `lines` without a counterpart in `other`, each line of
+/// `other` matching at most once.
+fn unmatched(lines: &[String], other: &[String]) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::with_capacity(other.len());
+
+    for line in other {
+        *counts.entry(line.as_str()).or_default() += 1;
+    }
+
+    lines
+        .iter()
+        .filter(|line| match counts.get_mut(line.as_str()) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect()
+}
+
+/// A difference between the current and the previous crawl of a page.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PageChange {
+    /// The page was not crawled by the previous run.
+    Added {
+        /// The page url.
+        url: String,
+        /// The HTTP status code.
+        status: u16,
+    },
+    /// The page was crawled by the previous run but not by this one.
+    Removed {
+        /// The page url.
+        url: String,
+        /// The HTTP status code of the previous run.
+        status: u16,
+    },
+    /// The visible text of the page changed.
+    Modified {
+        /// The page url.
+        url: String,
+        /// The lines of text added and removed since the previous run.
+        diff: TextDiff,
+    },
+    /// The page answered with a different status code.
+    StatusChanged {
+        /// The page url.
+        url: String,
+        /// The status code of the previous run.
+        from: u16,
+        /// The current status code.
+        to: u16,
+    },
+}
+
+impl PageChange {
+    /// The url of the changed page.
+    pub fn url(&self) -> &str {
+        match self {
+            Self::Added { url, .. }
+            | Self::Removed { url, .. }
+            | Self::Modified { url, .. }
+            | Self::StatusChanged { url, .. } => url,
+        }
+    }
+}
+
+/// Compares the pages of a crawl against the snapshots of the previous run.
+#[derive(Debug, Default)]
+pub struct ChangeMonitor {
+    /// Snapshots of the previous run.
+    previous: HashMap<String, PageSnapshot>,
+    /// Snapshots of the current run.
+    current: HashMap<String, PageSnapshot>,
+    /// The crawl was cut short.
+    incomplete: bool,
+}
+
+impl ChangeMonitor {
+    /// Monitor against the `previous` snapshots.
+    pub fn new(previous: HashMap<String, PageSnapshot>) -> Self {
+        Self {
+            previous,
+            ..Default::default()
+        }
+    }
+
+    /// Record a snapshot of the current run and return how it differs from
+    /// the previous run. Pages seen more than once are compared once.
+    pub fn observe(&mut self, snapshot: PageSnapshot) -> Vec<PageChange> {
+        let mut changes = Vec::new();
+
+        if self.current.contains_key(&snapshot.url) {
+            return changes;
+        }
+
+        match self.previous.get(&snapshot.url) {
+            None => changes.push(PageChange::Added {
+                url: snapshot.url.clone(),
+                status: snapshot.status,
+            }),
+            Some(previous) => {
+                if previous.status != snapshot.status {
+                    changes.push(PageChange::StatusChanged {
+                        url: snapshot.url.clone(),
+                        from: previous.status,
+                        to: snapshot.status,
+                    });
+                }
+                if previous.fingerprint != snapshot.fingerprint {
+                    changes.push(PageChange::Modified {
+                        url: snapshot.url.clone(),
+                        diff: previous.diff(&snapshot),
+                    });
+                }
+            }
+        }
+
+        self.current.insert(snapshot.url.clone(), snapshot);
+        changes
+    }
+
+    /// Mark the current run as cut short, e.g. after a timeout or a
+    /// shutdown. Removals are not reported for an incomplete run and unseen
+    /// pages keep their previous snapshot.
+    pub fn set_incomplete(&mut self) {
+        self.incomplete = true;
+    }
+
+    /// End the run, returning the removed pages and the snapshots to store.
+    /// A page of the previous run is removed when the crawl did not
+    /// `visit` it; a visited page that was never observed, such as one
+    /// missed after the channel lagged, keeps its previous snapshot.
+    pub fn finish(
+        self,
+        visited: impl Fn(&str) -> bool,
+    ) -> (Vec<PageChange>, HashMap<String, PageSnapshot>) {
+        let Self {
+            previous,
+            mut current,
+            incomplete,
+        } = self;
+        let mut removed = Vec::new();
+
+        for (url, snapshot) in previous {
+            if current.contains_key(&url) {
+                continue;
+            }
+            if incomplete || visited(&url) {
+                current.insert(url, snapshot);
+            } else {
+                removed.push(PageChange::Removed {
+                    url,
+                    status: snapshot.status,
+                });
+            }
+        }
+
+        (removed, current)
+    }
+}
+
+/// A monitor attached to the page channel of a running crawl.
+#[derive(Debug)]
+pub struct MonitorRun {
+    /// Where snapshots are stored.
+    store: MonitorStore,
+    /// Signals the end of the crawl.
+    done: oneshot::Sender<()>,
+    /// The task snapshotting pages off the channel, returning how many it missed.
+    receiver: tokio::task::JoinHandle<u64>,
+    /// The task diffing the queued snapshots.
+    task: tokio::task::JoinHandle<ChangeMonitor>,
+    /// Where changes are broadcast.
+    changes: broadcast::Sender<PageChange>,
+}
+
+impl MonitorRun {
+    /// Load the previous snapshots and start comparing the pages received on `pages`.
+    pub async fn start(
+        config: &MonitorConfig,
+        mut pages: broadcast::Receiver<Page>,
+        changes: broadcast::Sender<PageChange>,
+    ) -> Self {
+        let previous = match config.store.load().await {
+            Ok(previous) => previous,
+            Err(e) => {
+                log::warn!("failed to load monitor snapshots {:?}: {e}", config.store);
+                HashMap::new()
+            }
+        };
+
+        let (done, mut done_rx) = oneshot::channel();
+        let (queue, mut queued) = mpsc::channel::<PageSnapshot>(QUEUE_CAPACITY);
+        let max_text_bytes = config.max_text_bytes;
+
+        let snapshot = move |page: Page| async move {
+            PageSnapshot::new(
+                page.get_url(),
+                page.status_code.as_u16(),
+                &page.get_html_async().await,
+                max_text_bytes,
+            )
+        };
+
+        // Snapshot pages as they arrive so only their text is queued.
+        let receiver = crate::utils::spawn_task("monitor_receiver", async move {
+            let mut missed = 0;
+
+            loop {
+                tokio::select! {
+                    biased;
+                    page = pages.recv() => match page {
+                        Ok(page) => {
+                            let _ = queue.send(snapshot(page).await).await;
+                        }
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => missed += skipped,
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    _ = &mut done_rx => break,
+                }
+            }
+
+            // Pages sent before the crawl finished are still buffered.
+            loop {
+                match pages.try_recv() {
+                    Ok(page) => {
+                        let _ = queue.send(snapshot(page).await).await;
+                    }
+                    Err(broadcast::error::TryRecvError::Lagged(skipped)) => missed += skipped,
+                    Err(_) => break,
+                }
+            }
+
+            missed
+        });
+
+        let sender = changes.clone();
+
+        let task = crate::utils::spawn_task("monitor", async move {
+            let mut monitor = ChangeMonitor::new(previous);
+
+            while let Some(snapshot) = queued.recv().await {
+                for change in monitor.observe(snapshot) {
+                    let _ = sender.send(change);
+                }
+            }
+
+            monitor
+        });
+
+        Self {
+            store: config.store.clone(),
+            done,
+            receiver,
+            task,
+            changes,
+        }
+    }
+
+    /// Compare the pages still queued, report removals and store the
+    /// snapshots for the next run. `complete` is false when the crawl was
+    /// cut short; `visited` tells whether the crawl visited a url.
+    pub async fn finish(self, complete: bool, visited: impl Fn(&str) -> bool) {
+        let _ = self.done.send(());
+
+        match self.receiver.await {
+            Ok(0) => (),
+            Ok(missed) => log::warn!("monitor missed {missed} pages"),
+            Err(e) => log::warn!("monitor receiver failed: {e}"),
+        }
+
+        let mut monitor = match self.task.await {
+            Ok(monitor) => monitor,
+            Err(e) => {
+                log::warn!("monitor task failed: {e}");
+                return;
+            }
+        };
+
+        if !complete {
+            monitor.set_incomplete();
+        }
+
+        let (removed, snapshots) = monitor.finish(visited);
+
+        for change in removed {
+            let _ = self.changes.send(change);
+        }
+
+        if let Err(e) = self.store.save(&snapshots).await {
+            log::warn!("failed to store monitor snapshots {:?}: {e}", self.store);
+        }
+    }
+}
+
+/// Encode snapshots to the file representation: a header line, then one
+/// tab separated `status fingerprint url text` record per page with the
+/// text escaped onto a single line.
+pub fn encode_snapshots(snapshots: &HashMap<String, PageSnapshot>) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(
+        64 + snapshots
+            .values()
+            .map(|s| s.url.len() + s.text.len() + 32)
+            .sum::<usize>(),
+    );
+
+    let _ = writeln!(buf, "{MAGIC}");
+
+    for snapshot in snapshots.values() {
+        if snapshot.url.contains(['\n', '\r', '\t']) {
+            continue;
+        }
+        let _ = writeln!(
+            buf,
+            "{}\t{}\t{}\t{}",
+            snapshot.status,
+            snapshot.fingerprint,
+            snapshot.url,
+            escape_line(&snapshot.text)
+        );
+    }
+
+    buf
+}
+
+/// Decode snapshots written by [`encode_snapshots`].
+pub fn decode_snapshots(bytes: &[u8]) -> io::Result<HashMap<String, PageSnapshot>> {
+    let text = std::str::from_utf8(bytes).map_err(io::Error::other)?;
+    let mut lines = text.lines();
+
+    if lines.next() != Some(MAGIC) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "missing monitor snapshot header",
+        ));
+    }
+
+    let mut snapshots = HashMap::new();
+
+    for line in lines {
+        let mut fields = line.splitn(4, '\t');
+        let (Some(status), Some(fingerprint), Some(url), Some(text)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let (Ok(status), Ok(fingerprint)) = (status.parse(), fingerprint.parse()) else {
+            continue;
+        };
+        snapshots.insert(
+            url.to_string(),
+            PageSnapshot {
+                url: url.to_string(),
+                status,
+                fingerprint,
+                text: unescape_line(text),
+            },
+        );
+    }
+
+    Ok(snapshots)
+}
+
+/// Escape backslashes and line breaks so the text fits a single record.
+fn escape_line(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Reverse [`escape_line`].
+fn unescape_line(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some(c) => out.push(c),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Write `bytes` to `<path>.tmp` and rename it over `path`.
+async fn write_atomic(path: &Path, bytes: Vec<u8>) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            crate::utils::uring_fs::create_dir_all(parent.to_string_lossy().into_owned()).await?;
+        }
+    }
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = std::path::PathBuf::from(tmp);
+
+    crate::utils::uring_fs::write_file(tmp.to_string_lossy().into_owned(), bytes).await?;
+
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(feature = "disk")]
+mod sqlite {
+    use super::PageSnapshot;
+    use hashbrown::HashMap;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+
+    /// Open the database, creating the snapshot table when missing.
+    async fn connect(path: &str) -> Result<SqlitePool, sqlx::Error> {
+        let pool = SqlitePool::connect_with(
+            SqliteConnectOptions::new()
+                .filename(path)
+                .create_if_missing(true),
+        )
+        .await?;
+
+        sqlx::query(
+            r#"CREATE TABLE IF NOT EXISTS monitor_snapshots (
+                url TEXT PRIMARY KEY,
+                status INTEGER NOT NULL,
+                fingerprint INTEGER NOT NULL,
+                text TEXT NOT NULL
+            )"#,
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(pool)
+    }
+
+    /// Load every stored snapshot.
+    pub(super) async fn load(path: &str) -> Result<HashMap<String, PageSnapshot>, sqlx::Error> {
+        let pool = connect(path).await?;
+        let rows: Vec<(String, i64, i64, String)> =
+            sqlx::query_as("SELECT url, status, fingerprint, text FROM monitor_snapshots")
+                .fetch_all(&pool)
+                .await?;
+        pool.close().await;
+
+        Ok(rows
+            .into_iter()
+            .map(|(url, status, fingerprint, text)| {
+                (
+                    url.clone(),
+                    PageSnapshot {
+                        url,
+                        status: status as u16,
+                        fingerprint: fingerprint as u64,
+                        text,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// Replace the stored snapshots in one transaction.
+    pub(super) async fn save(
+        path: &str,
+        snapshots: &HashMap<String, PageSnapshot>,
+    ) -> Result<(), sqlx::Error> {
+        let pool = connect(path).await?;
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM monitor_snapshots")
+            .execute(&mut *tx)
+            .await?;
+
+        for snapshot in snapshots.values() {
+            sqlx::query(
+                "INSERT INTO monitor_snapshots (url, status, fingerprint, text) VALUES (?, ?, ?, ?)",
+            )
+            .bind(&snapshot.url)
+            .bind(snapshot.status as i64)
+            .bind(snapshot.fingerprint as i64)
+            .bind(&snapshot.text)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        pool.close().await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(url: &str, status: u16, html: &str) -> PageSnapshot {
+        PageSnapshot::new(url, status, html, DEFAULT_MAX_TEXT_BYTES)
+    }
+
+    #[test]
+    fn test_monitor_reports_changes() {
+        let mut first = ChangeMonitor::new(HashMap::new());
+        let added = first.observe(snapshot("https://a.com/", 200, "<p>Home</p>"));
+        assert!(matches!(
+            &added[..],
+            [PageChange::Added { url, status: 200 }] if url == "https://a.com/"
+        ));
+        first.observe(snapshot("https://a.com/old", 200, "<p>Old</p>"));
+        first.observe(snapshot("https://a.com/gone", 200, "<p>Gone</p>"));
+        first.observe(snapshot(
+            "https://a.com/price",
+            200,
+            "<body><p>Widget</p><p>$10</p></body>",
+        ));
+        let (removed, previous) = first.finish(|_| false);
+        assert!(removed.is_empty());
+
+        let mut second = ChangeMonitor::new(previous.clone());
+        // Markup only changes are not reported.
+        assert!(second
+            .observe(snapshot(
+                "https://a.com/",
+                200,
+                "<div class=\"x\"><p>Home</p></div>"
+            ))
+            .is_empty());
+        let modified = second.observe(snapshot(
+            "https://a.com/price",
+            404,
+            "<body><p>Widget</p><p>$12</p></body>",
+        ));
+        assert_eq!(modified.len(), 2);
+        assert!(matches!(
+            &modified[0],
+            PageChange::StatusChanged { url, from: 200, to: 404 } if url == "https://a.com/price"
+        ));
+        match &modified[1] {
+            PageChange::Modified { url, diff } => {
+                assert_eq!(url, "https://a.com/price");
+                assert_eq!(diff.added, ["$12"]);
+                assert_eq!(diff.removed, ["$10"]);
+            }
+            change => panic!("unexpected change {change:?}"),
+        }
+        // `/old` was crawled but never observed, e.g. after the channel lagged.
+        let (removed, stored) = second.finish(|url| url == "https://a.com/old");
+        assert!(matches!(
+            &removed[..],
+            [PageChange::Removed { url, status: 200 }] if url == "https://a.com/gone"
+        ));
+        assert_eq!(stored.len(), 3);
+        assert_eq!(stored["https://a.com/old"], previous["https://a.com/old"]);
+
+        let mut partial = ChangeMonitor::new(previous);
+        partial.set_incomplete();
+        let (removed, stored) = partial.finish(|_| false);
+        assert!(removed.is_empty());
+        assert_eq!(stored.len(), 4);
+    }
+
+    #[test]
+    fn test_snapshot_keeps_capped_text() {
+        let page = PageSnapshot::new(
+            "https://a.com/",
+            200,
+            "<html><head><script>var x = 1;</script></head><body><p>Héllo world</p></body></html>",
+            2,
+        );
+        assert_eq!(page.text, "H");
+        assert_eq!(
+            page.fingerprint,
+            snapshot("https://a.com/", 200, "<p>Héllo world</p>").fingerprint
+        );
+
+        let diff = TextDiff::new("a\nb\nb\nc", "b\n a \nd\nb");
+        assert_eq!(diff.added, ["d"]);
+        assert_eq!(diff.removed, ["c"]);
+        assert!(TextDiff::new("a\n\nb", "a\nb").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_file_store_round_trip() {
+        let dir = std::env::temp_dir().join(format!("spider-monitor-{}", std::process::id()));
+        let store = MonitorStore::File(dir.join("snapshots").to_string_lossy().into_owned());
+
+        assert!(store.load().await.unwrap().is_empty());
+
+        let mut snapshots = HashMap::new();
+        let mut page = snapshot("https://a.com/", 200, "<p>a\tb</p><p>c \\ d</p>");
+        page.text.push_str("\r\tend");
+        snapshots.insert(page.url.clone(), page);
+        store.save(&snapshots).await.unwrap();
+
+        assert_eq!(store.load().await.unwrap(), snapshots);
+        assert!(decode_snapshots(b"not a snapshot").is_err());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
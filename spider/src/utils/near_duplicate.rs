@@ -0,0 +1,454 @@
+//! Near-duplicate page detection with SimHash.
+//!
+//! Exact content signatures miss pages that differ only by a timestamp, an
+//! ad slot or a session token. [`simhash`] fingerprints the visible text of
+//! a page from overlapping word shingles, so small edits only flip a few
+//! bits, and [`NearDuplicates`] finds earlier pages within a Hamming
+//! distance of a new fingerprint.
+use crate::page::Page;
+use hashbrown::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// FNV-1a offset basis.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+/// FNV-1a prime.
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Elements whose text is not visible content.
+const HIDDEN_ELEMENTS: [&[u8]; 5] = [b"script", b"style", b"noscript", b"template", b"svg"];
+
+/// Largest Hamming distance the index serves. Larger thresholds are
+/// clamped to it.
+pub const MAX_THRESHOLD: u32 = 3;
+
+/// Number of fingerprint blocks in the index. Any two fingerprints within
+/// [`MAX_THRESHOLD`] bits share at least one of the `MAX_THRESHOLD + 1`
+/// blocks exactly, and each block keeps 16 bits so buckets stay small.
+const INDEX_BLOCKS: u32 = MAX_THRESHOLD + 1;
+
+/// Near-duplicate detection settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct NearDuplicateConfig {
+    /// Maximum Hamming distance between two fingerprints for the pages to
+    /// count as near duplicates, at most [`MAX_THRESHOLD`]. Default: 3.
+    pub threshold: u32,
+    /// Number of words per shingle. Default: 3.
+    pub shingle_size: usize,
+    /// Pages with fewer visible words are not fingerprinted. Default: 10.
+    pub min_words: usize,
+    /// Skip emitting near duplicates and following their links instead of
+    /// only flagging them with [`Page::near_duplicate_of`]. Default: false.
+    pub skip: bool,
+}
+
+impl Default for NearDuplicateConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 3,
+            shingle_size: 3,
+            min_words: 10,
+            skip: false,
+        }
+    }
+}
+
+impl NearDuplicateConfig {
+    /// Set the maximum Hamming distance, clamped to [`MAX_THRESHOLD`].
+    pub fn with_threshold(mut self, threshold: u32) -> Self {
+        self.threshold = threshold.min(MAX_THRESHOLD);
+        self
+    }
+
+    /// Set the number of words per shingle.
+    pub fn with_shingle_size(mut self, shingle_size: usize) -> Self {
+        self.shingle_size = shingle_size;
+        self
+    }
+
+    /// Set the minimum number of visible words to fingerprint a page.
+    pub fn with_min_words(mut self, min_words: usize) -> Self {
+        self.min_words = min_words;
+        self
+    }
+
+    /// Skip near duplicates instead of flagging them.
+    pub fn with_skip(mut self, skip: bool) -> Self {
+        self.skip = skip;
+        self
+    }
+}
+
+/// Hash every visible word of an HTML document, lowercased, in order.
+/// Tags, comments and the content of script-like elements are dropped.
+///
+/// Words are hashed with FNV-1a so fingerprints are stable across
+/// platforms and runs.
+pub fn visible_word_hashes(html: &[u8]) -> Vec<u64> {
+    let mut words = Vec::new();
+    let mut word: Option<u64> = None;
+    let mut i = 0;
+
+    while i < html.len() {
+        let b = html[i];
+
+        if b == b'<' {
+            words.extend(word.take());
+            i = skip_markup(html, i);
+            continue;
+        }
+
+        if b.is_ascii_alphanumeric() || b >= 0x80 {
+            let hash = word.get_or_insert(FNV_OFFSET);
+            *hash = (*hash ^ u64::from(b.to_ascii_lowercase())).wrapping_mul(FNV_PRIME);
+        } else {
+            words.extend(word.take());
+        }
+
+        i += 1;
+    }
+
+    words.extend(word);
+
+    words
+}
+
+/// The splitmix64 finalizer, spreading word hashes over all 64 bits.
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Skip the markup starting at `html[start] == b'<'`, including the body
+/// of hidden elements. Returns the index after it.
+fn skip_markup(html: &[u8], start: usize) -> usize {
+    let rest = &html[start..];
+
+    if rest.starts_with(b"<!--") {
+        return find(html, start + 4, b"-->").map_or(html.len(), |end| end + 3);
+    }
+
+    let tag_end = find(html, start, b">").map_or(html.len(), |end| end + 1);
+    let name_len = rest[1..]
+        .iter()
+        .take_while(|b| b.is_ascii_alphanumeric())
+        .count();
+    let name = &rest[1..1 + name_len];
+
+    match HIDDEN_ELEMENTS
+        .iter()
+        .find(|hidden| hidden.eq_ignore_ascii_case(name))
+    {
+        Some(hidden) => {
+            let mut close = Vec::with_capacity(hidden.len() + 2);
+            close.extend_from_slice(b"</");
+            close.extend_from_slice(hidden);
+            find_ignore_case(html, tag_end, &close)
+                .and_then(|close| find(html, close, b">"))
+                .map_or(html.len(), |end| end + 1)
+        }
+        None => tag_end,
+    }
+}
+
+/// Position of `needle` in `html[from..]`.
+fn find(html: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    html.get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|p| from + p)
+}
+
+/// Position of `needle` in `html[from..]`, ASCII case-insensitive.
+fn find_ignore_case(html: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    html.get(from..)?
+        .windows(needle.len())
+        .position(|w| w.eq_ignore_ascii_case(needle))
+        .map(|p| from + p)
+}
+
+/// The SimHash of the visible text of an HTML document over
+/// `shingle_size`-word shingles. `None` when the document has fewer than
+/// `min_words` visible words.
+pub fn simhash(html: &[u8], shingle_size: usize, min_words: usize) -> Option<u64> {
+    let words = visible_word_hashes(html);
+
+    if words.is_empty() || words.len() < min_words {
+        return None;
+    }
+
+    let mut weights = [0i32; 64];
+
+    for shingle in words.windows(shingle_size.clamp(1, words.len())) {
+        let hash = shingle
+            .iter()
+            .fold(FNV_OFFSET, |hash, word| mix(hash ^ word));
+
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            if hash & (1 << bit) != 0 {
+                *weight += 1;
+            } else {
+                *weight -= 1;
+            }
+        }
+    }
+
+    Some(
+        weights
+            .iter()
+            .enumerate()
+            .filter(|(_, weight)| **weight > 0)
+            .fold(0u64, |fingerprint, (bit, _)| fingerprint | (1 << bit)),
+    )
+}
+
+/// Number of differing bits between two fingerprints.
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// The `block`-th of [`INDEX_BLOCKS`] slices of a fingerprint.
+fn block_key(fingerprint: u64, block: u32) -> u64 {
+    let start = block * 64 / INDEX_BLOCKS;
+    let end = (block + 1) * 64 / INDEX_BLOCKS;
+    (fingerprint >> start) & ((1u64 << (end - start)) - 1)
+}
+
+/// One table per block: block value to the fingerprints and URLs.
+type Blocks = Vec<HashMap<u64, Vec<(u64, Arc<str>)>>>;
+
+/// Fingerprints seen so far. Clones share the same index so concurrent
+/// crawl tasks see each other's pages.
+///
+/// Each fingerprint is filed under every one of its blocks, so a lookup
+/// only compares against pages sharing a block. A lookup and the insert that
+/// follows it hold the same lock, so of two near-identical pages checked at
+/// once exactly one is reported as the duplicate.
+#[derive(Debug, Clone)]
+pub struct NearDuplicates {
+    /// The block tables, keyed by block index.
+    blocks: Arc<Mutex<Blocks>>,
+}
+
+impl Default for NearDuplicates {
+    fn default() -> Self {
+        Self {
+            blocks: Arc::new(Mutex::new(
+                (0..INDEX_BLOCKS).map(|_| HashMap::new()).collect(),
+            )),
+        }
+    }
+}
+
+impl NearDuplicates {
+    /// Lock the block tables, recovering from a poisoned lock.
+    fn lock(&self) -> MutexGuard<'_, Blocks> {
+        self.blocks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Look up a fingerprint and record it for `url`. Returns the URL of
+    /// an earlier, different page within `threshold` bits, clamped to
+    /// [`MAX_THRESHOLD`].
+    pub fn check(&self, url: &str, fingerprint: u64, threshold: u32) -> Option<String> {
+        let threshold = threshold.min(MAX_THRESHOLD);
+        let within = |(other, other_url): &(u64, Arc<str>)| {
+            &**other_url != url && hamming_distance(fingerprint, *other) <= threshold
+        };
+
+        let mut blocks = self.lock();
+
+        let found = blocks.iter().enumerate().find_map(|(block, table)| {
+            table
+                .get(&block_key(fingerprint, block as u32))
+                .and_then(|bucket| bucket.iter().find(|e| within(e)))
+                .map(|e| e.1.to_string())
+        });
+
+        if found.is_none() {
+            let url: Arc<str> = Arc::from(url);
+            for (block, table) in blocks.iter_mut().enumerate() {
+                let bucket = table
+                    .entry(block_key(fingerprint, block as u32))
+                    .or_default();
+                if !bucket.iter().any(|(f, u)| *f == fingerprint && *u == url) {
+                    bucket.push((fingerprint, url.clone()));
+                }
+            }
+        }
+
+        found
+    }
+
+    /// Fingerprint the page and check it against earlier pages, setting
+    /// [`Page::near_duplicate_of`] on a match. Returns `true` when the page
+    /// should be skipped.
+    pub fn observe(&self, page: &mut Page, config: &NearDuplicateConfig) -> bool {
+        let Some(fingerprint) = simhash(
+            page.get_html_bytes_u8(),
+            config.shingle_size,
+            config.min_words,
+        ) else {
+            return false;
+        };
+
+        match self.check(page.get_url(), fingerprint, config.threshold) {
+            Some(original) => {
+                page.near_duplicate_of = Some(original);
+                config.skip
+            }
+            _ => false,
+        }
+    }
+
+    /// Number of fingerprints recorded.
+    pub fn len(&self) -> usize {
+        self.lock()[0].values().map(|bucket| bucket.len()).sum()
+    }
+
+    /// No fingerprint was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.lock()[0].is_empty()
+    }
+
+    /// Forget every fingerprint.
+    pub fn clear(&self) {
+        for table in self.lock().iter_mut() {
+            table.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A product listing of about a thousand words.
+    fn listing(subject: &str) -> String {
+        (0..64)
+            .map(|i| {
+                format!(
+                    "Item {i} in the {subject} catalog ships within {} days and \
+                     ranks {} by customer rating. ",
+                    i % 7 + 1,
+                    64 - i
+                )
+            })
+            .collect()
+    }
+
+    fn page_html(body: &str, footer: &str) -> String {
+        format!(
+            "<html><head><title>Spider</title><style>p {{ color: red }}</style></head>\
+             <body><p>{body}</p><script>var session = \"{footer}\";</script>\
+             <footer>Generated {footer}</footer></body></html>"
+        )
+    }
+
+    #[test]
+    fn test_visible_word_hashes_skips_markup() {
+        let html = b"<p>Hello <b>World</b></p><!-- hidden words --><SCRIPT>var x = 1;</SCRIPT>";
+        assert_eq!(
+            visible_word_hashes(html),
+            visible_word_hashes(b"hello world")
+        );
+    }
+
+    #[test]
+    fn test_simhash_near_and_far() {
+        let listing = listing("garden");
+        let a = simhash(page_html(&listing, "2024-01-01 10:00").as_bytes(), 3, 10).unwrap();
+        let b = simhash(page_html(&listing, "2024-06-30 23:59").as_bytes(), 3, 10).unwrap();
+        let other = simhash(
+            page_html(
+                &"Shipping rates depend on the weight of the parcel, the destination \
+                  country and the delivery speed chosen at checkout. "
+                    .repeat(20),
+                "2024-01-01 10:00",
+            )
+            .as_bytes(),
+            3,
+            10,
+        )
+        .unwrap();
+
+        assert!(hamming_distance(a, b) <= 3, "{}", hamming_distance(a, b));
+        assert!(hamming_distance(a, other) > 3);
+        assert!(simhash(b"<p>too short</p>", 3, 10).is_none());
+    }
+
+    #[test]
+    fn test_near_duplicates_index() {
+        let index = NearDuplicates::default();
+
+        assert!(index.check("https://a.com/1", 0b1011, 3).is_none());
+        // The same page again is not a duplicate of itself.
+        assert!(index.check("https://a.com/1", 0b1011, 3).is_none());
+        assert_eq!(
+            index
+                .check("https://a.com/2", 0b1011 ^ (1 << 63) ^ 1, 3)
+                .as_deref(),
+            Some("https://a.com/1")
+        );
+        assert!(index.check("https://a.com/3", !0b1011, 3).is_none());
+        // One flipped bit in each block is past the clamped threshold.
+        assert!(index
+            .check("https://a.com/4", 0b1011 ^ 0x0001_0001_0001_0001, 20)
+            .is_none());
+        assert_eq!(index.len(), 3);
+    }
+
+    #[test]
+    fn test_near_duplicates_check_is_atomic() {
+        let index = NearDuplicates::default();
+        let duplicates = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|i| {
+                    let index = &index;
+                    scope.spawn(move || index.check(&format!("https://a.com/{i}"), 0b1011, 3))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().unwrap())
+                .count()
+        });
+
+        assert_eq!(duplicates, 7, "only the first page of the set is kept");
+        assert_eq!(index.len(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_config_serde_default() {
+        let config: NearDuplicateConfig = serde_json::from_str(r#"{"skip": true}"#).unwrap();
+        assert_eq!(config, NearDuplicateConfig::default().with_skip(true));
+    }
+
+    #[test]
+    fn test_observe_flags_or_skips() {
+        let index = NearDuplicates::default();
+        let page = |url: &str, footer: &str| {
+            let mut page = Page::default();
+            page.set_url(url.into());
+            page.set_html_bytes(Some(page_html(&listing("garden"), footer).into_bytes()));
+            page
+        };
+
+        let config = NearDuplicateConfig::default();
+        let mut first = page("https://a.com/p?sort=asc", "10:00");
+        assert!(!index.observe(&mut first, &config));
+        assert!(first.near_duplicate_of.is_none());
+
+        let mut second = page("https://a.com/p?sort=desc", "10:01");
+        assert!(!index.observe(&mut second, &config));
+        assert_eq!(
+            second.near_duplicate_of.as_deref(),
+            Some("https://a.com/p?sort=asc")
+        );
+
+        let mut third = page("https://a.com/p?sort=price", "10:02");
+        assert!(index.observe(&mut third, &config.clone().with_skip(true)));
+    }
+}
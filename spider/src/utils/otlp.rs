@@ -0,0 +1,565 @@
+//! OTLP/HTTP exporter for the crawl spans in [`crate::utils::trace`].
+//!
+//! [`OtlpLayer`] is a `tracing-subscriber` layer that assigns W3C trace and
+//! span ids, collects span attributes and ships finished spans in batches to
+//! an OpenTelemetry collector using the OTLP/HTTP JSON encoding
+//! (`POST {endpoint}/v1/traces`). With [`OtlpConfig::with_traceparent`] the
+//! HTTP backend also stamps a `traceparent` header on every request so the
+//! origin can join its own traces to the crawl.
+//!
+//! ```no_run
+//! # async fn run() {
+//! use spider::utils::otlp::OtlpConfig;
+//!
+//! let exporter = OtlpConfig::new("http://localhost:4318")
+//!     .with_service_name("crawler")
+//!     .with_traceparent(true)
+//!     .install()
+//!     .expect("global subscriber already set");
+//!
+//! let mut website = spider::website::Website::new("https://example.com");
+//! website.crawl().await;
+//!
+//! exporter.flush().await;
+//! # }
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Span, Subscriber};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, Registry};
+
+/// Default number of finished spans buffered before a batch is exported.
+const DEFAULT_BATCH_SIZE: usize = 512;
+
+/// Batches held while no tokio runtime is around to export them. Past
+/// this the oldest batch is dropped so the buffer stays bounded.
+const MAX_PENDING_BATCHES: usize = 8;
+
+/// OTLP `SPAN_KIND_INTERNAL`.
+const SPAN_KIND_INTERNAL: u8 = 1;
+/// OTLP `SPAN_KIND_CLIENT`.
+const SPAN_KIND_CLIENT: u8 = 3;
+/// OTLP `STATUS_CODE_ERROR`.
+const STATUS_CODE_ERROR: u8 = 2;
+
+/// Format a W3C `traceparent` header value (version `00`, sampled).
+pub fn format_traceparent(trace_id: u128, span_id: u64) -> String {
+    format!("00-{trace_id:032x}-{span_id:016x}-01")
+}
+
+/// Parse a W3C `traceparent` header value into `(trace_id, span_id, sampled)`.
+pub fn parse_traceparent(value: &str) -> Option<(u128, u64, bool)> {
+    let mut parts = value.trim().split('-');
+    let version = parts.next()?;
+    let trace_id = parts.next()?;
+    let span_id = parts.next()?;
+    let flags = parts.next()?;
+
+    if version.len() != 2 || version == "ff" || trace_id.len() != 32 || span_id.len() != 16 {
+        return None;
+    }
+
+    let trace_id = u128::from_str_radix(trace_id, 16).ok()?;
+    let span_id = u64::from_str_radix(span_id, 16).ok()?;
+    let flags = u8::from_str_radix(flags, 16).ok()?;
+
+    if trace_id == 0 || span_id == 0 {
+        return None;
+    }
+
+    Some((trace_id, span_id, flags & 1 == 1))
+}
+
+/// The `traceparent` value for `span`, when an [`OtlpLayer`] with header
+/// propagation enabled is installed on the span's subscriber.
+pub fn traceparent(span: &Span) -> Option<String> {
+    span.with_subscriber(|(id, dispatch)| {
+        let layer = dispatch.downcast_ref::<OtlpLayer>()?;
+        if !layer.traceparent {
+            return None;
+        }
+        let registry = dispatch.downcast_ref::<Registry>()?;
+        let span = registry.span(id)?;
+        let extensions = span.extensions();
+        let state = extensions.get::<SpanState>()?;
+        Some(format_traceparent(state.trace_id, state.span_id))
+    })
+    .flatten()
+}
+
+/// Stamp the `traceparent` header for `span` on an outgoing request.
+pub(crate) fn inject_traceparent(
+    req: crate::client::request_client::RequestBuilder,
+    span: &Span,
+) -> crate::client::request_client::RequestBuilder {
+    match traceparent(span) {
+        Some(value) => req.header("traceparent", value),
+        None => req,
+    }
+}
+
+/// Configuration for the OTLP exporter.
+#[derive(Debug, Clone)]
+pub struct OtlpConfig {
+    /// Collector base URL, e.g. `http://localhost:4318`. `/v1/traces` is appended.
+    pub endpoint: String,
+    /// The `service.name` resource attribute.
+    pub service_name: String,
+    /// Extra headers sent with each export, e.g. collector auth.
+    pub headers: Vec<(String, String)>,
+    /// Finished spans buffered before a batch is exported.
+    pub batch_size: usize,
+    /// Inject a W3C `traceparent` header into crawl requests.
+    pub traceparent: bool,
+}
+
+impl OtlpConfig {
+    /// Export to the collector at `endpoint`.
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            service_name: "spider".into(),
+            headers: Vec::new(),
+            batch_size: DEFAULT_BATCH_SIZE,
+            traceparent: false,
+        }
+    }
+
+    /// Set the `service.name` resource attribute.
+    pub fn with_service_name(mut self, service_name: impl Into<String>) -> Self {
+        self.service_name = service_name.into();
+        self
+    }
+
+    /// Add a header sent with each export.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set how many finished spans are buffered before a batch is exported.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Inject a W3C `traceparent` header into crawl requests.
+    pub fn with_traceparent(mut self, traceparent: bool) -> Self {
+        self.traceparent = traceparent;
+        self
+    }
+
+    /// Build the layer to compose with other `tracing-subscriber` layers.
+    /// The layer must sit on a [`Registry`].
+    pub fn layer(self) -> OtlpLayer {
+        let mut url = self.endpoint.trim_end_matches('/').to_string();
+        url.push_str("/v1/traces");
+
+        OtlpLayer {
+            traceparent: self.traceparent,
+            exporter: OtlpExporter {
+                inner: Arc::new(ExporterInner {
+                    url,
+                    service_name: self.service_name,
+                    headers: self.headers,
+                    batch_size: self.batch_size,
+                    client: reqwest::Client::new(),
+                    buffer: Mutex::new(Vec::new()),
+                }),
+            },
+        }
+    }
+
+    /// Install the layer as the global default subscriber.
+    pub fn install(self) -> Result<OtlpExporter, tracing::subscriber::SetGlobalDefaultError> {
+        let layer = self.layer();
+        let exporter = layer.exporter();
+        tracing::subscriber::set_global_default(Registry::default().with(layer))?;
+        Ok(exporter)
+    }
+}
+
+/// Handle to the export buffer of an [`OtlpLayer`].
+#[derive(Clone)]
+pub struct OtlpExporter {
+    inner: Arc<ExporterInner>,
+}
+
+struct ExporterInner {
+    url: String,
+    service_name: String,
+    headers: Vec<(String, String)>,
+    batch_size: usize,
+    client: reqwest::Client,
+    buffer: Mutex<Vec<FinishedSpan>>,
+}
+
+impl std::fmt::Debug for OtlpExporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OtlpExporter")
+            .field("url", &self.inner.url)
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+impl OtlpExporter {
+    /// Finished spans waiting for the next export.
+    pub fn pending(&self) -> usize {
+        self.inner.buffer.lock().map_or(0, |b| b.len())
+    }
+
+    /// Export every buffered span now. Call before shutdown.
+    pub async fn flush(&self) {
+        let batch = match self.inner.buffer.lock() {
+            Ok(mut buffer) => std::mem::take(&mut *buffer),
+            Err(_) => return,
+        };
+        self.export(batch).await;
+    }
+
+    /// Buffer a finished span, exporting in the background once a batch fills.
+    /// Without a runtime the spans wait for [`flush`](Self::flush), up to
+    /// `MAX_PENDING_BATCHES` batches.
+    fn push(&self, span: FinishedSpan) {
+        let handle = tokio::runtime::Handle::try_current().ok();
+        let batch_size = self.inner.batch_size;
+        let batch = match self.inner.buffer.lock() {
+            Ok(mut buffer) => {
+                if handle.is_none() && buffer.len() >= batch_size * MAX_PENDING_BATCHES {
+                    buffer.drain(..batch_size);
+                    log::warn!(
+                        "otlp buffer full without a tokio runtime, dropped {batch_size} spans"
+                    );
+                }
+                buffer.push(span);
+                if buffer.len() >= batch_size && handle.is_some() {
+                    Some(std::mem::take(&mut *buffer))
+                } else {
+                    None
+                }
+            }
+            Err(_) => None,
+        };
+        if let (Some(batch), Some(handle)) = (batch, handle) {
+            let exporter = self.clone();
+            handle.spawn(async move { exporter.export(batch).await });
+        }
+    }
+
+    async fn export(&self, batch: Vec<FinishedSpan>) {
+        if batch.is_empty() {
+            return;
+        }
+        let body = match serde_json::to_vec(&self.encode(&batch)) {
+            Ok(body) => body,
+            Err(_) => return,
+        };
+        let mut req = self
+            .inner
+            .client
+            .post(&self.inner.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
+        for (name, value) in &self.inner.headers {
+            req = req.header(name.as_str(), value.as_str());
+        }
+        match req.send().await {
+            Ok(res) if !res.status().is_success() => {
+                log::warn!("otlp export rejected: {}", res.status());
+            }
+            Err(e) => log::warn!("otlp export failed: {e}"),
+            _ => (),
+        }
+    }
+
+    /// Encode a batch as an OTLP `ExportTraceServiceRequest` (JSON mapping).
+    fn encode(&self, batch: &[FinishedSpan]) -> serde_json::Value {
+        let spans: Vec<serde_json::Value> = batch.iter().map(FinishedSpan::encode).collect();
+
+        serde_json::json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [encode_attribute(
+                        "service.name",
+                        &AttributeValue::Str(self.inner.service_name.clone()),
+                    )],
+                },
+                "scopeSpans": [{
+                    "scope": { "name": "spider", "version": env!("CARGO_PKG_VERSION") },
+                    "spans": spans,
+                }],
+            }],
+        })
+    }
+}
+
+/// A `tracing-subscriber` layer exporting spans over OTLP/HTTP.
+pub struct OtlpLayer {
+    exporter: OtlpExporter,
+    traceparent: bool,
+}
+
+impl OtlpLayer {
+    /// Handle used to flush buffered spans.
+    pub fn exporter(&self) -> OtlpExporter {
+        self.exporter.clone()
+    }
+}
+
+impl<S> Layer<S> for OtlpLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        let parent = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<SpanState>()
+                .map(|p| (p.trace_id, p.span_id))
+        });
+        let mut state = SpanState {
+            trace_id: parent.map_or_else(|| fastrand::u128(1..), |(trace_id, _)| trace_id),
+            span_id: fastrand::u64(1..),
+            parent_span_id: parent.map(|(_, span_id)| span_id),
+            start: SystemTime::now(),
+            attributes: Vec::new(),
+        };
+        attrs.record(&mut AttributeVisitor(&mut state.attributes));
+        span.extensions_mut().insert(state);
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(state) = span.extensions_mut().get_mut::<SpanState>() {
+                values.record(&mut AttributeVisitor(&mut state.attributes));
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            let state = span.extensions_mut().remove::<SpanState>();
+            if let Some(state) = state {
+                self.exporter.push(FinishedSpan {
+                    name: span.name(),
+                    state,
+                    end: SystemTime::now(),
+                });
+            }
+        }
+    }
+}
+
+/// Ids and attributes kept in the registry extensions of each open span.
+struct SpanState {
+    trace_id: u128,
+    span_id: u64,
+    parent_span_id: Option<u64>,
+    start: SystemTime,
+    attributes: Vec<(&'static str, AttributeValue)>,
+}
+
+struct FinishedSpan {
+    name: &'static str,
+    state: SpanState,
+    end: SystemTime,
+}
+
+impl FinishedSpan {
+    fn encode(&self) -> serde_json::Value {
+        let state = &self.state;
+        let attributes: Vec<serde_json::Value> = state
+            .attributes
+            .iter()
+            .map(|(key, value)| encode_attribute(key, value))
+            .collect();
+        let failed = state.attributes.iter().any(|(key, value)| {
+            *key == "http.response.status_code"
+                && matches!(value, AttributeValue::Int(code) if *code >= 400)
+        });
+
+        let mut span = serde_json::json!({
+            "traceId": format!("{:032x}", state.trace_id),
+            "spanId": format!("{:016x}", state.span_id),
+            "name": self.name,
+            "kind": if self.name == "backend" { SPAN_KIND_CLIENT } else { SPAN_KIND_INTERNAL },
+            "startTimeUnixNano": unix_nanos(state.start).to_string(),
+            "endTimeUnixNano": unix_nanos(self.end).to_string(),
+            "attributes": attributes,
+        });
+        if let Some(parent) = state.parent_span_id {
+            span["parentSpanId"] = format!("{parent:016x}").into();
+        }
+        if failed {
+            span["status"] = serde_json::json!({ "code": STATUS_CODE_ERROR });
+        }
+        span
+    }
+}
+
+enum AttributeValue {
+    Str(String),
+    Int(i64),
+    Double(f64),
+    Bool(bool),
+}
+
+fn encode_attribute(key: &str, value: &AttributeValue) -> serde_json::Value {
+    let value = match value {
+        AttributeValue::Str(v) => serde_json::json!({ "stringValue": v }),
+        // OTLP JSON encodes 64-bit integers as strings.
+        AttributeValue::Int(v) => serde_json::json!({ "intValue": v.to_string() }),
+        AttributeValue::Double(v) => serde_json::json!({ "doubleValue": v }),
+        AttributeValue::Bool(v) => serde_json::json!({ "boolValue": v }),
+    };
+    serde_json::json!({ "key": key, "value": value })
+}
+
+fn unix_nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos())
+}
+
+/// Records span fields, replacing earlier values recorded under the same key.
+struct AttributeVisitor<'a>(&'a mut Vec<(&'static str, AttributeValue)>);
+
+impl AttributeVisitor<'_> {
+    fn set(&mut self, field: &Field, value: AttributeValue) {
+        let key = field.name();
+        match self.0.iter_mut().find(|(k, _)| *k == key) {
+            Some(slot) => slot.1 = value,
+            None => self.0.push((key, value)),
+        }
+    }
+}
+
+impl Visit for AttributeVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.set(field, AttributeValue::Str(value.to_string()));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.set(field, AttributeValue::Int(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.set(
+            field,
+            AttributeValue::Int(i64::try_from(value).unwrap_or(i64::MAX)),
+        );
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.set(field, AttributeValue::Double(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.set(field, AttributeValue::Bool(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.set(field, AttributeValue::Str(format!("{value:?}")));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::trace::{backend_span, crawl_span, page_span};
+
+    #[test]
+    fn test_traceparent_round_trip() {
+        let value = format_traceparent(0x0af7651916cd43dd8448eb211c80319c, 0xb7ad6b7169203331);
+        assert_eq!(
+            value,
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"
+        );
+        assert_eq!(
+            parse_traceparent(&value),
+            Some((0x0af7651916cd43dd8448eb211c80319c, 0xb7ad6b7169203331, true))
+        );
+        assert_eq!(
+            parse_traceparent("00-00000000000000000000000000000000-b7ad6b7169203331-01"),
+            None
+        );
+        assert_eq!(parse_traceparent("00-abc-def-01"), None);
+    }
+
+    #[test]
+    fn test_span_tree_is_exported() {
+        let layer = OtlpConfig::new("http://127.0.0.1:4318")
+            .with_traceparent(true)
+            .layer();
+        let exporter = layer.exporter();
+        let subscriber = Registry::default().with(layer);
+
+        let (crawl_tp, backend_tp) = tracing::subscriber::with_default(subscriber, || {
+            let crawl = crawl_span("https://example.com");
+            let _crawl = crawl.enter();
+            let page = page_span();
+            let _page = page.enter();
+            page.record("http.response.status_code", 503u16);
+            let backend = backend_span("http");
+            (traceparent(&crawl), traceparent(&backend))
+        });
+
+        let crawl_tp = parse_traceparent(&crawl_tp.expect("crawl traceparent")).unwrap();
+        let backend_tp = parse_traceparent(&backend_tp.expect("backend traceparent")).unwrap();
+        assert_eq!(crawl_tp.0, backend_tp.0, "one trace per crawl");
+        assert_ne!(crawl_tp.1, backend_tp.1);
+
+        assert_eq!(exporter.pending(), 3);
+        let batch = std::mem::take(&mut *exporter.inner.buffer.lock().unwrap());
+        let body = exporter.encode(&batch);
+        let spans = body["resourceSpans"][0]["scopeSpans"][0]["spans"]
+            .as_array()
+            .unwrap();
+        let page = spans.iter().find(|s| s["name"] == "page").unwrap();
+        let crawl = spans.iter().find(|s| s["name"] == "crawl").unwrap();
+        let backend = spans.iter().find(|s| s["name"] == "backend").unwrap();
+
+        assert_eq!(page["parentSpanId"], crawl["spanId"]);
+        assert_eq!(backend["parentSpanId"], page["spanId"]);
+        assert!(crawl.get("parentSpanId").is_none());
+        assert_eq!(backend["kind"], SPAN_KIND_CLIENT);
+        assert_eq!(page["status"]["code"], STATUS_CODE_ERROR);
+        assert!(page["attributes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|a| a["key"] == "http.response.status_code" && a["value"]["intValue"] == "503"));
+    }
+
+    #[test]
+    fn test_buffer_is_capped_without_runtime() {
+        let layer = OtlpConfig::new("http://127.0.0.1:4318")
+            .with_batch_size(2)
+            .layer();
+        let exporter = layer.exporter();
+        let subscriber = Registry::default().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..100 {
+                drop(crawl_span("https://example.com"));
+            }
+        });
+
+        assert_eq!(exporter.pending(), 2 * MAX_PENDING_BATCHES);
+    }
+
+    #[test]
+    fn test_traceparent_disabled_by_default() {
+        let subscriber = Registry::default().with(OtlpConfig::new("http://127.0.0.1:4318").layer());
+        let value = tracing::subscriber::with_default(subscriber, || traceparent(&crawl_span("a")));
+        assert_eq!(value, None);
+    }
+}
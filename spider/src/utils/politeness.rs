@@ -0,0 +1,532 @@
+//! Per-host politeness scheduling.
+//!
+//! Feature-gated behind `politeness`. Paces every host of a crawl on its own:
+//! robots.txt `Crawl-delay` / `Request-rate`, a per-host concurrency cap and
+//! `Retry-After` back-off on 429 / 503 responses. Rates are enforced through a
+//! [`DomainRateLimiter`] and, with `auto_throttle`, widened by the latency
+//! based [`AutoThrottle`](crate::utils::auto_throttle::AutoThrottle) delay.
+//!
+//! Useful for subdomain, TLD or `with_external_domains` crawls, where a single
+//! crawl-wide `delay` is either too slow for fast hosts or too fast for the
+//! slow ones.
+
+use crate::client::StatusCode;
+use crate::compact_str::CompactString;
+use crate::packages::robotparser::parser::{RequestRate, RobotFileParser};
+use crate::utils::rate_limiter::DomainRateLimiter;
+use crate::Client;
+use dashmap::DashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{OnceCell, OwnedSemaphorePermit, Semaphore};
+
+/// Rate used for hosts without any delay: effectively unlimited.
+const UNLIMITED_RATE: f64 = 1_000_000.0;
+
+/// Back-off used for 429 / 503 responses without a usable `Retry-After`.
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_millis(2_500);
+
+/// Fetch tasks that may wait on their host at once. Bounds the tasks spawned
+/// ahead of slow hosts, which hold no crawl-wide permit while they wait.
+const MAX_PARKED_TASKS: usize = 512;
+
+/// Configuration for the per-host politeness scheduler.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolitenessConfig {
+    /// Max in-flight requests per host. Default: 2.
+    pub max_concurrency_per_host: usize,
+    /// Minimum delay between requests to the same host. Robots.txt and
+    /// auto-throttle delays only ever widen it. Default: zero.
+    pub default_delay: Duration,
+    /// Fetch each host's robots.txt and honor its `Crawl-delay` and
+    /// `Request-rate`. Default: true.
+    pub respect_robots: bool,
+    /// Pause a host for its `Retry-After` on 429 and 503 responses. Default: true.
+    pub respect_retry_after: bool,
+    /// Upper bound for robots.txt delays and `Retry-After` pauses. Default: 60s.
+    pub max_delay: Duration,
+}
+
+impl Default for PolitenessConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrency_per_host: 2,
+            default_delay: Duration::ZERO,
+            respect_robots: true,
+            respect_retry_after: true,
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Scheduling state for a single host.
+struct HostState {
+    /// Concurrency cap for the host.
+    permits: Arc<Semaphore>,
+    /// Resolved once the host's robots.txt has been read.
+    robots: OnceCell<()>,
+    /// Delay between requests from robots.txt in milliseconds, 0 when unset.
+    robots_delay_ms: AtomicU64,
+    /// `Retry-After` pause end in milliseconds since the scheduler epoch.
+    paused_until_ms: AtomicU64,
+}
+
+/// Held for the duration of a request; releases the host's concurrency slot on drop.
+#[must_use]
+pub struct HostPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+/// A crawl-wide concurrency permit for a fetch task. With a scheduler the
+/// permit is only taken once the task's host accepts the request, so tasks
+/// paced by a slow host never hold a permit other hosts could use.
+pub enum CrawlPermit {
+    /// Permit taken before the task was spawned.
+    Ready(OwnedSemaphorePermit),
+    /// Permit to take after the host slot.
+    Paced {
+        /// The crawl-wide semaphore.
+        semaphore: Arc<Semaphore>,
+        /// The host scheduler of the crawl.
+        scheduler: Arc<HostScheduler>,
+        /// Place among the tasks waiting on their host.
+        parked: OwnedSemaphorePermit,
+    },
+}
+
+impl CrawlPermit {
+    /// Reserve a fetch task. Without a scheduler this takes the crawl-wide
+    /// permit, with one it only waits for room among the parked tasks.
+    pub async fn new(
+        semaphore: &Arc<Semaphore>,
+        scheduler: Option<&Arc<HostScheduler>>,
+    ) -> Result<Self, tokio::sync::AcquireError> {
+        match scheduler {
+            Some(scheduler) => Ok(Self::Paced {
+                parked: scheduler.parked.clone().acquire_owned().await?,
+                semaphore: semaphore.clone(),
+                scheduler: scheduler.clone(),
+            }),
+            _ => semaphore.clone().acquire_owned().await.map(Self::Ready),
+        }
+    }
+
+    /// Wait for the host slot of `url`, then for the crawl-wide permit. Both
+    /// are held until the request is done.
+    pub async fn acquire(
+        self,
+        url: &str,
+        client: &Client,
+    ) -> (Option<HostPermit>, Option<OwnedSemaphorePermit>) {
+        match self {
+            Self::Ready(permit) => (None, Some(permit)),
+            Self::Paced {
+                semaphore,
+                scheduler,
+                parked,
+            } => {
+                let host_permit = scheduler.acquire(url, client).await;
+                let permit = semaphore.acquire_owned().await.ok();
+                drop(parked);
+                (host_permit, permit)
+            }
+        }
+    }
+}
+
+/// Per-host politeness scheduler shared by every fetch task of a crawl.
+///
+/// Thread-safe: `DashMap` for the host table, atomics for per-host state.
+pub struct HostScheduler {
+    config: PolitenessConfig,
+    limiter: DomainRateLimiter,
+    hosts: DashMap<CompactString, Arc<HostState>>,
+    /// Reference point for the millisecond timestamps in [`HostState`].
+    epoch: Instant,
+    /// User agent matched against robots.txt groups.
+    user_agent: Option<Box<CompactString>>,
+    /// Room for fetch tasks waiting on their host.
+    parked: Arc<Semaphore>,
+    #[cfg(feature = "auto_throttle")]
+    /// Latency based delay applied on top of the robots delay.
+    auto_throttle: Option<Arc<crate::utils::auto_throttle::AutoThrottle>>,
+}
+
+impl HostScheduler {
+    /// Create a new scheduler.
+    pub fn new(config: PolitenessConfig) -> Self {
+        Self {
+            config,
+            limiter: DomainRateLimiter::new(UNLIMITED_RATE, 1),
+            hosts: DashMap::with_capacity(64),
+            epoch: Instant::now(),
+            user_agent: None,
+            parked: Arc::new(Semaphore::new(MAX_PARKED_TASKS)),
+            #[cfg(feature = "auto_throttle")]
+            auto_throttle: None,
+        }
+    }
+
+    /// Set the user agent matched against robots.txt groups.
+    pub fn with_user_agent(mut self, user_agent: Option<Box<CompactString>>) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+
+    #[cfg(feature = "auto_throttle")]
+    /// Widen each host's delay with the auto-throttle delay for that host.
+    pub fn with_auto_throttle(
+        mut self,
+        auto_throttle: Option<Arc<crate::utils::auto_throttle::AutoThrottle>>,
+    ) -> Self {
+        self.auto_throttle = auto_throttle;
+        self
+    }
+
+    /// The scheduler configuration.
+    pub fn config(&self) -> &PolitenessConfig {
+        &self.config
+    }
+
+    /// Wait for `url`'s host to accept another request. Reads the host's robots.txt on
+    /// first contact, then waits for a concurrency slot, any `Retry-After` pause and the
+    /// host's rate. Returns `None` when the url has no host.
+    pub async fn acquire(&self, url: &str, client: &Client) -> Option<HostPermit> {
+        let host = crate::utils::get_domain_from_url(url);
+
+        if host.is_empty() {
+            return None;
+        }
+
+        let state = self.host(host);
+
+        if self.config.respect_robots {
+            state
+                .robots
+                .get_or_init(|| self.read_robots(url, host, client))
+                .await;
+        }
+
+        let permit = state.permits.clone().acquire_owned().await.ok()?;
+
+        let paused_until = state.paused_until_ms.load(Ordering::Relaxed);
+        let now = self.now_ms();
+
+        if paused_until > now {
+            tokio::time::sleep(Duration::from_millis(paused_until - now)).await;
+        }
+
+        self.limiter
+            .set_rate(host, rate(self.delay_for(host, &state)));
+
+        loop {
+            let wait = self.limiter.acquire(host);
+            if wait.is_zero() {
+                break;
+            }
+            tokio::time::sleep(wait).await;
+        }
+
+        Some(HostPermit { _permit: permit })
+    }
+
+    /// Apply a host's robots.txt `Crawl-delay` and `Request-rate`. The stricter of the two wins.
+    pub fn set_robots(
+        &self,
+        host: &str,
+        crawl_delay: Option<Duration>,
+        request_rate: Option<RequestRate>,
+    ) {
+        let state = self.host(host);
+        let _ = state.robots.set(());
+
+        if !self.config.respect_robots {
+            return;
+        }
+
+        let request_delay = request_rate
+            .filter(|r| r.requests > 0)
+            .map(|r| Duration::from_secs(r.seconds as u64) / r.requests as u32);
+
+        let delay = crawl_delay
+            .max(request_delay)
+            .unwrap_or_default()
+            .min(self.config.max_delay);
+
+        state
+            .robots_delay_ms
+            .store(delay.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Pause `host` for `wait`, capped at the configured max delay.
+    pub fn retry_after(&self, host: &str, wait: Duration) {
+        let until = self.now_ms() + wait.min(self.config.max_delay).as_millis() as u64;
+        self.host(host)
+            .paused_until_ms
+            .fetch_max(until, Ordering::Relaxed);
+    }
+
+    /// Pause the host of `url` when the response is a 429 or 503, using its `Retry-After`.
+    pub fn record_response(
+        &self,
+        url: &str,
+        status: StatusCode,
+        headers: Option<&reqwest::header::HeaderMap>,
+    ) {
+        if !self.config.respect_retry_after
+            || (status != StatusCode::TOO_MANY_REQUESTS
+                && status != StatusCode::SERVICE_UNAVAILABLE)
+        {
+            return;
+        }
+
+        let host = crate::utils::get_domain_from_url(url);
+
+        if host.is_empty() {
+            return;
+        }
+
+        let wait = headers
+            .and_then(|h| h.get(reqwest::header::RETRY_AFTER))
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after)
+            .unwrap_or(DEFAULT_RETRY_AFTER);
+
+        self.retry_after(host, wait);
+    }
+
+    /// The current delay between requests to `host`.
+    pub fn delay(&self, host: &str) -> Duration {
+        match self.hosts.get(host) {
+            Some(state) => self.delay_for(host, &state),
+            _ => self.config.default_delay,
+        }
+    }
+
+    /// Number of tracked hosts.
+    pub fn len(&self) -> usize {
+        self.hosts.len()
+    }
+
+    /// Whether any host is tracked.
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+    }
+
+    /// Get or create the state for `host`.
+    fn host(&self, host: &str) -> Arc<HostState> {
+        if let Some(state) = self.hosts.get(host) {
+            return state.clone();
+        }
+        self.hosts
+            .entry(CompactString::new(host))
+            .or_insert_with(|| {
+                Arc::new(HostState {
+                    permits: Arc::new(Semaphore::new(self.config.max_concurrency_per_host.max(1))),
+                    robots: OnceCell::new(),
+                    robots_delay_ms: AtomicU64::new(0),
+                    paused_until_ms: AtomicU64::new(0),
+                })
+            })
+            .clone()
+    }
+
+    /// Delay for `host`: the largest of the default, robots.txt and auto-throttle delays.
+    fn delay_for(&self, _host: &str, state: &HostState) -> Duration {
+        let delay = self.config.default_delay.max(Duration::from_millis(
+            state.robots_delay_ms.load(Ordering::Relaxed),
+        ));
+
+        #[cfg(feature = "auto_throttle")]
+        if let Some(at) = self.auto_throttle.as_deref() {
+            return delay.max(at.delay_for(_host));
+        }
+
+        delay
+    }
+
+    /// Fetch and apply the robots.txt of `url`'s host.
+    async fn read_robots(&self, url: &str, host: &str, client: &Client) {
+        let scheme = match url.find("://") {
+            Some(pos) => &url[..pos],
+            _ => "https",
+        };
+        let mut parser = RobotFileParser::new();
+
+        parser
+            .read(client, &string_concat!(scheme, "://", host, "/"))
+            .await;
+
+        let user_agent = self
+            .user_agent
+            .as_deref()
+            .map(|ua| ua.as_str())
+            .unwrap_or("*");
+
+        self.set_robots(
+            host,
+            parser.get_crawl_delay(&self.user_agent),
+            parser.get_req_rate(user_agent),
+        );
+    }
+
+    /// Milliseconds since the scheduler epoch.
+    fn now_ms(&self) -> u64 {
+        self.epoch.elapsed().as_millis() as u64
+    }
+}
+
+/// Token rate for a delay between requests.
+fn rate(delay: Duration) -> f64 {
+    if delay.is_zero() {
+        UNLIMITED_RATE
+    } else {
+        1.0 / delay.as_secs_f64()
+    }
+}
+
+/// Parse a `Retry-After` value given as delay seconds or an HTTP date.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    httpdate::parse_http_date(value).ok().map(|date| {
+        date.duration_since(std::time::SystemTime::now())
+            .unwrap_or_default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn robots_delay_takes_stricter_rule() {
+        let scheduler = HostScheduler::new(PolitenessConfig::default());
+
+        scheduler.set_robots(
+            "a.com",
+            Some(Duration::from_secs(1)),
+            Some(RequestRate {
+                requests: 1,
+                seconds: 5,
+            }),
+        );
+        scheduler.set_robots("b.com", Some(Duration::from_millis(500)), None);
+        scheduler.set_robots("c.com", Some(Duration::from_secs(600)), None);
+
+        assert_eq!(scheduler.delay("a.com"), Duration::from_secs(5));
+        assert_eq!(scheduler.delay("b.com"), Duration::from_millis(500));
+        assert_eq!(scheduler.delay("c.com"), Duration::from_secs(60));
+        assert_eq!(scheduler.delay("d.com"), Duration::ZERO);
+    }
+
+    #[test]
+    fn parses_retry_after() {
+        assert_eq!(parse_retry_after(" 7 "), Some(Duration::from_secs(7)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[tokio::test]
+    async fn paces_each_host_independently() {
+        let scheduler = HostScheduler::new(PolitenessConfig {
+            max_concurrency_per_host: 1,
+            default_delay: Duration::from_millis(200),
+            respect_robots: false,
+            ..Default::default()
+        });
+        let client = crate::website::Website::new("https://a.com").configure_http_client();
+
+        let first = scheduler.acquire("https://slow.com/a", &client).await;
+        assert!(first.is_some());
+
+        // The concurrency cap holds the second request until the first is released.
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(50),
+            scheduler.acquire("https://slow.com/b", &client),
+        )
+        .await;
+        assert!(blocked.is_err());
+
+        // Other hosts keep their own slot and rate.
+        let start = Instant::now();
+        let other = scheduler.acquire("https://fast.com/a", &client).await;
+        assert!(other.is_some());
+        assert!(start.elapsed() < Duration::from_millis(50));
+
+        drop(first);
+
+        let start = Instant::now();
+        let second = scheduler.acquire("https://slow.com/b", &client).await;
+        assert!(second.is_some());
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn paced_permit_waits_for_host_first() {
+        let scheduler = Arc::new(HostScheduler::new(PolitenessConfig {
+            max_concurrency_per_host: 1,
+            respect_robots: false,
+            ..Default::default()
+        }));
+        let semaphore = Arc::new(Semaphore::new(1));
+        let client = crate::website::Website::new("https://a.com").configure_http_client();
+
+        let slow = scheduler.acquire("https://slow.com/a", &client).await;
+        let parked = CrawlPermit::new(&semaphore, Some(&scheduler))
+            .await
+            .unwrap();
+        let waiting = tokio::spawn({
+            let client = client.clone();
+            async move { parked.acquire("https://slow.com/b", &client).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // The task parked on the slow host holds no crawl-wide permit.
+        assert_eq!(semaphore.available_permits(), 1);
+        let (host, permit) = CrawlPermit::new(&semaphore, Some(&scheduler))
+            .await
+            .unwrap()
+            .acquire("https://fast.com/a", &client)
+            .await;
+        assert!(host.is_some() && permit.is_some());
+        drop((host, permit, slow));
+
+        let (host, permit) = waiting.await.unwrap();
+        assert!(host.is_some() && permit.is_some());
+    }
+
+    #[tokio::test]
+    async fn retry_after_pauses_host() {
+        let scheduler = HostScheduler::new(PolitenessConfig {
+            respect_robots: false,
+            ..Default::default()
+        });
+        let client = crate::website::Website::new("https://a.com").configure_http_client();
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, "1".parse().unwrap());
+
+        scheduler.record_response("https://a.com/x", StatusCode::OK, Some(&headers));
+        let start = Instant::now();
+        drop(scheduler.acquire("https://a.com/y", &client).await);
+        assert!(start.elapsed() < Duration::from_millis(500));
+
+        scheduler.record_response(
+            "https://a.com/x",
+            StatusCode::TOO_MANY_REQUESTS,
+            Some(&headers),
+        );
+        let start = Instant::now();
+        drop(scheduler.acquire("https://a.com/y", &client).await);
+        assert!(start.elapsed() >= Duration::from_millis(900));
+    }
+}
@@ -0,0 +1,446 @@
+//! Rich page metadata: Open Graph, Twitter cards, `hreflang` alternates,
+//! `<html lang>`, the canonical link, JSON-LD blocks and feed links.
+//!
+//! Collected by extra lol_html handlers registered next to the title,
+//! description and `og:image` ones, so the document is still parsed once.
+use crate::compact_str::CompactString;
+
+/// Open Graph metadata extracted from `<meta property="og:*">` and
+/// `<meta property="article:*">` tags.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OpenGraph {
+    /// The Open Graph title (`og:title`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub title: Option<CompactString>,
+    /// The Open Graph description (`og:description`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub description: Option<CompactString>,
+    /// The Open Graph image URL (`og:image`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub image: Option<CompactString>,
+    /// The canonical page URL (`og:url`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub url: Option<CompactString>,
+    /// The content type (`og:type`, e.g., "article", "website").
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub content_type: Option<CompactString>,
+    /// The site name (`og:site_name`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub site_name: Option<CompactString>,
+    /// The locale of the content (`og:locale`, e.g., "en_US").
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub locale: Option<CompactString>,
+    /// The author's name (`article:author` or `og:author`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub author: Option<CompactString>,
+    /// The time the content was first published (`article:published_time`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub published_time: Option<CompactString>,
+    /// The time the content was last modified (`article:modified_time`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub modified_time: Option<CompactString>,
+    /// Every `og:*` / `article:*` property in document order, including
+    /// repeated and structured ones such as `og:image:width`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub properties: Vec<(CompactString, CompactString)>,
+}
+
+impl OpenGraph {
+    /// Record a property. The first value of a known property wins.
+    pub fn insert(&mut self, property: &str, content: &str) {
+        let slot = match property {
+            "og:title" => Some(&mut self.title),
+            "og:description" => Some(&mut self.description),
+            "og:image" | "og:image:url" => Some(&mut self.image),
+            "og:url" => Some(&mut self.url),
+            "og:type" => Some(&mut self.content_type),
+            "og:site_name" => Some(&mut self.site_name),
+            "og:locale" => Some(&mut self.locale),
+            "og:author" | "article:author" => Some(&mut self.author),
+            "article:published_time" => Some(&mut self.published_time),
+            "article:modified_time" => Some(&mut self.modified_time),
+            _ => None,
+        };
+
+        if let Some(slot) = slot.filter(|slot| slot.is_none()) {
+            *slot = Some(content.into());
+        }
+
+        self.properties.push((property.into(), content.into()));
+    }
+}
+
+/// Twitter card metadata extracted from `<meta name="twitter:*">` tags.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TwitterCard {
+    /// The card type (`twitter:card`, e.g., "summary_large_image").
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub card: Option<CompactString>,
+    /// The site account (`twitter:site`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub site: Option<CompactString>,
+    /// The content creator account (`twitter:creator`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub creator: Option<CompactString>,
+    /// The card title (`twitter:title`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub title: Option<CompactString>,
+    /// The card description (`twitter:description`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub description: Option<CompactString>,
+    /// The card image URL (`twitter:image`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub image: Option<CompactString>,
+    /// Every `twitter:*` property in document order.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub properties: Vec<(CompactString, CompactString)>,
+}
+
+impl TwitterCard {
+    /// Record a property. The first value of a known property wins.
+    pub fn insert(&mut self, name: &str, content: &str) {
+        let slot = match name {
+            "twitter:card" => Some(&mut self.card),
+            "twitter:site" => Some(&mut self.site),
+            "twitter:creator" => Some(&mut self.creator),
+            "twitter:title" => Some(&mut self.title),
+            "twitter:description" => Some(&mut self.description),
+            "twitter:image" | "twitter:image:src" => Some(&mut self.image),
+            _ => None,
+        };
+
+        if let Some(slot) = slot.filter(|slot| slot.is_none()) {
+            *slot = Some(content.into());
+        }
+
+        self.properties.push((name.into(), content.into()));
+    }
+}
+
+/// A `<link rel="alternate" hreflang="..." href="...">` translation.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HreflangAlternate {
+    /// The language tag, e.g. "en-US" or "x-default".
+    pub hreflang: CompactString,
+    /// The alternate URL as declared in the document.
+    pub href: CompactString,
+}
+
+/// The format a discovered feed declares through its `type` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FeedKind {
+    /// RSS 2.0 (`application/rss+xml`).
+    Rss,
+    /// Atom (`application/atom+xml`).
+    Atom,
+    /// JSON Feed (`application/feed+json`).
+    JsonFeed,
+}
+
+impl FeedKind {
+    /// The kind for a `<link type>` MIME type.
+    pub fn from_mime(mime: &str) -> Option<Self> {
+        let mime = mime.split(';').next().unwrap_or_default().trim();
+        if mime.eq_ignore_ascii_case("application/rss+xml") {
+            Some(Self::Rss)
+        } else if mime.eq_ignore_ascii_case("application/atom+xml") {
+            Some(Self::Atom)
+        } else if mime.eq_ignore_ascii_case("application/feed+json") {
+            Some(Self::JsonFeed)
+        } else {
+            None
+        }
+    }
+}
+
+/// A `<link rel="alternate" type="application/rss+xml" href="...">` feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeedLink {
+    /// The feed format.
+    pub kind: FeedKind,
+    /// The feed URL as declared in the document.
+    pub href: CompactString,
+    /// The link `title`, if any.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub title: Option<CompactString>,
+}
+
+/// Metadata beyond title, description and image.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RichMetadata {
+    /// Open Graph properties.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub og: Option<OpenGraph>,
+    /// Twitter card properties.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub twitter: Option<TwitterCard>,
+    /// `hreflang` alternates in document order.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub alternates: Vec<HreflangAlternate>,
+    /// The `<html lang>` attribute.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub lang: Option<CompactString>,
+    /// The `<link rel="canonical">` href as declared in the document.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub canonical: Option<CompactString>,
+    /// Parsed `<script type="application/ld+json">` blocks. Blocks that are
+    /// not valid JSON are skipped.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub json_ld: Vec<serde_json::Value>,
+    /// RSS, Atom and JSON Feed links in document order.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub feeds: Vec<FeedLink>,
+}
+
+impl RichMetadata {
+    /// Nothing was collected.
+    pub fn is_empty(&self) -> bool {
+        self.og.is_none()
+            && self.twitter.is_none()
+            && self.alternates.is_empty()
+            && self.lang.is_none()
+            && self.canonical.is_none()
+            && self.json_ld.is_empty()
+            && self.feeds.is_empty()
+    }
+}
+
+/// Per-document collection state for the rich metadata handlers.
+#[derive(Debug, Default)]
+pub(crate) struct RichMetadataCollector {
+    /// The metadata collected so far.
+    metadata: RichMetadata,
+    /// Text of the JSON-LD script being read; lol_html may split it.
+    json_ld_buf: String,
+}
+
+impl RichMetadataCollector {
+    /// Anything was collected.
+    pub(crate) fn exist(&self) -> bool {
+        !self.metadata.is_empty()
+    }
+
+    /// Move the collected metadata onto the page metadata.
+    pub(crate) fn apply(self, metadata: &mut crate::page::Metadata) {
+        if self.exist() {
+            metadata.rich = Some(Box::new(self.metadata));
+        }
+    }
+}
+
+/// Number of handlers [`rich_metadata_handlers`] registers.
+pub(crate) const RICH_METADATA_HANDLERS: usize = 7;
+
+/// The lol_html handlers filling `slot`.
+pub(crate) fn rich_metadata_handlers<'h>(
+    slot: &'h mut RichMetadataCollector,
+) -> Vec<(
+    std::borrow::Cow<'static, lol_html::Selector>,
+    lol_html::send::ElementContentHandlers<'h>,
+)> {
+    let RichMetadataCollector {
+        metadata,
+        json_ld_buf,
+    } = slot;
+    let RichMetadata {
+        og,
+        twitter,
+        alternates,
+        lang,
+        canonical,
+        json_ld,
+        feeds,
+    } = metadata;
+
+    vec![
+        lol_html::element!(
+            r#"meta[property^="og:"][content], meta[property^="article:"][content]"#,
+            |el| {
+                if let (Some(property), Some(content)) =
+                    (el.get_attribute("property"), el.get_attribute("content"))
+                {
+                    if !content.is_empty() {
+                        og.get_or_insert_with(Default::default)
+                            .insert(&property.to_ascii_lowercase(), &content);
+                    }
+                }
+                Ok(())
+            }
+        ),
+        lol_html::element!(
+            r#"meta[name^="twitter:"][content], meta[property^="twitter:"][content]"#,
+            |el| {
+                let name = el
+                    .get_attribute("name")
+                    .or_else(|| el.get_attribute("property"));
+                if let (Some(name), Some(content)) = (name, el.get_attribute("content")) {
+                    if !content.is_empty() {
+                        twitter
+                            .get_or_insert_with(Default::default)
+                            .insert(&name.to_ascii_lowercase(), &content);
+                    }
+                }
+                Ok(())
+            }
+        ),
+        lol_html::element!(r#"link[rel~="alternate" i][hreflang][href]"#, |el| {
+            if let (Some(hreflang), Some(href)) =
+                (el.get_attribute("hreflang"), el.get_attribute("href"))
+            {
+                let hreflang = hreflang.trim();
+                let href = href.trim();
+                if !hreflang.is_empty() && !href.is_empty() {
+                    alternates.push(HreflangAlternate {
+                        hreflang: hreflang.into(),
+                        href: href.into(),
+                    });
+                }
+            }
+            Ok(())
+        }),
+        lol_html::element!(r#"link[rel~="alternate" i][type][href]"#, |el| {
+            if let (Some(kind), Some(href)) = (
+                el.get_attribute("type")
+                    .as_deref()
+                    .and_then(FeedKind::from_mime),
+                el.get_attribute("href"),
+            ) {
+                let href = href.trim();
+                if !href.is_empty() {
+                    feeds.push(FeedLink {
+                        kind,
+                        href: href.into(),
+                        title: el
+                            .get_attribute("title")
+                            .filter(|t| !t.trim().is_empty())
+                            .map(|t| t.trim().into()),
+                    });
+                }
+            }
+            Ok(())
+        }),
+        lol_html::element!("html[lang]", |el| {
+            if let Some(value) = el.get_attribute("lang") {
+                let value = value.trim();
+                if lang.is_none() && !value.is_empty() {
+                    *lang = Some(value.into());
+                }
+            }
+            Ok(())
+        }),
+        lol_html::element!(r#"link[rel~="canonical" i][href]"#, |el| {
+            if let Some(href) = el.get_attribute("href") {
+                let href = href.trim();
+                if canonical.is_none() && !href.is_empty() {
+                    *canonical = Some(href.into());
+                }
+            }
+            Ok(())
+        }),
+        lol_html::text!(r#"script[type="application/ld+json" i]"#, |text| {
+            json_ld_buf.push_str(text.as_str());
+            if text.last_in_text_node() {
+                if let Ok(value) = serde_json::from_str(json_ld_buf.trim()) {
+                    json_ld.push(value);
+                }
+                json_ld_buf.clear();
+            }
+            Ok(())
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(html: &str) -> RichMetadata {
+        let mut slot = RichMetadataCollector::default();
+        {
+            let mut rewriter = lol_html::send::HtmlRewriter::new(
+                lol_html::send::Settings {
+                    element_content_handlers: rich_metadata_handlers(&mut slot),
+                    ..lol_html::send::Settings::new_for_handler_types()
+                },
+                |_c: &[u8]| {},
+            );
+            // Feed in small chunks so text handlers see split chunks.
+            for chunk in html.as_bytes().chunks(7) {
+                rewriter.write(chunk).unwrap();
+            }
+            rewriter.end().unwrap();
+        }
+        slot.metadata
+    }
+
+    #[test]
+    fn test_rich_metadata_collects_everything() {
+        let html = r#"<!doctype html><html lang="en-US"><head>
+            <meta property="og:title" content="Title">
+            <meta property="og:image" content="https://a.com/1.png">
+            <meta property="og:image" content="https://a.com/2.png">
+            <meta property="og:image:width" content="1200">
+            <meta property="article:published_time" content="2024-01-01">
+            <meta name="twitter:card" content="summary_large_image">
+            <meta name="twitter:site" content="@spider">
+            <link rel="alternate" hreflang="de" href="https://a.com/de/">
+            <link rel="alternate" hreflang="x-default" href="https://a.com/">
+            <link rel="alternate" type="application/rss+xml" title="Posts" href="/feed.xml">
+            <link rel="alternate" type="application/feed+json" href="/feed.json">
+            <link rel="alternate" type="text/html" href="/print">
+            <link rel="canonical" href="https://a.com/">
+            <script type="application/ld+json">{"@context":"https://schema.org","@type":"Article","headline":"Hello"}</script>
+            <script type="application/ld+json">{not json}</script>
+            </head><body></body></html>"#;
+
+        let rich = collect(html);
+        let og = rich.og.unwrap();
+
+        assert_eq!(og.title.as_deref(), Some("Title"));
+        assert_eq!(og.image.as_deref(), Some("https://a.com/1.png"));
+        assert_eq!(og.published_time.as_deref(), Some("2024-01-01"));
+        assert_eq!(og.properties.len(), 5);
+
+        let twitter = rich.twitter.unwrap();
+        assert_eq!(twitter.card.as_deref(), Some("summary_large_image"));
+        assert_eq!(twitter.site.as_deref(), Some("@spider"));
+
+        assert_eq!(rich.alternates.len(), 2);
+        assert_eq!(rich.alternates[1].hreflang, "x-default");
+        assert_eq!(rich.lang.as_deref(), Some("en-US"));
+        assert_eq!(rich.canonical.as_deref(), Some("https://a.com/"));
+        assert_eq!(rich.json_ld.len(), 1);
+        assert_eq!(rich.json_ld[0]["headline"], "Hello");
+        assert_eq!(rich.feeds.len(), 2);
+        assert_eq!(rich.feeds[0].kind, FeedKind::Rss);
+        assert_eq!(rich.feeds[0].href, "/feed.xml");
+        assert_eq!(rich.feeds[0].title.as_deref(), Some("Posts"));
+        assert_eq!(rich.feeds[1].kind, FeedKind::JsonFeed);
+    }
+
+    #[test]
+    fn test_rich_metadata_empty() {
+        assert!(collect("<html><head><title>t</title></head></html>").is_empty());
+    }
+}
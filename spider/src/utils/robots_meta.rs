@@ -0,0 +1,379 @@
+//! Page-level robots directives from `<meta name="robots">` and the
+//! `X-Robots-Tag` response header.
+//!
+//! `robots.txt` decides whether a URL may be fetched at all; these
+//! directives tell the crawler what it may do with a page once fetched:
+//! follow its links (`nofollow`), emit it (`noindex`), or archive it
+//! (`noarchive`).
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The agent token matched against bot-scoped directives such as
+/// `<meta name="spider">` or `X-Robots-Tag: spider: nofollow`.
+pub const ROBOTS_META_AGENT: &str = "spider";
+
+/// How many bytes of the document are scanned for robots meta tags when
+/// no `</head>` is found first.
+const HEAD_SCAN_LIMIT: usize = 64 * 1024;
+
+/// Indexing directives a page declares about itself.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RobotsDirectives {
+    /// The page should not be indexed or emitted.
+    pub noindex: bool,
+    /// Links on the page should not be followed.
+    pub nofollow: bool,
+    /// The page should not be archived or cached.
+    pub noarchive: bool,
+}
+
+impl RobotsDirectives {
+    /// Parse a comma separated directive list such as `noindex, nofollow`.
+    /// `none` implies `noindex` and `nofollow`; `nocache` is treated as
+    /// `noarchive`. Unknown tokens are ignored.
+    pub fn parse(content: &str) -> Self {
+        let mut directives = Self::default();
+
+        for token in content.split([',', ' ', '\t']) {
+            let token = token.trim();
+
+            if token.eq_ignore_ascii_case("noindex") {
+                directives.noindex = true;
+            } else if token.eq_ignore_ascii_case("nofollow") {
+                directives.nofollow = true;
+            } else if token.eq_ignore_ascii_case("noarchive")
+                || token.eq_ignore_ascii_case("nocache")
+            {
+                directives.noarchive = true;
+            } else if token.eq_ignore_ascii_case("none") {
+                directives.noindex = true;
+                directives.nofollow = true;
+            }
+        }
+
+        directives
+    }
+
+    /// Parse a single `X-Robots-Tag` value. Values scoped to another
+    /// agent (`googlebot: noindex`) are ignored.
+    pub fn parse_header_value(value: &str) -> Self {
+        match value.split_once(':') {
+            Some((agent, rest)) if is_agent_prefix(agent) => {
+                if agent.trim().eq_ignore_ascii_case(ROBOTS_META_AGENT) {
+                    Self::parse(rest)
+                } else {
+                    Self::default()
+                }
+            }
+            _ => Self::parse(value),
+        }
+    }
+
+    /// Collect the directives from every `X-Robots-Tag` header.
+    pub fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let mut directives = Self::default();
+
+        for value in headers.get_all("x-robots-tag").iter() {
+            if let Ok(value) = value.to_str() {
+                directives.merge(Self::parse_header_value(value));
+            }
+        }
+
+        directives
+    }
+
+    /// Collect the directives from the robots meta tags in the document head.
+    pub fn from_html(html: &[u8]) -> Self {
+        let mut directives = Self::default();
+        let head = head_slice(html);
+
+        // Cheap pre-check so pages without robots meta skip the tag walk.
+        if !head
+            .windows(6)
+            .any(|w| w.eq_ignore_ascii_case(b"robots") || w.eq_ignore_ascii_case(b"spider"))
+        {
+            return directives;
+        }
+
+        for_each_head_tag(head, "meta", |tag| {
+            if let (Some(name), Some(content)) = (tag_attr(tag, "name"), tag_attr(tag, "content")) {
+                if is_robots_meta_name(name) {
+                    directives.merge(Self::parse(content));
+                }
+            }
+        });
+
+        directives
+    }
+
+    /// Combine two sets of directives; any directive set in either wins.
+    pub fn merge(&mut self, other: Self) {
+        self.noindex |= other.noindex;
+        self.nofollow |= other.nofollow;
+        self.noarchive |= other.noarchive;
+    }
+
+    /// No directive is set.
+    pub fn is_empty(&self) -> bool {
+        !(self.noindex || self.nofollow || self.noarchive)
+    }
+}
+
+/// Which `nofollow` signals the link extractor honors.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NofollowPolicy {
+    /// Drop every link on a page declaring `nofollow` in a robots meta tag
+    /// or `X-Robots-Tag` header, and withhold `noindex` and `noarchive`
+    /// pages from subscribers.
+    pub robots_meta: bool,
+    /// Drop individual links marked `rel="nofollow"`.
+    pub rel_nofollow: bool,
+}
+
+impl NofollowPolicy {
+    /// A policy honoring the given signals.
+    pub fn new(robots_meta: bool, rel_nofollow: bool) -> Self {
+        Self {
+            robots_meta,
+            rel_nofollow,
+        }
+    }
+
+    /// Any signal is honored.
+    pub fn is_enabled(&self) -> bool {
+        self.robots_meta || self.rel_nofollow
+    }
+
+    /// Build the per-document filter. `page_nofollow` seeds the page-level
+    /// state, e.g. from response headers. `None` when nothing is honored.
+    pub(crate) fn filter(&self, page_nofollow: bool) -> Option<NofollowFilter> {
+        if self.is_enabled() {
+            Some(NofollowFilter {
+                policy: *self,
+                page: AtomicBool::new(self.robots_meta && page_nofollow),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Per-document link filter shared by the link extraction handlers. The
+/// page-level state flips once a robots meta tag with `nofollow` is seen,
+/// which in a well-formed document happens in `<head>` before any anchor.
+#[derive(Debug)]
+pub(crate) struct NofollowFilter {
+    policy: NofollowPolicy,
+    page: AtomicBool,
+}
+
+impl NofollowFilter {
+    /// The policy this filter applies.
+    pub(crate) fn policy(&self) -> NofollowPolicy {
+        self.policy
+    }
+
+    /// Record a `<meta name=.. content=..>` element.
+    pub(crate) fn observe_meta(&self, name: &str, content: &str) {
+        if self.policy.robots_meta
+            && is_robots_meta_name(name)
+            && RobotsDirectives::parse(content).nofollow
+        {
+            self.page.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Whether the page-level `nofollow` is in effect.
+    pub(crate) fn page_nofollow(&self) -> bool {
+        self.page.load(Ordering::Relaxed)
+    }
+
+    /// Whether a link with the given `rel` attribute should be skipped.
+    pub(crate) fn skip(&self, rel: Option<&str>) -> bool {
+        self.page_nofollow() || (self.policy.rel_nofollow && rel.is_some_and(is_rel_nofollow))
+    }
+}
+
+/// The `rel` attribute contains the `nofollow` token.
+pub fn is_rel_nofollow(rel: &str) -> bool {
+    rel.split_ascii_whitespace()
+        .any(|token| token.eq_ignore_ascii_case("nofollow"))
+}
+
+/// The meta `name` addresses this crawler: `robots` or the agent token.
+pub(crate) fn is_robots_meta_name(name: &str) -> bool {
+    let name = name.trim();
+    name.eq_ignore_ascii_case("robots") || name.eq_ignore_ascii_case(ROBOTS_META_AGENT)
+}
+
+/// The text before `:` in a header value names an agent rather than a
+/// directive with an argument such as `unavailable_after: ...`.
+fn is_agent_prefix(agent: &str) -> bool {
+    let agent = agent.trim();
+
+    !agent.is_empty()
+        && !agent.contains([',', ' '])
+        && !agent.eq_ignore_ascii_case("unavailable_after")
+        && !agent.to_ascii_lowercase().starts_with("max-")
+}
+
+/// The document up to `</head>`, or the first [`HEAD_SCAN_LIMIT`] bytes.
+pub(crate) fn head_slice(html: &[u8]) -> &[u8] {
+    let html = &html[..html.len().min(HEAD_SCAN_LIMIT)];
+
+    match html
+        .windows(7)
+        .position(|w| w.eq_ignore_ascii_case(b"</head>"))
+    {
+        Some(end) => &html[..end],
+        None => html,
+    }
+}
+
+/// Call `f` with the attribute text of every `<tag ...>` start tag in `head`.
+pub(crate) fn for_each_head_tag(head: &[u8], tag: &str, mut f: impl FnMut(&str)) {
+    let head = String::from_utf8_lossy(head);
+    // ASCII lowercasing keeps byte offsets, so matches index `head` directly.
+    let lower = head.to_ascii_lowercase();
+    let open = format!("<{tag}");
+    let mut cursor = 0;
+
+    while let Some(start) = lower[cursor..].find(&open) {
+        let start = cursor + start + open.len();
+        let end = match lower[start..].find('>') {
+            Some(end) => start + end,
+            None => break,
+        };
+
+        // `<metadata>` and friends share the prefix.
+        if lower[start..]
+            .bytes()
+            .next()
+            .is_some_and(|b| b.is_ascii_whitespace() || b == b'/')
+        {
+            f(&head[start..end]);
+        }
+
+        cursor = end + 1;
+    }
+}
+
+/// Read an attribute value from the inside of a start tag. Attribute names
+/// match case-insensitively.
+pub(crate) fn tag_attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let bytes = tag.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        while i < bytes.len() && (bytes[i].is_ascii_whitespace() || bytes[i] == b'/') {
+            i += 1;
+        }
+        let key_start = i;
+        while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'=' {
+            i += 1;
+        }
+        let key = &tag[key_start..i];
+
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        if i >= bytes.len() || bytes[i] != b'=' {
+            if key.eq_ignore_ascii_case(name) {
+                return Some("");
+            }
+            continue;
+        }
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+
+        let value = match bytes.get(i) {
+            Some(&quote) if quote == b'"' || quote == b'\'' => {
+                let value_start = i + 1;
+                let value_end = tag[value_start..]
+                    .find(quote as char)
+                    .map_or(tag.len(), |end| value_start + end);
+                i = value_end + 1;
+                &tag[value_start..value_end]
+            }
+            _ => {
+                let value_start = i;
+                while i < bytes.len() && !bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                &tag[value_start..i]
+            }
+        };
+
+        if key.eq_ignore_ascii_case(name) {
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_directives() {
+        let d = RobotsDirectives::parse("NoIndex, nofollow");
+        assert!(d.noindex && d.nofollow && !d.noarchive);
+
+        let d = RobotsDirectives::parse("none");
+        assert!(d.noindex && d.nofollow);
+
+        let d = RobotsDirectives::parse("index,follow,nocache");
+        assert!(!d.noindex && !d.nofollow && d.noarchive);
+    }
+
+    #[test]
+    fn test_header_agent_scoping() {
+        assert!(RobotsDirectives::parse_header_value("nofollow").nofollow);
+        assert!(RobotsDirectives::parse_header_value("spider: nofollow").nofollow);
+        assert!(RobotsDirectives::parse_header_value("googlebot: noindex").is_empty());
+        assert!(
+            RobotsDirectives::parse_header_value("noarchive, unavailable_after: 25 Jun 2030")
+                .noarchive
+        );
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.append("X-Robots-Tag", "noindex".parse().unwrap());
+        headers.append("X-Robots-Tag", "otherbot: nofollow".parse().unwrap());
+        headers.append("X-Robots-Tag", "noarchive".parse().unwrap());
+
+        let d = RobotsDirectives::from_headers(&headers);
+        assert!(d.noindex && !d.nofollow && d.noarchive);
+    }
+
+    #[test]
+    fn test_from_html() {
+        let html = br#"<html><head><meta charset="utf-8"><META Name="Robots" CONTENT='noindex, NOFOLLOW'><meta name=spider content=noarchive /></head><body></body></html>"#;
+        let d = RobotsDirectives::from_html(html);
+        assert!(d.noindex && d.nofollow && d.noarchive);
+
+        let html = br#"<html><head><meta name="googlebot" content="noindex"></head><body><meta name="robots" content="nofollow"></body></html>"#;
+        assert!(RobotsDirectives::from_html(html).is_empty());
+    }
+
+    #[test]
+    fn test_nofollow_filter() {
+        assert!(NofollowPolicy::default().filter(true).is_none());
+
+        let filter = NofollowPolicy::new(false, true).filter(true).unwrap();
+        assert!(!filter.skip(None));
+        assert!(filter.skip(Some("external NoFollow")));
+        filter.observe_meta("robots", "nofollow");
+        assert!(!filter.skip(Some("noopener")));
+
+        let filter = NofollowPolicy::new(true, false).filter(false).unwrap();
+        assert!(!filter.skip(Some("nofollow")));
+        filter.observe_meta("description", "nofollow");
+        assert!(!filter.skip(None));
+        filter.observe_meta("robots", "noindex, nofollow");
+        assert!(filter.skip(None));
+    }
+}
@@ -0,0 +1,680 @@
+//! `sitemap.xml`, `llms.txt` and `llms-full.txt` generation from a completed crawl.
+//!
+//! Collect pages (or bare links) into a [`SiteExport`] and render it. Sitemaps
+//! are split into files of at most [`MAX_SITEMAP_URLS`] URLs; when more than
+//! one file is needed, `sitemap.xml` becomes a `<sitemapindex>` pointing at
+//! `sitemap-1.xml`, `sitemap-2.xml`, ….
+//!
+//! `llms.txt` lists every page with its `<title>` and meta description.
+//! `llms-full.txt` inlines the page content, which the caller supplies —
+//! usually the markdown produced by `spider_transformations` — or the
+//! visible text of the pages with [`SiteExport::from_website`]. Large exports
+//! can keep the content in a spool file until it is written with
+//! [`SiteExport::with_content_spool`].
+//!
+//! Reference: <https://www.sitemaps.org/protocol.html>, <https://llmstxt.org/>
+
+use crate::page::Page;
+use crate::website::Website;
+use crate::CaseInsensitiveString;
+use hashbrown::HashSet;
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Most URLs a single sitemap file may hold, per the sitemaps.org protocol.
+pub const MAX_SITEMAP_URLS: usize = 50_000;
+
+/// A page to export.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExportEntry {
+    /// The page URL.
+    pub url: String,
+    /// The page `<title>`.
+    pub title: Option<String>,
+    /// The page `<meta name="description">`.
+    pub description: Option<String>,
+    /// When the page was last modified.
+    pub lastmod: Option<SystemTime>,
+    /// The page content for `llms-full.txt`, usually markdown. Left empty
+    /// in [`SiteExport::entries`] when the content was spooled to disk.
+    pub content: Option<String>,
+}
+
+impl ExportEntry {
+    /// An entry with only a URL.
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// An entry from a crawled page, using [`Page::get_metadata`] for the
+    /// title and description. The `Last-Modified` header fills `lastmod` with
+    /// the `headers` flag.
+    pub fn from_page(page: &Page) -> Self {
+        let metadata = page.get_metadata().as_deref();
+
+        Self {
+            url: page.get_url().to_string(),
+            title: metadata
+                .and_then(|m| m.title.as_deref())
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty()),
+            description: metadata
+                .and_then(|m| m.description.as_deref())
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty()),
+            lastmod: last_modified(page),
+            content: None,
+        }
+    }
+
+    /// Set the content inlined into `llms-full.txt`.
+    pub fn with_content(mut self, content: Option<String>) -> Self {
+        self.content = content;
+        self
+    }
+}
+
+/// The `Last-Modified` response header of the page.
+#[cfg(feature = "headers")]
+fn last_modified(page: &Page) -> Option<SystemTime> {
+    let value = page.headers.as_ref()?.get(reqwest::header::LAST_MODIFIED)?;
+    httpdate::parse_http_date(value.to_str().ok()?).ok()
+}
+
+/// The `Last-Modified` response header of the page.
+#[cfg(not(feature = "headers"))]
+fn last_modified(_page: &Page) -> Option<SystemTime> {
+    None
+}
+
+/// A rendered sitemap file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitemapFile {
+    /// The file name, e.g. `sitemap.xml` or `sitemap-2.xml`.
+    pub name: String,
+    /// The XML document.
+    pub body: String,
+}
+
+/// Page content kept on disk until `llms-full.txt` is written. The file is
+/// removed on drop.
+#[derive(Debug)]
+struct ContentSpool {
+    /// The spool file path.
+    path: PathBuf,
+    /// The spool file, opened for reading and writing.
+    file: std::fs::File,
+    /// Bytes written so far.
+    len: u64,
+}
+
+impl ContentSpool {
+    /// Append `content`, returning its offset and length.
+    fn append(&mut self, content: &str) -> std::io::Result<(u64, u64)> {
+        let mut file = &self.file;
+        file.seek(std::io::SeekFrom::Start(self.len))?;
+        file.write_all(content.as_bytes())?;
+        let span = (self.len, content.len() as u64);
+        self.len += span.1;
+        Ok(span)
+    }
+
+    /// Read the content at `span` back.
+    fn read(&self, (offset, len): (u64, u64)) -> std::io::Result<String> {
+        let mut file = &self.file;
+        file.seek(std::io::SeekFrom::Start(offset))?;
+        let mut content = String::with_capacity(len as usize);
+        file.take(len).read_to_string(&mut content)?;
+        Ok(content)
+    }
+}
+
+impl Drop for ContentSpool {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Pages collected for export.
+#[derive(Debug)]
+pub struct SiteExport {
+    /// The site root, used for the sitemap index `<loc>` entries.
+    base_url: String,
+    /// The `llms.txt` heading. Defaults to the host of the site.
+    title: Option<String>,
+    /// The `llms.txt` blockquote summary.
+    summary: Option<String>,
+    /// Most URLs per sitemap file.
+    max_urls_per_sitemap: usize,
+    /// Entries in the order they were added.
+    entries: Vec<ExportEntry>,
+    /// Where the content of each entry sits in the spool.
+    spans: Vec<Option<(u64, u64)>>,
+    /// The content spool, when enabled.
+    spool: Option<ContentSpool>,
+    /// URLs already added.
+    seen: HashSet<String>,
+}
+
+impl SiteExport {
+    /// A new export for the site rooted at `base_url`.
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            title: None,
+            summary: None,
+            max_urls_per_sitemap: MAX_SITEMAP_URLS,
+            entries: Vec::new(),
+            spans: Vec::new(),
+            spool: None,
+            seen: HashSet::new(),
+        }
+    }
+
+    /// An export of a finished crawl: its pages with their visible text as
+    /// the `llms-full.txt` content when the website was scraped, otherwise
+    /// its visited links.
+    pub fn from_website(website: &Website) -> Self {
+        let mut export = Self::new(website.get_url().inner());
+
+        match website.get_pages() {
+            Some(pages) => {
+                for page in pages.iter() {
+                    let content = crate::utils::text::extract_text(&page.get_html());
+                    export.push_page(page, Some(content));
+                }
+            }
+            _ => export.extend_links(website.get_links().iter()),
+        }
+
+        export
+    }
+
+    /// Set the `llms.txt` heading.
+    pub fn with_title(mut self, title: Option<String>) -> Self {
+        self.title = title;
+        self
+    }
+
+    /// Set the `llms.txt` summary.
+    pub fn with_summary(mut self, summary: Option<String>) -> Self {
+        self.summary = summary;
+        self
+    }
+
+    /// Keep the `llms-full.txt` content in a file at `path` instead of in
+    /// memory until the export is written. The file is removed with the export.
+    pub fn with_content_spool(mut self, path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        self.spool = Some(ContentSpool { path, file, len: 0 });
+        Ok(self)
+    }
+
+    /// Set the most URLs per sitemap file, capped at [`MAX_SITEMAP_URLS`].
+    pub fn with_max_urls_per_sitemap(mut self, max_urls: usize) -> Self {
+        self.max_urls_per_sitemap = max_urls.clamp(1, MAX_SITEMAP_URLS);
+        self
+    }
+
+    /// Add an entry. Returns `false` if the URL was already added. The
+    /// content moves to the spool when one is set.
+    pub fn push(&mut self, mut entry: ExportEntry) -> bool {
+        if !self.seen.insert(entry.url.clone()) {
+            return false;
+        }
+
+        let mut span = None;
+
+        if let (Some(spool), Some(content)) = (self.spool.as_mut(), entry.content.as_deref()) {
+            match spool.append(content.trim()) {
+                Ok(spooled) => {
+                    span = Some(spooled);
+                    entry.content = None;
+                }
+                Err(e) => log::warn!("failed to spool the content of {}: {e}", entry.url),
+            }
+        }
+
+        self.spans.push(span);
+        self.entries.push(entry);
+        true
+    }
+
+    /// Add a crawled page with its optional `llms-full.txt` content. Pages
+    /// without a success status are skipped.
+    pub fn push_page(&mut self, page: &Page, content: Option<String>) -> bool {
+        page.status_code.is_success()
+            && self.push(ExportEntry::from_page(page).with_content(content))
+    }
+
+    /// Add bare links, e.g. from [`Website::get_links`].
+    pub fn extend_links<'a>(&mut self, links: impl Iterator<Item = &'a CaseInsensitiveString>) {
+        for link in links {
+            self.push(ExportEntry::new(link.inner()));
+        }
+    }
+
+    /// The entries collected.
+    pub fn entries(&self) -> &[ExportEntry] {
+        &self.entries
+    }
+
+    /// Number of entries collected.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// No entries were collected.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries ordered by URL so repeated exports diff cleanly.
+    fn sorted(&self) -> Vec<&ExportEntry> {
+        self.sorted_indices()
+            .into_iter()
+            .map(|i| &self.entries[i])
+            .collect()
+    }
+
+    /// Entry indices ordered by URL.
+    fn sorted_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.entries.len()).collect();
+        indices.sort_unstable_by(|&a, &b| self.entries[a].url.cmp(&self.entries[b].url));
+        indices
+    }
+
+    /// Render the sitemap files. A single `sitemap.xml` `<urlset>` when the
+    /// entries fit in one file, otherwise numbered `<urlset>` files plus a
+    /// `sitemap.xml` `<sitemapindex>`.
+    pub fn sitemaps(&self) -> Vec<SitemapFile> {
+        let entries = self.sorted();
+        let chunks: Vec<&[&ExportEntry]> = entries.chunks(self.max_urls_per_sitemap).collect();
+
+        if chunks.len() <= 1 {
+            return vec![SitemapFile {
+                name: "sitemap.xml".into(),
+                body: urlset(chunks.first().copied().unwrap_or_default()),
+            }];
+        }
+
+        let mut files = Vec::with_capacity(chunks.len() + 1);
+        let mut index = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<sitemapindex xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            let name = format!("sitemap-{}.xml", i + 1);
+            index.push_str("  <sitemap>\n    <loc>");
+            push_xml_escaped(&mut index, &self.base_url);
+            index.push('/');
+            push_xml_escaped(&mut index, &name);
+            index.push_str("</loc>\n");
+            if let Some(lastmod) = chunk.iter().filter_map(|e| e.lastmod).max() {
+                index.push_str("    <lastmod>");
+                index.push_str(&w3c_date(lastmod));
+                index.push_str("</lastmod>\n");
+            }
+            index.push_str("  </sitemap>\n");
+            files.push(SitemapFile {
+                name,
+                body: urlset(chunk),
+            });
+        }
+
+        index.push_str("</sitemapindex>\n");
+        files.push(SitemapFile {
+            name: "sitemap.xml".into(),
+            body: index,
+        });
+
+        files
+    }
+
+    /// The `llms.txt` heading: the configured title or the site host.
+    fn heading(&self) -> String {
+        match &self.title {
+            Some(title) => title.clone(),
+            _ => url::Url::parse(&self.base_url)
+                .ok()
+                .and_then(|u| u.host_str().map(|h| h.to_string()))
+                .unwrap_or_else(|| self.base_url.clone()),
+        }
+    }
+
+    /// The heading and summary shared by both llms files.
+    fn llms_header(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# ");
+        out.push_str(&self.heading());
+        out.push_str("\n\n");
+        if let Some(summary) = &self.summary {
+            out.push_str("> ");
+            out.push_str(summary.trim());
+            out.push_str("\n\n");
+        }
+        out
+    }
+
+    /// Render `llms.txt`: a markdown index linking every page with its
+    /// title and description.
+    pub fn llms_txt(&self) -> String {
+        let mut out = self.llms_header();
+        out.push_str("## Pages\n\n");
+
+        for entry in self.sorted() {
+            out.push_str("- [");
+            out.push_str(&markdown_link_text(
+                entry.title.as_deref().unwrap_or(&entry.url),
+            ));
+            out.push_str("](");
+            out.push_str(&markdown_link_url(&entry.url));
+            out.push(')');
+            if let Some(description) = &entry.description {
+                out.push_str(": ");
+                out.push_str(&single_line(description));
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Render `llms-full.txt`: every page with content, inlined after its
+    /// title and source URL.
+    pub fn llms_full_txt(&self) -> String {
+        let mut out = Vec::new();
+        if let Err(e) = self.write_llms_full(&mut out) {
+            log::warn!("failed to read the spooled llms-full.txt content: {e}");
+        }
+        String::from_utf8(out).unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into())
+    }
+
+    /// Write `llms-full.txt` one page at a time.
+    fn write_llms_full(&self, out: &mut impl Write) -> std::io::Result<()> {
+        out.write_all(self.llms_header().as_bytes())?;
+
+        for i in self.sorted_indices() {
+            let entry = &self.entries[i];
+            let spooled = match (&self.spool, self.spans[i]) {
+                (Some(spool), Some(span)) => Some(spool.read(span)?),
+                _ => None,
+            };
+            let content = match spooled
+                .as_deref()
+                .or(entry.content.as_deref())
+                .map(str::trim)
+            {
+                Some(content) if !content.is_empty() => content,
+                _ => continue,
+            };
+            write!(
+                out,
+                "## {}\n\nSource: {}\n\n{content}\n\n---\n\n",
+                single_line(entry.title.as_deref().unwrap_or(&entry.url)),
+                entry.url
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the sitemap files, `llms.txt` and `llms-full.txt` into `dir`,
+    /// creating it if needed. Returns the paths written.
+    pub fn write_to(&self, dir: impl AsRef<Path>) -> std::io::Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        let mut written = Vec::new();
+
+        for file in self.sitemaps() {
+            let path = dir.join(&file.name);
+            std::fs::write(&path, file.body)?;
+            written.push(path);
+        }
+
+        let path = dir.join("llms.txt");
+        std::fs::write(&path, self.llms_txt())?;
+        written.push(path);
+
+        let path = dir.join("llms-full.txt");
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+        self.write_llms_full(&mut file)?;
+        file.flush()?;
+        written.push(path);
+
+        Ok(written)
+    }
+}
+
+/// Render a `<urlset>` document.
+fn urlset(entries: &[&ExportEntry]) -> String {
+    let mut out = String::with_capacity(64 + entries.len() * 96);
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n");
+
+    for entry in entries {
+        out.push_str("  <url>\n    <loc>");
+        push_xml_escaped(&mut out, &entry.url);
+        out.push_str("</loc>\n");
+        if let Some(lastmod) = entry.lastmod {
+            out.push_str("    <lastmod>");
+            out.push_str(&w3c_date(lastmod));
+            out.push_str("</lastmod>\n");
+        }
+        out.push_str("  </url>\n");
+    }
+
+    out.push_str("</urlset>\n");
+    out
+}
+
+/// Append `s` with the five XML special characters escaped.
+fn push_xml_escaped(out: &mut String, s: &str) {
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// Collapse whitespace runs, including newlines, to single spaces.
+fn single_line(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Escape the brackets that would end a markdown link text early.
+fn markdown_link_text(s: &str) -> String {
+    single_line(s).replace('[', "\\[").replace(']', "\\]")
+}
+
+/// Percent-encode the characters that would end a markdown link target early.
+fn markdown_link_url(url: &str) -> std::borrow::Cow<'_, str> {
+    if !url.contains([' ', '(', ')']) {
+        return std::borrow::Cow::Borrowed(url);
+    }
+    std::borrow::Cow::Owned(
+        url.replace(' ', "%20")
+            .replace('(', "%28")
+            .replace(')', "%29"),
+    )
+}
+
+/// Format a time as a W3C `YYYY-MM-DD` date (UTC).
+fn w3c_date(time: SystemTime) -> String {
+    let days = match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => (d.as_secs() / 86_400) as i64,
+        Err(e) => -(e.duration().as_secs().div_ceil(86_400) as i64),
+    };
+    // Civil date from days since the epoch (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn entry(url: &str, title: Option<&str>, description: Option<&str>) -> ExportEntry {
+        ExportEntry {
+            url: url.into(),
+            title: title.map(Into::into),
+            description: description.map(Into::into),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_w3c_date() {
+        assert_eq!(w3c_date(UNIX_EPOCH), "1970-01-01");
+        assert_eq!(
+            w3c_date(UNIX_EPOCH + Duration::from_secs(1_709_164_800)),
+            "2024-02-29"
+        );
+        assert_eq!(
+            w3c_date(UNIX_EPOCH - Duration::from_secs(86_400)),
+            "1969-12-31"
+        );
+    }
+
+    #[test]
+    fn test_sitemap_single_file() {
+        let mut export = SiteExport::new("https://example.com/");
+        let mut page = entry("https://example.com/b?x=1&y=2", None, None);
+        page.lastmod = Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+        assert!(export.push(page));
+        assert!(export.push(entry("https://example.com/a", None, None)));
+        assert!(!export.push(entry("https://example.com/a", None, None)));
+
+        let files = export.sitemaps();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].name, "sitemap.xml");
+        let body = &files[0].body;
+        assert!(body.contains("<urlset"));
+        assert!(body.contains("<loc>https://example.com/b?x=1&amp;y=2</loc>"));
+        assert!(body.contains("<lastmod>2023-11-14</lastmod>"));
+        assert!(body.find("/a</loc>").unwrap() < body.find("/b?").unwrap());
+    }
+
+    #[test]
+    fn test_sitemap_index_split() {
+        let mut export = SiteExport::new("https://example.com").with_max_urls_per_sitemap(2);
+        for i in 0..5 {
+            export.push(ExportEntry::new(&format!("https://example.com/{i}")));
+        }
+
+        let files = export.sitemaps();
+        let names: Vec<_> = files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "sitemap-1.xml",
+                "sitemap-2.xml",
+                "sitemap-3.xml",
+                "sitemap.xml"
+            ]
+        );
+        let index = &files[3].body;
+        assert!(index.contains("<sitemapindex"));
+        assert!(index.contains("<loc>https://example.com/sitemap-3.xml</loc>"));
+        assert_eq!(files[2].body.matches("<url>").count(), 1);
+    }
+
+    #[test]
+    fn test_llms_txt() {
+        let mut export =
+            SiteExport::new("https://example.com").with_summary(Some("Example docs.".into()));
+        export.push(entry(
+            "https://example.com/guide",
+            Some("The [Guide]"),
+            Some("How to\n use it."),
+        ));
+        export.push(
+            entry("https://example.com/", Some("Home"), None)
+                .with_content(Some("# Home\n\nWelcome.".into())),
+        );
+
+        let llms = export.llms_txt();
+        assert!(llms.starts_with("# example.com\n\n> Example docs.\n\n## Pages\n\n"));
+        assert!(llms.contains("- [Home](https://example.com/)\n"));
+        assert!(llms.contains("- [The \\[Guide\\]](https://example.com/guide): How to use it.\n"));
+
+        let full = export.llms_full_txt();
+        assert!(full.contains("## Home\n\nSource: https://example.com/\n\n# Home\n\nWelcome.\n"));
+        assert!(!full.contains("/guide"));
+    }
+
+    #[test]
+    fn test_llms_txt_escapes_link_urls() {
+        let mut export = SiteExport::new("https://example.com");
+        export.push(entry(
+            "https://example.com/wiki/Rust_(language) page",
+            Some("Rust"),
+            None,
+        ));
+
+        assert!(export
+            .llms_txt()
+            .contains("- [Rust](https://example.com/wiki/Rust_%28language%29%20page)\n"));
+    }
+
+    #[test]
+    fn test_content_spool() {
+        let dir = std::env::temp_dir().join(format!("spider-export-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let spool = dir.join("content.spool");
+
+        let mut export = SiteExport::new("https://example.com")
+            .with_content_spool(&spool)
+            .unwrap();
+        export
+            .push(entry("https://example.com/b", Some("B"), None).with_content(Some("Bee".into())));
+        export.push(
+            entry("https://example.com/a", Some("A"), None).with_content(Some(" Ay \n".into())),
+        );
+        assert!(export.entries().iter().all(|e| e.content.is_none()));
+
+        let full = export.llms_full_txt();
+        let a = full
+            .find("## A\n\nSource: https://example.com/a\n\nAy\n")
+            .unwrap();
+        let b = full
+            .find("## B\n\nSource: https://example.com/b\n\nBee\n")
+            .unwrap();
+        assert!(a < b);
+
+        export.write_to(&dir).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("llms-full.txt")).unwrap(),
+            full
+        );
+
+        drop(export);
+        assert!(!spool.exists());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,304 @@
+//! Sitemap `<url>` metadata, index depth limits and `.xml.gz` decoding.
+//!
+//! The sitemap crawl records the `lastmod`, `changefreq` and `priority` of
+//! every `<url>` entry it reads as a [`SitemapEntry`] when a
+//! [`SitemapConfig`] is set. The same config bounds `<sitemapindex>`
+//! recursion and, with [`SitemapConfig::with_modified_since`], skips entries
+//! (and whole child sitemaps) whose `lastmod` predates the previous crawl.
+
+use sitemap::reader::{SiteMapEntity, SiteMapReader};
+use std::io::Read;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Largest uncompressed sitemap accepted, per the sitemaps.org protocol.
+const MAX_SITEMAP_BYTES: u64 = 50 * 1024 * 1024;
+
+/// The `<changefreq>` hint of a sitemap entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChangeFrequency {
+    /// Changes on every access.
+    Always,
+    /// Changes hourly.
+    Hourly,
+    /// Changes daily.
+    Daily,
+    /// Changes weekly.
+    Weekly,
+    /// Changes monthly.
+    Monthly,
+    /// Changes yearly.
+    Yearly,
+    /// Archived, never changes.
+    Never,
+}
+
+impl ChangeFrequency {
+    /// The sitemap protocol value.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Always => "always",
+            Self::Hourly => "hourly",
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+            Self::Yearly => "yearly",
+            Self::Never => "never",
+        }
+    }
+}
+
+/// A `<url>` entry read from a sitemap.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SitemapEntry {
+    /// The page URL.
+    pub url: String,
+    /// When the page was last modified.
+    pub lastmod: Option<SystemTime>,
+    /// How often the page is expected to change.
+    pub changefreq: Option<ChangeFrequency>,
+    /// Priority relative to other URLs on the site, `0.0..=1.0`.
+    pub priority: Option<f32>,
+    /// Sitemap index nesting level the entry was found at; `0` is the root sitemap.
+    pub depth: u32,
+}
+
+impl SitemapEntry {
+    /// Build an entry from a parsed sitemap `<url>`.
+    pub(crate) fn from_url_entry(
+        url: &str,
+        entry: &sitemap::structs::UrlEntry,
+        depth: u32,
+    ) -> Self {
+        use sitemap::structs::ChangeFreq;
+
+        Self {
+            url: url.to_string(),
+            lastmod: lastmod_time(&entry.lastmod),
+            changefreq: match entry.changefreq {
+                ChangeFreq::Always => Some(ChangeFrequency::Always),
+                ChangeFreq::Hourly => Some(ChangeFrequency::Hourly),
+                ChangeFreq::Daily => Some(ChangeFrequency::Daily),
+                ChangeFreq::Weekly => Some(ChangeFrequency::Weekly),
+                ChangeFreq::Monthly => Some(ChangeFrequency::Monthly),
+                ChangeFreq::Yearly => Some(ChangeFrequency::Yearly),
+                ChangeFreq::Never => Some(ChangeFrequency::Never),
+                ChangeFreq::None | ChangeFreq::ParseErr(_) => None,
+            },
+            priority: entry_priority(entry),
+            depth,
+        }
+    }
+
+    /// Frontier score for the entry. Sitemap URLs are scored as seeds
+    /// (depth `0`), shifted by their `<priority>`.
+    #[cfg(feature = "priority_frontier")]
+    pub fn frontier_score(&self) -> i32 {
+        crate::utils::frontier::score_sitemap_url(&self.url, 0, self.priority)
+    }
+}
+
+/// The `<priority>` of a sitemap entry, clamped to `0.0..=1.0`.
+fn entry_priority(entry: &sitemap::structs::UrlEntry) -> Option<f32> {
+    entry
+        .priority
+        .get_priority()
+        .filter(|p| p.is_finite())
+        .map(|p| p.clamp(0.0, 1.0))
+}
+
+/// Limits and incremental-crawl settings for the sitemap crawl.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SitemapConfig {
+    /// How many levels of nested `<sitemapindex>` to follow. `0` reads only the root sitemap.
+    pub max_depth: u32,
+    /// Skip entries whose `lastmod` is before this time.
+    pub modified_since: Option<SystemTime>,
+}
+
+impl Default for SitemapConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 5,
+            modified_since: None,
+        }
+    }
+}
+
+impl SitemapConfig {
+    /// Set how many levels of nested `<sitemapindex>` to follow.
+    pub fn with_max_depth(mut self, max_depth: u32) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Skip entries and child sitemaps whose `lastmod` is before `since`.
+    /// Entries without a `lastmod` are always crawled.
+    pub fn with_modified_since(mut self, since: Option<SystemTime>) -> Self {
+        self.modified_since = since;
+        self
+    }
+
+    /// Whether an entry with this `lastmod` is unchanged since [`modified_since`](Self::modified_since).
+    pub fn is_unchanged(&self, lastmod: Option<SystemTime>) -> bool {
+        matches!((self.modified_since, lastmod), (Some(since), Some(lastmod)) if lastmod < since)
+    }
+
+    /// Whether a child sitemap found at `depth` should be followed.
+    pub(crate) fn follow(&self, depth: u32, lastmod: &sitemap::structs::LastMod) -> bool {
+        depth <= self.max_depth && !self.is_unchanged(lastmod_time(lastmod))
+    }
+}
+
+/// Convert a sitemap `lastmod` to a [`SystemTime`].
+pub(crate) fn lastmod_time(lastmod: &sitemap::structs::LastMod) -> Option<SystemTime> {
+    let secs = lastmod.get_time()?.timestamp();
+    if secs >= 0 {
+        UNIX_EPOCH.checked_add(Duration::from_secs(secs as u64))
+    } else {
+        UNIX_EPOCH.checked_sub(Duration::from_secs(secs.unsigned_abs()))
+    }
+}
+
+/// Whether the body is gzip compressed.
+#[inline]
+pub fn is_gzip(body: &[u8]) -> bool {
+    body.starts_with(&[0x1f, 0x8b])
+}
+
+/// Decode a sitemap body, inflating `.xml.gz` payloads that were not already
+/// decoded through `Content-Encoding`. Returns `None` when the gzip stream is
+/// corrupt. Payloads inflating past the 50 MiB protocol limit are cut at the
+/// limit with a warning, keeping the entries read before it.
+pub fn decode_sitemap(body: bytes::Bytes) -> Option<bytes::Bytes> {
+    decode_sitemap_limited(body, MAX_SITEMAP_BYTES)
+}
+
+fn decode_sitemap_limited(body: bytes::Bytes, limit: u64) -> Option<bytes::Bytes> {
+    if !is_gzip(&body) {
+        return Some(body);
+    }
+    let mut out = Vec::with_capacity(body.len().saturating_mul(4));
+    flate2::read::GzDecoder::new(&body[..])
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut out)
+        .ok()?;
+    if out.len() as u64 > limit {
+        log::warn!("sitemap exceeds {limit} bytes uncompressed, truncating");
+        out.truncate(limit as usize);
+    }
+    Some(out.into())
+}
+
+/// Read the entities of a sitemap document in crawl order.
+///
+/// With the `priority_frontier` flag the `<url>` entries are queued on a
+/// [`UrlFrontier`](crate::utils::frontier::UrlFrontier) and handed out by
+/// their [`SitemapEntry::frontier_score`], after the nested sitemaps and
+/// parse errors. Duplicate `<loc>` entries are dropped.
+#[cfg(feature = "priority_frontier")]
+pub(crate) fn read_sitemap(body: &[u8]) -> impl Iterator<Item = SiteMapEntity> {
+    use sitemap::structs::Location;
+
+    let mut frontier = crate::utils::frontier::UrlFrontier::new(false);
+    let mut urls = hashbrown::HashMap::new();
+    let mut entities = Vec::new();
+
+    for entity in SiteMapReader::new(body) {
+        match entity {
+            SiteMapEntity::Url(entry) => {
+                if let Location::Url(url) = &entry.loc {
+                    let url = url.as_str();
+                    let score =
+                        crate::utils::frontier::score_sitemap_url(url, 0, entry_priority(&entry));
+                    if frontier.push(url.into(), score) {
+                        urls.insert(url.to_string(), entry);
+                    }
+                }
+            }
+            entity => entities.push(entity),
+        }
+    }
+
+    entities.reserve(urls.len());
+    while let Some(url) = frontier.pop() {
+        if let Some(entry) = urls.remove(url.inner().as_str()) {
+            entities.push(SiteMapEntity::Url(entry));
+        }
+    }
+
+    entities.into_iter()
+}
+
+/// Read the entities of a sitemap document in document order.
+#[cfg(not(feature = "priority_frontier"))]
+pub(crate) fn read_sitemap(body: &[u8]) -> impl Iterator<Item = SiteMapEntity> + '_ {
+    SiteMapReader::new(body)
+}
+
+/// Whether the body looks like a sitemap XML document.
+pub fn is_sitemap_xml(body: &[u8]) -> bool {
+    let body = body.strip_prefix(b"\xef\xbb\xbf").unwrap_or(body);
+    let start = body
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(body.len());
+    let body = &body[start..];
+    body.starts_with(b"<?xml") || body.starts_with(b"<urlset") || body.starts_with(b"<sitemapindex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_decode_gzip_sitemap() {
+        let xml = b"<?xml version=\"1.0\"?><urlset></urlset>";
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(xml).unwrap();
+        let gz = encoder.finish().unwrap();
+
+        assert!(is_gzip(&gz));
+        let decoded = decode_sitemap(gz.into()).unwrap();
+        assert_eq!(&decoded[..], &xml[..]);
+        assert!(is_sitemap_xml(&decoded));
+
+        let plain = decode_sitemap(bytes::Bytes::from_static(xml)).unwrap();
+        assert_eq!(&plain[..], &xml[..]);
+        assert!(decode_sitemap(bytes::Bytes::from_static(&[0x1f, 0x8b, 0, 1])).is_none());
+    }
+
+    #[test]
+    fn test_decode_sitemap_over_limit() {
+        let xml = b"<?xml version=\"1.0\"?><urlset></urlset>";
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(xml).unwrap();
+        let gz: bytes::Bytes = encoder.finish().unwrap().into();
+
+        let cut = decode_sitemap_limited(gz.clone(), 8).unwrap();
+        assert_eq!(&cut[..], &xml[..8]);
+        let whole = decode_sitemap_limited(gz, xml.len() as u64).unwrap();
+        assert_eq!(&whole[..], &xml[..]);
+    }
+
+    #[test]
+    fn test_is_sitemap_xml() {
+        assert!(is_sitemap_xml(b"\xef\xbb\xbf\n  <urlset>"));
+        assert!(is_sitemap_xml(b"<sitemapindex xmlns=\"\">"));
+        assert!(!is_sitemap_xml(b"<!doctype html><html>"));
+    }
+
+    #[test]
+    fn test_modified_since() {
+        let since = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let config = SitemapConfig::default().with_modified_since(Some(since));
+
+        assert!(config.is_unchanged(Some(since - Duration::from_secs(1))));
+        assert!(!config.is_unchanged(Some(since)));
+        assert!(!config.is_unchanged(None));
+        assert!(!SitemapConfig::default().is_unchanged(Some(UNIX_EPOCH)));
+    }
+}
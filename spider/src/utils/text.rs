@@ -0,0 +1,98 @@
+//! Visible text extraction from HTML documents.
+
+/// Extract the visible text of an HTML document, one block per line.
+/// Script and style contents are skipped and entities are decoded.
+pub fn extract_text(html: &str) -> String {
+    use lol_html::html_content::TextType;
+
+    let buf = std::rc::Rc::new(std::cell::RefCell::new(String::with_capacity(
+        html.len() / 4,
+    )));
+
+    {
+        let mut rewriter = lol_html::HtmlRewriter::new(
+            lol_html::Settings {
+                element_content_handlers: vec![lol_html::element!(
+                    "address, article, aside, blockquote, br, dd, div, dl, dt, fieldset, figcaption, figure, footer, form, h1, h2, h3, h4, h5, h6, header, hr, li, main, nav, ol, p, pre, section, table, td, th, title, tr, ul",
+                    |el| {
+                        buf.borrow_mut().push('\n');
+                        if let Some(handlers) = el.end_tag_handlers() {
+                            let buf = buf.clone();
+                            let handler: lol_html::EndTagHandler<'static> = Box::new(move |_end| {
+                                buf.borrow_mut().push('\n');
+                                Ok(())
+                            });
+                            handlers.push(handler);
+                        }
+                        Ok(())
+                    }
+                )],
+                document_content_handlers: vec![lol_html::doc_text!(|text| {
+                    if !matches!(text.text_type(), TextType::ScriptData | TextType::RawText) {
+                        buf.borrow_mut().push_str(text.as_str());
+                    }
+                    Ok(())
+                })],
+                ..lol_html::Settings::new()
+            },
+            |_c: &[u8]| {},
+        );
+
+        if rewriter.write(html.as_bytes()).is_err() || rewriter.end().is_err() {
+            return String::new();
+        }
+    }
+
+    let buf = buf.take();
+    let mut out = String::with_capacity(buf.len());
+
+    for line in buf.lines() {
+        let line = unescape(line);
+        let mut words = line.split_whitespace().peekable();
+
+        if words.peek().is_none() {
+            continue;
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        for (i, word) in words.enumerate() {
+            if i > 0 {
+                out.push(' ');
+            }
+            out.push_str(word);
+        }
+    }
+
+    out
+}
+
+/// Decode the XML entities, numeric references and `&nbsp;`, leaving the
+/// line as is when it holds a bare `&`.
+fn unescape(line: &str) -> std::borrow::Cow<'_, str> {
+    quick_xml::escape::unescape_with(line, |entity| {
+        quick_xml::escape::resolve_predefined_entity(entity).or(match entity {
+            "nbsp" => Some(" "),
+            _ => None,
+        })
+    })
+    .unwrap_or(std::borrow::Cow::Borrowed(line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_text() {
+        let html = r#"<html><head><title>Shop &amp; Co</title>
+            <style>body { color: red }</style><script>var a = "<p>";</script></head>
+            <body><h1>Prices</h1><p>Widget:   <b>$10</b>&nbsp;each</p>per <i>unit</i><ul><li>One</li><li>Two</li></ul>
+            <noscript>enable js</noscript></body></html>"#;
+
+        assert_eq!(
+            extract_text(html),
+            "Shop & Co\nPrices\nWidget: $10 each\nper unit\nOne\nTwo"
+        );
+    }
+}
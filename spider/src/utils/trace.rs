@@ -0,0 +1,210 @@
+//! Structured spans for the crawl pipeline.
+//!
+//! Every crawl entry point opens a `crawl` span, each spawned page task
+//! runs inside a `page` span, every call into a `Page` fetch constructor
+//! is an `attempt` (retries included, numbered per page), and the actual
+//! network work — the HTTP send, the Chrome navigation, each side of a
+//! hedged race — is a `backend` span beneath it:
+//!
+//! ```text
+//! crawl { url.full }
+//! └── page { url.full, http.response.status_code, spider.bytes, spider.proxy, spider.attempts }
+//!     └── attempt { url.full, spider.attempt, http.response.status_code, spider.bytes }
+//!         └── backend { spider.backend, http.response.status_code }
+//! ```
+//!
+//! Attribute names follow the OpenTelemetry HTTP semantic conventions where
+//! one exists. Any `tracing` subscriber sees the spans; the `otel` feature
+//! adds an OTLP exporter in [`crate::utils::otlp`].
+
+use crate::configuration::RequestProxy;
+use crate::page::Page;
+use std::future::Future;
+use std::sync::atomic::{AtomicU32, Ordering};
+use tracing::field::Empty;
+use tracing::{Instrument, Span};
+
+tokio::task_local! {
+    /// Number of fetch attempts made by the current page task.
+    ///
+    /// Scoped by `utils::spawn_set` for `page_fetch*` tasks so that every
+    /// retry the task makes — whichever of the retry loops drives it —
+    /// is numbered without threading a counter through the fetch API.
+    static PAGE_ATTEMPTS: AtomicU32;
+}
+
+/// Span covering a whole crawl of `url`.
+#[inline]
+pub fn crawl_span(url: &str) -> Span {
+    tracing::info_span!("crawl", url.full = url)
+}
+
+/// Span covering one page task. The URL and response attributes are
+/// recorded once the page is produced (see [`record_page`]).
+#[inline]
+pub fn page_span() -> Span {
+    tracing::info_span!(
+        "page",
+        url.full = Empty,
+        http.response.status_code = Empty,
+        spider.bytes = Empty,
+        spider.proxy = Empty,
+        spider.attempts = Empty,
+    )
+}
+
+/// Span covering one fetch attempt for `url` made outside the `Page`
+/// fetch constructors, e.g. the Chrome upgrade of a smart crawl. Fields
+/// match the `attempt` spans the constructors open.
+#[inline]
+pub fn attempt_span(url: &str) -> Span {
+    tracing::info_span!(
+        "attempt",
+        url.full = url,
+        spider.attempt = next_attempt(),
+        http.response.status_code = Empty,
+        spider.bytes = Empty,
+    )
+}
+
+/// Span covering a single network backend inside an attempt, e.g. `http`,
+/// `chrome` or one side of a hedged race.
+#[inline]
+pub fn backend_span(backend: &'static str) -> Span {
+    tracing::info_span!(
+        "backend",
+        spider.backend = backend,
+        http.response.status_code = Empty
+    )
+}
+
+/// Claim the next attempt number for the current page task. Returns `1`
+/// outside a page task.
+#[inline]
+pub fn next_attempt() -> u32 {
+    PAGE_ATTEMPTS
+        .try_with(|n| n.fetch_add(1, Ordering::Relaxed) + 1)
+        .unwrap_or(1)
+}
+
+/// Bytes received for `page`, falling back to the body size when the
+/// transfer size is unknown.
+#[inline]
+fn page_bytes(page: &Page) -> u64 {
+    page.bytes_transferred
+        .map_or(page.size() as u64, |b| b as u64)
+}
+
+/// Record the response status and size of `page` on the `attempt` span
+/// that fetched it.
+#[inline]
+pub fn record_response(span: &Span, page: &Page) {
+    span.record("http.response.status_code", page.status_code.as_u16());
+    span.record("spider.bytes", page_bytes(page));
+}
+
+/// The kind of proxy the requests go through: the scheme shared by the
+/// configured proxies (`http`, `https`, `socks5`, ...), `mixed` when they
+/// differ, or `direct` when there are none.
+pub fn proxy_kind(proxies: Option<&[RequestProxy]>) -> &'static str {
+    let mut kind = None;
+    for proxy in proxies.unwrap_or_default() {
+        let scheme = match proxy.addr.split_once("://") {
+            Some((scheme, _)) => match scheme.to_ascii_lowercase().as_str() {
+                "http" => "http",
+                "https" => "https",
+                "socks4" => "socks4",
+                "socks4a" => "socks4a",
+                "socks5" => "socks5",
+                "socks5h" => "socks5h",
+                _ => "other",
+            },
+            // reqwest treats a bare `host:port` as an http proxy.
+            None => "http",
+        };
+        match kind {
+            None => kind = Some(scheme),
+            Some(k) if k != scheme => return "mixed",
+            _ => (),
+        }
+    }
+    kind.unwrap_or("direct")
+}
+
+/// Record the kind of proxy the current page task fetches through (see
+/// [`proxy_kind`]).
+#[inline]
+pub fn record_proxy(proxies: Option<&[RequestProxy]>) {
+    Span::current().record("spider.proxy", proxy_kind(proxies));
+}
+
+/// Record the final page attributes on `span`. The proxy kind is kept
+/// when [`record_proxy`] already set it.
+pub fn record_page(span: &Span, page: &Page) {
+    span.record("url.full", page.get_url());
+    span.record("http.response.status_code", page.status_code.as_u16());
+    span.record("spider.bytes", page_bytes(page));
+    if !page.proxy_configured {
+        span.record("spider.proxy", "direct");
+    }
+    if let Ok(attempts) = PAGE_ATTEMPTS.try_with(|n| n.load(Ordering::Relaxed)) {
+        span.record("spider.attempts", attempts);
+    }
+}
+
+/// Attach a spawned task to the span tree. `page_fetch*` tasks get their
+/// own `page` span and attempt counter; everything else inherits the
+/// spawning span so the crawl span still covers it.
+pub(crate) fn instrument_task<F: Future>(
+    task_name: &str,
+    future: F,
+) -> tracing::instrument::Instrumented<impl Future<Output = F::Output>> {
+    let span = if task_name.starts_with("page_fetch") {
+        page_span()
+    } else {
+        Span::current()
+    };
+    PAGE_ATTEMPTS
+        .scope(AtomicU32::new(0), future)
+        .instrument(span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_attempts_are_numbered_per_page_task() {
+        assert_eq!(next_attempt(), 1);
+        let first = instrument_task("page_fetch", async {
+            (next_attempt(), next_attempt(), next_attempt())
+        })
+        .await;
+        assert_eq!(first, (1, 2, 3));
+        let second = instrument_task("page_fetch", async { next_attempt() }).await;
+        assert_eq!(second, 1);
+    }
+
+    #[test]
+    fn test_proxy_kind() {
+        let proxy = |addr: &str| RequestProxy {
+            addr: addr.into(),
+            ..Default::default()
+        };
+        assert_eq!(proxy_kind(None), "direct");
+        assert_eq!(proxy_kind(Some(&[])), "direct");
+        assert_eq!(
+            proxy_kind(Some(&[proxy("SOCKS5://127.0.0.1:1080")])),
+            "socks5"
+        );
+        assert_eq!(proxy_kind(Some(&[proxy("127.0.0.1:8080")])), "http");
+        assert_eq!(
+            proxy_kind(Some(&[proxy("http://a:1"), proxy("http://b:2")])),
+            "http"
+        );
+        assert_eq!(
+            proxy_kind(Some(&[proxy("http://a:1"), proxy("socks5://b:2")])),
+            "mixed"
+        );
+    }
+}
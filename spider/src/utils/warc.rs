@@ -1,8 +1,9 @@
-//! WARC 1.1 writer for web archive output.
+//! WARC 1.1 writer and reader for web archive output.
 //!
 //! Feature-gated behind `warc`. Produces spec-compliant WARC/1.1 files
-//! from crawled pages. Zero external WARC dependencies — writes the format
-//! directly.
+//! from crawled pages, and reads them back with [`WarcReader`] — or replays
+//! them as a crawl source with [`WarcReplayFetcher`]. Zero external WARC
+//! dependencies — reads and writes the format directly.
 //!
 //! **Fully lock-free**: callers serialize records into `Vec<u8>` and send
 //! them through an unbounded MPSC channel. A single background task drains
@@ -14,7 +15,7 @@
 #[cfg(feature = "warc")]
 mod inner {
     use crate::page::Page;
-    use std::io::{self, Write};
+    use std::io::{self, BufRead, Read, Write};
     use std::path::{Path, PathBuf};
     use std::sync::atomic::{AtomicU64, Ordering};
     use tokio::sync::{broadcast, mpsc};
@@ -28,13 +29,39 @@ mod inner {
     /// Configuration for WARC output.
     #[derive(Debug, Clone, PartialEq)]
     #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub struct WarcConfig {
-        /// Output file path. Defaults to `"output.warc"`.
+        /// Output file path. Defaults to `"output.warc"`. A `.gz` suffix turns
+        /// on [`gzip`](Self::gzip).
         pub path: String,
         /// Whether to include a `warcinfo` record at the start. Default: true.
         pub write_warcinfo: bool,
         /// Software identifier for the `warcinfo` record.
         pub software: String,
+        /// Whether to write a CDXJ index of the `response` records. Default: true.
+        pub write_cdx: bool,
+        /// Output path for the CDXJ index. Defaults to the WARC path with a
+        /// `.cdxj` extension.
+        pub cdx_path: Option<String>,
+        /// Compress each record as its own gzip member (`.warc.gz`). Default: false.
+        pub gzip: bool,
+        /// Start a new file once the current one reaches this many bytes.
+        pub max_file_size: Option<u64>,
+        /// Start a new file once the current one holds this many records,
+        /// not counting its `warcinfo`.
+        pub max_records: Option<u64>,
+        /// Write repeated payloads as `revisit` records referring to their
+        /// first capture instead of storing the body again. Default: false.
+        pub revisits: bool,
+        /// Leave out pages declaring `noarchive` through a robots meta tag or
+        /// the `X-Robots-Tag` header. Default: false.
+        pub skip_noarchive: bool,
+        /// Path template for rotated files. `{serial}` expands to a zero-padded
+        /// file number and `{timestamp}` to the file's creation time
+        /// (`YYYYMMDDhhmmss`). Defaults to `path` with `-{serial}` before the
+        /// extension when rotation is enabled. A rotating template without
+        /// `{serial}` gets `-{serial}` added the same way.
+        pub filename_template: Option<String>,
     }
 
     impl Default for WarcConfig {
@@ -43,10 +70,125 @@ mod inner {
                 path: "output.warc".to_string(),
                 write_warcinfo: true,
                 software: format!("spider/{}", env!("CARGO_PKG_VERSION")),
+                write_cdx: true,
+                cdx_path: None,
+                gzip: false,
+                max_file_size: None,
+                max_records: None,
+                revisits: false,
+                skip_noarchive: false,
+                filename_template: None,
             }
         }
     }
 
+    impl WarcConfig {
+        /// The CDXJ index path, or `None` when indexing is disabled.
+        pub fn cdx_path(&self) -> Option<PathBuf> {
+            if !self.write_cdx {
+                return None;
+            }
+            if let Some(ref path) = self.cdx_path {
+                return Some(PathBuf::from(path));
+            }
+            let base = self
+                .path
+                .strip_suffix(".warc.gz")
+                .or_else(|| self.path.strip_suffix(".warc"))
+                .unwrap_or(&self.path);
+            Some(PathBuf::from(format!("{base}.cdxj")))
+        }
+
+        /// Whether records are written as gzip members.
+        pub fn is_gzip(&self) -> bool {
+            self.gzip
+                || self.path.ends_with(".gz")
+                || self
+                    .filename_template
+                    .as_deref()
+                    .is_some_and(|t| t.ends_with(".gz"))
+        }
+
+        /// Whether output rotates across several files.
+        pub fn rotates(&self) -> bool {
+            self.max_file_size.is_some() || self.max_records.is_some()
+        }
+
+        /// The path of the `serial`-th output file.
+        pub fn file_path(&self, serial: u64) -> PathBuf {
+            let serial = format!("{serial:05}");
+
+            if let Some(ref template) = self.filename_template {
+                let timestamp: String = warc_date_now()
+                    .chars()
+                    .filter(|c| c.is_ascii_digit())
+                    .collect();
+                let path = template
+                    .replace("{serial}", &serial)
+                    .replace("{timestamp}", &timestamp);
+
+                // Without `{serial}` rotated files would reuse one name and
+                // truncate each other.
+                if template.contains("{serial}") || !self.rotates() {
+                    return PathBuf::from(path);
+                }
+                return serial_path(&path, &serial);
+            }
+
+            if !self.rotates() {
+                return PathBuf::from(&self.path);
+            }
+
+            serial_path(&self.path, &serial)
+        }
+
+        /// Whether the current file is full and the next record opens a new one.
+        fn rotation_due(&self, bytes: u64, records: u64) -> bool {
+            records > 0
+                && (self.max_file_size.is_some_and(|max| bytes >= max)
+                    || self.max_records.is_some_and(|max| records >= max))
+        }
+    }
+
+    /// `path` with `-{serial}` before its WARC extension.
+    fn serial_path(path: &str, serial: &str) -> PathBuf {
+        let (base, ext) = [".warc.gz", ".warc", ".gz"]
+            .iter()
+            .find_map(|ext| path.strip_suffix(ext).map(|base| (base, *ext)))
+            .unwrap_or((path, ""));
+        PathBuf::from(format!("{base}-{serial}{ext}"))
+    }
+
+    /// Compress a serialized record into a standalone gzip member.
+    fn gzip_member(record: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(
+            Vec::with_capacity(record.len() / 3 + 64),
+            flate2::Compression::default(),
+        );
+        match encoder.write_all(record).and_then(|_| encoder.finish()) {
+            Ok(compressed) => compressed,
+            // Writing into a Vec cannot fail; fall back to the raw record regardless.
+            Err(_) => record.to_vec(),
+        }
+    }
+
+    /// Create `path` along with any missing parent directories.
+    fn create_file(path: &Path) -> io::Result<std::fs::File> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        std::fs::File::create(path)
+    }
+
+    /// The file name component of `path`, used in CDXJ lines and `warcinfo`.
+    fn file_name(path: &Path) -> String {
+        path.file_name()
+            .map(|f| f.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
     /// Generate a UUID v4-style string for WARC record IDs.
     /// Format: `xxxxxxxx-xxxx-4xxx-yxxx-xxxxxxxxxxxx`
     fn generate_uuid() -> String {
@@ -127,6 +269,7 @@ mod inner {
     }
 
     /// Append WARC record headers to a buffer (everything before the payload).
+    #[allow(clippy::too_many_arguments)]
     fn append_warc_header(
         buf: &mut Vec<u8>,
         record_type: &str,
@@ -135,6 +278,7 @@ mod inner {
         target_uri: Option<&str>,
         content_type: &str,
         content_length: usize,
+        extra: &[(&str, &str)],
         #[cfg(feature = "remote_addr")] ip_address: Option<&str>,
     ) {
         let _ = write!(buf, "WARC/1.1\r\n");
@@ -148,18 +292,23 @@ mod inner {
         if let Some(ip) = ip_address {
             let _ = write!(buf, "WARC-IP-Address: {ip}\r\n");
         }
+        for (name, value) in extra {
+            let _ = write!(buf, "{name}: {value}\r\n");
+        }
         let _ = write!(buf, "Content-Type: {content_type}\r\n");
         let _ = write!(buf, "Content-Length: {content_length}\r\n");
         buf.extend_from_slice(CRLF);
     }
 
     /// Serialize a `warcinfo` record into a self-contained byte buffer.
-    fn serialize_warcinfo(software: &str) -> Vec<u8> {
+    fn serialize_warcinfo(software: &str, filename: Option<&str>) -> Vec<u8> {
         let record_id = generate_uuid();
         let date = warc_date_now();
         let payload = format!("software: {software}\r\nformat: WARC File Format 1.1\r\n");
         let payload_bytes = payload.as_bytes();
 
+        let extra: Vec<(&str, &str)> = filename.map(|f| ("WARC-Filename", f)).into_iter().collect();
+
         let mut buf = Vec::with_capacity(256 + payload_bytes.len());
         append_warc_header(
             &mut buf,
@@ -169,6 +318,7 @@ mod inner {
             None,
             "application/warc-fields",
             payload_bytes.len(),
+            &extra,
             #[cfg(feature = "remote_addr")]
             None,
         );
@@ -218,6 +368,14 @@ mod inner {
     /// Serialize a WARC `response` record from a `Page` into a self-contained byte buffer.
     /// Returns `None` if the page has an empty URL.
     pub fn serialize_page(page: &Page) -> Option<Vec<u8>> {
+        serialize_response(page, None).map(|entry| entry.bytes)
+    }
+
+    /// Serialize a `response` record along with the fields its CDXJ line needs.
+    ///
+    /// With `revisits`, a page whose payload was already archived is written
+    /// as a `revisit` record referring to the first capture instead.
+    fn serialize_response(page: &Page, revisits: Option<&WarcRevisits>) -> Option<WarcEntry> {
         let url = page.get_url();
         if url.is_empty() {
             return None;
@@ -242,12 +400,14 @@ mod inner {
         // Content-Type in the HTTP payload to know how to serve the resource.
         let mut has_content_type = false;
         let mut has_content_length = false;
+        let mut mime = None;
 
         if let Some(ref headers) = page.headers {
             for (name, value) in headers.iter() {
                 let n = name.as_str();
                 if n.eq_ignore_ascii_case("content-type") {
                     has_content_type = true;
+                    mime = value.to_str().ok();
                 } else if n.eq_ignore_ascii_case("content-length") {
                     has_content_length = true;
                 }
@@ -265,6 +425,7 @@ mod inner {
             payload.extend_from_slice(b"Content-Type: ");
             payload.extend_from_slice(ct.as_bytes());
             payload.extend_from_slice(CRLF);
+            mime = Some(ct);
         }
 
         // Ensure Content-Length reflects the actual body length for the replay
@@ -274,31 +435,484 @@ mod inner {
         }
 
         payload.extend_from_slice(CRLF);
-        payload.extend_from_slice(body);
 
         let record_id = generate_uuid();
         let date = warc_date_now();
+        let digest = payload_digest(body);
+
+        let original = revisits.and_then(|revisits| {
+            revisits.check(
+                page.signature.unwrap_or_else(|| digest_key(&digest)),
+                WarcRevisitTarget {
+                    record_id: format!("<urn:uuid:{record_id}>"),
+                    url: url.to_string(),
+                    date: date.clone(),
+                    digest: digest.clone(),
+                },
+            )
+        });
+
+        // Revisits carry the HTTP head only; the payload lives in the original.
+        if original.is_none() {
+            payload.extend_from_slice(body);
+        }
 
         #[cfg(feature = "remote_addr")]
         let ip_str = page.remote_addr.as_ref().map(|a| a.ip().to_string());
 
         let mut buf = Vec::with_capacity(512 + payload.len());
-        append_warc_header(
-            &mut buf,
-            "response",
-            &record_id,
-            &date,
-            Some(url),
-            "application/http; msgtype=response",
-            payload.len(),
-            #[cfg(feature = "remote_addr")]
-            ip_str.as_deref(),
-        );
+        match original {
+            Some(ref original) => append_warc_header(
+                &mut buf,
+                "revisit",
+                &record_id,
+                &date,
+                Some(url),
+                "application/http; msgtype=response",
+                payload.len(),
+                &[
+                    ("WARC-Profile", REVISIT_PROFILE),
+                    ("WARC-Refers-To", &original.record_id),
+                    ("WARC-Refers-To-Target-URI", &original.url),
+                    ("WARC-Refers-To-Date", &original.date),
+                    ("WARC-Payload-Digest", &digest),
+                ],
+                #[cfg(feature = "remote_addr")]
+                ip_str.as_deref(),
+            ),
+            None => append_warc_header(
+                &mut buf,
+                "response",
+                &record_id,
+                &date,
+                Some(url),
+                "application/http; msgtype=response",
+                payload.len(),
+                &[("WARC-Payload-Digest", &digest)],
+                #[cfg(feature = "remote_addr")]
+                ip_str.as_deref(),
+            ),
+        }
         buf.extend_from_slice(&payload);
         buf.extend_from_slice(CRLF);
         buf.extend_from_slice(CRLF);
 
-        Some(buf)
+        let mime = if original.is_some() {
+            // Replay tools recognize revisits in the index by this media type.
+            "warc/revisit".to_string()
+        } else {
+            mime.and_then(|m| m.split(';').next())
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase()
+        };
+
+        Some(WarcEntry {
+            bytes: buf,
+            cdx: Some(CdxjRecord {
+                urlkey: surt_key(url),
+                timestamp: date.chars().filter(|c| c.is_ascii_digit()).collect(),
+                url: url.to_string(),
+                mime,
+                status,
+                digest,
+                ..Default::default()
+            }),
+        })
+    }
+
+    /// `WARC-Profile` of revisits whose payload matches the referenced record.
+    const REVISIT_PROFILE: &str =
+        "http://netpreserve.org/warc/1.1/revisit/identical-payload-digest";
+
+    /// The capture a `revisit` record points back to.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct WarcRevisitTarget {
+        /// `WARC-Record-ID` of the original record, with angle brackets.
+        pub record_id: String,
+        /// Target URI of the original record.
+        pub url: String,
+        /// `WARC-Date` of the original record.
+        pub date: String,
+        /// Payload digest of the original record.
+        pub digest: String,
+    }
+
+    /// First captures of each payload seen by a [`WarcWriter`], keyed by
+    /// [`Page::signature`] (or the payload digest when the page has none).
+    ///
+    /// Cheap to clone; clones share the table. Hand the same table to the
+    /// writer of every run of a recurring crawl so unchanged pages are written
+    /// as revisits of the earlier archive — pair it with
+    /// [`WarcConfig::filename_template`] so earlier files are not overwritten.
+    #[derive(Debug, Clone, Default)]
+    pub struct WarcRevisits {
+        seen: std::sync::Arc<dashmap::DashMap<u64, WarcRevisitTarget>>,
+    }
+
+    impl WarcRevisits {
+        /// Record `capture` under `key` unless the payload was archived before.
+        /// Returns the original capture when this one should be a revisit.
+        ///
+        /// A key match is only trusted when the payload digests agree, so a
+        /// signature computed on normalized content never turns a changed
+        /// payload into a revisit.
+        fn check(&self, key: u64, capture: WarcRevisitTarget) -> Option<WarcRevisitTarget> {
+            match self.seen.entry(key) {
+                dashmap::Entry::Occupied(original) => {
+                    let original = original.get();
+                    (original.digest == capture.digest).then(|| original.clone())
+                }
+                dashmap::Entry::Vacant(slot) => {
+                    slot.insert(capture);
+                    None
+                }
+            }
+        }
+
+        /// Number of distinct payloads tracked.
+        pub fn len(&self) -> usize {
+            self.seen.len()
+        }
+
+        /// Whether no payload has been tracked yet.
+        pub fn is_empty(&self) -> bool {
+            self.seen.is_empty()
+        }
+    }
+
+    /// Fold a payload digest into a dedup key for pages without a signature.
+    fn digest_key(digest: &str) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = ahash::AHasher::default();
+        digest.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// `sha1:<base32>` digest of an HTTP payload, as used by
+    /// `WARC-Payload-Digest` and CDX indexes.
+    fn payload_digest(body: &[u8]) -> String {
+        use sha1::Digest;
+        let hash = sha1::Sha1::digest(body);
+        format!("sha1:{}", base32(&hash))
+    }
+
+    /// RFC 4648 base32 without padding (20-byte digests need none).
+    fn base32(bytes: &[u8]) -> String {
+        const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+        let mut out = String::with_capacity(bytes.len().div_ceil(5) * 8);
+        let mut acc: u64 = 0;
+        let mut bits = 0;
+        for &b in bytes {
+            acc = (acc << 8) | b as u64;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                out.push(ALPHABET[((acc >> bits) & 31) as usize] as char);
+            }
+        }
+        if bits > 0 {
+            out.push(ALPHABET[((acc << (5 - bits)) & 31) as usize] as char);
+        }
+        out
+    }
+
+    /// Build the SURT-ordered key used to sort CDX lines, e.g.
+    /// `https://www.Example.com/A?b=1&a=2` → `com,example)/a?a=2&b=1`.
+    pub fn surt_key(url: &str) -> String {
+        let Ok(parsed) = url::Url::parse(url) else {
+            return url.to_ascii_lowercase();
+        };
+
+        let mut key = match parsed.host() {
+            Some(url::Host::Domain(host)) => {
+                let host = host.to_ascii_lowercase();
+                let host = host.strip_prefix("www.").unwrap_or(&host);
+                host.split('.').rev().collect::<Vec<_>>().join(",")
+            }
+            Some(host) => host.to_string(),
+            None => String::new(),
+        };
+
+        if let Some(port) = parsed.port() {
+            let _ = std::fmt::Write::write_fmt(&mut key, format_args!(":{port}"));
+        }
+        key.push(')');
+        key.push_str(&parsed.path().to_ascii_lowercase());
+
+        if let Some(query) = parsed.query() {
+            let mut params: Vec<&str> = query.split('&').filter(|p| !p.is_empty()).collect();
+            if !params.is_empty() {
+                params.sort_unstable();
+                key.push('?');
+                key.push_str(&params.join("&").to_ascii_lowercase());
+            }
+        }
+
+        key
+    }
+
+    /// One line of a CDXJ index: `<urlkey> <timestamp> <json>`.
+    #[derive(Debug, Clone, Default, PartialEq, Eq)]
+    pub struct CdxjRecord {
+        /// SURT-ordered lookup key.
+        pub urlkey: String,
+        /// 14-digit capture timestamp (`YYYYMMDDhhmmss`).
+        pub timestamp: String,
+        /// The captured URL.
+        pub url: String,
+        /// The response media type without parameters.
+        pub mime: String,
+        /// The response status code.
+        pub status: u16,
+        /// The payload digest (`sha1:<base32>`).
+        pub digest: String,
+        /// Length of the record in the WARC file (compressed size for `.warc.gz`).
+        pub length: u64,
+        /// Byte offset of the record in the WARC file.
+        pub offset: u64,
+        /// Name of the WARC file holding the record.
+        pub filename: String,
+    }
+
+    impl CdxjRecord {
+        /// Format the record as an index line (without a trailing newline).
+        pub fn to_line(&self) -> String {
+            format!(
+                "{} {} {{\"url\": \"{}\", \"mime\": \"{}\", \"status\": \"{}\", \"digest\": \"{}\", \"length\": \"{}\", \"offset\": \"{}\", \"filename\": \"{}\"}}",
+                self.urlkey,
+                self.timestamp,
+                json_escape(&self.url),
+                json_escape(&self.mime),
+                self.status,
+                self.digest,
+                self.length,
+                self.offset,
+                json_escape(&self.filename),
+            )
+        }
+
+        /// Parse an index line. Returns `None` for malformed lines.
+        pub fn parse(line: &str) -> Option<Self> {
+            let mut parts = line.trim().splitn(3, ' ');
+            let urlkey = parts.next()?.to_string();
+            let timestamp = parts.next()?.to_string();
+            let fields = parse_flat_json(parts.next()?)?;
+            let field = |name: &str| fields.get(name).cloned().unwrap_or_default();
+
+            Some(Self {
+                urlkey,
+                timestamp,
+                url: field("url"),
+                mime: field("mime"),
+                status: field("status").parse().unwrap_or_default(),
+                digest: field("digest"),
+                length: field("length").parse().ok()?,
+                offset: field("offset").parse().ok()?,
+                filename: field("filename"),
+            })
+        }
+
+        /// Read every record of a CDXJ index file, skipping malformed lines.
+        pub fn read_index(path: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+            let file = io::BufReader::new(std::fs::File::open(path)?);
+            let mut records = Vec::new();
+            for line in file.lines() {
+                if let Some(record) = Self::parse(&line?) {
+                    records.push(record);
+                }
+            }
+            Ok(records)
+        }
+    }
+
+    /// Escape a value for a JSON string literal.
+    fn json_escape(value: &str) -> std::borrow::Cow<'_, str> {
+        if !value.contains(|c: char| c == '"' || c == '\\' || c.is_control()) {
+            return std::borrow::Cow::Borrowed(value);
+        }
+        let mut out = String::with_capacity(value.len() + 8);
+        for c in value.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if c.is_control() => {
+                    let _ =
+                        std::fmt::Write::write_fmt(&mut out, format_args!("\\u{:04x}", c as u32));
+                }
+                c => out.push(c),
+            }
+        }
+        std::borrow::Cow::Owned(out)
+    }
+
+    /// Parse a flat JSON object of string or scalar values, as found in CDXJ lines.
+    fn parse_flat_json(input: &str) -> Option<hashbrown::HashMap<String, String>> {
+        let mut chars = input.trim().chars().peekable();
+        let mut fields = hashbrown::HashMap::new();
+
+        fn skip_ws(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        }
+
+        fn parse_string(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Option<String> {
+            let mut out = String::new();
+            loop {
+                match chars.next()? {
+                    '"' => return Some(out),
+                    '\\' => match chars.next()? {
+                        'n' => out.push('\n'),
+                        'r' => out.push('\r'),
+                        't' => out.push('\t'),
+                        'b' => out.push('\u{8}'),
+                        'f' => out.push('\u{c}'),
+                        'u' => {
+                            let hex: String = chars.by_ref().take(4).collect();
+                            out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                        }
+                        c => out.push(c),
+                    },
+                    c => out.push(c),
+                }
+            }
+        }
+
+        if chars.next()? != '{' {
+            return None;
+        }
+
+        loop {
+            skip_ws(&mut chars);
+            match chars.next()? {
+                '}' => return Some(fields),
+                ',' => continue,
+                '"' => {
+                    let key = parse_string(&mut chars)?;
+                    skip_ws(&mut chars);
+                    if chars.next()? != ':' {
+                        return None;
+                    }
+                    skip_ws(&mut chars);
+                    let value = if chars.next_if_eq(&'"').is_some() {
+                        parse_string(&mut chars)?
+                    } else {
+                        let mut raw = String::new();
+                        while let Some(c) = chars.next_if(|c| *c != ',' && *c != '}') {
+                            raw.push(c);
+                        }
+                        raw.trim().to_string()
+                    };
+                    fields.insert(key, value);
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Bytes of index lines sorted in memory at once. Larger indexes are
+    /// sorted in runs of this size that are merged back into the index.
+    const SORT_CHUNK_BYTES: usize = 16 * 1024 * 1024;
+
+    /// Sort a CDXJ index in place so replay tools can binary search it.
+    /// The index is read line by line, so memory stays bounded by
+    /// [`SORT_CHUNK_BYTES`] however large the crawl was.
+    fn sort_index(path: &Path) -> io::Result<()> {
+        sort_index_chunked(path, SORT_CHUNK_BYTES)
+    }
+
+    /// Sort a CDXJ index in place, `chunk_size` bytes of lines at a time.
+    fn sort_index_chunked(path: &Path, chunk_size: usize) -> io::Result<()> {
+        let mut lines = io::BufReader::new(std::fs::File::open(path)?).lines();
+        let mut runs: Vec<PathBuf> = Vec::new();
+        let mut chunk: Vec<String> = Vec::new();
+        let result = (|| {
+            loop {
+                let mut chunk_bytes = 0;
+                for line in lines.by_ref() {
+                    let line = line?;
+                    if !line.is_empty() {
+                        chunk_bytes += line.len() + 1;
+                        chunk.push(line);
+                    }
+                    if chunk_bytes >= chunk_size {
+                        break;
+                    }
+                }
+                chunk.sort_unstable();
+                if chunk_bytes < chunk_size && runs.is_empty() {
+                    // The whole index fit in one chunk.
+                    return write_lines(path, chunk.drain(..).map(Ok));
+                }
+                let run = index_run_path(path, runs.len());
+                write_lines(&run, chunk.drain(..).map(Ok))?;
+                runs.push(run);
+                if chunk_bytes < chunk_size {
+                    break;
+                }
+            }
+            merge_runs(path, &runs)
+        })();
+        for run in &runs {
+            let _ = std::fs::remove_file(run);
+        }
+        result
+    }
+
+    /// The path of sorted run `n` of an index.
+    fn index_run_path(path: &Path, n: usize) -> PathBuf {
+        let mut run = path.as_os_str().to_owned();
+        run.push(format!(".run{n}"));
+        PathBuf::from(run)
+    }
+
+    /// Write `lines` to `path`, one per line.
+    fn write_lines(path: &Path, lines: impl Iterator<Item = io::Result<String>>) -> io::Result<()> {
+        let mut w = io::BufWriter::new(std::fs::File::create(path)?);
+        for line in lines {
+            w.write_all(line?.as_bytes())?;
+            w.write_all(b"\n")?;
+        }
+        w.flush()
+    }
+
+    /// Merge sorted runs into `path`.
+    fn merge_runs(path: &Path, runs: &[PathBuf]) -> io::Result<()> {
+        use std::cmp::Reverse;
+        use std::collections::BinaryHeap;
+
+        let mut readers = runs
+            .iter()
+            .map(|run| std::fs::File::open(run).map(|f| io::BufReader::new(f).lines()))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut heap = BinaryHeap::with_capacity(readers.len());
+        for (i, reader) in readers.iter_mut().enumerate() {
+            if let Some(line) = reader.next() {
+                heap.push(Reverse((line?, i)));
+            }
+        }
+
+        let merged = std::iter::from_fn(|| {
+            let Reverse((line, i)) = heap.pop()?;
+            match readers[i].next() {
+                Some(Ok(next)) => heap.push(Reverse((next, i))),
+                Some(Err(e)) => return Some(Err(e)),
+                None => (),
+            }
+            Some(Ok(line))
+        });
+        write_lines(path, merged)
+    }
+
+    /// A serialized record queued for the writer task.
+    struct WarcEntry {
+        /// The complete record bytes.
+        bytes: Vec<u8>,
+        /// Index fields for records that belong in the CDXJ index. The writer
+        /// task fills in the offset, length and filename.
+        cdx: Option<CdxjRecord>,
     }
 
     /// A lock-free WARC 1.1 file writer.
@@ -308,77 +922,182 @@ mod inner {
     /// [`WarcWriter::spawn_writer_task`]) drains the channel and writes
     /// sequentially to disk. Zero contention on the hot path.
     ///
+    /// With [`WarcConfig::gzip`] each record is compressed on the caller's
+    /// thread as its own gzip member. With [`WarcConfig::max_file_size`] or
+    /// [`WarcConfig::max_records`] the task rotates to a new file between
+    /// records, opening each file with a fresh `warcinfo` record.
+    ///
     /// Safe to clone and share across tasks.
     #[derive(Clone)]
     pub struct WarcWriter {
-        tx: mpsc::UnboundedSender<Vec<u8>>,
+        tx: mpsc::UnboundedSender<WarcEntry>,
         record_count: std::sync::Arc<AtomicU64>,
+        file_count: std::sync::Arc<AtomicU64>,
         path: std::sync::Arc<PathBuf>,
+        gzip: bool,
+        revisits: Option<WarcRevisits>,
+        skip_noarchive: bool,
     }
 
     impl WarcWriter {
         /// Create a new WARC writer and spawn the background file-writing task.
         ///
+        /// When [`WarcConfig::write_cdx`] is set the task also writes a CDXJ
+        /// index next to the archive, sorted by SURT key once the writer closes.
+        ///
         /// Returns `(writer, join_handle)`. The handle resolves when the writer
         /// is dropped (all senders closed) or an I/O error occurs.
         pub fn create(
             config: &WarcConfig,
         ) -> io::Result<(Self, tokio::task::JoinHandle<io::Result<()>>)> {
-            let path = PathBuf::from(&config.path);
+            Self::create_with_revisits(config, WarcRevisits::default())
+        }
+
+        /// Like [`create`](Self::create), deduplicating payloads against a
+        /// shared [`WarcRevisits`] table that can outlive this writer.
+        pub fn create_with_revisits(
+            config: &WarcConfig,
+            revisits: WarcRevisits,
+        ) -> io::Result<(Self, tokio::task::JoinHandle<io::Result<()>>)> {
+            let path = config.file_path(0);
+            let file = create_file(&path)?;
 
-            // Ensure parent directory exists.
-            if let Some(parent) = path.parent() {
-                if !parent.as_os_str().is_empty() {
-                    std::fs::create_dir_all(parent)?;
+            let index = match config.cdx_path() {
+                Some(cdx_path) => {
+                    let cdx_file = create_file(&cdx_path)?;
+                    Some((cdx_path, cdx_file))
                 }
-            }
+                None => None,
+            };
 
-            let file = std::fs::File::create(&path)?;
-            let (tx, rx) = mpsc::unbounded_channel::<Vec<u8>>();
+            let (tx, rx) = mpsc::unbounded_channel::<WarcEntry>();
             let record_count = std::sync::Arc::new(AtomicU64::new(0));
+            let file_count = std::sync::Arc::new(AtomicU64::new(1));
+
+            // The task writes the first warcinfo itself; count it up front so
+            // `record_count` is accurate as soon as `create` returns.
+            if config.write_warcinfo {
+                record_count.fetch_add(1, Ordering::Relaxed);
+            }
 
             let writer = Self {
                 tx,
                 record_count: record_count.clone(),
+                file_count: file_count.clone(),
                 path: std::sync::Arc::new(path),
+                gzip: config.is_gzip(),
+                revisits: config.revisits.then_some(revisits),
+                skip_noarchive: config.skip_noarchive,
             };
 
-            // Send warcinfo as first record if requested.
-            if config.write_warcinfo {
-                let buf = serialize_warcinfo(&config.software);
-                let _ = writer.tx.send(buf);
-                writer.record_count.fetch_add(1, Ordering::Relaxed);
-            }
-
             // Spawn the single file-writer task on a blocking thread.
-            let handle = Self::spawn_writer_task(file, rx);
+            let handle = Self::spawn_writer_task(
+                config.clone(),
+                file,
+                file_name(&writer.path),
+                index,
+                record_count,
+                file_count,
+                rx,
+            );
 
             Ok((writer, handle))
         }
 
         /// Spawn the background task that drains the channel and writes to disk.
+        ///
+        /// Index lines are appended as records land so a crash still leaves a
+        /// usable (unsorted) index; the file is sorted once the channel closes.
         fn spawn_writer_task(
+            config: WarcConfig,
             file: std::fs::File,
-            mut rx: mpsc::UnboundedReceiver<Vec<u8>>,
+            name: String,
+            index: Option<(PathBuf, std::fs::File)>,
+            record_count: std::sync::Arc<AtomicU64>,
+            file_count: std::sync::Arc<AtomicU64>,
+            mut rx: mpsc::UnboundedReceiver<WarcEntry>,
         ) -> tokio::task::JoinHandle<io::Result<()>> {
             tokio::task::spawn_blocking(move || {
+                let gzip = config.is_gzip();
+                let warcinfo = |name: &str| {
+                    let record = serialize_warcinfo(&config.software, Some(name));
+                    if gzip {
+                        gzip_member(&record)
+                    } else {
+                        record
+                    }
+                };
+
                 let mut w = io::BufWriter::with_capacity(BUF_SIZE, file);
+                let mut name = name;
+                let mut index = index.map(|(path, file)| (path, io::BufWriter::new(file)));
+                let mut serial: u64 = 0;
+                // Bytes and records in the current file.
+                let mut offset: u64 = 0;
+                let mut records: u64 = 0;
+
+                if config.write_warcinfo {
+                    let info = warcinfo(&name);
+                    w.write_all(&info)?;
+                    offset += info.len() as u64;
+                }
+
                 // Use blocking recv via a small runtime-free loop.
-                while let Some(buf) = rx.blocking_recv() {
-                    w.write_all(&buf)?;
+                while let Some(entry) = rx.blocking_recv() {
+                    if config.rotation_due(offset, records) {
+                        w.flush()?;
+                        serial += 1;
+                        let path = config.file_path(serial);
+                        w = io::BufWriter::with_capacity(BUF_SIZE, create_file(&path)?);
+                        name = file_name(&path);
+                        offset = 0;
+                        records = 0;
+                        file_count.fetch_add(1, Ordering::Relaxed);
+
+                        if config.write_warcinfo {
+                            let info = warcinfo(&name);
+                            w.write_all(&info)?;
+                            offset += info.len() as u64;
+                            record_count.fetch_add(1, Ordering::Relaxed);
+                        }
+                    }
+
+                    w.write_all(&entry.bytes)?;
+                    let length = entry.bytes.len() as u64;
+                    if let (Some((_, cdx)), Some(mut record)) = (&mut index, entry.cdx) {
+                        record.offset = offset;
+                        record.length = length;
+                        record.filename.clone_from(&name);
+                        writeln!(cdx, "{}", record.to_line())?;
+                    }
+                    offset += length;
+                    records += 1;
                 }
                 w.flush()?;
+                if let Some((path, mut cdx)) = index {
+                    cdx.flush()?;
+                    drop(cdx);
+                    sort_index(&path)?;
+                }
                 Ok(())
             })
         }
 
-        /// Write a WARC `response` record from a crawled `Page`.
+        /// Write a WARC `response` record from a crawled `Page`, or a `revisit`
+        /// record when its payload was already archived.
         ///
-        /// Serializes the record on the caller's thread and sends the bytes
-        /// through the channel — fully lock-free.
+        /// Serializes (and compresses) the record on the caller's thread and
+        /// sends the bytes through the channel — fully lock-free. Pages marked
+        /// `noarchive` are skipped when [`WarcConfig::skip_noarchive`] is set.
         pub fn write_page(&self, page: &Page) {
-            if let Some(buf) = serialize_page(page) {
-                if self.tx.send(buf).is_ok() {
+            if self.skip_noarchive && page.is_noarchive() {
+                return;
+            }
+            if let Some(mut entry) = serialize_response(page, self.revisits.as_ref()) {
+                if self.gzip {
+                    entry.bytes = gzip_member(&entry.bytes);
+                }
+                if self.tx.send(entry).is_ok() {
                     self.record_count.fetch_add(1, Ordering::Relaxed);
                 }
             }
@@ -389,7 +1108,18 @@ mod inner {
             self.record_count.load(Ordering::Relaxed)
         }
 
-        /// The output file path.
+        /// The payload dedup table, when [`WarcConfig::revisits`] is enabled.
+        pub fn revisits(&self) -> Option<&WarcRevisits> {
+            self.revisits.as_ref()
+        }
+
+        /// Number of files opened so far, including the current one.
+        pub fn file_count(&self) -> u64 {
+            self.file_count.load(Ordering::Relaxed)
+        }
+
+        /// The path of the first output file. Rotated files follow
+        /// [`WarcConfig::file_path`].
         pub fn path(&self) -> &Path {
             &self.path
         }
@@ -426,93 +1156,872 @@ mod inner {
         })
     }
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-        use reqwest::StatusCode;
+    /// A WARC record type (`WARC-Type` header).
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum WarcRecordType {
+        /// `warcinfo` — describes the records that follow it.
+        Warcinfo,
+        /// `response` — a full protocol response.
+        Response,
+        /// `request` — a full protocol request.
+        Request,
+        /// `revisit` — a response whose payload duplicates an earlier record.
+        Revisit,
+        /// `resource` — a resource without protocol framing.
+        Resource,
+        /// `metadata` — content describing another record.
+        Metadata,
+        /// Any other record type.
+        Other(String),
+    }
 
-        /// Helper to create a minimal Page for testing.
-        fn make_test_page(url: &str, status: u16, body: &str) -> Page {
-            let mut page = Page::default();
-            page.url = url.to_string();
-            page.status_code = StatusCode::from_u16(status).unwrap_or_default();
-            page.html = Some(bytes::Bytes::from(body.to_owned()));
-            page
+    impl WarcRecordType {
+        /// Parse the `WARC-Type` header value.
+        fn parse(value: &str) -> Self {
+            match value.trim().to_ascii_lowercase().as_str() {
+                "warcinfo" => Self::Warcinfo,
+                "response" => Self::Response,
+                "request" => Self::Request,
+                "revisit" => Self::Revisit,
+                "resource" => Self::Resource,
+                "metadata" => Self::Metadata,
+                _ => Self::Other(value.trim().to_string()),
+            }
         }
+    }
 
-        #[test]
-        fn warc_date_format_is_valid() {
-            let date = warc_date_now();
-            assert_eq!(date.len(), 20, "date = {date}");
-            assert!(date.ends_with('Z'));
-            assert_eq!(&date[4..5], "-");
-            assert_eq!(&date[7..8], "-");
-            assert_eq!(&date[10..11], "T");
-            assert_eq!(&date[13..14], ":");
-            assert_eq!(&date[16..17], ":");
-        }
+    /// A single record read back from a WARC file.
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct WarcRecord {
+        /// The version line, e.g. `WARC/1.1`.
+        pub version: String,
+        /// Named fields in file order. Names keep their original casing.
+        pub headers: Vec<(String, String)>,
+        /// The record content block (`Content-Length` bytes).
+        pub block: Vec<u8>,
+    }
 
-        #[test]
-        fn uuid_format_is_valid() {
-            let uuid = generate_uuid();
-            assert_eq!(uuid.len(), 36);
-            assert_eq!(&uuid[8..9], "-");
-            assert_eq!(&uuid[13..14], "-");
-            assert_eq!(&uuid[18..19], "-");
-            assert_eq!(&uuid[23..24], "-");
-            assert_eq!(&uuid[14..15], "4");
+    impl WarcRecord {
+        /// Get a named field, matched case-insensitively.
+        pub fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
         }
 
-        #[test]
-        fn uuid_uniqueness() {
-            let mut set = hashbrown::HashSet::new();
-            for _ in 0..1000 {
-                assert!(set.insert(generate_uuid()), "duplicate UUID generated");
-            }
+        /// The record type.
+        pub fn record_type(&self) -> WarcRecordType {
+            WarcRecordType::parse(self.header("WARC-Type").unwrap_or_default())
         }
 
-        #[test]
-        fn days_to_ymd_epoch() {
-            let (y, m, d) = days_to_ymd(0);
-            assert_eq!((y, m, d), (1970, 1, 1));
+        /// The `WARC-Record-ID` including the angle brackets.
+        pub fn record_id(&self) -> Option<&str> {
+            self.header("WARC-Record-ID")
         }
 
-        #[test]
-        fn days_to_ymd_known_date() {
-            let (y, m, d) = days_to_ymd(20537);
-            assert_eq!((y, m, d), (2026, 3, 25));
+        /// The `WARC-Target-URI`. Some writers wrap it in angle brackets.
+        pub fn target_uri(&self) -> Option<&str> {
+            self.header("WARC-Target-URI").map(strip_angle_brackets)
         }
 
-        #[test]
-        fn serialize_page_valid_record() {
-            let page = make_test_page("https://example.com", 200, "<html>Hello</html>");
-            let buf = serialize_page(&page).unwrap();
-            let content = String::from_utf8_lossy(&buf);
+        /// The `WARC-Date`.
+        pub fn date(&self) -> Option<&str> {
+            self.header("WARC-Date")
+        }
 
-            assert!(content.starts_with("WARC/1.1\r\n"));
-            assert!(content.contains("WARC-Type: response\r\n"));
-            assert!(content.contains("WARC-Target-URI: https://example.com\r\n"));
-            assert!(content.contains("Content-Type: application/http; msgtype=response\r\n"));
-            assert!(content.contains("HTTP/1.1 200 OK\r\n"));
-            assert!(content.contains("<html>Hello</html>"));
-            assert!(buf.ends_with(b"\r\n\r\n"));
+        /// The `WARC-Refers-To` record id of a revisit.
+        pub fn refers_to(&self) -> Option<&str> {
+            self.header("WARC-Refers-To")
         }
 
-        #[test]
-        fn serialize_page_empty_url_returns_none() {
-            let page = make_test_page("", 200, "body");
-            assert!(serialize_page(&page).is_none());
+        /// The `WARC-Refers-To-Target-URI` of a revisit.
+        pub fn refers_to_target_uri(&self) -> Option<&str> {
+            self.header("WARC-Refers-To-Target-URI")
+                .map(strip_angle_brackets)
         }
 
-        #[test]
-        fn serialize_page_404() {
-            let page = make_test_page("https://example.com/missing", 404, "Not Found");
-            let buf = serialize_page(&page).unwrap();
-            let content = String::from_utf8_lossy(&buf);
-            assert!(content.contains("HTTP/1.1 404 Not Found\r\n"));
+        /// Parse the block of a `response` or `revisit` record as an HTTP response.
+        ///
+        /// Revisit blocks usually carry only the status line and headers, so
+        /// the returned body may be empty.
+        pub fn http_response(&self) -> Option<WarcHttpResponse> {
+            let (start, mut headers, body) = parse_http_block(&self.block)?;
+            let mut parts = start.splitn(3, ' ');
+            let version = parts.next()?;
+            if !version.starts_with("HTTP/") {
+                return None;
+            }
+            let status = reqwest::StatusCode::from_bytes(parts.next()?.as_bytes()).ok()?;
+            let body = decode_http_body(&mut headers, body);
+            Some(WarcHttpResponse {
+                status,
+                headers,
+                body,
+            })
         }
 
-        #[test]
+        /// Parse the block of a `request` record as an HTTP request.
+        pub fn http_request(&self) -> Option<WarcHttpRequest> {
+            let (start, headers, body) = parse_http_block(&self.block)?;
+            let mut parts = start.splitn(3, ' ');
+            let method = parts.next()?.to_string();
+            let uri = parts.next()?.to_string();
+            Some(WarcHttpRequest {
+                method,
+                uri,
+                headers,
+                body: body.to_vec(),
+            })
+        }
+    }
+
+    /// An HTTP response stored in a WARC record.
+    #[derive(Debug, Clone, Default)]
+    pub struct WarcHttpResponse {
+        /// The response status.
+        pub status: reqwest::StatusCode,
+        /// The response headers. The encodings removed from the body are
+        /// dropped along with `Content-Length`.
+        pub headers: reqwest::header::HeaderMap,
+        /// The response body with transfer and content encodings removed.
+        pub body: Vec<u8>,
+    }
+
+    /// An HTTP request stored in a WARC record.
+    #[derive(Debug, Clone, Default)]
+    pub struct WarcHttpRequest {
+        /// The request method.
+        pub method: String,
+        /// The request target as written on the request line.
+        pub uri: String,
+        /// The request headers.
+        pub headers: reqwest::header::HeaderMap,
+        /// The request body.
+        pub body: Vec<u8>,
+    }
+
+    /// Strip a surrounding `<...>` from a WARC URI field.
+    fn strip_angle_brackets(value: &str) -> &str {
+        value
+            .strip_prefix('<')
+            .and_then(|v| v.strip_suffix('>'))
+            .unwrap_or(value)
+    }
+
+    /// Split an HTTP message into its start line, headers and raw body.
+    fn parse_http_block(block: &[u8]) -> Option<(String, reqwest::header::HeaderMap, &[u8])> {
+        let head_end = memchr::memmem::find(block, b"\r\n\r\n")
+            .map(|i| (i, i + 4))
+            .or_else(|| memchr::memmem::find(block, b"\n\n").map(|i| (i, i + 2)))
+            .unwrap_or((block.len(), block.len()));
+        let head = String::from_utf8_lossy(&block[..head_end.0]);
+        let mut lines = head.lines();
+        let start = lines.next()?.trim().to_string();
+        let mut headers = reqwest::header::HeaderMap::new();
+
+        for line in lines {
+            if let Some((name, value)) = line.split_once(':') {
+                if let (Ok(name), Ok(value)) = (
+                    reqwest::header::HeaderName::from_bytes(name.trim().as_bytes()),
+                    reqwest::header::HeaderValue::from_str(value.trim()),
+                ) {
+                    headers.append(name, value);
+                }
+            }
+        }
+
+        Some((start, headers, &block[head_end.1..]))
+    }
+
+    /// Undo `Transfer-Encoding: chunked` and `Content-Encoding: gzip|deflate`
+    /// when the stored body is still encoded, removing the undone header and
+    /// the stale `Content-Length`. Bodies written by [`serialize_page`] are
+    /// already decoded, so any failure keeps the raw bytes and headers.
+    fn decode_http_body(headers: &mut reqwest::header::HeaderMap, body: &[u8]) -> Vec<u8> {
+        use reqwest::header::{CONTENT_ENCODING, CONTENT_LENGTH, TRANSFER_ENCODING};
+
+        let has_token = |headers: &reqwest::header::HeaderMap,
+                         name: reqwest::header::HeaderName,
+                         token: &str| {
+            headers.get_all(name).iter().any(|v| {
+                v.to_str()
+                    .map(|v| v.split(',').any(|t| t.trim().eq_ignore_ascii_case(token)))
+                    .unwrap_or_default()
+            })
+        };
+
+        let mut body = match has_token(headers, TRANSFER_ENCODING, "chunked")
+            .then(|| dechunk(body))
+            .flatten()
+        {
+            Some(body) => {
+                headers.remove(TRANSFER_ENCODING);
+                headers.remove(CONTENT_LENGTH);
+                body
+            }
+            None => body.to_vec(),
+        };
+
+        let mut out = Vec::with_capacity(body.len() * 4);
+        let decoded =
+            if has_token(headers, CONTENT_ENCODING, "gzip") && body.starts_with(&[0x1f, 0x8b]) {
+                flate2::read::MultiGzDecoder::new(&body[..])
+                    .read_to_end(&mut out)
+                    .is_ok()
+            } else if has_token(headers, CONTENT_ENCODING, "deflate") {
+                flate2::read::ZlibDecoder::new(&body[..])
+                    .read_to_end(&mut out)
+                    .is_ok()
+            } else {
+                false
+            };
+
+        if decoded {
+            headers.remove(CONTENT_ENCODING);
+            headers.remove(CONTENT_LENGTH);
+            body = out;
+        }
+
+        body
+    }
+
+    /// Decode a chunked HTTP body. Returns `None` if the framing is invalid.
+    fn dechunk(mut body: &[u8]) -> Option<Vec<u8>> {
+        let mut out = Vec::with_capacity(body.len());
+        loop {
+            let line_end = memchr::memchr(b'\n', body)?;
+            let line = std::str::from_utf8(&body[..line_end]).ok()?.trim();
+            let size = line.split(';').next()?.trim();
+            let size = usize::from_str_radix(size, 16).ok()?;
+            body = &body[line_end + 1..];
+            if size == 0 {
+                return Some(out);
+            }
+            out.extend_from_slice(body.get(..size)?);
+            body = body.get(size..)?;
+            body = body
+                .strip_prefix(b"\r\n")
+                .or_else(|| body.strip_prefix(b"\n"))?;
+        }
+    }
+
+    /// A streaming WARC reader.
+    ///
+    /// Reads one record at a time, so archives of any size can be walked with
+    /// constant memory beyond the largest record. Gzip-compressed archives
+    /// (`.warc.gz`, one gzip member per record or a single member for the
+    /// whole file) are detected from the magic bytes and decompressed on the
+    /// fly.
+    pub struct WarcReader {
+        inner: Box<dyn io::BufRead + Send>,
+        line: Vec<u8>,
+        done: bool,
+    }
+
+    impl WarcReader {
+        /// Wrap any reader, detecting gzip compression.
+        pub fn new<R: io::Read + Send + 'static>(reader: R) -> io::Result<Self> {
+            let mut reader = io::BufReader::with_capacity(BUF_SIZE, reader);
+            let gzip = reader.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+
+            let inner: Box<dyn io::BufRead + Send> = if gzip {
+                Box::new(io::BufReader::with_capacity(
+                    BUF_SIZE,
+                    flate2::bufread::MultiGzDecoder::new(reader),
+                ))
+            } else {
+                Box::new(reader)
+            };
+
+            Ok(Self {
+                inner,
+                line: Vec::with_capacity(256),
+                done: false,
+            })
+        }
+
+        /// Open a `.warc` or `.warc.gz` file.
+        pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+            Self::new(std::fs::File::open(path)?)
+        }
+
+        /// Open a file positioned at a record offset, e.g. from a [`CdxjRecord`].
+        pub fn open_at(path: impl AsRef<Path>, offset: u64) -> io::Result<Self> {
+            use std::io::Seek;
+            let mut file = std::fs::File::open(path)?;
+            file.seek(io::SeekFrom::Start(offset))?;
+            Self::new(file)
+        }
+
+        /// Read the next record. Returns `Ok(None)` at the end of the archive.
+        pub fn next_record(&mut self) -> io::Result<Option<WarcRecord>> {
+            read_record(&mut self.inner, &mut self.line)
+        }
+    }
+
+    /// Read the next line without its line terminator. `None` at EOF.
+    fn read_line<'a>(
+        inner: &mut (impl io::BufRead + ?Sized),
+        line: &'a mut Vec<u8>,
+    ) -> io::Result<Option<&'a str>> {
+        line.clear();
+        if inner.read_until(b'\n', line)? == 0 {
+            return Ok(None);
+        }
+        while matches!(line.last(), Some(b'\n' | b'\r')) {
+            line.pop();
+        }
+        std::str::from_utf8(line)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Read the next record from an uncompressed stream, using `line` as
+    /// scratch space. Returns `Ok(None)` at the end of the stream.
+    fn read_record(
+        inner: &mut (impl io::BufRead + ?Sized),
+        line: &mut Vec<u8>,
+    ) -> io::Result<Option<WarcRecord>> {
+        // Skip the blank lines terminating the previous record.
+        let version = loop {
+            match read_line(inner, line)? {
+                None => return Ok(None),
+                Some("") => continue,
+                Some(line) => break line.to_string(),
+            }
+        };
+
+        if !version.starts_with("WARC/") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid WARC version line: {version}"),
+            ));
+        }
+
+        let mut headers: Vec<(String, String)> = Vec::with_capacity(8);
+
+        loop {
+            let line = match read_line(inner, line)? {
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "truncated WARC record header",
+                    ))
+                }
+                Some("") => break,
+                Some(line) => line,
+            };
+
+            if line.starts_with([' ', '\t']) {
+                // Folded continuation of the previous field.
+                if let Some((_, value)) = headers.last_mut() {
+                    value.push(' ');
+                    value.push_str(line.trim());
+                }
+            } else if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_string(), value.trim().to_string()));
+            }
+        }
+
+        let length = headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case("Content-Length"))
+            .and_then(|(_, v)| v.parse::<u64>().ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "WARC record without a valid Content-Length",
+                )
+            })?;
+
+        let mut block = Vec::with_capacity(length.min(BUF_SIZE as u64 * 64) as usize);
+        io::Read::take(&mut *inner, length).read_to_end(&mut block)?;
+
+        if (block.len() as u64) < length {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "truncated WARC record block",
+            ));
+        }
+
+        Ok(Some(WarcRecord {
+            version,
+            headers,
+            block,
+        }))
+    }
+
+    impl Iterator for WarcReader {
+        type Item = io::Result<WarcRecord>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.done {
+                return None;
+            }
+            let next = self.next_record().transpose();
+            if !matches!(next, Some(Ok(_))) {
+                self.done = true;
+            }
+            next
+        }
+    }
+
+    /// Maximum redirect hops followed inside an archive during replay.
+    const MAX_REPLAY_REDIRECTS: usize = 10;
+
+    /// Where a record lives in the archives of a [`WarcReplayFetcher`].
+    #[derive(Debug, Clone, Copy)]
+    struct RecordLocation {
+        /// Index of the archive file.
+        file: u32,
+        /// Byte offset of the record, or of the gzip member holding it.
+        offset: u64,
+        /// Records to skip from `offset`, when a gzip member holds several.
+        skip: u32,
+    }
+
+    /// A capture indexed for replay.
+    #[derive(Debug, Clone, Copy)]
+    struct ReplayEntry {
+        /// The `response` or `revisit` record.
+        record: RecordLocation,
+        /// The `response` record holding the payload of a revisit.
+        payload: Option<RecordLocation>,
+    }
+
+    /// The replay index built while scanning an archive.
+    #[derive(Default)]
+    struct ReplayIndex {
+        /// Target uri -> latest capture.
+        entries: hashbrown::HashMap<String, ReplayEntry>,
+        /// `WARC-Record-ID` -> `response` record, to resolve `WARC-Refers-To`.
+        ids: hashbrown::HashMap<String, RecordLocation>,
+    }
+
+    impl ReplayIndex {
+        /// Index a record found at `at`.
+        fn insert(&mut self, record: &WarcRecord, at: RecordLocation) {
+            let record_type = record.record_type();
+
+            if record_type != WarcRecordType::Response && record_type != WarcRecordType::Revisit {
+                return;
+            }
+
+            let Some(target) = record.target_uri() else {
+                return;
+            };
+            if !record.block.starts_with(b"HTTP/") {
+                return;
+            }
+
+            let payload = if record_type == WarcRecordType::Revisit {
+                let payload = record.refers_to().and_then(|id| self.ids.get(id)).copied();
+                let payload = payload.or_else(|| {
+                    self.entries
+                        .get(record.refers_to_target_uri().unwrap_or(target))
+                        .map(|entry| entry.payload.unwrap_or(entry.record))
+                });
+                match payload {
+                    Some(payload) => Some(payload),
+                    None => return,
+                }
+            } else {
+                if let Some(id) = record.record_id() {
+                    self.ids.insert(id.to_string(), at);
+                }
+                None
+            };
+
+            self.entries.insert(
+                target.to_string(),
+                ReplayEntry {
+                    record: at,
+                    payload,
+                },
+            );
+        }
+    }
+
+    /// A [`RemoteFetcher`](crate::fetcher::RemoteFetcher) that serves pages
+    /// from a WARC archive instead of the network.
+    ///
+    /// Install it with
+    /// [`Website::with_remote_fetcher`](crate::website::Website::with_remote_fetcher)
+    /// to re-crawl an archive offline — for regression tests or for
+    /// re-running extraction without hitting the origin. `response` records
+    /// are indexed by target URI (the latest capture wins), `revisit` records
+    /// resolve their payload through the `WARC-Refers-To` record id, falling
+    /// back to `WARC-Refers-To-Target-URI`, and redirects captured in the
+    /// archive are followed. URLs missing from the archive replay as
+    /// `404 Not Found`.
+    ///
+    /// Only the record offsets are kept in memory; each replay reads its
+    /// record back from disk.
+    #[derive(Debug, Clone, Default)]
+    pub struct WarcReplayFetcher {
+        files: std::sync::Arc<Vec<PathBuf>>,
+        entries: std::sync::Arc<hashbrown::HashMap<String, ReplayEntry>>,
+    }
+
+    impl WarcReplayFetcher {
+        /// Index a `.warc` or `.warc.gz` file by scanning its records once.
+        pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+            use std::io::Seek;
+
+            let path = path.as_ref().to_path_buf();
+            let mut source = io::BufReader::with_capacity(BUF_SIZE, std::fs::File::open(&path)?);
+            let gzip = source.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+            let mut index = ReplayIndex::default();
+            let mut line = Vec::with_capacity(256);
+
+            loop {
+                let offset = source.stream_position()?;
+
+                if gzip {
+                    if source.fill_buf()?.is_empty() {
+                        break;
+                    }
+                    let mut member = io::BufReader::with_capacity(
+                        BUF_SIZE,
+                        flate2::bufread::GzDecoder::new(&mut source),
+                    );
+                    let mut skip = 0;
+                    while let Some(record) = read_record(&mut member, &mut line)? {
+                        index.insert(
+                            &record,
+                            RecordLocation {
+                                file: 0,
+                                offset,
+                                skip,
+                            },
+                        );
+                        skip += 1;
+                    }
+                } else {
+                    match read_record(&mut source, &mut line)? {
+                        Some(record) => index.insert(
+                            &record,
+                            RecordLocation {
+                                file: 0,
+                                offset,
+                                skip: 0,
+                            },
+                        ),
+                        None => break,
+                    }
+                }
+            }
+
+            Ok(Self {
+                files: std::sync::Arc::new(vec![path]),
+                entries: std::sync::Arc::new(index.entries),
+            })
+        }
+
+        /// Index the archives listed in a CDXJ index, such as the one written
+        /// with [`WarcConfig::write_cdx`]. The archives are looked up by their
+        /// `filename` next to the index. Only the `revisit` records and the
+        /// captures they may refer to are read while indexing.
+        pub fn from_cdxj(path: impl AsRef<Path>) -> io::Result<Self> {
+            let path = path.as_ref();
+            let dir = path.parent().unwrap_or_else(|| Path::new(""));
+            let mut records = CdxjRecord::read_index(path)?;
+            // Oldest first, so the latest capture of a URL wins.
+            records.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+
+            let mut files: Vec<PathBuf> = Vec::new();
+            let mut file_ids: hashbrown::HashMap<String, u32> = hashbrown::HashMap::new();
+            let mut captures: hashbrown::HashMap<String, Vec<RecordLocation>> =
+                hashbrown::HashMap::new();
+            let mut revisits: Vec<(String, RecordLocation)> = Vec::new();
+
+            for record in records {
+                let file = *file_ids.entry(record.filename.clone()).or_insert_with(|| {
+                    files.push(dir.join(&record.filename));
+                    files.len() as u32 - 1
+                });
+                let at = RecordLocation {
+                    file,
+                    offset: record.offset,
+                    skip: 0,
+                };
+                if record.mime == "warc/revisit" {
+                    revisits.push((record.url, at));
+                } else {
+                    captures.entry(record.url).or_default().push(at);
+                }
+            }
+
+            let fetcher = Self {
+                files: std::sync::Arc::new(files),
+                entries: Default::default(),
+            };
+            let mut entries: hashbrown::HashMap<String, ReplayEntry> = captures
+                .iter()
+                .filter_map(|(url, at)| {
+                    let record = *at.last()?;
+                    Some((
+                        url.clone(),
+                        ReplayEntry {
+                            record,
+                            payload: None,
+                        },
+                    ))
+                })
+                .collect();
+
+            for (url, at) in revisits {
+                let Some(revisit) = fetcher.read(at)? else {
+                    continue;
+                };
+                let original = revisit.refers_to_target_uri().unwrap_or(&url);
+                let Some(candidates) = captures.get(original) else {
+                    continue;
+                };
+                let mut payload = candidates.last().copied();
+                if let Some(id) = revisit.refers_to() {
+                    for candidate in candidates.iter().rev() {
+                        let record = fetcher.read(*candidate)?;
+                        if record.as_ref().and_then(|r| r.record_id()) == Some(id) {
+                            payload = Some(*candidate);
+                            break;
+                        }
+                    }
+                }
+                entries.insert(
+                    url,
+                    ReplayEntry {
+                        record: at,
+                        payload,
+                    },
+                );
+            }
+
+            Ok(Self {
+                entries: std::sync::Arc::new(entries),
+                ..fetcher
+            })
+        }
+
+        /// Number of URLs available for replay.
+        pub fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        /// Whether the archive holds no replayable responses.
+        pub fn is_empty(&self) -> bool {
+            self.entries.is_empty()
+        }
+
+        /// Whether a capture exists for `url`.
+        pub fn contains(&self, url: &str) -> bool {
+            self.lookup(url).is_some()
+        }
+
+        /// Find a capture, tolerating a missing or extra trailing slash.
+        fn lookup(&self, url: &str) -> Option<&ReplayEntry> {
+            self.entries
+                .get(url)
+                .or_else(|| match url.strip_suffix('/') {
+                    Some(stripped) => self.entries.get(stripped),
+                    None => self.entries.get(&format!("{url}/")),
+                })
+        }
+
+        /// Read the record at `at` back from its archive.
+        fn read(&self, at: RecordLocation) -> io::Result<Option<WarcRecord>> {
+            let Some(path) = self.files.get(at.file as usize) else {
+                return Ok(None);
+            };
+            let mut reader = WarcReader::open_at(path, at.offset)?;
+            for _ in 0..at.skip {
+                if reader.next_record()?.is_none() {
+                    return Ok(None);
+                }
+            }
+            reader.next_record()
+        }
+
+        /// Load the HTTP response of a capture, with the payload of the
+        /// original record for a revisit.
+        fn load(&self, entry: &ReplayEntry) -> io::Result<Option<WarcHttpResponse>> {
+            let Some(mut response) = self.read(entry.record)?.and_then(|r| r.http_response())
+            else {
+                return Ok(None);
+            };
+
+            if let Some(payload) = entry.payload {
+                let Some(original) = self.read(payload)?.and_then(|r| r.http_response()) else {
+                    return Ok(None);
+                };
+                // The payload keeps the encodings of the original response.
+                for name in [
+                    reqwest::header::CONTENT_ENCODING,
+                    reqwest::header::TRANSFER_ENCODING,
+                    reqwest::header::CONTENT_LENGTH,
+                ] {
+                    response.headers.remove(&name);
+                    for value in original.headers.get_all(&name) {
+                        response.headers.append(name.clone(), value.clone());
+                    }
+                }
+                response.body = original.body;
+            }
+
+            Ok(Some(response))
+        }
+
+        /// Replay `url`, following captured redirects.
+        pub fn replay(&self, url: &str) -> crate::utils::PageResponse {
+            let mut current = url.to_string();
+            let mut response = crate::utils::PageResponse::default();
+
+            for _ in 0..=MAX_REPLAY_REDIRECTS {
+                let loaded = self.lookup(&current).and_then(|entry| {
+                    self.load(entry).unwrap_or_else(|e| {
+                        log::warn!("WARC replay failed to read {current}: {e}");
+                        None
+                    })
+                });
+                let Some(entry) = loaded else {
+                    response.status_code = reqwest::StatusCode::NOT_FOUND;
+                    response.final_url = Some(current);
+                    return response;
+                };
+
+                let location = if entry.status.is_redirection() {
+                    entry
+                        .headers
+                        .get(reqwest::header::LOCATION)
+                        .and_then(|l| l.to_str().ok())
+                        .and_then(|l| url::Url::parse(&current).ok()?.join(l).ok())
+                        .map(|l| l.to_string())
+                        .filter(|l| self.lookup(l).is_some())
+                } else {
+                    None
+                };
+
+                match location {
+                    Some(next) => current = next,
+                    None => {
+                        response.status_code = entry.status;
+                        response.headers = Some(entry.headers);
+                        response.content = Some(entry.body);
+                        response.final_url = Some(current);
+                        return response;
+                    }
+                }
+            }
+
+            response.status_code = reqwest::StatusCode::LOOP_DETECTED;
+            response.final_url = Some(current);
+            response
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl crate::fetcher::RemoteFetcher for WarcReplayFetcher {
+        async fn fetch(&self, ctx: crate::fetcher::FetchContext<'_>) -> crate::utils::PageResponse {
+            let fetcher = self.clone();
+            let url = ctx.url.to_string();
+            // Replay reads the archive with blocking file I/O.
+            tokio::task::spawn_blocking(move || fetcher.replay(&url))
+                .await
+                .unwrap_or_else(|_| crate::utils::PageResponse {
+                    status_code: reqwest::StatusCode::INTERNAL_SERVER_ERROR,
+                    ..Default::default()
+                })
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use reqwest::StatusCode;
+
+        /// Helper to create a minimal Page for testing.
+        fn make_test_page(url: &str, status: u16, body: &str) -> Page {
+            let mut page = Page::default();
+            page.url = url.to_string();
+            page.status_code = StatusCode::from_u16(status).unwrap_or_default();
+            page.html = Some(bytes::Bytes::from(body.to_owned()));
+            page
+        }
+
+        #[test]
+        fn warc_date_format_is_valid() {
+            let date = warc_date_now();
+            assert_eq!(date.len(), 20, "date = {date}");
+            assert!(date.ends_with('Z'));
+            assert_eq!(&date[4..5], "-");
+            assert_eq!(&date[7..8], "-");
+            assert_eq!(&date[10..11], "T");
+            assert_eq!(&date[13..14], ":");
+            assert_eq!(&date[16..17], ":");
+        }
+
+        #[test]
+        fn uuid_format_is_valid() {
+            let uuid = generate_uuid();
+            assert_eq!(uuid.len(), 36);
+            assert_eq!(&uuid[8..9], "-");
+            assert_eq!(&uuid[13..14], "-");
+            assert_eq!(&uuid[18..19], "-");
+            assert_eq!(&uuid[23..24], "-");
+            assert_eq!(&uuid[14..15], "4");
+        }
+
+        #[test]
+        fn uuid_uniqueness() {
+            let mut set = hashbrown::HashSet::new();
+            for _ in 0..1000 {
+                assert!(set.insert(generate_uuid()), "duplicate UUID generated");
+            }
+        }
+
+        #[test]
+        fn days_to_ymd_epoch() {
+            let (y, m, d) = days_to_ymd(0);
+            assert_eq!((y, m, d), (1970, 1, 1));
+        }
+
+        #[test]
+        fn days_to_ymd_known_date() {
+            let (y, m, d) = days_to_ymd(20537);
+            assert_eq!((y, m, d), (2026, 3, 25));
+        }
+
+        #[test]
+        fn serialize_page_valid_record() {
+            let page = make_test_page("https://example.com", 200, "<html>Hello</html>");
+            let buf = serialize_page(&page).unwrap();
+            let content = String::from_utf8_lossy(&buf);
+
+            assert!(content.starts_with("WARC/1.1\r\n"));
+            assert!(content.contains("WARC-Type: response\r\n"));
+            assert!(content.contains("WARC-Target-URI: https://example.com\r\n"));
+            assert!(content.contains("Content-Type: application/http; msgtype=response\r\n"));
+            assert!(content.contains("HTTP/1.1 200 OK\r\n"));
+            assert!(content.contains("<html>Hello</html>"));
+            assert!(buf.ends_with(b"\r\n\r\n"));
+        }
+
+        #[test]
+        fn serialize_page_empty_url_returns_none() {
+            let page = make_test_page("", 200, "body");
+            assert!(serialize_page(&page).is_none());
+        }
+
+        #[test]
+        fn serialize_page_404() {
+            let page = make_test_page("https://example.com/missing", 404, "Not Found");
+            let buf = serialize_page(&page).unwrap();
+            let content = String::from_utf8_lossy(&buf);
+            assert!(content.contains("HTTP/1.1 404 Not Found\r\n"));
+        }
+
+        #[test]
         fn serialize_page_with_headers() {
             let mut page = make_test_page("https://example.com", 200, "<html>Hi</html>");
             let mut hdr_map = reqwest::header::HeaderMap::new();
@@ -560,7 +2069,7 @@ mod inner {
 
         #[test]
         fn serialize_warcinfo_is_valid() {
-            let buf = serialize_warcinfo("spider-test/0.1");
+            let buf = serialize_warcinfo("spider-test/0.1", None);
             let content = String::from_utf8_lossy(&buf);
             assert!(content.starts_with("WARC/1.1\r\n"));
             assert!(content.contains("WARC-Type: warcinfo\r\n"));
@@ -579,6 +2088,7 @@ mod inner {
                 path: path.to_string_lossy().to_string(),
                 write_warcinfo: true,
                 software: "spider-test/0.1".to_string(),
+                ..Default::default()
             };
 
             let (writer, handle) = WarcWriter::create(&config).unwrap();
@@ -615,6 +2125,7 @@ mod inner {
                 path: path.to_string_lossy().to_string(),
                 write_warcinfo: false,
                 software: "test".to_string(),
+                ..Default::default()
             };
 
             let (writer, handle) = WarcWriter::create(&config).unwrap();
@@ -643,6 +2154,7 @@ mod inner {
                 path: path.to_string_lossy().to_string(),
                 write_warcinfo: false,
                 software: "test".to_string(),
+                ..Default::default()
             };
 
             let (writer, handle) = WarcWriter::create(&config).unwrap();
@@ -688,6 +2200,7 @@ mod inner {
                 path: path.to_string_lossy().to_string(),
                 write_warcinfo: false,
                 software: "test".to_string(),
+                ..Default::default()
             };
 
             let (writer, file_handle) = WarcWriter::create(&config).unwrap();
@@ -731,6 +2244,7 @@ mod inner {
                 path: path.to_string_lossy().to_string(),
                 write_warcinfo: false,
                 software: "test".to_string(),
+                ..Default::default()
             };
 
             let (writer, file_handle) = WarcWriter::create(&config).unwrap();
@@ -757,19 +2271,55 @@ mod inner {
         }
 
         #[tokio::test]
-        async fn empty_crawl_produces_only_warcinfo() {
-            let dir = std::env::temp_dir().join("spider_warc_test_empty_crawl");
+        async fn write_page_skips_noarchive_pages() {
+            let dir = std::env::temp_dir().join("spider_warc_test_noarchive");
             let _ = std::fs::create_dir_all(&dir);
-            let path = dir.join("test_empty_crawl.warc");
+            let path = dir.join("test_noarchive.warc");
 
             let config = WarcConfig {
                 path: path.to_string_lossy().to_string(),
-                write_warcinfo: true,
-                software: "test".to_string(),
+                write_warcinfo: false,
+                write_cdx: false,
+                skip_noarchive: true,
+                ..Default::default()
             };
 
-            let (writer, handle) = WarcWriter::create(&config).unwrap();
-            assert_eq!(writer.record_count(), 1);
+            let (writer, file_handle) = WarcWriter::create(&config).unwrap();
+
+            writer.write_page(&make_test_page("https://example.com/keep", 200, "Keep"));
+            let mut private = make_test_page("https://example.com/private", 200, "Private");
+            private.robots.noarchive = true;
+            writer.write_page(&private);
+
+            assert_eq!(writer.record_count(), 1);
+            drop(writer);
+            file_handle.await.unwrap().unwrap();
+
+            let records: Vec<_> = WarcReader::open(&path)
+                .unwrap()
+                .collect::<io::Result<_>>()
+                .unwrap();
+            assert_eq!(records.len(), 1);
+            assert_eq!(records[0].target_uri(), Some("https://example.com/keep"));
+
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+
+        #[tokio::test]
+        async fn empty_crawl_produces_only_warcinfo() {
+            let dir = std::env::temp_dir().join("spider_warc_test_empty_crawl");
+            let _ = std::fs::create_dir_all(&dir);
+            let path = dir.join("test_empty_crawl.warc");
+
+            let config = WarcConfig {
+                path: path.to_string_lossy().to_string(),
+                write_warcinfo: true,
+                software: "test".to_string(),
+                ..Default::default()
+            };
+
+            let (writer, handle) = WarcWriter::create(&config).unwrap();
+            assert_eq!(writer.record_count(), 1);
 
             drop(writer);
             handle.await.unwrap().unwrap();
@@ -847,6 +2397,7 @@ mod inner {
                 path: path.to_string_lossy().to_string(),
                 write_warcinfo: false,
                 software: "test".to_string(),
+                ..Default::default()
             };
 
             let (writer, handle) = WarcWriter::create(&config).unwrap();
@@ -866,8 +2417,762 @@ mod inner {
 
             let _ = std::fs::remove_dir_all(&dir);
         }
+
+        #[test]
+        fn payload_digest_is_sha1_base32() {
+            assert_eq!(payload_digest(b""), "sha1:3I42H3S6NNFQ2MSVX7XZKYAYSCX5QBYJ");
+            let page = make_test_page("https://example.com/", 200, "<html></html>");
+            let record = String::from_utf8(serialize_page(&page).unwrap()).unwrap();
+            assert!(record.contains(&format!(
+                "WARC-Payload-Digest: {}\r\n",
+                payload_digest(b"<html></html>")
+            )));
+        }
+
+        #[test]
+        fn surt_key_normalizes_urls() {
+            assert_eq!(
+                surt_key("https://www.Example.com/A/b?z=1&a=2#frag"),
+                "com,example)/a/b?a=2&z=1"
+            );
+            assert_eq!(surt_key("http://example.com"), "com,example)/");
+            assert_eq!(
+                surt_key("http://sub.example.com:8080/x"),
+                "com,example,sub:8080)/x"
+            );
+            assert_eq!(surt_key("http://127.0.0.1/x"), "127.0.0.1)/x");
+        }
+
+        #[test]
+        fn cdxj_record_round_trips() {
+            let record = CdxjRecord {
+                urlkey: "com,example)/".into(),
+                timestamp: "20240101000000".into(),
+                url: "https://example.com/?q=\"x\"".into(),
+                mime: "text/html".into(),
+                status: 200,
+                digest: "sha1:AAAA".into(),
+                length: 512,
+                offset: 1024,
+                filename: "output.warc".into(),
+            };
+            let line = record.to_line();
+            assert!(line.starts_with("com,example)/ 20240101000000 {\"url\": "));
+            assert_eq!(CdxjRecord::parse(&line), Some(record));
+            assert!(CdxjRecord::parse("com,example)/ 20240101000000 not-json").is_none());
+        }
+
+        #[test]
+        fn sort_index_merges_runs() {
+            let dir = std::env::temp_dir().join("spider_warc_test_sort_index");
+            let _ = std::fs::create_dir_all(&dir);
+            let path = dir.join("runs.cdxj");
+            let line = |i: usize| format!("com,example)/{:07} 20240101000000 {{}}", i);
+            let count = 1_000;
+
+            write_lines(&path, (0..count).rev().map(|i| Ok(line(i)))).unwrap();
+            // Roughly eight lines a run.
+            sort_index_chunked(&path, line(0).len() * 8).unwrap();
+
+            let sorted = std::fs::read_to_string(&path).unwrap();
+            assert!(sorted.lines().map(String::from).eq((0..count).map(line)));
+            assert!(!index_run_path(&path, 0).exists());
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+
+        #[test]
+        fn cdx_path_defaults_next_to_archive() {
+            let mut config = WarcConfig {
+                path: "out/crawl.warc.gz".into(),
+                ..Default::default()
+            };
+            assert_eq!(config.cdx_path(), Some(PathBuf::from("out/crawl.cdxj")));
+            config.cdx_path = Some("index.cdxj".into());
+            assert_eq!(config.cdx_path(), Some(PathBuf::from("index.cdxj")));
+            config.write_cdx = false;
+            assert_eq!(config.cdx_path(), None);
+        }
+
+        #[tokio::test]
+        async fn writer_creates_sorted_cdxj_index() {
+            let dir = std::env::temp_dir().join("spider_warc_test_cdxj");
+            let _ = std::fs::create_dir_all(&dir);
+            let path = dir.join("indexed.warc");
+
+            let config = WarcConfig {
+                path: path.to_string_lossy().to_string(),
+                write_cdx: true,
+                ..Default::default()
+            };
+
+            let (writer, handle) = WarcWriter::create(&config).unwrap();
+            for name in ["zeta", "alpha", "mid"] {
+                let page = make_test_page(
+                    &format!("https://example.com/{name}"),
+                    200,
+                    &format!("<html>{name}</html>"),
+                );
+                writer.write_page(&page);
+            }
+            drop(writer);
+            handle.await.unwrap().unwrap();
+
+            let index = CdxjRecord::read_index(dir.join("indexed.cdxj")).unwrap();
+            // The warcinfo record is not indexed.
+            assert_eq!(index.len(), 3);
+            assert_eq!(index[0].urlkey, "com,example)/alpha");
+            assert_eq!(index[2].urlkey, "com,example)/zeta");
+
+            let bytes = std::fs::read(&path).unwrap();
+            for entry in index {
+                assert_eq!(entry.filename, "indexed.warc");
+                assert_eq!(entry.mime, "text/html");
+                assert_eq!(entry.status, 200);
+
+                // Offsets give random access into the archive.
+                let record = WarcReader::open_at(&path, entry.offset)
+                    .unwrap()
+                    .next_record()
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(record.target_uri(), Some(entry.url.as_str()));
+                assert_eq!(
+                    record.header("WARC-Payload-Digest"),
+                    Some(entry.digest.as_str())
+                );
+
+                let raw = &bytes[entry.offset as usize..(entry.offset + entry.length) as usize];
+                assert!(raw.starts_with(b"WARC/1.1\r\n"));
+                assert!(raw.ends_with(b"\r\n\r\n"));
+            }
+
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+
+        #[test]
+        fn file_path_rotation_naming() {
+            let mut config = WarcConfig {
+                path: "out/crawl.warc.gz".into(),
+                ..Default::default()
+            };
+            assert!(config.is_gzip());
+            assert_eq!(config.file_path(3), PathBuf::from("out/crawl.warc.gz"));
+
+            config.max_records = Some(100);
+            assert_eq!(
+                config.file_path(3),
+                PathBuf::from("out/crawl-00003.warc.gz")
+            );
+
+            config.filename_template = Some("out/{serial}-{timestamp}.warc".into());
+            let templated = config.file_path(12).to_string_lossy().into_owned();
+            assert!(templated.starts_with("out/00012-"));
+            assert_eq!(templated.len(), "out/00012-".len() + 14 + ".warc".len());
+
+            config.filename_template = Some("out/{timestamp}.warc".into());
+            let templated = config.file_path(12).to_string_lossy().into_owned();
+            assert!(templated.ends_with("-00012.warc"));
+            assert_ne!(config.file_path(1), config.file_path(2));
+        }
+
+        #[tokio::test]
+        async fn writer_gzips_each_record() {
+            let dir = std::env::temp_dir().join("spider_warc_test_gzip");
+            let _ = std::fs::create_dir_all(&dir);
+            let path = dir.join("compressed.warc.gz");
+
+            let config = WarcConfig {
+                path: path.to_string_lossy().to_string(),
+                write_cdx: true,
+                ..Default::default()
+            };
+
+            let (writer, handle) = WarcWriter::create(&config).unwrap();
+            for i in 0..3 {
+                let page = make_test_page(
+                    &format!("https://example.com/{i}"),
+                    200,
+                    &"<p>compressible</p>".repeat(100),
+                );
+                writer.write_page(&page);
+            }
+            drop(writer);
+            handle.await.unwrap().unwrap();
+
+            let bytes = std::fs::read(&path).unwrap();
+            assert!(bytes.starts_with(&[0x1f, 0x8b]));
+
+            let records: Vec<WarcRecord> = WarcReader::open(&path)
+                .unwrap()
+                .collect::<io::Result<_>>()
+                .unwrap();
+            assert_eq!(records.len(), 4);
+            assert_eq!(
+                records[0].header("WARC-Filename"),
+                Some("compressed.warc.gz")
+            );
+
+            // Every indexed record is an independent gzip member.
+            let index = CdxjRecord::read_index(dir.join("compressed.cdxj")).unwrap();
+            assert_eq!(index.len(), 3);
+            for entry in index {
+                let member = &bytes[entry.offset as usize..(entry.offset + entry.length) as usize];
+                assert!(member.starts_with(&[0x1f, 0x8b]));
+                let record = WarcReader::new(std::io::Cursor::new(member.to_vec()))
+                    .unwrap()
+                    .next_record()
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(record.target_uri(), Some(entry.url.as_str()));
+            }
+
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+
+        #[tokio::test]
+        async fn writer_rotates_by_record_count() {
+            let dir = std::env::temp_dir().join("spider_warc_test_rotate_records");
+            let _ = std::fs::remove_dir_all(&dir);
+            let path = dir.join("rotated.warc");
+
+            let config = WarcConfig {
+                path: path.to_string_lossy().to_string(),
+                max_records: Some(2),
+                write_cdx: true,
+                ..Default::default()
+            };
+
+            let (writer, handle) = WarcWriter::create(&config).unwrap();
+            for i in 0..5 {
+                writer.write_page(&make_test_page(
+                    &format!("https://example.com/{i}"),
+                    200,
+                    "<html></html>",
+                ));
+            }
+            let record_count = writer.record_count.clone();
+            let file_count = writer.file_count.clone();
+            drop(writer);
+            handle.await.unwrap().unwrap();
+
+            assert_eq!(file_count.load(Ordering::Relaxed), 3);
+            // 5 responses + one warcinfo per file.
+            assert_eq!(record_count.load(Ordering::Relaxed), 8);
+
+            for (serial, expected) in [(0, 2), (1, 2), (2, 1)] {
+                let records: Vec<WarcRecord> = WarcReader::open(config.file_path(serial))
+                    .unwrap()
+                    .collect::<io::Result<_>>()
+                    .unwrap();
+                assert_eq!(records[0].record_type(), WarcRecordType::Warcinfo);
+                assert_eq!(
+                    records[0].header("WARC-Filename"),
+                    Some(format!("rotated-{serial:05}.warc").as_str())
+                );
+                assert_eq!(records.len(), expected + 1);
+            }
+
+            let index = CdxjRecord::read_index(dir.join("rotated.cdxj")).unwrap();
+            let files: hashbrown::HashSet<_> = index.iter().map(|e| e.filename.clone()).collect();
+            assert_eq!(index.len(), 5);
+            assert_eq!(files.len(), 3);
+
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+
+        #[tokio::test]
+        async fn writer_rotates_by_size_with_template() {
+            let dir = std::env::temp_dir().join("spider_warc_test_rotate_size");
+            let _ = std::fs::remove_dir_all(&dir);
+
+            let config = WarcConfig {
+                path: dir.join("unused.warc").to_string_lossy().to_string(),
+                filename_template: Some(
+                    dir.join("part-{serial}.warc").to_string_lossy().to_string(),
+                ),
+                max_file_size: Some(1),
+                write_warcinfo: false,
+                ..Default::default()
+            };
+
+            let (writer, handle) = WarcWriter::create(&config).unwrap();
+            for i in 0..3 {
+                writer.write_page(&make_test_page(
+                    &format!("https://example.com/{i}"),
+                    200,
+                    &format!("<html>{i}</html>"),
+                ));
+            }
+            let file_count = writer.file_count.clone();
+            drop(writer);
+            handle.await.unwrap().unwrap();
+
+            // Records are never split; each lands in its own file.
+            assert_eq!(file_count.load(Ordering::Relaxed), 3);
+            assert!(!dir.join("unused.warc").exists());
+            for serial in 0..3 {
+                let records: Vec<WarcRecord> =
+                    WarcReader::open(dir.join(format!("part-{serial:05}.warc")))
+                        .unwrap()
+                        .collect::<io::Result<_>>()
+                        .unwrap();
+                assert_eq!(records.len(), 1);
+                assert_eq!(records[0].record_type(), WarcRecordType::Response);
+            }
+
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+
+        #[tokio::test]
+        async fn writer_emits_revisits_for_repeated_payloads() {
+            let dir = std::env::temp_dir().join("spider_warc_test_revisit");
+            let _ = std::fs::remove_dir_all(&dir);
+            let path = dir.join("dedup.warc");
+
+            let config = WarcConfig {
+                path: path.to_string_lossy().to_string(),
+                write_cdx: true,
+                revisits: true,
+                ..Default::default()
+            };
+
+            let (writer, handle) = WarcWriter::create(&config).unwrap();
+            writer.write_page(&make_test_page(
+                "https://example.com/a",
+                200,
+                "<html>same</html>",
+            ));
+            writer.write_page(&make_test_page(
+                "https://example.com/b",
+                200,
+                "<html>same</html>",
+            ));
+            writer.write_page(&make_test_page(
+                "https://example.com/c",
+                200,
+                "<html>new</html>",
+            ));
+            drop(writer);
+            handle.await.unwrap().unwrap();
+
+            let records: Vec<WarcRecord> = WarcReader::open(&path)
+                .unwrap()
+                .collect::<io::Result<_>>()
+                .unwrap();
+            assert_eq!(records.len(), 4);
+            assert_eq!(records[1].record_type(), WarcRecordType::Response);
+            assert_eq!(records[2].record_type(), WarcRecordType::Revisit);
+            assert_eq!(records[3].record_type(), WarcRecordType::Response);
+
+            let revisit = &records[2];
+            assert_eq!(revisit.refers_to(), records[1].record_id());
+            assert_eq!(
+                revisit.refers_to_target_uri(),
+                Some("https://example.com/a")
+            );
+            assert_eq!(revisit.header("WARC-Refers-To-Date"), records[1].date());
+            assert_eq!(revisit.header("WARC-Profile"), Some(REVISIT_PROFILE));
+            assert_eq!(
+                revisit.header("WARC-Payload-Digest"),
+                records[1].header("WARC-Payload-Digest")
+            );
+            // The revisit stores the HTTP head only.
+            let head = revisit.http_response().unwrap();
+            assert_eq!(head.status, StatusCode::OK);
+            assert!(head.body.is_empty());
+
+            let index = CdxjRecord::read_index(dir.join("dedup.cdxj")).unwrap();
+            assert_eq!(index[1].url, "https://example.com/b");
+            assert_eq!(index[1].mime, "warc/revisit");
+
+            // Replay resolves the revisit to the original payload, from a
+            // scan of the archive or from its index.
+            for fetcher in [
+                WarcReplayFetcher::open(&path).unwrap(),
+                WarcReplayFetcher::from_cdxj(dir.join("dedup.cdxj")).unwrap(),
+            ] {
+                assert_eq!(fetcher.len(), 3);
+                assert_eq!(
+                    fetcher.replay("https://example.com/b").content.as_deref(),
+                    Some(&b"<html>same</html>"[..])
+                );
+                assert_eq!(
+                    fetcher.replay("https://example.com/c").content.as_deref(),
+                    Some(&b"<html>new</html>"[..])
+                );
+            }
+
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+
+        #[test]
+        fn revisits_require_matching_digest() {
+            let revisits = WarcRevisits::default();
+
+            let mut page = make_test_page("https://example.com/a", 200, "<html>a</html>");
+            page.signature = Some(7);
+            let first = serialize_response(&page, Some(&revisits)).unwrap();
+            assert_eq!(first.cdx.unwrap().mime, "text/html");
+
+            // Same signature (normalized content) but different bytes.
+            let mut page = make_test_page("https://example.com/b", 200, "<html> a </html>");
+            page.signature = Some(7);
+            let second = serialize_response(&page, Some(&revisits)).unwrap();
+            assert!(String::from_utf8_lossy(&second.bytes).contains("WARC-Type: response\r\n"));
+
+            let mut page = make_test_page("https://example.com/c", 200, "<html>a</html>");
+            page.signature = Some(7);
+            let third = serialize_response(&page, Some(&revisits)).unwrap();
+            assert!(String::from_utf8_lossy(&third.bytes).contains("WARC-Type: revisit\r\n"));
+            assert_eq!(revisits.len(), 1);
+        }
+
+        #[tokio::test]
+        async fn revisits_span_recurring_crawls() {
+            let dir = std::env::temp_dir().join("spider_warc_test_revisit_runs");
+            let _ = std::fs::remove_dir_all(&dir);
+            let revisits = WarcRevisits::default();
+            let page = make_test_page("https://example.com/", 200, "<html>stable</html>");
+
+            for (run, disabled) in [("first", false), ("second", false), ("third", true)] {
+                let config = WarcConfig {
+                    path: dir
+                        .join(format!("{run}.warc"))
+                        .to_string_lossy()
+                        .to_string(),
+                    revisits: !disabled,
+                    ..Default::default()
+                };
+                let (writer, handle) =
+                    WarcWriter::create_with_revisits(&config, revisits.clone()).unwrap();
+                assert_eq!(writer.revisits().is_some(), !disabled);
+                writer.write_page(&page);
+                drop(writer);
+                handle.await.unwrap().unwrap();
+            }
+
+            let types: Vec<WarcRecordType> = ["first", "second", "third"]
+                .iter()
+                .map(|run| {
+                    WarcReader::open(dir.join(format!("{run}.warc")))
+                        .unwrap()
+                        .nth(1)
+                        .unwrap()
+                        .unwrap()
+                        .record_type()
+                })
+                .collect();
+            assert_eq!(
+                types,
+                [
+                    WarcRecordType::Response,
+                    WarcRecordType::Revisit,
+                    WarcRecordType::Response
+                ]
+            );
+
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+
+        /// Build a raw record the way third-party archivers lay them out.
+        fn raw_record(headers: &[(&str, &str)], block: &[u8]) -> Vec<u8> {
+            let mut buf = Vec::new();
+            buf.extend_from_slice(b"WARC/1.0\r\n");
+            for (name, value) in headers {
+                let _ = write!(buf, "{name}: {value}\r\n");
+            }
+            let _ = write!(buf, "Content-Length: {}\r\n\r\n", block.len());
+            buf.extend_from_slice(block);
+            buf.extend_from_slice(b"\r\n\r\n");
+            buf
+        }
+
+        fn gzip(bytes: &[u8]) -> Vec<u8> {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(bytes).unwrap();
+            encoder.finish().unwrap()
+        }
+
+        #[test]
+        fn reader_round_trips_written_records() {
+            let mut archive = serialize_warcinfo("spider-test/0.1", None);
+            let mut page = make_test_page("https://example.com/a", 200, "<html>A</html>");
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert("content-type", "text/html".parse().unwrap());
+            page.headers = Some(headers);
+            archive.extend(serialize_page(&page).unwrap());
+
+            let records: Vec<WarcRecord> = WarcReader::new(std::io::Cursor::new(archive))
+                .unwrap()
+                .collect::<io::Result<_>>()
+                .unwrap();
+
+            assert_eq!(records.len(), 2);
+            assert_eq!(records[0].record_type(), WarcRecordType::Warcinfo);
+            assert_eq!(records[1].record_type(), WarcRecordType::Response);
+            assert_eq!(records[1].target_uri(), Some("https://example.com/a"));
+            assert!(records[1].record_id().unwrap().starts_with("<urn:uuid:"));
+
+            let response = records[1].http_response().unwrap();
+            assert_eq!(response.status, StatusCode::OK);
+            assert_eq!(response.headers["content-type"], "text/html");
+            assert_eq!(response.body, b"<html>A</html>");
+        }
+
+        #[test]
+        fn reader_handles_gzip_per_record() {
+            let mut archive = gzip(&serialize_warcinfo("spider-test/0.1", None));
+            for i in 0..3 {
+                let page = make_test_page(
+                    &format!("https://example.com/{i}"),
+                    200,
+                    &format!("<html>{i}</html>"),
+                );
+                archive.extend(gzip(&serialize_page(&page).unwrap()));
+            }
+
+            let records: Vec<WarcRecord> = WarcReader::new(std::io::Cursor::new(archive))
+                .unwrap()
+                .collect::<io::Result<_>>()
+                .unwrap();
+
+            assert_eq!(records.len(), 4);
+            assert_eq!(records[3].target_uri(), Some("https://example.com/2"));
+            assert_eq!(records[3].http_response().unwrap().body, b"<html>2</html>");
+        }
+
+        #[test]
+        fn reader_parses_request_records_and_folded_headers() {
+            let archive = raw_record(
+                &[
+                    ("WARC-Type", "request"),
+                    ("WARC-Target-URI", "<https://example.com/>"),
+                    ("WARC-Record-ID", "<urn:uuid:1>"),
+                    ("WARC-Date", "2024-01-01T00:00:00Z"),
+                    ("Content-Type", "application/http;\r\n  msgtype=request"),
+                ],
+                b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n",
+            );
+
+            let record = WarcReader::new(std::io::Cursor::new(archive))
+                .unwrap()
+                .next_record()
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(record.version, "WARC/1.0");
+            assert_eq!(record.record_type(), WarcRecordType::Request);
+            assert_eq!(record.target_uri(), Some("https://example.com/"));
+            assert_eq!(
+                record.header("content-type"),
+                Some("application/http; msgtype=request")
+            );
+            let request = record.http_request().unwrap();
+            assert_eq!(request.method, "GET");
+            assert_eq!(request.uri, "/");
+            assert_eq!(request.headers["host"], "example.com");
+        }
+
+        #[test]
+        fn reader_rejects_truncated_block() {
+            let mut archive = raw_record(&[("WARC-Type", "resource")], b"0123456789");
+            archive.truncate(archive.len() - 10);
+            let mut reader = WarcReader::new(std::io::Cursor::new(archive)).unwrap();
+            assert!(reader.next().unwrap().is_err());
+            assert!(reader.next().is_none());
+        }
+
+        #[test]
+        fn http_response_decodes_chunked_and_gzip_bodies() {
+            let body = gzip(b"<html>hi</html>");
+            let mut block = Vec::new();
+            block.extend_from_slice(
+                b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nContent-Encoding: gzip\r\n\r\n",
+            );
+            let _ = write!(block, "{:x}\r\n", body.len());
+            block.extend_from_slice(&body);
+            block.extend_from_slice(b"\r\n0\r\n\r\n");
+
+            let record = WarcRecord {
+                version: "WARC/1.1".into(),
+                headers: vec![("WARC-Type".into(), "response".into())],
+                block,
+            };
+
+            let response = record.http_response().unwrap();
+            assert_eq!(response.body, b"<html>hi</html>");
+            assert!(!response.headers.contains_key("transfer-encoding"));
+            assert!(!response.headers.contains_key("content-encoding"));
+        }
+
+        fn replay_records() -> Vec<Vec<u8>> {
+            let mut archive = Vec::new();
+            archive.push(raw_record(
+                &[
+                    ("WARC-Type", "response"),
+                    ("WARC-Target-URI", "https://example.com/"),
+                    ("WARC-Record-ID", "<urn:uuid:root>"),
+                ],
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n<html><a href=\"/a\">a</a><a href=\"/old\">old</a></html>",
+            ));
+            archive.push(raw_record(
+                &[
+                    ("WARC-Type", "response"),
+                    ("WARC-Target-URI", "https://example.com/a"),
+                    ("WARC-Record-ID", "<urn:uuid:a>"),
+                ],
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n<html><a href=\"/missing\">m</a></html>",
+            ));
+            archive.push(raw_record(
+                &[
+                    ("WARC-Type", "response"),
+                    ("WARC-Target-URI", "https://example.com/old"),
+                ],
+                b"HTTP/1.1 301 Moved Permanently\r\nLocation: /b\r\n\r\n",
+            ));
+            archive.push(raw_record(
+                &[
+                    ("WARC-Type", "revisit"),
+                    ("WARC-Target-URI", "https://example.com/b"),
+                    ("WARC-Refers-To", "<urn:uuid:a>"),
+                    ("WARC-Refers-To-Target-URI", "https://example.com/a"),
+                ],
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\n\r\n",
+            ));
+            archive
+        }
+
+        fn replay_archive() -> Vec<u8> {
+            replay_records().concat()
+        }
+
+        #[test]
+        fn replay_fetcher_serves_archived_pages() {
+            let dir = std::env::temp_dir().join("spider_warc_test_replay_serve");
+            let _ = std::fs::create_dir_all(&dir);
+            let plain = dir.join("replay.warc");
+            std::fs::write(&plain, replay_archive()).unwrap();
+            let gzipped = dir.join("replay.warc.gz");
+            std::fs::write(
+                &gzipped,
+                replay_records()
+                    .iter()
+                    .flat_map(|record| gzip(record))
+                    .collect::<Vec<u8>>(),
+            )
+            .unwrap();
+
+            for path in [&plain, &gzipped] {
+                assert_replays_archive(&WarcReplayFetcher::open(path).unwrap());
+            }
+
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+
+        fn assert_replays_archive(fetcher: &WarcReplayFetcher) {
+            assert_eq!(fetcher.len(), 4);
+            assert!(fetcher.contains("https://example.com"));
+
+            let root = fetcher.replay("https://example.com/");
+            assert_eq!(root.status_code, StatusCode::OK);
+            assert!(root.headers.unwrap().contains_key("content-type"));
+
+            // Revisit resolves its payload from the referenced record.
+            let revisit = fetcher.replay("https://example.com/b");
+            assert_eq!(
+                revisit.content,
+                fetcher.replay("https://example.com/a").content
+            );
+
+            // Captured redirects are followed inside the archive.
+            let redirected = fetcher.replay("https://example.com/old");
+            assert_eq!(redirected.status_code, StatusCode::OK);
+            assert_eq!(
+                redirected.final_url.as_deref(),
+                Some("https://example.com/b")
+            );
+
+            let missing = fetcher.replay("https://example.com/missing");
+            assert_eq!(missing.status_code, StatusCode::NOT_FOUND);
+            assert!(missing.content.is_none());
+        }
+
+        #[test]
+        fn replay_fetcher_resolves_revisits_by_record_id() {
+            let capture = |id: &str, body: &str| {
+                raw_record(
+                    &[
+                        ("WARC-Type", "response"),
+                        ("WARC-Target-URI", "https://example.com/a"),
+                        ("WARC-Record-ID", id),
+                    ],
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .as_bytes(),
+                )
+            };
+            let mut archive = capture("<urn:uuid:first>", "first");
+            archive.extend(capture("<urn:uuid:second>", "second"));
+            archive.extend(raw_record(
+                &[
+                    ("WARC-Type", "revisit"),
+                    ("WARC-Target-URI", "https://example.com/b"),
+                    ("WARC-Refers-To", "<urn:uuid:first>"),
+                    ("WARC-Refers-To-Target-URI", "https://example.com/a"),
+                ],
+                b"HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: 3\r\n\r\n",
+            ));
+
+            let dir = std::env::temp_dir().join("spider_warc_test_replay_ids");
+            let _ = std::fs::create_dir_all(&dir);
+            let path = dir.join("ids.warc");
+            std::fs::write(&path, archive).unwrap();
+
+            let fetcher = WarcReplayFetcher::open(&path).unwrap();
+            let latest = fetcher.replay("https://example.com/a");
+            assert_eq!(latest.content.as_deref(), Some(&b"second"[..]));
+
+            let revisit = fetcher.replay("https://example.com/b");
+            assert_eq!(revisit.content.as_deref(), Some(&b"first"[..]));
+            let headers = revisit.headers.unwrap();
+            assert!(!headers.contains_key("content-encoding"));
+            assert_eq!(headers["content-length"], "5");
+
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+
+        #[tokio::test]
+        async fn replay_fetcher_drives_offline_crawl() {
+            let dir = std::env::temp_dir().join("spider_warc_test_replay");
+            let _ = std::fs::create_dir_all(&dir);
+            let path = dir.join("replay.warc.gz");
+            std::fs::write(&path, gzip(&replay_archive())).unwrap();
+
+            let fetcher = WarcReplayFetcher::open(&path).unwrap();
+            let mut website = crate::website::Website::new("https://example.com");
+            website.with_remote_fetcher(fetcher);
+            website.crawl().await;
+
+            let links = website.get_all_links_visited().await;
+            assert!(links.contains(&crate::CaseInsensitiveString::from("https://example.com/a")));
+            assert!(links.contains(&crate::CaseInsensitiveString::from(
+                "https://example.com/old"
+            )));
+            assert!(links.contains(&crate::CaseInsensitiveString::from(
+                "https://example.com/missing"
+            )));
+
+            let _ = std::fs::remove_dir_all(&dir);
+        }
     }
 }
 
 #[cfg(feature = "warc")]
-pub use inner::{serialize_page, spawn_warc_writer, WarcConfig, WarcWriter};
+pub use inner::{
+    serialize_page, spawn_warc_writer, surt_key, CdxjRecord, WarcConfig, WarcHttpRequest,
+    WarcHttpResponse, WarcReader, WarcRecord, WarcRecordType, WarcReplayFetcher, WarcRevisitTarget,
+    WarcRevisits, WarcWriter,
+};
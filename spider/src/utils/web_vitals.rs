@@ -0,0 +1,191 @@
+//! Core Web Vitals and performance metrics for Chrome pages.
+//!
+//! A `PerformanceObserver` script is added before navigation to record LCP,
+//! CLS, INP and the long tasks behind TBT. After the page renders the values
+//! are read back together with FCP, TTFB and the CDP `Performance.getMetrics`
+//! counters into [`WebVitals`] on [`crate::page::Page::web_vitals`].
+//!
+//! Values are lab measurements of a single load. INP is only set when the
+//! page was interacted with, e.g. through automation scripts.
+
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// The observers added to every document. The state is kept on a
+/// non-enumerable symbol so it does not show up on `window`.
+const OBSERVER_SCRIPT: &str = r#"(() => {
+  const key = Symbol.for('spider.vitals');
+  if (window[key]) return;
+  const v = { lcp: null, cls: null, inp: null, tasks: [] };
+  let session = 0, entries = [];
+  const observe = (type, cb, opts) => {
+    try {
+      new PerformanceObserver((l) => l.getEntries().forEach(cb)).observe(Object.assign({ type, buffered: true }, opts));
+    } catch (e) {}
+  };
+  observe('largest-contentful-paint', (e) => { v.lcp = e.renderTime || e.loadTime || e.startTime; });
+  observe('layout-shift', (e) => {
+    if (e.hadRecentInput) return;
+    const first = entries[0], last = entries[entries.length - 1];
+    if (last && e.startTime - last.startTime < 1000 && e.startTime - first.startTime < 5000) {
+      session += e.value;
+      entries.push(e);
+    } else {
+      session = e.value;
+      entries = [e];
+    }
+    v.cls = Math.max(v.cls || 0, session);
+  });
+  observe('longtask', (e) => { v.tasks.push([e.startTime, e.duration]); });
+  observe('event', (e) => { if (e.interactionId) v.inp = Math.max(v.inp || 0, e.duration); }, { durationThreshold: 16 });
+  observe('first-input', (e) => { v.inp = Math.max(v.inp || 0, e.duration); });
+  Object.defineProperty(window, key, { value: v });
+})();"#;
+
+/// Read the observed values as JSON.
+const READ_SCRIPT: &str = r#"(() => {
+  const v = window[Symbol.for('spider.vitals')];
+  const nav = performance.getEntriesByType('navigation')[0];
+  const paint = performance.getEntriesByName('first-contentful-paint')[0];
+  const fcp = paint ? paint.startTime : null;
+  let tbt = null;
+  if (v) {
+    tbt = 0;
+    for (const [start, duration] of v.tasks) {
+      if (fcp === null || start >= fcp) tbt += Math.max(0, duration - 50);
+    }
+  }
+  return JSON.stringify({
+    lcp: v ? v.lcp : null,
+    cls: v ? (v.cls === null ? 0 : v.cls) : null,
+    inp: v ? v.inp : null,
+    tbt,
+    fcp,
+    ttfb: nav ? Math.max(0, nav.responseStart - (nav.activationStart || 0)) : null,
+  });
+})()"#;
+
+/// The Core Web Vitals and performance metrics of a page. Times are in
+/// milliseconds from the start of the navigation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WebVitals {
+    /// Largest Contentful Paint.
+    pub lcp: Option<f64>,
+    /// Cumulative Layout Shift, the largest session window score.
+    pub cls: Option<f64>,
+    /// Interaction to Next Paint, set when the page was interacted with.
+    pub inp: Option<f64>,
+    /// Total Blocking Time of the long tasks after the first contentful paint.
+    pub tbt: Option<f64>,
+    /// First Contentful Paint.
+    pub fcp: Option<f64>,
+    /// Time To First Byte.
+    pub ttfb: Option<f64>,
+    /// The CDP `Performance.getMetrics` counters such as `JSHeapUsedSize` or `LayoutCount`.
+    #[serde(default)]
+    pub metrics: HashMap<String, f64>,
+}
+
+/// How a vital compares to the thresholds published on web.dev.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VitalRating {
+    /// Good.
+    Good,
+    /// Needs improvement.
+    NeedsImprovement,
+    /// Poor.
+    Poor,
+}
+
+impl VitalRating {
+    fn rate(value: Option<f64>, good: f64, poor: f64) -> Option<Self> {
+        value.map(|v| {
+            if v <= good {
+                Self::Good
+            } else if v <= poor {
+                Self::NeedsImprovement
+            } else {
+                Self::Poor
+            }
+        })
+    }
+}
+
+impl WebVitals {
+    /// Rate the Largest Contentful Paint.
+    pub fn lcp_rating(&self) -> Option<VitalRating> {
+        VitalRating::rate(self.lcp, 2500.0, 4000.0)
+    }
+
+    /// Rate the Cumulative Layout Shift.
+    pub fn cls_rating(&self) -> Option<VitalRating> {
+        VitalRating::rate(self.cls, 0.1, 0.25)
+    }
+
+    /// Rate the Interaction to Next Paint.
+    pub fn inp_rating(&self) -> Option<VitalRating> {
+        VitalRating::rate(self.inp, 200.0, 500.0)
+    }
+
+    /// Rate the Total Blocking Time with the Lighthouse thresholds.
+    pub fn tbt_rating(&self) -> Option<VitalRating> {
+        VitalRating::rate(self.tbt, 200.0, 600.0)
+    }
+
+    /// Rate the First Contentful Paint.
+    pub fn fcp_rating(&self) -> Option<VitalRating> {
+        VitalRating::rate(self.fcp, 1800.0, 3000.0)
+    }
+
+    /// Rate the Time To First Byte.
+    pub fn ttfb_rating(&self) -> Option<VitalRating> {
+        VitalRating::rate(self.ttfb, 800.0, 1800.0)
+    }
+}
+
+/// Add the observers and enable the `Performance` domain before navigating.
+pub(crate) async fn install(page: &chromiumoxide::Page) {
+    let _ = tokio::join!(
+        page.evaluate_on_new_document(OBSERVER_SCRIPT),
+        page.enable_performance()
+    );
+}
+
+/// Read the vitals and metrics of the rendered page.
+pub(crate) async fn collect(page: &chromiumoxide::Page) -> Option<WebVitals> {
+    let (vitals, metrics) = tokio::join!(page.evaluate(READ_SCRIPT), page.metrics());
+
+    let mut vitals: WebVitals = vitals
+        .ok()
+        .and_then(|v| v.into_value::<String>().ok())
+        .and_then(|v| serde_json::from_str(&v).ok())?;
+
+    if let Ok(metrics) = metrics {
+        vitals.metrics = metrics.into_iter().map(|m| (m.name, m.value)).collect();
+    }
+
+    Some(vitals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_script_output_parses() {
+        let vitals: WebVitals = serde_json::from_str(
+            r#"{"lcp":1200.5,"cls":0.3,"inp":null,"tbt":250,"fcp":800,"ttfb":120}"#,
+        )
+        .unwrap();
+
+        assert_eq!(vitals.lcp, Some(1200.5));
+        assert_eq!(vitals.inp, None);
+        assert!(vitals.metrics.is_empty());
+        assert_eq!(vitals.lcp_rating(), Some(VitalRating::Good));
+        assert_eq!(vitals.cls_rating(), Some(VitalRating::Poor));
+        assert_eq!(vitals.tbt_rating(), Some(VitalRating::NeedsImprovement));
+        assert_eq!(vitals.inp_rating(), None);
+        assert_eq!(vitals.ttfb_rating(), Some(VitalRating::Good));
+    }
+}
@@ -25,21 +25,56 @@ use crate::utils::{
 
 /// Run a future with an optional hard wall-clock timeout.
 /// When `timeout` is `None`, the future runs without any timer overhead.
-/// When the timeout fires, the future is dropped (cancelling all in-flight work).
+/// When the timeout fires, the future is dropped (cancelling all in-flight work)
+/// and `false` is returned.
+#[cfg(not(feature = "tracing"))]
 async fn run_with_crawl_timeout(
     timeout: Option<core::time::Duration>,
     url: &str,
     fut: impl core::future::Future<Output = ()>,
-) {
+) -> bool {
     match timeout {
         Some(t) => {
             if tokio::time::timeout(t, fut).await.is_err() {
                 log::warn!("crawl hard timeout after {t:?} for {url}");
+                return false;
             }
+            true
+        }
+        None => {
+            fut.await;
+            true
         }
-        None => fut.await,
     }
 }
+
+/// Run a future with an optional hard wall-clock timeout inside a `crawl`
+/// span. The instrumented future is boxed: nested into the crawl entry
+/// points it is otherwise deep enough to overflow the compiler's query
+/// depth limit in callers that await a crawl inside their own futures.
+#[cfg(feature = "tracing")]
+fn run_with_crawl_timeout<'a>(
+    timeout: Option<core::time::Duration>,
+    url: &'a str,
+    fut: impl core::future::Future<Output = ()> + Send + 'a,
+) -> std::pin::Pin<Box<dyn core::future::Future<Output = bool> + Send + 'a>> {
+    Box::pin(async move {
+        let fut = tracing::Instrument::instrument(fut, crate::utils::trace::crawl_span(url));
+        match timeout {
+            Some(t) => {
+                if tokio::time::timeout(t, fut).await.is_err() {
+                    log::warn!("crawl hard timeout after {t:?} for {url}");
+                    return false;
+                }
+                true
+            }
+            None => {
+                fut.await;
+                true
+            }
+        }
+    })
+}
 use crate::{CaseInsensitiveString, Client, ClientBuilder, RelativeSelectors};
 #[cfg(feature = "cron")]
 use async_job::{async_trait, Job, Runner};
@@ -169,6 +204,8 @@ macro_rules! chrome_page_fetch {
                 };
                 let mut attempt: u32 = 0;
                 page.proxy_configured = $shared.6.proxies.is_some();
+                #[cfg(feature = "tracing")]
+                crate::utils::trace::record_proxy($shared.6.proxies.as_deref());
                 while page.needs_retry() && retry_count > 0 {
                     retry_count -= 1;
                     attempt += 1;
@@ -623,7 +660,7 @@ macro_rules! chrome_page_fetch_on {
 macro_rules! chrome_page_post_process {
     ($page:expr, $pre_links:expr, $pre_links_pages:expr, $extract_succeeded:expr,
      $shared:expr, $add_external:expr, $full_resources:expr,
-     $return_page_links:expr, $on_should_crawl_callback:expr, $permit:expr) => {{
+     $return_page_links:expr, $on_should_crawl_callback:expr, $page_dedup:expr, $permit:expr) => {{
         let mut page = $page;
         let pre_links = $pre_links;
         let pre_links_pages = $pre_links_pages;
@@ -632,6 +669,7 @@ macro_rules! chrome_page_post_process {
         if $add_external {
             page.set_external($shared.3.clone());
         }
+        page.set_nofollow_policy($shared.6.nofollow_policy());
         let prev_domain = page.base.take();
         page.set_url_parsed_direct();
         let page_base = page.base.take().map(Box::new);
@@ -662,6 +700,10 @@ macro_rules! chrome_page_post_process {
             page.signature
                 .replace(crate::utils::hash_html(page.get_html_bytes_u8()).await);
         }
+        if let Some(links) = dedup_page(&$page_dedup, &mut page) {
+            drop($permit);
+            return (links, None);
+        }
         if let Some(ref cb) = $on_should_crawl_callback {
             if !cb.call(&page) {
                 page.blocked_crawl = true;
@@ -677,7 +719,7 @@ macro_rules! chrome_page_post_process {
     // Variant with parallel backends support.
     ($page:expr, $pre_links:expr, $pre_links_pages:expr, $extract_succeeded:expr,
      $shared:expr, $add_external:expr, $full_resources:expr,
-     $return_page_links:expr, $on_should_crawl_callback:expr, $permit:expr,
+     $return_page_links:expr, $on_should_crawl_callback:expr, $page_dedup:expr, $permit:expr,
      $pb_backend_set:expr, $pb_config_ref:expr, $pb_tracker_ref:expr) => {{
         let mut page = $page;
         let pre_links = $pre_links;
@@ -800,6 +842,7 @@ macro_rules! chrome_page_post_process {
         if $add_external {
             page.set_external($shared.3.clone());
         }
+        page.set_nofollow_policy($shared.6.nofollow_policy());
         let prev_domain = page.base.take();
         page.set_url_parsed_direct();
         let page_base = page.base.take().map(Box::new);
@@ -823,6 +866,10 @@ macro_rules! chrome_page_post_process {
             page.signature
                 .replace(crate::utils::hash_html(page.get_html_bytes_u8()).await);
         }
+        if let Some(links) = dedup_page(&$page_dedup, &mut page) {
+            drop($permit);
+            return (links, None);
+        }
         if let Some(ref cb) = $on_should_crawl_callback {
             if !cb.call(&page) {
                 page.blocked_crawl = true;
@@ -1234,6 +1281,54 @@ impl ClientRotator {
     }
 }
 
+/// Canonical and near-duplicate dedup state shared with crawl tasks.
+#[derive(Debug, Clone)]
+pub(crate) struct PageDedup {
+    /// Canonical URL table, when canonical dedup is enabled.
+    canonical: Option<crate::utils::canonical::CanonicalUrls>,
+    /// Fingerprint index and settings, when near-duplicate detection is enabled.
+    near: Option<(
+        crate::utils::near_duplicate::NearDuplicates,
+        crate::utils::near_duplicate::NearDuplicateConfig,
+    )>,
+}
+
+/// The post-fetch dedup check shared by every crawl loop. `None` keeps the
+/// page; `Some` drops it and holds the links to crawl in its place, i.e.
+/// the canonical URL an alternate defers to.
+#[inline]
+pub(crate) fn dedup_page(
+    dedup: &Option<PageDedup>,
+    page: &mut Page,
+) -> Option<HashSet<CaseInsensitiveString>> {
+    dedup.as_ref().and_then(|d| d.check(page))
+}
+
+impl PageDedup {
+    /// Check the page against the canonical table and the near-duplicate
+    /// index. Flags kept near duplicates.
+    pub(crate) fn check(&self, page: &mut Page) -> Option<HashSet<CaseInsensitiveString>> {
+        use crate::utils::canonical::CanonicalVerdict;
+
+        if let Some(canonical) = &self.canonical {
+            match canonical.check(page) {
+                CanonicalVerdict::Keep => (),
+                CanonicalVerdict::Duplicate => return Some(HashSet::new()),
+                CanonicalVerdict::Follow(url) => {
+                    let mut links = HashSet::with_capacity(1);
+                    links.insert(url.into());
+                    return Some(links);
+                }
+            }
+        }
+
+        self.near
+            .as_ref()
+            .is_some_and(|(index, config)| index.observe(page, config))
+            .then(HashSet::new)
+    }
+}
+
 /// Represents a website to crawl and gather all links or page content.
 /// ```rust
 /// use spider::website::Website;
@@ -1273,6 +1368,12 @@ pub struct Website {
     /// Default `None` means today's behavior verbatim — every existing
     /// fetch site still runs spider's reqwest path.
     pub remote_fetcher: Option<crate::fetcher::SharedRemoteFetcher>,
+    #[cfg(feature = "frontier_store")]
+    /// Storage for the discovered links, told about the visited ones so they are never queued again.
+    frontier_store: Option<crate::utils::frontier_store::SharedFrontierStore>,
+    #[cfg(feature = "frontier_store")]
+    /// Links popped from the frontier store and not crawled yet.
+    frontier_batch: HashSet<CaseInsensitiveString>,
     /// Optional per-request proxy routing strategy.
     ///
     /// When set together with [`crate::configuration::Configuration::proxies_by_kind`],
@@ -1306,6 +1407,19 @@ pub struct Website {
     links_visited: Box<ListBucket>,
     /// All signatures.
     signatures: Box<HashSet<u64>>,
+    /// Canonical URLs claimed by crawled pages.
+    canonical_urls: crate::utils::canonical::CanonicalUrls,
+    /// Visible text fingerprints of crawled pages.
+    near_duplicates: crate::utils::near_duplicate::NearDuplicates,
+    #[cfg(feature = "sitemap")]
+    /// Sitemap `<url>` entries read by the sitemap crawl, keyed by URL.
+    sitemap_entries: Box<HashMap<CaseInsensitiveString, crate::utils::sitemap_meta::SitemapEntry>>,
+    #[cfg(feature = "feeds")]
+    /// Feed items the crawl was seeded with, keyed by URL.
+    feed_items: Box<HashMap<CaseInsensitiveString, crate::utils::feeds::FeedItem>>,
+    #[cfg(feature = "feeds")]
+    /// The feeds the start page links to, read when feed discovery is on.
+    seed_feeds: Vec<Url>,
     /// Extra links to crawl.
     extra_links: Box<HashSet<CaseInsensitiveString>>,
     /// Pages visited.
@@ -1320,6 +1434,12 @@ pub struct Website {
     channel: Option<(broadcast::Sender<Page>, Arc<broadcast::Receiver<Page>>)>,
     /// Guard counter for channel handling. This prevents things like the browser from closing after the crawl so that subscriptions can finalize events.
     channel_guard: Option<ChannelGuard>,
+    #[cfg(feature = "monitor")]
+    /// Subscribe and broadcast page changes across crawl runs.
+    channel_changes: Option<(
+        broadcast::Sender<crate::utils::monitor::PageChange>,
+        Arc<broadcast::Receiver<crate::utils::monitor::PageChange>>,
+    )>,
     /// Send links to process during the crawl.
     channel_queue: Option<(broadcast::Sender<String>, Arc<broadcast::Receiver<String>>)>,
     /// The status of the active crawl this is mapped to a general status and not the HTTP status code.
@@ -1364,12 +1484,30 @@ pub struct Website {
     #[cfg(feature = "auto_throttle")]
     /// Shared auto-throttle instance for latency-based adaptive delay.
     auto_throttle: Option<Arc<crate::utils::auto_throttle::AutoThrottle>>,
+    #[cfg(feature = "hedge")]
+    /// Shared hedge tracker for the crawl, set when hedging is configured.
+    hedge_tracker: Option<Arc<crate::utils::hedge::HedgeTracker>>,
+    #[cfg(feature = "politeness")]
+    /// Per-host politeness scheduler, set when politeness is configured.
+    politeness: Option<Arc<crate::utils::politeness::HostScheduler>>,
+    #[cfg(feature = "cluster")]
+    /// This node of a cluster crawl, set when a cluster is configured.
+    cluster: Option<Arc<crate::utils::cluster::ClusterNode>>,
+    #[cfg(feature = "metrics")]
+    /// Registry the crawl reports its metrics to.
+    metrics: Option<crate::utils::metrics::MetricsRegistry>,
     #[cfg(feature = "etag_cache")]
     /// Shared ETag cache for conditional requests across the crawl.
     etag_cache: Option<Arc<crate::utils::etag_cache::ETagCache>>,
     #[cfg(feature = "warc")]
     /// Shared WARC writer for archiving crawled pages. Lock-free via MPSC channel.
     warc_writer: Option<crate::utils::warc::WarcWriter>,
+    #[cfg(feature = "warc")]
+    /// Payloads archived by earlier runs, so recurring crawls write revisit records.
+    warc_revisits: crate::utils::warc::WarcRevisits,
+    #[cfg(feature = "checkpoint")]
+    /// Budget counters restored from a checkpoint, applied once the crawl sets up its limits.
+    resume_budget: Option<HashMap<CaseInsensitiveString, u32>>,
     #[cfg(feature = "parallel_backends")]
     /// Per-backend performance tracker for parallel crawl backends.
     pb_tracker: Option<Arc<crate::utils::parallel_backends::BackendTracker>>,
@@ -1431,6 +1569,8 @@ impl fmt::Debug for Website {
             .field("shutdown", &self.shutdown)
             .field("extra_links_len", &self.extra_links.len())
             .field("signatures_len", &self.signatures.len())
+            .field("canonical_urls_len", &self.canonical_urls.len())
+            .field("near_duplicates_len", &self.near_duplicates.len())
             .field("pages_len", &pages_len)
             // channels / sqlite / client: just booleans
             .field("channel_present", &self.channel.is_some())
@@ -1672,6 +1812,65 @@ impl Website {
         !self.signatures.contains(&signature) || self.is_allowed_signature_disk(signature).await
     }
 
+    /// Get the canonical URLs claimed by crawled pages.
+    pub fn get_canonical_urls(&self) -> &crate::utils::canonical::CanonicalUrls {
+        &self.canonical_urls
+    }
+
+    /// Get the visible text fingerprints of crawled pages.
+    pub fn get_near_duplicates(&self) -> &crate::utils::near_duplicate::NearDuplicates {
+        &self.near_duplicates
+    }
+
+    /// Get the sitemap `<url>` entries read by the sitemap crawl. Entries are
+    /// only recorded when a [`SitemapConfig`](crate::utils::sitemap_meta::SitemapConfig) is set.
+    #[cfg(feature = "sitemap")]
+    pub fn get_sitemap_entries(
+        &self,
+    ) -> &HashMap<CaseInsensitiveString, crate::utils::sitemap_meta::SitemapEntry> {
+        &self.sitemap_entries
+    }
+
+    /// Get the feed items the crawl was seeded with.
+    #[cfg(feature = "feeds")]
+    pub fn get_feed_items(&self) -> &HashMap<CaseInsensitiveString, crate::utils::feeds::FeedItem> {
+        &self.feed_items
+    }
+
+    /// The dedup state for crawl tasks, `None` unless canonical or
+    /// near-duplicate dedup is enabled.
+    fn page_dedup(&self) -> Option<PageDedup> {
+        let dedup = PageDedup {
+            canonical: self
+                .configuration
+                .canonical_dedup
+                .then(|| self.canonical_urls.clone()),
+            near: self
+                .configuration
+                .near_duplicate
+                .clone()
+                .map(|config| (self.near_duplicates.clone(), config)),
+        };
+
+        (dedup.canonical.is_some() || dedup.near.is_some()).then_some(dedup)
+    }
+
+    /// The page is an alternate of a canonical URL already crawled or a
+    /// near duplicate to skip. Flags kept near duplicates.
+    fn dedup_page(&self, page: &mut Page) -> Option<HashSet<CaseInsensitiveString>> {
+        dedup_page(&self.page_dedup(), page)
+    }
+
+    /// Mark the canonical URLs claimed by alternate pages as visited so the
+    /// canonical document is not fetched again.
+    async fn mark_canonicals_visited(&mut self) {
+        if self.configuration.canonical_dedup {
+            for url in self.canonical_urls.take_discovered() {
+                self.insert_visited(&url.into()).await;
+            }
+        }
+    }
+
     /// Clear the disk. This does nothing with `disk` flag enabled.
     #[cfg(feature = "disk")]
     pub async fn clear_disk(&self) {
@@ -1713,9 +1912,19 @@ impl Website {
     /// Insert a new URL if it doesn't exist. This does nothing with `disk` flag enabled.
     ///
     /// Accepts a reference to avoid cloning at the call site. The URL is only
-    /// cloned internally when it is actually new and needs to be stored.
+    /// cloned internally when it is actually new and needs to be stored. The
+    /// canonical URLs claimed by alternate pages since the last call are
+    /// recorded as visited with it.
+    pub async fn insert_link(&mut self, link: &CaseInsensitiveString) {
+        self.mark_canonicals_visited().await;
+        self.insert_visited(link).await;
+    }
+
+    /// Record a visited URL in the frontier store, on disk or in memory.
     #[cfg(feature = "disk")]
-    pub async fn insert_link(&mut self, new_url: &CaseInsensitiveString) {
+    async fn insert_visited(&mut self, new_url: &CaseInsensitiveString) {
+        self.insert_link_store(new_url).await;
+
         let mem_load = crate::utils::detect_system::get_global_memory_state_sync();
         let beyond_memory_limits = self.links_visited.len() >= *LINKS_VISITED_MEMORY_LIMIT;
         let seed_check = mem_load == 2 || mem_load == 1 || beyond_memory_limits;
@@ -1748,17 +1957,28 @@ impl Website {
         }
     }
 
-    /// Insert a new URL if it doesn't exist. This does nothing with `disk` flag enabled.
-    ///
-    /// Accepts a reference to avoid cloning at the call site. The URL is only
-    /// cloned internally when it is actually new and needs to be stored.
+    /// Record a visited URL in the frontier store or in memory.
     #[cfg(not(feature = "disk"))]
-    pub async fn insert_link(&mut self, link: &CaseInsensitiveString) {
+    async fn insert_visited(&mut self, link: &CaseInsensitiveString) {
+        self.insert_link_store(link).await;
         if !self.links_visited.contains(link) {
             self.links_visited.insert(link.clone());
         }
     }
 
+    #[cfg(feature = "frontier_store")]
+    /// Record a visited link in the frontier store when one is set, so it is never queued again.
+    async fn insert_link_store(&mut self, link: &CaseInsensitiveString) {
+        if let Some(ref store) = self.frontier_store {
+            self.frontier_batch.remove(link);
+            store.mark_visited(link.inner()).await;
+        }
+    }
+
+    #[cfg(not(feature = "frontier_store"))]
+    /// Record a visited link in the frontier store when one is set, so it is never queued again.
+    async fn insert_link_store(&mut self, _link: &CaseInsensitiveString) {}
+
     /// Insert a new signature if it doesn't exist. This does nothing with `disk` flag enabled.
     #[cfg(feature = "disk")]
     pub async fn insert_signature(&mut self, new_signature: u64) {
@@ -1897,6 +2117,7 @@ impl Website {
     /// - is optionally whitelisted
     /// - is not blacklisted
     /// - is not forbidden in robot.txt file (if parameter is defined)
+    /// - is not a canonical URL already represented by an alternate page
     #[inline]
     #[cfg(not(feature = "regex"))]
     pub fn is_allowed_budgetless(&mut self, link: &CaseInsensitiveString) -> ProcessLinkStatus {
@@ -1920,6 +2141,7 @@ impl Website {
     /// - is optionally whitelisted
     /// - is not blacklisted
     /// - is not forbidden in robot.txt file (if parameter is defined)
+    /// - is not a canonical URL already represented by an alternate page
     #[inline]
     #[cfg(feature = "regex")]
     pub fn is_allowed_budgetless(&mut self, link: &CaseInsensitiveString) -> ProcessLinkStatus {
@@ -2340,6 +2562,14 @@ impl Website {
     pub fn clear(&mut self) {
         self.links_visited.clear();
         self.signatures.clear();
+        self.canonical_urls.clear();
+        self.near_duplicates.clear();
+        #[cfg(feature = "sitemap")]
+        self.sitemap_entries.clear();
+        #[cfg(feature = "feeds")]
+        self.feed_items.clear();
+        #[cfg(feature = "feeds")]
+        self.seed_feeds.clear();
         self.pages.take();
         self.extra_links.clear();
     }
@@ -2423,6 +2653,114 @@ impl Website {
         self.auto_throttle.as_ref()
     }
 
+    #[cfg(feature = "hedge")]
+    /// Get the shared hedge tracker, if hedging is configured.
+    pub fn get_hedge_tracker(&self) -> Option<&Arc<crate::utils::hedge::HedgeTracker>> {
+        self.hedge_tracker.as_ref()
+    }
+
+    #[cfg(feature = "politeness")]
+    /// Wait for the seed host's slot and rate so the seed request counts against them.
+    async fn acquire_seed_slot(
+        &self,
+        client: &Client,
+    ) -> Option<crate::utils::politeness::HostPermit> {
+        match self.politeness.as_deref() {
+            Some(scheduler) if !self.skip_initial => {
+                scheduler.acquire(self.url.inner(), client).await
+            }
+            _ => None,
+        }
+    }
+
+    #[cfg(feature = "politeness")]
+    /// Get the per-host politeness scheduler, if politeness is configured.
+    pub fn get_politeness(&self) -> Option<&Arc<crate::utils::politeness::HostScheduler>> {
+        self.politeness.as_ref()
+    }
+
+    #[cfg(feature = "cluster")]
+    /// Get this node of the cluster crawl, joining the cluster if a cluster is configured.
+    pub fn cluster_node(&mut self) -> Option<Arc<crate::utils::cluster::ClusterNode>> {
+        // Keep the node across runs of the same crawl so the exchange
+        // counters and forwarded links carry over.
+        let current = self.cluster.as_ref().map(|node| node.config());
+        if current != self.configuration.cluster.as_ref() {
+            self.cluster = self
+                .configuration
+                .cluster
+                .clone()
+                .map(crate::utils::cluster::ClusterNode::start);
+        }
+        self.cluster.clone()
+    }
+
+    #[cfg(feature = "cluster")]
+    /// Whether this node crawls `link`: the host is owned by this node or no cluster is set.
+    fn cluster_owns(&self, link: &str) -> bool {
+        self.cluster.as_ref().is_none_or(|node| node.owns(link))
+    }
+
+    #[cfg(not(feature = "cluster"))]
+    /// Links are always crawled locally without the `cluster` feature.
+    fn cluster_owns(&self, _link: &str) -> bool {
+        true
+    }
+
+    #[cfg(feature = "cluster")]
+    /// Send the queued links of hosts owned by other nodes of the cluster to their owner.
+    fn cluster_forward(&self, links: &mut HashSet<CaseInsensitiveString>) {
+        if let Some(node) = &self.cluster {
+            node.forward(links);
+        }
+    }
+
+    #[cfg(not(feature = "cluster"))]
+    /// Links are always crawled locally without the `cluster` feature.
+    fn cluster_forward(&self, _links: &mut HashSet<CaseInsensitiveString>) {}
+
+    #[cfg(feature = "metrics")]
+    /// Register the crawl's hedge tracker, auto-throttle and concurrency
+    /// permits with the metrics registry, keyed by the crawl URL.
+    fn register_metrics(&self) {
+        let Some(registry) = self.metrics.as_ref() else {
+            return;
+        };
+        let domain = self.url.inner().to_string();
+        #[cfg(feature = "hedge")]
+        let hedge_tracker = self.hedge_tracker.clone();
+        #[cfg(feature = "auto_throttle")]
+        let auto_throttle = self.auto_throttle.clone();
+        let semaphore = self.adaptive_concurrency_semaphore.clone();
+
+        registry.register(format!("website:{domain}"), move |encoder| {
+            #[cfg(feature = "hedge")]
+            if let Some(tracker) = hedge_tracker.as_deref() {
+                crate::utils::metrics::encode_hedge(encoder, &domain, tracker);
+            }
+            #[cfg(feature = "auto_throttle")]
+            if let Some(throttle) = auto_throttle.as_deref() {
+                crate::utils::metrics::encode_auto_throttle(encoder, throttle);
+            }
+            if let Some(semaphore) = semaphore.as_deref() {
+                encoder.gauge(
+                    "spider_crawl_permits_available",
+                    "Crawl concurrency permits available.",
+                    &[("domain", &domain)],
+                    semaphore.available_permits() as f64,
+                );
+            }
+        });
+    }
+
+    #[cfg(feature = "metrics")]
+    /// Remove the crawl's collectors from the metrics registry.
+    fn unregister_metrics(&self) {
+        if let Some(registry) = self.metrics.as_ref() {
+            registry.unregister(&format!("website:{}", self.url.inner()));
+        }
+    }
+
     #[cfg(feature = "etag_cache")]
     /// Get the shared ETag cache instance, if enabled.
     pub fn get_etag_cache(&self) -> Option<&Arc<crate::utils::etag_cache::ETagCache>> {
@@ -2435,6 +2773,19 @@ impl Website {
         self.warc_writer.as_ref()
     }
 
+    #[cfg(feature = "warc")]
+    /// Get the payload table used to write WARC revisit records across crawls.
+    pub fn get_warc_revisits(&self) -> &crate::utils::warc::WarcRevisits {
+        &self.warc_revisits
+    }
+
+    #[cfg(feature = "warc")]
+    /// Share a WARC revisit table, e.g. with another `Website` archiving the same site.
+    pub fn set_warc_revisits(&mut self, revisits: crate::utils::warc::WarcRevisits) -> &mut Self {
+        self.warc_revisits = revisits;
+        self
+    }
+
     #[cfg(feature = "warc")]
     /// Get the number of WARC records written so far.
     pub fn warc_record_count(&self) -> u64 {
@@ -2444,6 +2795,212 @@ impl Website {
             .unwrap_or(0)
     }
 
+    #[cfg(feature = "checkpoint")]
+    /// Snapshot the crawl progress: the visited set, the pending queue, the
+    /// prioritized frontier store entries, the content signatures, the
+    /// remaining budget and the crawl status. With a frontier store the
+    /// visited set includes the URLs the store popped or marked visited.
+    pub async fn checkpoint_state(&self) -> crate::utils::checkpoint::CrawlCheckpoint {
+        self.checkpoint_state_with(None).await
+    }
+
+    #[cfg(feature = "checkpoint")]
+    /// Snapshot the crawl progress including the in-memory queue of an active crawl loop.
+    async fn checkpoint_state_with(
+        &self,
+        queued: Option<&HashSet<CaseInsensitiveString>>,
+    ) -> crate::utils::checkpoint::CrawlCheckpoint {
+        let mut pending = self.extra_links.as_ref().clone();
+
+        if let Some(queued) = queued {
+            pending.extend(queued.iter().cloned());
+        }
+
+        let mut visited = self.get_all_links_visited().await;
+
+        #[cfg(feature = "frontier_store")]
+        let frontier = match self.frontier_store {
+            Some(ref store) => {
+                visited.extend(store.visited().await);
+                store.entries().await
+            }
+            _ => Vec::new(),
+        };
+        #[cfg(not(feature = "frontier_store"))]
+        let frontier = Vec::new();
+
+        // Links popped from the frontier store and not crawled yet are pending.
+        visited.retain(|link| !pending.contains(link));
+
+        crate::utils::checkpoint::CrawlCheckpoint {
+            url: self.url.inner().to_string(),
+            status: self.status,
+            visited,
+            pending,
+            frontier,
+            signatures: self.signatures.as_ref().clone(),
+            budget: self
+                .configuration
+                .inner_budget
+                .clone()
+                .or_else(|| self.resume_budget.clone()),
+        }
+    }
+
+    #[cfg(feature = "checkpoint")]
+    /// Persist the crawl state to `path` so it can be continued later with [`Website::resume_from`].
+    pub async fn checkpoint(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        self.checkpoint_state().await.write_to(path).await
+    }
+
+    #[cfg(feature = "checkpoint")]
+    /// Restore the crawl state from a checkpoint written with [`Website::checkpoint`].
+    /// The next `crawl` continues from the pending queue instead of starting over.
+    /// Fails with [`std::io::ErrorKind::InvalidInput`] when the checkpoint is of another website.
+    pub async fn resume_from(&mut self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let checkpoint = crate::utils::checkpoint::CrawlCheckpoint::read_from(path).await?;
+        self.restore_checkpoint(checkpoint).await
+    }
+
+    #[cfg(feature = "checkpoint")]
+    /// Restore the crawl state from an in-memory checkpoint. Prioritized frontier
+    /// entries are queued in the frontier store with their priorities, an
+    /// in-memory store is installed when none is set. Without the
+    /// `frontier_store` feature they are merged into the pending queue. The
+    /// visited links are recorded in the frontier store as well. Fails with
+    /// [`std::io::ErrorKind::InvalidInput`] when the checkpoint is of another website.
+    pub async fn restore_checkpoint(
+        &mut self,
+        checkpoint: crate::utils::checkpoint::CrawlCheckpoint,
+    ) -> std::io::Result<()> {
+        if *self.url != CaseInsensitiveString::from(checkpoint.url.as_str()) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "checkpoint of {} cannot resume {}",
+                    checkpoint.url,
+                    self.url.inner()
+                ),
+            ));
+        }
+
+        self.signatures.extend(checkpoint.signatures);
+        self.extra_links_extend(checkpoint.pending);
+
+        #[cfg(feature = "frontier_store")]
+        if !checkpoint.frontier.is_empty() {
+            match self.frontier_store {
+                Some(ref store) => {
+                    store
+                        .push_batch(
+                            checkpoint
+                                .frontier
+                                .into_iter()
+                                .map(|entry| (entry.url.inner().to_string(), entry.priority))
+                                .collect(),
+                        )
+                        .await;
+                }
+                _ => {
+                    self.frontier_store = Some(Arc::new(
+                        crate::utils::frontier_store::MemoryFrontierStore::from_entries(
+                            false,
+                            checkpoint.frontier,
+                        ),
+                    ));
+                }
+            }
+        }
+        #[cfg(not(feature = "frontier_store"))]
+        self.extra_links_extend(checkpoint.frontier.into_iter().map(|entry| entry.url));
+
+        for link in checkpoint.visited {
+            self.insert_link_store(&link).await;
+            self.links_visited.insert(link);
+        }
+
+        self.resume_budget = checkpoint.budget;
+        // Keep the restored state from being cleared when the crawl is set up.
+        self.status = CrawlStatus::Active;
+        Ok(())
+    }
+
+    #[cfg(feature = "checkpoint")]
+    /// Is a periodic checkpoint due.
+    fn checkpoint_due(&self, last: &Instant) -> bool {
+        match self
+            .configuration
+            .checkpoint
+            .as_ref()
+            .and_then(|c| c.interval)
+        {
+            Some(interval) => last.elapsed() >= interval,
+            _ => false,
+        }
+    }
+
+    #[cfg(feature = "checkpoint")]
+    /// Write the configured checkpoint including the queue of the active crawl loop.
+    async fn checkpoint_queued(&self, queued: &HashSet<CaseInsensitiveString>) {
+        if let Some(ref config) = self.configuration.checkpoint {
+            if let Err(e) = self
+                .checkpoint_state_with(Some(queued))
+                .await
+                .write_to(&config.path)
+                .await
+            {
+                log::warn!("failed to write checkpoint {}: {e}", config.path);
+            }
+        }
+    }
+
+    #[cfg(feature = "checkpoint")]
+    /// Write the configured checkpoint once the crawl finished or stopped.
+    async fn checkpoint_on_finish(&self) {
+        if let Some(ref config) = self.configuration.checkpoint {
+            if let Err(e) = self.checkpoint(&config.path).await {
+                log::warn!("failed to write checkpoint {}: {e}", config.path);
+            }
+        }
+    }
+
+    #[cfg(feature = "monitor")]
+    /// Load the previous run's snapshots and start comparing the crawled pages against them.
+    async fn monitor_start(&mut self) -> Option<crate::utils::monitor::MonitorRun> {
+        let config = self.configuration.monitor.clone()?;
+        let pages = self.subscribe(0);
+        self.subscribe_changes(0);
+        let changes = self.channel_changes.as_ref()?.0.clone();
+
+        Some(crate::utils::monitor::MonitorRun::start(&config, pages, changes).await)
+    }
+
+    #[cfg(not(feature = "monitor"))]
+    /// Change detection is disabled without the `monitor` feature.
+    async fn monitor_start(&mut self) -> Option<()> {
+        None
+    }
+
+    #[cfg(feature = "monitor")]
+    /// Report the removed pages and store the snapshots for the next run.
+    async fn monitor_on_finish(
+        &self,
+        run: Option<crate::utils::monitor::MonitorRun>,
+        completed: bool,
+    ) {
+        if let Some(run) = run {
+            let visited = self.get_all_links_visited().await;
+            run.finish(completed && !self.shutdown, |url| {
+                visited.contains(&CaseInsensitiveString::from(url))
+            })
+            .await;
+        }
+    }
+
+    #[cfg(not(feature = "monitor"))]
+    /// Change detection is disabled without the `monitor` feature.
+    async fn monitor_on_finish(&self, _run: Option<()>, _completed: bool) {}
+
     /// Get the active crawl status.
     pub fn get_status(&self) -> &CrawlStatus {
         &self.status
@@ -2521,10 +3078,38 @@ impl Website {
                             .await;
                     }
                 }
-                if let Some(delay) =
-                    robot_file_parser.get_crawl_delay(&self.configuration.user_agent)
-                {
-                    self.configuration.delay = delay.as_millis().min(60000) as u64;
+                #[cfg(feature = "politeness")]
+                let host_paced = self.politeness.is_some();
+                #[cfg(not(feature = "politeness"))]
+                let host_paced = false;
+
+                // With politeness the delay applies to the seed host only, below.
+                if !host_paced {
+                    if let Some(delay) =
+                        robot_file_parser.get_crawl_delay(&self.configuration.user_agent)
+                    {
+                        self.configuration.delay = delay.as_millis().min(60000) as u64;
+                    }
+                }
+            }
+
+            #[cfg(feature = "politeness")]
+            if let Some(scheduler) = self.politeness.as_deref() {
+                let host = crate::utils::get_domain_from_url(self.url.inner());
+
+                if !host.is_empty() {
+                    let user_agent = self
+                        .configuration
+                        .user_agent
+                        .as_deref()
+                        .map(|ua| ua.as_str())
+                        .unwrap_or("*");
+
+                    scheduler.set_robots(
+                        host,
+                        robot_file_parser.get_crawl_delay(&self.configuration.user_agent),
+                        robot_file_parser.get_req_rate(user_agent),
+                    );
                 }
             }
         }
@@ -3724,6 +4309,10 @@ impl Website {
     /// Base configuration setup.
     pub fn setup_base(&mut self) -> (Client, Option<(Arc<AtomicI8>, tokio::task::JoinHandle<()>)>) {
         self.determine_limits();
+        #[cfg(feature = "checkpoint")]
+        if let Some(budget) = self.resume_budget.take() {
+            self.configuration.inner_budget = Some(budget);
+        }
         self.setup_disk();
         self.configure_headers();
 
@@ -3743,6 +4332,32 @@ impl Website {
             });
         }
 
+        #[cfg(feature = "hedge")]
+        {
+            self.hedge_tracker = self
+                .configuration
+                .hedge
+                .as_ref()
+                .map(|_| Arc::new(crate::utils::hedge::HedgeTracker::default()));
+        }
+
+        #[cfg(feature = "politeness")]
+        {
+            self.politeness = self.configuration.politeness.as_ref().map(|config| {
+                let scheduler = crate::utils::politeness::HostScheduler::new(config.clone())
+                    .with_user_agent(self.configuration.user_agent.clone());
+                #[cfg(feature = "auto_throttle")]
+                let scheduler = scheduler.with_auto_throttle(self.auto_throttle.clone());
+                Arc::new(scheduler)
+            });
+        }
+
+        #[cfg(feature = "cluster")]
+        self.cluster_node();
+
+        #[cfg(feature = "metrics")]
+        self.register_metrics();
+
         #[cfg(feature = "etag_cache")]
         {
             if self.configuration.etag_cache && self.etag_cache.is_none() {
@@ -3753,7 +4368,10 @@ impl Website {
         #[cfg(feature = "warc")]
         {
             if let Some(ref warc_config) = self.configuration.warc {
-                match crate::utils::warc::WarcWriter::create(warc_config) {
+                match crate::utils::warc::WarcWriter::create_with_revisits(
+                    warc_config,
+                    self.warc_revisits.clone(),
+                ) {
                     Ok((writer, file_handle)) => {
                         self.warc_writer = Some(writer.clone());
                         // Subscribe to the page broadcast channel for lock-free WARC writing.
@@ -3935,6 +4553,7 @@ impl Website {
         if !self
             .is_allowed_default(self.get_base_link())
             .eq(&ProcessLinkStatus::Allowed)
+            || !self.cluster_owns(self.url.inner())
         {
             return HashSet::new();
         }
@@ -3954,6 +4573,7 @@ impl Website {
         page_links_settings.subdomains = self.configuration.subdomains;
         page_links_settings.tld = self.configuration.tld;
         page_links_settings.normalize = self.configuration.normalize;
+        page_links_settings.nofollow = self.configuration.nofollow_policy();
 
         let mut domain_parsed = self.domain_parsed.take();
 
@@ -4086,6 +4706,11 @@ impl Website {
 
         self.set_crawl_initial_status(&page, &links);
 
+        self.discover_seed_feeds(&page);
+
+        if let Some(links) = self.dedup_page(&mut page) {
+            return links;
+        }
         if let Some(ref cb) = self.on_should_crawl_callback {
             if !cb.call(&page) {
                 page.blocked_crawl = true;
@@ -4114,6 +4739,7 @@ impl Website {
         if self
             .is_allowed_default(self.get_base_link())
             .eq(&ProcessLinkStatus::Allowed)
+            && self.cluster_owns(self.url.inner())
         {
             let url = self.url.inner();
 
@@ -4130,6 +4756,7 @@ impl Website {
             page_links_settings.subdomains = self.configuration.subdomains;
             page_links_settings.tld = self.configuration.tld;
             page_links_settings.normalize = self.configuration.normalize;
+            page_links_settings.nofollow = self.configuration.nofollow_policy();
             // Skip link extraction for single-page crawls unless the user wants page links.
             page_links_settings.skip_links =
                 self.single_page() && !self.configuration.return_page_links;
@@ -4200,6 +4827,8 @@ impl Website {
                 None => self.configuration.retry as u32,
             };
             page.proxy_configured = self.configuration.proxies.is_some();
+            #[cfg(feature = "tracing")]
+            crate::utils::trace::record_proxy(self.configuration.proxies.as_deref());
             let mut attempt: u32 = 0;
 
             while page.needs_retry() && retry_count > 0 {
@@ -4338,6 +4967,11 @@ impl Website {
 
             self.set_crawl_initial_status(&page, &links);
 
+            self.discover_seed_feeds(&page);
+
+            if let Some(links) = self.dedup_page(&mut page) {
+                return links;
+            }
             if let Some(ref cb) = self.on_should_crawl_callback {
                 if !cb.call(&page) {
                     page.blocked_crawl = true;
@@ -4460,7 +5094,8 @@ impl Website {
                     self.configuration.subdomains,
                     self.configuration.tld,
                     self.configuration.normalize,
-                ),
+                )
+                .with_nofollow(self.configuration.nofollow_policy()),
                 &mut links,
                 None,
                 &self.domain_parsed,
@@ -4474,6 +5109,7 @@ impl Website {
         }
 
         let on_should_crawl_callback = self.on_should_crawl_callback.clone();
+        let page_dedup = self.page_dedup();
         let return_page_links = self.configuration.return_page_links;
         let full_resources = self.configuration.full_resources;
         let mut q = self.channel_queue.as_ref().map(|q| q.0.subscribe());
@@ -4504,7 +5140,8 @@ impl Website {
                 self.configuration.subdomains,
                 self.configuration.tld,
                 self.configuration.normalize,
-            ),
+            )
+            .with_nofollow(self.configuration.nofollow_policy()),
             self.domain_parsed.clone(),
             self.on_link_find_callback.clone(),
         ));
@@ -4574,6 +5211,7 @@ impl Website {
                         if let Ok(permit) = semaphore.clone().acquire_owned().await {
                             let shared = shared.clone();
                             let on_should_crawl_callback = on_should_crawl_callback.clone();
+                            let page_dedup = page_dedup.clone();
                             let retry_strategy_ref = retry_strategy_ref.clone();
                             spawn_set("page_fetch_cmd", &mut set, async move {
                                 let link_result = match &shared.10 {
@@ -4684,6 +5322,10 @@ impl Website {
                                         .map(Box::new);
                                 }
 
+                                if let Some(links) = dedup_page(&page_dedup, &mut page) {
+                                    drop(permit);
+                                    return (links, None);
+                                }
                                 if let Some(ref cb) = on_should_crawl_callback {
                                     if !cb.call(&page) {
                                         page.blocked_crawl = true;
@@ -4802,6 +5444,7 @@ impl Website {
         if self
             .is_allowed_default(self.get_base_link())
             .eq(&ProcessLinkStatus::Allowed)
+            && self.cluster_owns(self.url.inner())
         {
             let (_, intercept_handle) = tokio::join!(
                 crate::features::chrome::setup_chrome_events(chrome_page, &self.configuration),
@@ -4877,6 +5520,8 @@ impl Website {
                 None => self.configuration.retry as u32,
             };
             page.proxy_configured = self.configuration.proxies.is_some();
+            #[cfg(feature = "tracing")]
+            crate::utils::trace::record_proxy(self.configuration.proxies.as_deref());
 
             if let Some(final_redirect_destination) = &page.final_redirect_destination {
                 if final_redirect_destination == "chrome-error://chromewebdata/"
@@ -5209,6 +5854,11 @@ impl Website {
 
             self.set_crawl_initial_status(&page, &links);
 
+            self.discover_seed_feeds(&page);
+
+            if let Some(links) = self.dedup_page(&mut page) {
+                return links;
+            }
             if let Some(ref cb) = self.on_should_crawl_callback {
                 if !cb.call(&page) {
                     page.blocked_crawl = true;
@@ -5240,6 +5890,7 @@ impl Website {
         if self
             .is_allowed_default(self.get_base_link())
             .eq(&ProcessLinkStatus::Allowed)
+            && self.cluster_owns(self.url.inner())
         {
             let (_, intercept_handle) = tokio::join!(
                 crate::features::chrome::setup_chrome_events(chrome_page, &self.configuration),
@@ -5291,6 +5942,8 @@ impl Website {
                 None => self.configuration.retry as u32,
             };
             page.proxy_configured = self.configuration.proxies.is_some();
+            #[cfg(feature = "tracing")]
+            crate::utils::trace::record_proxy(self.configuration.proxies.as_deref());
 
             if let Some(final_redirect_destination) = &page.final_redirect_destination {
                 if final_redirect_destination == "chrome-error://chromewebdata/"
@@ -5595,6 +6248,9 @@ impl Website {
                 }
             }
 
+            if let Some(links) = self.dedup_page(&mut page) {
+                return links;
+            }
             if let Some(ref cb) = self.on_should_crawl_callback {
                 if !cb.call(&page) {
                     page.blocked_crawl = true;
@@ -5627,6 +6283,7 @@ impl Website {
         if self
             .is_allowed_default(self.get_base_link())
             .eq(&ProcessLinkStatus::Allowed)
+            && self.cluster_owns(self.url.inner())
         {
             let timeout = self
                 .configuration
@@ -5646,6 +6303,8 @@ impl Website {
                 None => self.configuration.retry as u32,
             };
             page.proxy_configured = self.configuration.proxies.is_some();
+            #[cfg(feature = "tracing")]
+            crate::utils::trace::record_proxy(self.configuration.proxies.as_deref());
             let mut attempt: u32 = 0;
 
             while page.needs_retry() && retry_count > 0 {
@@ -5750,6 +6409,11 @@ impl Website {
                 }
             }
 
+            self.discover_seed_feeds(&page);
+
+            if let Some(links) = self.dedup_page(&mut page) {
+                return links;
+            }
             if let Some(ref cb) = self.on_should_crawl_callback {
                 if !cb.call(&page) {
                     page.blocked_crawl = true;
@@ -5778,6 +6442,7 @@ impl Website {
         let links: HashSet<CaseInsensitiveString> = if self
             .is_allowed_default(&self.get_base_link())
             .eq(&ProcessLinkStatus::Allowed)
+            && self.cluster_owns(self.url.inner())
         {
             let link = self.url.inner();
 
@@ -5823,6 +6488,8 @@ impl Website {
 
             self.set_crawl_initial_status(&page, &links);
 
+            self.discover_seed_feeds(&page);
+
             channel_send_page(&self.channel, page, &self.channel_guard).await;
 
             links
@@ -6023,6 +6690,7 @@ impl Website {
             if self
                 .is_allowed_default(url_ref)
                 .eq(&ProcessLinkStatus::Allowed)
+                && self.cluster_owns(url.inner())
             {
                 let mut links_ssg = HashSet::with_capacity(16);
                 let mut links_pages = if self.configuration.return_page_links {
@@ -6036,6 +6704,7 @@ impl Website {
                 page_links_settings.subdomains = self.configuration.subdomains;
                 page_links_settings.tld = self.configuration.tld;
                 page_links_settings.normalize = self.configuration.normalize;
+                page_links_settings.nofollow = self.configuration.nofollow_policy();
                 // Skip link extraction for single-page crawls unless the user wants page links.
                 page_links_settings.skip_links =
                     self.single_page() && !self.configuration.return_page_links;
@@ -6071,6 +6740,8 @@ impl Website {
                     None => self.configuration.retry as u32,
                 };
                 page.proxy_configured = self.configuration.proxies.is_some();
+                #[cfg(feature = "tracing")]
+                crate::utils::trace::record_proxy(self.configuration.proxies.as_deref());
                 let mut attempt: u32 = 0;
 
                 while page.needs_retry() && retry_count > 0 {
@@ -6206,6 +6877,11 @@ impl Website {
 
                 self.set_crawl_initial_status(&page, &links);
 
+                self.discover_seed_feeds(&page);
+
+                if let Some(links) = self.dedup_page(&mut page) {
+                    return links;
+                }
                 if let Some(ref cb) = self.on_should_crawl_callback {
                     if !cb.call(&page) {
                         page.blocked_crawl = true;
@@ -6236,6 +6912,7 @@ impl Website {
         let links: HashSet<CaseInsensitiveString> = if self
             .is_allowed_default(self.get_base_link())
             .eq(&ProcessLinkStatus::Allowed)
+            && self.cluster_owns(self.url.inner())
         {
             let url = self.url.inner();
 
@@ -6258,6 +6935,8 @@ impl Website {
                 None => self.configuration.retry as u32,
             };
             page.proxy_configured = self.configuration.proxies.is_some();
+            #[cfg(feature = "tracing")]
+            crate::utils::trace::record_proxy(self.configuration.proxies.as_deref());
             let mut attempt: u32 = 0;
             // Track whether the loop already invoked chrome. Smart mode's
             // post-loop transport-failure fallback (below) must not double
@@ -6466,6 +7145,8 @@ impl Website {
 
             self.set_crawl_initial_status(&page, &links);
 
+            self.discover_seed_feeds(&page);
+
             if self.configuration.return_page_links {
                 page.page_links = if links.is_empty() {
                     None
@@ -6474,6 +7155,9 @@ impl Website {
                 };
             }
 
+            if let Some(links) = self.dedup_page(&mut page) {
+                return links;
+            }
             if let Some(cb) = &mut self.on_should_crawl_callback {
                 if !cb.call(&page) {
                     page.blocked_crawl = true;
@@ -6782,6 +7466,7 @@ impl Website {
         if !self.configuration.external_domains_caseless.is_empty() {
             page.set_external(self.configuration.external_domains_caseless.clone());
         }
+        page.set_nofollow_policy(self.configuration.nofollow_policy());
         page.set_url_parsed_direct();
         if return_page_links {
             page.page_links = Some(Default::default());
@@ -6831,6 +7516,16 @@ impl Website {
 
         self.set_crawl_initial_status(&page, &links);
 
+        self.discover_seed_feeds(&page);
+
+        if let Some(deferred) = self.dedup_page(&mut page) {
+            if deferred.is_empty() {
+                self.subscription_guard().await;
+                return true;
+            }
+            self.extra_links_extend(deferred);
+            return false;
+        }
         if let Some(ref cb) = self.on_should_crawl_callback {
             if !cb.call(&page) {
                 page.blocked_crawl = true;
@@ -6888,6 +7583,7 @@ impl Website {
                         if !self.configuration.external_domains_caseless.is_empty() {
                             page.set_external(self.configuration.external_domains_caseless.clone());
                         }
+                        page.set_nofollow_policy(self.configuration.nofollow_policy());
                         page.set_url_parsed_direct();
                         if return_page_links {
                             page.page_links = Some(Default::default());
@@ -6912,6 +7608,10 @@ impl Website {
                             }
                         }
 
+                        if let Some(deferred) = self.dedup_page(&mut page) {
+                            links.extend(deferred);
+                            continue;
+                        }
                         if let Some(ref cb) = self.on_should_crawl_callback {
                             if !cb.call(&page) {
                                 page.blocked_crawl = true;
@@ -6964,18 +7664,24 @@ impl Website {
                     return;
                 }
                 let (client, handle) = self.setup().await;
+                let monitor = self.monitor_start().await;
                 let (handle, join_handle) = match handle {
                     Some(h) => (Some(h.0), Some(h.1)),
                     _ => (None, None),
                 };
                 let crawl_timeout = self.configuration.crawl_timeout;
                 let url = self.url.inner().to_string();
-                run_with_crawl_timeout(crawl_timeout, &url, async {
+                let completed = run_with_crawl_timeout(crawl_timeout, &url, async {
                     self.crawl_concurrent(&client, &handle).await;
                     self.sitemap_crawl_chain(&client, &handle, false).await;
                 })
                 .await;
                 self.set_crawl_status();
+                self.monitor_on_finish(monitor, completed).await;
+                #[cfg(feature = "metrics")]
+                self.unregister_metrics();
+                #[cfg(feature = "checkpoint")]
+                self.checkpoint_on_finish().await;
                 if let Some(h) = join_handle {
                     h.abort()
                 }
@@ -7002,17 +7708,21 @@ impl Website {
                     return;
                 }
                 let (client, handle) = self.setup().await;
+                let monitor = self.monitor_start().await;
                 let (handle, join_handle) = match handle {
                     Some(h) => (Some(h.0), Some(h.1)),
                     _ => (None, None),
                 };
                 let crawl_timeout = self.configuration.crawl_timeout;
                 let url = self.url.inner().to_string();
-                run_with_crawl_timeout(crawl_timeout, &url, async {
+                let completed = run_with_crawl_timeout(crawl_timeout, &url, async {
                     self.sitemap_crawl(&client, &handle, false).await;
                 })
                 .await;
                 self.set_crawl_status();
+                self.monitor_on_finish(monitor, completed).await;
+                #[cfg(feature = "metrics")]
+                self.unregister_metrics();
                 if let Some(h) = join_handle {
                     h.abort()
                 }
@@ -7040,17 +7750,21 @@ impl Website {
             if !self.status.eq(&CrawlStatus::FirewallBlocked) {
                 self.start();
                 let (client, handle) = self.setup().await;
+                let monitor = self.monitor_start().await;
                 let (handle, join_handle) = match handle {
                     Some(h) => (Some(h.0), Some(h.1)),
                     _ => (None, None),
                 };
                 let crawl_timeout = self.configuration.crawl_timeout;
                 let url = self.url.inner().to_string();
-                run_with_crawl_timeout(crawl_timeout, &url, async {
+                let completed = run_with_crawl_timeout(crawl_timeout, &url, async {
                     self.sitemap_crawl_chrome(&client, &handle, false).await;
                 })
                 .await;
                 self.set_crawl_status();
+                self.monitor_on_finish(monitor, completed).await;
+                #[cfg(feature = "metrics")]
+                self.unregister_metrics();
                 if let Some(h) = join_handle {
                     h.abort()
                 }
@@ -7240,17 +7954,23 @@ impl Website {
                     return;
                 }
                 let (client, handle) = self.setup().await;
+                let monitor = self.monitor_start().await;
                 let (handle, join_handle) = match handle {
                     Some(h) => (Some(h.0), Some(h.1)),
                     _ => (None, None),
                 };
                 let crawl_timeout = self.configuration.crawl_timeout;
                 let url = self.url.inner().to_string();
-                run_with_crawl_timeout(crawl_timeout, &url, async {
+                let completed = run_with_crawl_timeout(crawl_timeout, &url, async {
                     self.crawl_concurrent_smart(&client, &handle).await;
                 })
                 .await;
                 self.set_crawl_status();
+                self.monitor_on_finish(monitor, completed).await;
+                #[cfg(feature = "metrics")]
+                self.unregister_metrics();
+                #[cfg(feature = "checkpoint")]
+                self.checkpoint_on_finish().await;
                 if let Some(h) = join_handle {
                     h.abort()
                 }
@@ -7283,18 +8003,24 @@ impl Website {
                     return;
                 }
                 let (client, handle) = self.setup().await;
+                let monitor = self.monitor_start().await;
                 let (handle, join_handle) = match handle {
                     Some(h) => (Some(h.0), Some(h.1)),
                     _ => (None, None),
                 };
                 let crawl_timeout = self.configuration.crawl_timeout;
                 let url = self.url.inner().to_string();
-                run_with_crawl_timeout(crawl_timeout, &url, async {
+                let completed = run_with_crawl_timeout(crawl_timeout, &url, async {
                     self.crawl_concurrent_raw(&client, &handle).await;
                     self.sitemap_crawl_chain_raw(&client, &handle, false).await;
                 })
                 .await;
                 self.set_crawl_status();
+                self.monitor_on_finish(monitor, completed).await;
+                #[cfg(feature = "metrics")]
+                self.unregister_metrics();
+                #[cfg(feature = "checkpoint")]
+                self.checkpoint_on_finish().await;
                 if let Some(h) = join_handle {
                     h.abort()
                 }
@@ -7559,6 +8285,54 @@ impl Website {
                 self.links_visited.extend_with_new_links(links, s);
             }
         }
+
+        self.cluster_forward(links);
+
+        #[cfg(feature = "frontier_store")]
+        self.frontier_store_balance(links, *exceeded_budget).await;
+    }
+
+    #[cfg(feature = "frontier_store")]
+    /// Queue the newly discovered links in the frontier store, which drops
+    /// the ones queued or visited before, and refill the in-memory batch from
+    /// it once few links are left. Once the budget is exceeded the popped
+    /// links not crawled yet are kept in the extra links for the next crawl.
+    async fn frontier_store_balance(
+        &mut self,
+        links: &mut HashSet<CaseInsensitiveString>,
+        exceeded_budget: bool,
+    ) {
+        use crate::utils::frontier_store::MEMORY_LINKS;
+
+        let Some(store) = self.frontier_store.clone() else {
+            return;
+        };
+
+        let mut discovered = Vec::new();
+        links.retain(|link| {
+            self.frontier_batch.contains(link) || {
+                let priority = crate::utils::frontier::score_link(link.inner());
+                discovered.push((link.inner().to_string(), priority));
+                false
+            }
+        });
+
+        if !discovered.is_empty() {
+            store.push_batch(discovered).await;
+        }
+
+        if exceeded_budget {
+            self.frontier_batch.clear();
+            self.extra_links_extend(links.drain());
+        } else if links.len() < MEMORY_LINKS / 4 && !store.is_empty().await {
+            // Popped links skipped by the crawl loop are never visited, forget them.
+            if self.frontier_batch.len() > MEMORY_LINKS {
+                self.frontier_batch.retain(|link| links.contains(link));
+            }
+            let popped = store.pop_batch(MEMORY_LINKS / 2 - links.len()).await;
+            self.frontier_batch.extend(popped.iter().cloned());
+            links.extend(popped);
+        }
     }
 
     /// Apply URL pre-filter to links if configured. Returns filtered links.
@@ -7622,6 +8396,7 @@ impl Website {
         // through `self` on the dispatcher side.
         let cfg_arc: Arc<crate::configuration::Configuration> =
             Arc::new((*self.configuration).clone());
+        let page_dedup = self.page_dedup();
 
         // Drain mid-crawl queued links each iteration (parity with
         // `crawl_concurrent_raw`'s queue handling).
@@ -7678,6 +8453,7 @@ impl Website {
                 let selectors_clone = selectors.clone();
                 let base_clone = base.clone();
                 let cfg = cfg_arc.clone();
+                let page_dedup = page_dedup.clone();
 
                 set.spawn(async move {
                     let _permit = permit;
@@ -7692,6 +8468,11 @@ impl Website {
                     // No second network round-trip — `page::build` is
                     // pure.
                     let mut page = crate::page::build(&target_url, resp);
+                    page.set_nofollow_policy(cfg.nofollow_policy());
+
+                    if let Some(links) = dedup_page(&page_dedup, &mut page) {
+                        return links;
+                    }
 
                     // Link extraction — same primitives the standard
                     // crawl loop uses. `links` is async (HTML parse
@@ -7761,7 +8542,7 @@ impl Website {
         #[cfg(feature = "hedge")]
         let hedge_config = self.configuration.hedge.clone();
         #[cfg(feature = "hedge")]
-        let hedge_tracker = Arc::new(crate::utils::hedge::HedgeTracker::default());
+        let hedge_tracker = self.hedge_tracker.clone().unwrap_or_default();
         let mut selector: (
             CompactString,
             smallvec::SmallVec<[CompactString; 2]>,
@@ -7772,6 +8553,7 @@ impl Website {
             self.subscription_guard().await;
         } else {
             let on_should_crawl_callback = self.on_should_crawl_callback.clone();
+            let page_dedup = self.page_dedup();
             let full_resources = self.configuration.full_resources;
             let return_page_links = self.configuration.return_page_links;
             let only_html = self.configuration.only_html && !full_resources;
@@ -7809,7 +8591,12 @@ impl Website {
 
             let mut links: HashSet<CaseInsensitiveString> = self.drain_extra_links().collect();
 
+            #[cfg(feature = "politeness")]
+            let seed_permit = self.acquire_seed_slot(client).await;
             links.extend(self._crawl_establish(client, &mut selector, false).await);
+            #[cfg(feature = "politeness")]
+            drop(seed_permit);
+            links.extend(self.feed_links(client).await);
 
             self.configuration.configure_allowlist();
 
@@ -7829,7 +8616,8 @@ impl Website {
                     self.configuration.subdomains,
                     self.configuration.tld,
                     self.configuration.normalize,
-                ),
+                )
+                .with_nofollow(self.configuration.nofollow_policy()),
                 self.domain_parsed.clone(),
                 self.on_link_find_callback.clone(),
                 self.configuration.remote_multimodal.clone(),
@@ -7840,6 +8628,8 @@ impl Website {
 
             #[cfg(feature = "auto_throttle")]
             let auto_throttle_arc = self.auto_throttle.clone();
+            #[cfg(feature = "politeness")]
+            let politeness_arc = self.politeness.clone();
             #[cfg(feature = "etag_cache")]
             let etag_cache_arc = self.etag_cache.clone();
             #[cfg(feature = "parallel_backends")]
@@ -7875,13 +8665,32 @@ impl Website {
                 None
             };
 
+            #[cfg(feature = "checkpoint")]
+            let mut last_checkpoint = Instant::now();
+
             'outer: loop {
+                // Quiesced batch boundary: nothing is in flight, so the snapshot is consistent.
+                #[cfg(feature = "checkpoint")]
+                if set.is_empty() && self.checkpoint_due(&last_checkpoint) {
+                    self.checkpoint_queued(&links).await;
+                    last_checkpoint = Instant::now();
+                }
+
                 #[cfg(all(feature = "agent", feature = "serde"))]
                 self.apply_url_prefilter(&mut links).await;
 
                 let mut stream = tokio_stream::iter(std::mem::take(&mut links));
 
                 loop {
+                    // Hand the unstarted batch back to the queue so in-flight fetches drain
+                    // and the next batch boundary can checkpoint.
+                    #[cfg(feature = "checkpoint")]
+                    if self.checkpoint_due(&last_checkpoint) {
+                        while let Some(link) = stream.next().await {
+                            links.insert(link);
+                        }
+                    }
+
                     let semaphore =
                         get_semaphore(&semaphore, !self.configuration.shared_queue).await;
 
@@ -7919,10 +8728,15 @@ impl Website {
                             }
 
                             // Auto-throttle: apply adaptive per-domain delay on top of static delay.
+                            // The politeness scheduler applies it per host inside the fetch instead.
                             #[cfg(feature = "auto_throttle")]
                             if let Some(ref at) = auto_throttle_arc {
+                                #[cfg(feature = "politeness")]
+                                let host_paced = politeness_arc.is_some();
+                                #[cfg(not(feature = "politeness"))]
+                                let host_paced = false;
                                 let domain = crate::utils::get_domain_from_url(self.url.inner());
-                                if !domain.is_empty() {
+                                if !domain.is_empty() && !host_paced {
                                     let adaptive = at.delay_for(domain);
                                     if adaptive > *throttle {
                                         tokio::time::sleep(adaptive - *throttle).await;
@@ -7930,9 +8744,16 @@ impl Website {
                                 }
                             }
 
-                            if let Ok(permit) = semaphore.clone().acquire_owned().await {
+                            // With politeness the crawl-wide permit is taken in the task, after the host slot.
+                            #[cfg(feature = "politeness")]
+                            let permit = crate::utils::politeness::CrawlPermit::new(semaphore, politeness_arc.as_ref()).await;
+                            #[cfg(not(feature = "politeness"))]
+                            let permit = semaphore.clone().acquire_owned().await;
+
+                            if let Ok(permit) = permit {
                                 let shared = shared.clone();
                                 let on_should_crawl_callback = on_should_crawl_callback.clone();
+                                let page_dedup = page_dedup.clone();
                                 let rotator = client_rotator.clone();
                                 #[cfg(feature = "hedge")]
                                 let hedge_cfg = hedge_config.clone();
@@ -7950,6 +8771,8 @@ impl Website {
                                 let custom_antibot = self.compiled_custom_antibot.clone();
                                 #[cfg(feature = "auto_throttle")]
                                 let auto_throttle_ref = auto_throttle_arc.clone();
+                                #[cfg(feature = "politeness")]
+                                let politeness_ref = politeness_arc.clone();
                                 #[cfg(feature = "etag_cache")]
                                 let etag_cache_ref = etag_cache_arc.clone();
                                 #[cfg(feature = "parallel_backends")]
@@ -7974,6 +8797,10 @@ impl Website {
 
                                     let target_url = link_result.0.as_ref();
 
+                                    // Wait for the host's slot, robots.txt delay and any Retry-After pause.
+                                    #[cfg(feature = "politeness")]
+                                    let (_host_permit, permit) = permit.acquire(target_url, &shared.0).await;
+
                                     // ETag conditional request: if we have cached validators and
                                     // the server responds 304 Not Modified, skip the full fetch.
                                     #[cfg(feature = "etag_cache")]
@@ -8010,6 +8837,7 @@ impl Website {
                                                 if !shared.3.is_empty() {
                                                     page.set_external(shared.3.clone());
                                                 }
+                                                page.set_nofollow_policy(shared.7.nofollow);
                                                 page.set_url_parsed_direct();
                                                 if return_page_links {
                                                     page.page_links = Some(Default::default());
@@ -8030,6 +8858,10 @@ impl Website {
                                                 if normalize && page.signature.is_none() {
                                                     page.signature.replace(crate::utils::hash_html(page.get_html_bytes_u8()).await);
                                                 }
+                                                if let Some(links) = dedup_page(&page_dedup, &mut page) {
+                                                    drop(permit);
+                                                    return (links, None);
+                                                }
                                                 if let Some(ref cb) = on_should_crawl_callback {
                                                     if !cb.call(&page) {
                                                         page.blocked_crawl = true;
@@ -8599,6 +9431,11 @@ impl Website {
                                         }
                                     }
 
+                                    #[cfg(feature = "politeness")]
+                                    if let Some(ref scheduler) = politeness_ref {
+                                        scheduler.record_response(target_url, page.status_code, page.headers.as_ref());
+                                    }
+
                                     // Store ETag / Last-Modified from response for future conditional requests.
                                     #[cfg(feature = "etag_cache")]
                                     if let Some(ref ec) = etag_cache_ref {
@@ -8643,6 +9480,10 @@ impl Website {
                                         }
                                     }
 
+                                    if let Some(links) = dedup_page(&page_dedup, &mut page) {
+                                        drop(permit);
+                                        return (links, None)
+                                    }
                                     if let Some(ref cb) = on_should_crawl_callback {
                                         if !cb.call(&page) {
                                             page.blocked_crawl = true;
@@ -8755,7 +9596,7 @@ impl Website {
                         #[cfg(feature = "hedge")]
                         let hedge_config = self.configuration.hedge.clone();
                         #[cfg(feature = "hedge")]
-                        let hedge_tracker = Arc::new(crate::utils::hedge::HedgeTracker::default());
+                        let hedge_tracker = self.hedge_tracker.clone().unwrap_or_default();
 
                         if self.single_page() {
                             self.crawl_establish(
@@ -8776,6 +9617,8 @@ impl Website {
 
                             let mut q = self.channel_queue.as_ref().map(|q| q.0.subscribe());
 
+                            #[cfg(feature = "politeness")]
+                            let seed_permit = self.acquire_seed_slot(client).await;
                             let base_links = self
                                 .crawl_establish(
                                     client,
@@ -8786,6 +9629,8 @@ impl Website {
                                     &b.browser_dead,
                                 )
                                 .await;
+                            #[cfg(feature = "politeness")]
+                            drop(seed_permit);
 
                             drop(new_page);
 
@@ -8793,6 +9638,7 @@ impl Website {
                                 self.drain_extra_links().collect();
 
                             links.extend(base_links);
+                            links.extend(self.feed_links(client).await);
 
                             self.configuration.configure_allowlist();
 
@@ -8817,6 +9663,7 @@ impl Website {
 
                             let add_external = !shared.3.is_empty();
                             let on_should_crawl_callback = self.on_should_crawl_callback.clone();
+                            let page_dedup = self.page_dedup();
                             let retry_strategy_ref = self.retry_strategy.clone();
                             let full_resources = self.configuration.full_resources;
                             let return_page_links = self.configuration.return_page_links;
@@ -8849,6 +9696,8 @@ impl Website {
                             let pb_validator_chrome = self.pb_quality_validator.clone();
                             #[cfg(feature = "parallel_backends")]
                             let pb_semaphore_chrome = self.pb_semaphore.clone();
+                            #[cfg(feature = "politeness")]
+                            let politeness_arc = self.politeness.clone();
 
                             self.dequeue(&mut q, &mut links, &mut exceeded_budget).await;
 
@@ -8862,13 +9711,32 @@ impl Website {
                                 None
                             };
 
+                            #[cfg(feature = "checkpoint")]
+                            let mut last_checkpoint = Instant::now();
+
                             'outer: loop {
+                                // Quiesced batch boundary: nothing is in flight, so the snapshot is consistent.
+                                #[cfg(feature = "checkpoint")]
+                                if set.is_empty() && self.checkpoint_due(&last_checkpoint) {
+                                    self.checkpoint_queued(&links).await;
+                                    last_checkpoint = Instant::now();
+                                }
+
                                 #[cfg(all(feature = "agent", feature = "serde"))]
                                 self.apply_url_prefilter(&mut links).await;
 
                                 let mut stream = tokio_stream::iter(std::mem::take(&mut links));
 
                                 loop {
+                                    // Hand the unstarted batch back to the queue so in-flight fetches drain
+                                    // and the next batch boundary can checkpoint.
+                                    #[cfg(feature = "checkpoint")]
+                                    if self.checkpoint_due(&last_checkpoint) {
+                                        while let Some(link) = stream.next().await {
+                                            links.insert(link);
+                                        }
+                                    }
+
                                     let semaphore =
                                         get_semaphore(&semaphore, !self.configuration.shared_queue)
                                             .await;
@@ -8912,11 +9780,20 @@ impl Website {
                                                 tokio::time::sleep(*throttle).await;
                                             }
 
-                                            if let Ok(permit) = semaphore.clone().acquire_owned().await {
+                                            // With politeness the crawl-wide permit is taken in the task, after the host slot.
+                                            #[cfg(feature = "politeness")]
+                                            let permit = crate::utils::politeness::CrawlPermit::new(semaphore, politeness_arc.as_ref()).await;
+                                            #[cfg(not(feature = "politeness"))]
+                                            let permit = semaphore.clone().acquire_owned().await;
+
+                                            if let Ok(permit) = permit {
                                                 let shared = shared.clone();
                                                 let on_should_crawl_callback = on_should_crawl_callback.clone();
+                                                let page_dedup = page_dedup.clone();
                                                 #[cfg(any(feature = "cache", feature = "cache_mem", feature = "chrome_remote_cache"))]
                                                 let compiled_custom_antibot = compiled_custom_antibot.clone();
+                                                #[cfg(feature = "politeness")]
+                                                let politeness_ref = politeness_arc.clone();
                                                 #[cfg(feature = "hedge")]
                                                 let hedge_cfg = hedge_config.clone();
                                                 #[cfg(feature = "hedge")]
@@ -8946,6 +9823,10 @@ impl Website {
                                                     let target_url_string =
                                                         link_result.0.into_string();
 
+                                                    // Wait for the host's slot, robots.txt delay and any Retry-After pause.
+                                                    #[cfg(feature = "politeness")]
+                                                    let (_host_permit, permit) = permit.acquire(&target_url_string, &shared.0).await;
+
                                                     // Cache-first: skip tab creation entirely for cached pages
                                                     #[cfg(any(feature = "cache", feature = "cache_mem", feature = "chrome_remote_cache"))]
                                                     {
@@ -8967,6 +9848,7 @@ impl Website {
                                                                 if add_external {
                                                                     page.set_external(shared.3.clone());
                                                                 }
+                                                                page.set_nofollow_policy(shared.6.nofollow_policy());
                                                                 page.set_url_parsed_direct();
                                                                 let page_base = page.base.take().map(Box::new);
                                                                 if return_page_links {
@@ -8984,6 +9866,10 @@ impl Website {
                                                                 if shared.6.normalize && page.signature.is_none() {
                                                                     page.signature.replace(crate::utils::hash_html(page.get_html_bytes_u8()).await);
                                                                 }
+                                                                if let Some(links) = dedup_page(&page_dedup, &mut page) {
+                                                                    drop(permit);
+                                                                    return (links, None);
+                                                                }
                                                                 if let Some(ref cb) = on_should_crawl_callback {
                                                                     if !cb.call(&page) {
                                                                         page.blocked_crawl = true;
@@ -9137,9 +10023,14 @@ impl Website {
                                                             result
                                                         };
 
+                                                        #[cfg(feature = "politeness")]
+                                                        if let (Some(scheduler), Some((page, ..))) = (politeness_ref.as_deref(), page_opt.as_ref()) {
+                                                            scheduler.record_response(target_url, page.status_code, page.headers.as_ref());
+                                                        }
+
                                                         #[allow(unused_assignments)]
                                                         match page_opt {
-                                                            Some((page, pre_links, pre_links_pages, extract_succeeded)) => chrome_page_post_process!(page, pre_links, pre_links_pages, extract_succeeded, shared, add_external, full_resources, return_page_links, on_should_crawl_callback, permit, pb_backend_set, pb_config_ref, pb_tracker_ref),
+                                                            Some((page, pre_links, pre_links_pages, extract_succeeded)) => chrome_page_post_process!(page, pre_links, pre_links_pages, extract_succeeded, shared, add_external, full_resources, return_page_links, on_should_crawl_callback, page_dedup, permit, pb_backend_set, pb_config_ref, pb_tracker_ref),
                                                             None => {
                                                                 // Abort any spawned parallel backend tasks on primary failure.
                                                                 #[cfg(feature = "parallel_backends")]
@@ -9452,7 +10343,12 @@ impl Website {
                                                             #[cfg(feature = "parallel_backends")]
                                                             drop(pb_backend_set);
 
-                                                            chrome_page_post_process!(page, links, links_pages, extract_succeeded, shared, add_external, full_resources, return_page_links, on_should_crawl_callback, permit)
+                                                            #[cfg(feature = "politeness")]
+                                                            if let Some(ref scheduler) = politeness_ref {
+                                                                scheduler.record_response(target_url, page.status_code, page.headers.as_ref());
+                                                            }
+
+                                                            chrome_page_post_process!(page, links, links_pages, extract_succeeded, shared, add_external, full_resources, return_page_links, on_should_crawl_callback, page_dedup, permit)
                                                         }
                                                         _ => Default::default(),
                                                     }
@@ -9575,8 +10471,9 @@ impl Website {
             #[cfg(feature = "hedge")]
             let hedge_config = self.configuration.hedge.clone();
             #[cfg(feature = "hedge")]
-            let hedge_tracker = Arc::new(crate::utils::hedge::HedgeTracker::default());
+            let hedge_tracker = self.hedge_tracker.clone().unwrap_or_default();
             let on_should_crawl_callback = self.on_should_crawl_callback.clone();
+            let page_dedup = self.page_dedup();
             let full_resources = self.configuration.full_resources;
             let return_page_links = self.configuration.return_page_links;
             let only_html = self.configuration.only_html && !full_resources;
@@ -9586,7 +10483,11 @@ impl Website {
 
             let mut links: HashSet<CaseInsensitiveString> = website.drain_extra_links().collect();
 
+            #[cfg(feature = "politeness")]
+            let seed_permit = website.acquire_seed_slot(client).await;
             links.extend(website._crawl_establish(client, &mut selector, false).await);
+            #[cfg(feature = "politeness")]
+            drop(seed_permit);
 
             let semaphore = self.setup_semaphore();
 
@@ -9604,7 +10505,8 @@ impl Website {
                     self.configuration.subdomains,
                     self.configuration.tld,
                     self.configuration.normalize,
-                ),
+                )
+                .with_nofollow(self.configuration.nofollow_policy()),
                 self.domain_parsed.clone(),
                 self.on_link_find_callback.clone(),
                 self.configuration.remote_multimodal.clone(),
@@ -9612,6 +10514,8 @@ impl Website {
 
             let mut set: JoinSet<(HashSet<CaseInsensitiveString>, Option<u64>)> = JoinSet::new();
             let retry_strategy_ref = self.retry_strategy.clone();
+            #[cfg(feature = "politeness")]
+            let politeness_arc = website.politeness.clone();
 
             // track budgeting one time.
             let mut exceeded_budget = false;
@@ -9631,13 +10535,32 @@ impl Website {
                 None
             };
 
+            #[cfg(feature = "checkpoint")]
+            let mut last_checkpoint = Instant::now();
+
             'outer: loop {
+                // Quiesced batch boundary: nothing is in flight, so the snapshot is consistent.
+                #[cfg(feature = "checkpoint")]
+                if set.is_empty() && website.checkpoint_due(&last_checkpoint) {
+                    website.checkpoint_queued(&links).await;
+                    last_checkpoint = Instant::now();
+                }
+
                 #[cfg(all(feature = "agent", feature = "serde"))]
                 self.apply_url_prefilter(&mut links).await;
 
                 let mut stream = tokio_stream::iter(std::mem::take(&mut links));
 
                 loop {
+                    // Hand the unstarted batch back to the queue so in-flight fetches drain
+                    // and the next batch boundary can checkpoint.
+                    #[cfg(feature = "checkpoint")]
+                    if website.checkpoint_due(&last_checkpoint) {
+                        while let Some(link) = stream.next().await {
+                            links.insert(link);
+                        }
+                    }
+
                     let semaphore =
                         get_semaphore(&semaphore, !self.configuration.shared_queue).await;
 
@@ -9671,9 +10594,16 @@ impl Website {
                                 tokio::time::sleep(*throttle).await;
                             }
 
-                            if let Ok(permit) = semaphore.clone().acquire_owned().await {
+                            // With politeness the crawl-wide permit is taken in the task, after the host slot.
+                            #[cfg(feature = "politeness")]
+                            let permit = crate::utils::politeness::CrawlPermit::new(semaphore, politeness_arc.as_ref()).await;
+                            #[cfg(not(feature = "politeness"))]
+                            let permit = semaphore.clone().acquire_owned().await;
+
+                            if let Ok(permit) = permit {
                                 let shared = shared.clone();
                                 let on_should_crawl_callback = on_should_crawl_callback.clone();
+                                let page_dedup = page_dedup.clone();
                                 let rotator = client_rotator.clone();
                                 #[allow(unused_variables)]
                                 let retry_strategy_ref = retry_strategy_ref.clone();
@@ -9681,6 +10611,8 @@ impl Website {
                                 let hedge_cfg = hedge_config.clone();
                                 #[cfg(feature = "hedge")]
                                 let hedge_trk = hedge_tracker.clone();
+                                #[cfg(feature = "politeness")]
+                                let politeness_ref = politeness_arc.clone();
                                 spawn_set("page_fetch", &mut set, async move {
                                     let link_result = match &shared.9 {
                                         Some(cb) => cb(link, None),
@@ -9690,6 +10622,10 @@ impl Website {
                                     let target_url = link_result.0.as_ref();
                                     let external_domains_caseless = &shared.3;
 
+                                    // Wait for the host's slot, robots.txt delay and any Retry-After pause.
+                                    #[cfg(feature = "politeness")]
+                                    let (_host_permit, permit) = permit.acquire(target_url, &shared.0).await;
+
                                     // Hedge-enabled path
                                     #[cfg(feature = "hedge")]
                                     let (mut page, mut links, mut links_pages) = {
@@ -9949,6 +10885,11 @@ impl Website {
                                         }
                                     }
 
+                                    #[cfg(feature = "politeness")]
+                                    if let Some(ref scheduler) = politeness_ref {
+                                        scheduler.record_response(target_url, page.status_code, page.headers.as_ref());
+                                    }
+
                                     if return_page_links {
                                         page.page_links = links_pages.filter(|pages| !pages.is_empty()).map(Box::new);
                                     }
@@ -9983,6 +10924,10 @@ impl Website {
                                         }
                                     }
 
+                                    if let Some(links) = dedup_page(&page_dedup, &mut page) {
+                                        drop(permit);
+                                        return (links, None)
+                                    }
                                     if let Some(ref cb) = on_should_crawl_callback {
                                         if !cb.call(&page) {
                                             page.blocked_crawl = true;
@@ -10099,6 +11044,8 @@ impl Website {
                             website.configure_setup().await;
                         }
 
+                        #[cfg(feature = "politeness")]
+                        let seed_permit = website.acquire_seed_slot(client).await;
                         let base_links = website
                             .crawl_establish(
                                 client,
@@ -10109,6 +11056,8 @@ impl Website {
                                 &b.browser_dead,
                             )
                             .await;
+                        #[cfg(feature = "politeness")]
+                        drop(seed_permit);
 
                         drop(new_page);
 
@@ -10148,7 +11097,10 @@ impl Website {
 
                             let add_external = !shared.3.is_empty();
                             let on_should_crawl_callback = self.on_should_crawl_callback.clone();
+                            let page_dedup = self.page_dedup();
                             let retry_strategy_ref = self.retry_strategy.clone();
+                            #[cfg(feature = "politeness")]
+                            let politeness_arc = website.politeness.clone();
                             let full_resources = self.configuration.full_resources;
                             let return_page_links = self.configuration.return_page_links;
                             // Honor single-page mode: skip link extraction
@@ -10157,8 +11109,7 @@ impl Website {
                             #[cfg(feature = "hedge")]
                             let hedge_config = self.configuration.hedge.clone();
                             #[cfg(feature = "hedge")]
-                            let hedge_tracker =
-                                Arc::new(crate::utils::hedge::HedgeTracker::default());
+                            let hedge_tracker = self.hedge_tracker.clone().unwrap_or_default();
                             let mut exceeded_budget = false;
                             let concurrency = throttle.is_zero();
 
@@ -10176,13 +11127,32 @@ impl Website {
                                 None
                             };
 
+                            #[cfg(feature = "checkpoint")]
+                            let mut last_checkpoint = Instant::now();
+
                             'outer: loop {
+                                // Quiesced batch boundary: nothing is in flight, so the snapshot is consistent.
+                                #[cfg(feature = "checkpoint")]
+                                if set.is_empty() && website.checkpoint_due(&last_checkpoint) {
+                                    website.checkpoint_queued(&links).await;
+                                    last_checkpoint = Instant::now();
+                                }
+
                                 #[cfg(all(feature = "agent", feature = "serde"))]
                                 self.apply_url_prefilter(&mut links).await;
 
                                 let mut stream = tokio_stream::iter(std::mem::take(&mut links));
 
                                 loop {
+                                    // Hand the unstarted batch back to the queue so in-flight fetches drain
+                                    // and the next batch boundary can checkpoint.
+                                    #[cfg(feature = "checkpoint")]
+                                    if website.checkpoint_due(&last_checkpoint) {
+                                        while let Some(link) = stream.next().await {
+                                            links.insert(link);
+                                        }
+                                    }
+
                                     let semaphore =
                                         get_semaphore(&semaphore, !self.configuration.shared_queue)
                                             .await;
@@ -10226,15 +11196,24 @@ impl Website {
                                                 tokio::time::sleep(*throttle).await;
                                             }
 
-                                            if let Ok(permit) = semaphore.clone().acquire_owned().await {
+                                            // With politeness the crawl-wide permit is taken in the task, after the host slot.
+                                            #[cfg(feature = "politeness")]
+                                            let permit = crate::utils::politeness::CrawlPermit::new(semaphore, politeness_arc.as_ref()).await;
+                                            #[cfg(not(feature = "politeness"))]
+                                            let permit = semaphore.clone().acquire_owned().await;
+
+                                            if let Ok(permit) = permit {
                                                 let shared = shared.clone();
                                                 let on_should_crawl_callback = on_should_crawl_callback.clone();
+                                                let page_dedup = page_dedup.clone();
                                                 #[allow(unused_variables)]
                                                 let retry_strategy_ref = retry_strategy_ref.clone();
                                                 #[cfg(feature = "hedge")]
                                                 let hedge_cfg = hedge_config.clone();
                                                 #[cfg(feature = "hedge")]
                                                 let hedge_trk = hedge_tracker.clone();
+                                                #[cfg(feature = "politeness")]
+                                                let politeness_ref = politeness_arc.clone();
                                                 spawn_set("page_fetch", &mut set, async move {
                                                     // Resolve target URL before fetch (needed for hedge path)
                                                     let link_result =
@@ -10245,6 +11224,10 @@ impl Website {
                                                     let target_url_string =
                                                         link_result.0.into_string();
 
+                                                    // Wait for the host's slot, robots.txt delay and any Retry-After pause.
+                                                    #[cfg(feature = "politeness")]
+                                                    let (_host_permit, permit) = permit.acquire(&target_url_string, &shared.0).await;
+
                                                     // Hedge-enabled Chrome path: race primary tab vs hedge tab (new WS connection)
                                                     #[cfg(feature = "hedge")]
                                                     if shared.11.load(std::sync::atomic::Ordering::Acquire) {
@@ -10348,8 +11331,13 @@ impl Website {
                                                             result
                                                         };
 
+                                                        #[cfg(feature = "politeness")]
+                                                        if let (Some(scheduler), Some((page, ..))) = (politeness_ref.as_deref(), page_opt.as_ref()) {
+                                                            scheduler.record_response(target_url, page.status_code, page.headers.as_ref());
+                                                        }
+
                                                         match page_opt {
-                                                            Some((page, pre_links, pre_links_pages, extract_succeeded)) => chrome_page_post_process!(page, pre_links, pre_links_pages, extract_succeeded, shared, add_external, full_resources, return_page_links, on_should_crawl_callback, permit),
+                                                            Some((page, pre_links, pre_links_pages, extract_succeeded)) => chrome_page_post_process!(page, pre_links, pre_links_pages, extract_succeeded, shared, add_external, full_resources, return_page_links, on_should_crawl_callback, page_dedup, permit),
                                                             None => Default::default(),
                                                         }
                                                     };
@@ -10534,7 +11522,12 @@ impl Website {
                                                                     let _ = active.close().await;
                                                                 }
 
-                                                                chrome_page_post_process!(page, links, links_pages, extract_succeeded, shared, add_external, full_resources, return_page_links, on_should_crawl_callback, permit)
+                                                                #[cfg(feature = "politeness")]
+                                                                if let Some(ref scheduler) = politeness_ref {
+                                                                    scheduler.record_response(target_url, page.status_code, page.headers.as_ref());
+                                                                }
+
+                                                                chrome_page_post_process!(page, links, links_pages, extract_succeeded, shared, add_external, full_resources, return_page_links, on_should_crawl_callback, page_dedup, permit)
                                                             }
                                                             _ => Default::default(),
                                                         }
@@ -10721,14 +11714,19 @@ impl Website {
 
                     let mut q = self.channel_queue.as_ref().map(|q| q.0.subscribe());
 
+                    #[cfg(feature = "politeness")]
+                    let seed_permit = self.acquire_seed_slot(client).await;
                     let base_links = self
                         .crawl_establish_webdriver_one(client, &mut selectors, &None, driver)
                         .await;
+                    #[cfg(feature = "politeness")]
+                    drop(seed_permit);
 
                     let mut links: HashSet<CaseInsensitiveString> =
                         self.drain_extra_links().collect();
 
                     links.extend(base_links);
+                    links.extend(self.feed_links(client).await);
 
                     self.configuration.configure_allowlist();
 
@@ -10757,7 +11755,10 @@ impl Website {
 
                     let add_external = !shared.3.is_empty();
                     let on_should_crawl_callback = self.on_should_crawl_callback.clone();
+                    let page_dedup = self.page_dedup();
                     let retry_strategy_ref = self.retry_strategy.clone();
+                    #[cfg(feature = "politeness")]
+                    let politeness_arc = self.politeness.clone();
                     let full_resources = self.configuration.full_resources;
                     let return_page_links = self.configuration.return_page_links;
                     let mut exceeded_budget = false;
@@ -10775,13 +11776,32 @@ impl Website {
                         None
                     };
 
+                    #[cfg(feature = "checkpoint")]
+                    let mut last_checkpoint = Instant::now();
+
                     'outer: loop {
+                        // Quiesced batch boundary: nothing is in flight, so the snapshot is consistent.
+                        #[cfg(feature = "checkpoint")]
+                        if set.is_empty() && self.checkpoint_due(&last_checkpoint) {
+                            self.checkpoint_queued(&links).await;
+                            last_checkpoint = Instant::now();
+                        }
+
                         #[cfg(all(feature = "agent", feature = "serde"))]
                         self.apply_url_prefilter(&mut links).await;
 
                         let mut stream = tokio_stream::iter(std::mem::take(&mut links));
 
                         loop {
+                            // Hand the unstarted batch back to the queue so in-flight fetches drain
+                            // and the next batch boundary can checkpoint.
+                            #[cfg(feature = "checkpoint")]
+                            if self.checkpoint_due(&last_checkpoint) {
+                                while let Some(link) = stream.next().await {
+                                    links.insert(link);
+                                }
+                            }
+
                             let semaphore =
                                 get_semaphore(&semaphore, !self.configuration.shared_queue).await;
 
@@ -10822,10 +11842,19 @@ impl Website {
                                         tokio::time::sleep(*throttle).await;
                                     }
 
-                                    if let Ok(permit) = semaphore.clone().acquire_owned().await {
+                                    // With politeness the crawl-wide permit is taken in the task, after the host slot.
+                                    #[cfg(feature = "politeness")]
+                                    let permit = crate::utils::politeness::CrawlPermit::new(semaphore, politeness_arc.as_ref()).await;
+                                    #[cfg(not(feature = "politeness"))]
+                                    let permit = semaphore.clone().acquire_owned().await;
+
+                                    if let Ok(permit) = permit {
                                         let shared = shared.clone();
                                         let on_should_crawl_callback = on_should_crawl_callback.clone();
+                                        let page_dedup = page_dedup.clone();
                                         let retry_strategy_ref = retry_strategy_ref.clone();
+                                        #[cfg(feature = "politeness")]
+                                        let politeness_ref = politeness_arc.clone();
 
                                         spawn_set("page_fetch_webdriver", &mut set, async move {
                                             let link_result = match &shared.9 {
@@ -10835,6 +11864,10 @@ impl Website {
 
                                             let target_url = link_result.0.as_ref();
 
+                                            // Wait for the host's slot, robots.txt delay and any Retry-After pause.
+                                            #[cfg(feature = "politeness")]
+                                            let (_host_permit, permit) = permit.acquire(target_url, &shared.0).await;
+
                                             // Setup stealth events before navigation
                                             crate::features::webdriver::setup_driver_events(&shared.5, &shared.6).await;
 
@@ -10917,9 +11950,15 @@ impl Website {
                                                 }
                                             }
 
+                                            #[cfg(feature = "politeness")]
+                                            if let Some(ref scheduler) = politeness_ref {
+                                                scheduler.record_response(target_url, page.status_code, page.headers.as_ref());
+                                            }
+
                                             if add_external {
                                                 page.set_external(shared.3.clone());
                                             }
+                                            page.set_nofollow_policy(shared.6.nofollow_policy());
 
                                             let prev_domain = page.base.take();
 
@@ -10947,6 +11986,10 @@ impl Website {
                                                 page.signature.replace(crate::utils::hash_html(page.get_html_bytes_u8()).await);
                                             }
 
+                                            if let Some(links) = dedup_page(&page_dedup, &mut page) {
+                                                drop(permit);
+                                                return (links, None);
+                                            }
                                             if let Some(ref cb) = on_should_crawl_callback {
                                                 if !cb.call(&page) {
                                                     page.blocked_crawl = true;
@@ -11230,12 +12273,18 @@ impl Website {
 
             let (mut interval, throttle) = self.setup_crawl();
             let on_should_crawl_callback = self.on_should_crawl_callback.clone();
+            let page_dedup = self.page_dedup();
             let return_page_links = self.configuration.return_page_links;
 
+            #[cfg(feature = "politeness")]
+            let seed_permit = self.acquire_seed_slot(client).await;
             links.extend(
                 self.crawl_establish_smart(client, &mut selectors, &browser)
                     .await,
             );
+            #[cfg(feature = "politeness")]
+            drop(seed_permit);
+            links.extend(self.feed_links(client).await);
 
             self.configuration.configure_allowlist();
 
@@ -11256,6 +12305,8 @@ impl Website {
 
             let add_external = !self.configuration.external_domains_caseless.is_empty();
             let retry_strategy_ref = self.retry_strategy.clone();
+            #[cfg(feature = "politeness")]
+            let politeness_arc = self.politeness.clone();
             let mut exceeded_budget = false;
             let concurrency = throttle.is_zero();
 
@@ -11271,13 +12322,32 @@ impl Website {
                 None
             };
 
+            #[cfg(feature = "checkpoint")]
+            let mut last_checkpoint = Instant::now();
+
             'outer: loop {
+                // Quiesced batch boundary: nothing is in flight, so the snapshot is consistent.
+                #[cfg(feature = "checkpoint")]
+                if set.is_empty() && self.checkpoint_due(&last_checkpoint) {
+                    self.checkpoint_queued(&links).await;
+                    last_checkpoint = Instant::now();
+                }
+
                 #[cfg(all(feature = "agent", feature = "serde"))]
                 self.apply_url_prefilter(&mut links).await;
 
                 let mut stream = tokio_stream::iter(std::mem::take(&mut links));
 
                 loop {
+                    // Hand the unstarted batch back to the queue so in-flight fetches drain
+                    // and the next batch boundary can checkpoint.
+                    #[cfg(feature = "checkpoint")]
+                    if self.checkpoint_due(&last_checkpoint) {
+                        while let Some(link) = stream.next().await {
+                            links.insert(link);
+                        }
+                    }
+
                     let semaphore =
                         get_semaphore(&semaphore, !self.configuration.shared_queue).await;
 
@@ -11318,10 +12388,19 @@ impl Website {
                                 tokio::time::sleep(*throttle).await;
                             }
 
-                            if let Ok(permit) = semaphore.clone().acquire_owned().await {
+                            // With politeness the crawl-wide permit is taken in the task, after the host slot.
+                            #[cfg(feature = "politeness")]
+                            let permit = crate::utils::politeness::CrawlPermit::new(semaphore, politeness_arc.as_ref()).await;
+                            #[cfg(not(feature = "politeness"))]
+                            let permit = semaphore.clone().acquire_owned().await;
+
+                            if let Ok(permit) = permit {
                                 let shared = shared.clone();
                                 let on_should_crawl_callback = on_should_crawl_callback.clone();
+                                let page_dedup = page_dedup.clone();
                                 let retry_strategy_ref = retry_strategy_ref.clone();
+                                #[cfg(feature = "politeness")]
+                                let politeness_ref = politeness_arc.clone();
                                 spawn_set("page_fetch", &mut set, async move {
                                     let link_result = match &shared.7 {
                                         Some(cb) => cb(link, None),
@@ -11329,6 +12408,11 @@ impl Website {
                                     };
 
                                     let url = link_result.0.as_ref();
+
+                                    // Wait for the host's slot, robots.txt delay and any Retry-After pause.
+                                    #[cfg(feature = "politeness")]
+                                    let (_host_permit, permit) = permit.acquire(url, &shared.0).await;
+
                                     let mut page = Page::new_page_with_cache(
                                         url,
                                         &shared.0,
@@ -11465,6 +12549,11 @@ impl Website {
                                         .await;
                                     }
 
+                                    #[cfg(feature = "politeness")]
+                                    if let Some(ref scheduler) = politeness_ref {
+                                        scheduler.record_response(url, page.status_code, page.headers.as_ref());
+                                    }
+
                                     if add_external {
                                         page.set_external(
                                             shared
@@ -11473,6 +12562,7 @@ impl Website {
                                                 .clone(),
                                         );
                                     }
+                                    page.set_nofollow_policy(shared.4.nofollow_policy());
 
                                     let prev_domain = page.base.take();
 
@@ -11531,6 +12621,10 @@ impl Website {
                                         }
                                     }
 
+                                    if let Some(links) = dedup_page(&page_dedup, &mut page) {
+                                        drop(permit);
+                                        return (links, None)
+                                    }
                                     if let Some(ref cb) = on_should_crawl_callback {
                                         if !cb.call(&page) {
                                             page.blocked_crawl = true;
@@ -11619,6 +12713,18 @@ impl Website {
     ) {
     }
 
+    /// Queue the sitemaps declared in robots.txt alongside the configured sitemap.
+    #[cfg(feature = "sitemap")]
+    fn robots_sitemaps(&self, sitemaps: &mut Vec<(Box<CompactString>, u32)>) {
+        if let Some(parser) = self.robot_file_parser.as_deref() {
+            for sitemap in parser.get_sitemaps() {
+                if !sitemaps.iter().any(|(url, _)| url.as_str() == sitemap) {
+                    sitemaps.push((Box::new(sitemap.as_str().into()), 0));
+                }
+            }
+        }
+    }
+
     /// Setup the sitemap path
     #[cfg(feature = "sitemap")]
     pub(crate) fn get_sitemap_setup(&self, domain: &str) -> (&str, bool) {
@@ -11682,9 +12788,10 @@ impl Website {
                 domain_parsed_ref,
             ));
             let mut sitemaps = match &self.configuration.sitemap_url {
-                Some(sitemap) => Vec::from([sitemap.to_owned()]),
+                Some(sitemap) => Vec::from([(sitemap.to_owned(), 0)]),
                 _ => Default::default(),
             };
+            self.robots_sitemaps(&mut sitemaps);
 
             let return_page_links = self.configuration.return_page_links;
 
@@ -11700,12 +12807,12 @@ impl Website {
             }
 
             'outer: loop {
-                let drained: Vec<Box<CompactString>> = sitemaps.drain(..).collect();
+                let drained: Vec<(Box<CompactString>, u32)> = sitemaps.drain(..).collect();
 
-                let mut first_request = false;
-                let mut attempted_correct = false;
+                for (mut sitemap_url, depth) in drained {
+                    let mut first_request = false;
+                    let mut attempted_correct = false;
 
-                for mut sitemap_url in drained {
                     if !self.handle_process(handle, &mut interval, async {}).await {
                         break 'outer;
                     }
@@ -11759,7 +12866,6 @@ impl Website {
                                 if let Some(response_content_length) = response.content_length() {
                                     if limit > 0 && response_content_length >= limit {
                                         // we need a error here
-                                        first_request = true;
                                         log::info!(
                                             "{} exceeded parse limit: {:?}",
                                             sitemap_url,
@@ -11793,6 +12899,7 @@ impl Website {
                                                 &mut exceeded_budget,
                                                 &tx,
                                                 &mut sitemaps,
+                                                depth,
                                                 true,
                                                 &semaphore,
                                             )
@@ -11808,7 +12915,6 @@ impl Website {
                             Err(err) => {
                                 // do not retry error again.
                                 if attempted_correct {
-                                    first_request = true;
                                     break;
                                 }
 
@@ -11874,10 +12980,8 @@ impl Website {
         scrape: bool,
     ) {
         use crate::features::chrome::attempt_navigation;
-        use sitemap::{
-            reader::{SiteMapEntity, SiteMapReader},
-            structs::Location,
-        };
+        use crate::utils::sitemap_meta::{decode_sitemap, is_gzip, read_sitemap, SitemapEntry};
+        use sitemap::{reader::SiteMapEntity, structs::Location};
 
         let mut exceeded_budget = self.is_over_wild_budget(&self.configuration.budget);
 
@@ -11915,9 +13019,10 @@ impl Website {
                 ));
 
                 let mut sitemaps = match &self.configuration.sitemap_url {
-                    Some(sitemap) => Vec::from([sitemap.to_owned()]),
+                    Some(sitemap) => Vec::from([(sitemap.to_owned(), 0)]),
                     _ => Default::default(),
                 };
+                self.robots_sitemaps(&mut sitemaps);
 
                 let crawl_breaker = if self.configuration.crawl_timeout.is_some() {
                     Some(Instant::now())
@@ -11938,13 +13043,15 @@ impl Website {
                 }
 
                 'outer: loop {
-                    let drained: Vec<Box<CompactString>> = sitemaps.drain(..).collect();
+                    // One sitemap per pass: the select below only takes the first item.
+                    let drained: Vec<(Box<CompactString>, u32)> =
+                        sitemaps.drain(..sitemaps.len().min(1)).collect();
                     let stream = tokio_stream::iter(drained);
                     tokio::pin!(stream);
 
                     tokio::select! {
                         biased;
-                        Some(sitemap_url) = stream.next(), if semaphore.available_permits() > 0 && !crawl_duration_expired(&self.configuration.crawl_timeout, &crawl_breaker)  => {
+                        Some((sitemap_url, depth)) = stream.next(), if semaphore.available_permits() > 0 && !crawl_duration_expired(&self.configuration.crawl_timeout, &crawl_breaker)  => {
                             if !self.handle_process(handle, &mut interval, async {}).await {
                                 break 'outer;
                             }
@@ -11976,18 +13083,23 @@ impl Website {
                                         self.setup_chrome_interception(&new_page)
                                     );
 
-                                    let mut page = Page::new(
-                                        &sitemap_url,
-                                        &client,
-                                        &new_page,
-                                        false, // we use the initial about:blank page.
-                                        self.configuration.referer.clone(),
-                                        self.configuration.max_page_bytes,
-                                        self.configuration.get_cache_options(),
-                                        self.configuration.cache_namespace_str(),
-                                        &self.configuration.chrome_fetch_params(),
-                                    )
-                                    .await;
+                                    // chrome downloads `.xml.gz` sitemaps instead of rendering them.
+                                    let mut page = if sitemap_url.ends_with(".gz") {
+                                        Page::new_page(&sitemap_url, &client).await
+                                    } else {
+                                        Page::new(
+                                            &sitemap_url,
+                                            &client,
+                                            &new_page,
+                                            false, // we use the initial about:blank page.
+                                            self.configuration.referer.clone(),
+                                            self.configuration.max_page_bytes,
+                                            self.configuration.get_cache_options(),
+                                            self.configuration.cache_namespace_str(),
+                                            &self.configuration.chrome_fetch_params(),
+                                        )
+                                        .await
+                                    };
 
                                     if let Some(h) = intercept_handle {
                                         let abort_handle = h.abort_handle();
@@ -12001,22 +13113,45 @@ impl Website {
 
                                     drop(new_page);
 
+                                    let gzip_body = if is_gzip(page.get_html_bytes_u8()) {
+                                        decode_sitemap(bytes::Bytes::copy_from_slice(
+                                            page.get_html_bytes_u8(),
+                                        ))
+                                    } else {
+                                        None
+                                    };
+
                                     let is_xml_entry = page.is_xml;
-                                    let is_xml = is_xml_entry
-                                        && !page.get_html_bytes_u8().ends_with(b"</html>");
+                                    let is_xml = gzip_body.is_some()
+                                        || is_xml_entry
+                                            && !page.get_html_bytes_u8().ends_with(b"</html>");
 
                                     if is_xml {
-                                        let reader = SiteMapReader::new(&*page.get_html_bytes_u8());
+                                        let reader = read_sitemap(
+                                            gzip_body.as_deref().unwrap_or(page.get_html_bytes_u8()),
+                                        );
 
                                         for entity in reader {
                                             if !self.handle_process(handle, &mut interval, async {}).await {
                                                 break;
                                             }
                                             match entity {
-                                                SiteMapEntity::Url(url_entry) => match url_entry.loc {
+                                                SiteMapEntity::Url(url_entry) => match &url_entry.loc {
                                                     Location::Url(url) => {
                                                         let link: CaseInsensitiveString = url.as_str().into();
 
+                                                        if let Some(config) = &self.configuration.sitemap_config {
+                                                            let entry = SitemapEntry::from_url_entry(
+                                                                url.as_str(),
+                                                                &url_entry,
+                                                                depth,
+                                                            );
+                                                            if config.is_unchanged(entry.lastmod) {
+                                                                continue;
+                                                            }
+                                                            self.sitemap_entries.insert(link.clone(), entry);
+                                                        }
+
                                                         let allowed = self.is_allowed(&link);
 
                                                         if allowed.eq(&ProcessLinkStatus::Blocked) {
@@ -12031,8 +13166,17 @@ impl Website {
 
                                                         let client = client.clone();
                                                         let shared = shared.clone();
+                                                        #[cfg(feature = "politeness")]
+                                                        let politeness = self.politeness.clone();
 
                                                         spawn_set("page_fetch", &mut set, async move {
+                                                            // Wait for the host's slot, robots.txt delay and any Retry-After pause.
+                                                            #[cfg(feature = "politeness")]
+                                                            let _host_permit = match politeness {
+                                                                Some(ref scheduler) => scheduler.acquire(link.inner(), &client).await,
+                                                                _ => None,
+                                                            };
+
                                                             if let Ok(new_page) = attempt_navigation(
                                                                 "about:blank",
                                                                 &shared.2,
@@ -12105,6 +13249,11 @@ impl Website {
                                                                     }
                                                                 }
 
+                                                                #[cfg(feature = "politeness")]
+                                                                if let Some(ref scheduler) = politeness {
+                                                                    scheduler.record_response(link.inner(), page.status_code, page.headers.as_ref());
+                                                                }
+
                                                                 // Final tab disposition. With `chrome_store_page`
                                                                 // enabled the subscriber owns the live tab via
                                                                 // `Page::chrome_page` and is expected to call
@@ -12142,11 +13291,19 @@ impl Website {
                                                     Location::None | Location::ParseErr(_) => (),
                                                 },
                                                 SiteMapEntity::SiteMap(sitemap_entry) => {
-                                                    match sitemap_entry.loc {
+                                                    match &sitemap_entry.loc {
                                                         Location::Url(url) => {
-                                                            sitemaps.push(Box::new(CompactString::new(
-                                                                &url.as_str(),
-                                                            )));
+                                                            if self
+                                                                .configuration
+                                                                .sitemap_config
+                                                                .as_ref()
+                                                                .is_none_or(|c| c.follow(depth + 1, &sitemap_entry.lastmod))
+                                                            {
+                                                                sitemaps.push((
+                                                                    Box::new(CompactString::new(url.as_str())),
+                                                                    depth + 1,
+                                                                ));
+                                                            }
                                                         }
                                                         Location::None | Location::ParseErr(_) => (),
                                                     }
@@ -12173,8 +13330,18 @@ impl Website {
                                                 break;
                                             }
 
-                                            if link.ends_with(".xml") {
-                                                sitemaps.push(Box::new(link.into_string().into()));
+                                            if link.ends_with(".xml") || link.ends_with(".xml.gz") {
+                                                if self
+                                                    .configuration
+                                                    .sitemap_config
+                                                    .as_ref()
+                                                    .is_none_or(|c| depth < c.max_depth)
+                                                {
+                                                    sitemaps.push((
+                                                        Box::new(link.into_string().into()),
+                                                        depth + 1,
+                                                    ));
+                                                }
                                                 continue;
                                             }
 
@@ -12192,8 +13359,17 @@ impl Website {
 
                                             let client = client.clone();
                                             let shared = shared.clone();
+                                            #[cfg(feature = "politeness")]
+                                            let politeness = self.politeness.clone();
 
                                             spawn_set("page_fetch", &mut set, async move {
+                                                // Wait for the host's slot, robots.txt delay and any Retry-After pause.
+                                                #[cfg(feature = "politeness")]
+                                                let _host_permit = match politeness {
+                                                    Some(ref scheduler) => scheduler.acquire(link.inner(), &client).await,
+                                                    _ => None,
+                                                };
+
                                                 match attempt_navigation(
                                                     "about:blank",
                                                     &shared.2,
@@ -12263,6 +13439,11 @@ impl Website {
                                                             }
                                                         }
 
+                                                        #[cfg(feature = "politeness")]
+                                                        if let Some(ref scheduler) = politeness {
+                                                            scheduler.record_response(link.inner(), page.status_code, page.headers.as_ref());
+                                                        }
+
                                                         // Final tab disposition. With `chrome_store_page` the
                                                         // subscriber owns the live tab via `Page::chrome_page` and
                                                         // is expected to call `Page::close_page()`. Otherwise close
@@ -12403,6 +13584,100 @@ impl Website {
         }
     }
 
+    /// Read the configured feeds, plus the feeds the start page links to
+    /// when discovery is on, and return their item URLs to seed the crawl
+    /// queue with. The items are kept in [`get_feed_items`](Self::get_feed_items).
+    #[cfg(feature = "feeds")]
+    async fn feed_links(&mut self, client: &Client) -> HashSet<CaseInsensitiveString> {
+        use crate::utils::feeds::{parse_feed, read_feed_body};
+
+        let mut links = HashSet::new();
+
+        let config = match &self.configuration.feeds {
+            Some(config) => config.clone(),
+            _ => return links,
+        };
+        let base = match Url::parse(self.url.inner()) {
+            Ok(base) => base,
+            _ => return links,
+        };
+
+        let mut feed_urls: Vec<Url> = config
+            .urls
+            .iter()
+            .filter_map(|feed| base.join(feed).ok())
+            .collect();
+
+        if config.discover {
+            feed_urls.append(&mut self.seed_feeds);
+        }
+
+        let mut seen = HashSet::with_capacity(feed_urls.len());
+
+        for feed_url in feed_urls {
+            if !seen.insert(feed_url.clone()) {
+                continue;
+            }
+
+            let link: CaseInsensitiveString = feed_url.as_str().into();
+
+            if self
+                .is_allowed_budgetless(&link)
+                .eq(&ProcessLinkStatus::Blocked)
+            {
+                continue;
+            }
+
+            let feed = match client.get(feed_url.as_str()).send().await {
+                Ok(response) if response.status().is_success() => read_feed_body(response)
+                    .await
+                    .and_then(|body| parse_feed(&body, &feed_url)),
+                Ok(response) => {
+                    log::info!("feed {} returned {}", feed_url, response.status());
+                    None
+                }
+                Err(err) => {
+                    log::info!("feed request error: {:?}", err.to_string());
+                    None
+                }
+            };
+
+            if let Some(feed) = feed {
+                for item in config.select(feed.items) {
+                    let link: CaseInsensitiveString = item.url.as_str().into();
+                    links.insert(link.clone());
+                    self.feed_items.insert(link, item);
+                }
+            }
+        }
+
+        links
+    }
+
+    /// Feed seeding is a no-op without the `feeds` flag.
+    #[cfg(not(feature = "feeds"))]
+    async fn feed_links(&mut self, _client: &Client) -> HashSet<CaseInsensitiveString> {
+        Default::default()
+    }
+
+    /// Keep the feeds the start page links to for [`feed_links`](Self::feed_links)
+    /// when feed discovery is on.
+    #[cfg(feature = "feeds")]
+    fn discover_seed_feeds(&mut self, page: &Page) {
+        if self
+            .configuration
+            .feeds
+            .as_ref()
+            .is_some_and(|config| config.discover)
+        {
+            self.seed_feeds = crate::utils::feeds::discovered_feeds(page);
+        }
+    }
+
+    /// Feed discovery is a no-op without the `feeds` flag.
+    #[cfg(not(feature = "feeds"))]
+    fn discover_seed_feeds(&mut self, _page: &Page) {}
+
     /// Sitemap crawl entire lists. Note: this method does not re-crawl the links of the pages found on the sitemap. This does nothing without the `sitemap` flag.
     #[cfg(feature = "sitemap")]
     pub async fn sitemap_crawl(
@@ -12583,16 +13858,20 @@ impl Website {
         interval: &mut Interval,
         exceeded_budget: &mut bool,
         tx: &tokio::sync::mpsc::Sender<Page>,
-        sitemaps: &mut Vec<Box<CompactString>>,
+        sitemaps: &mut Vec<(Box<CompactString>, u32)>,
+        depth: u32,
         crawl: bool,
         semaphore: &Arc<Semaphore>,
     ) {
-        use sitemap::reader::{SiteMapEntity, SiteMapReader};
+        use crate::utils::sitemap_meta::{
+            decode_sitemap, is_sitemap_xml, read_sitemap, SitemapEntry,
+        };
+        use sitemap::reader::SiteMapEntity;
         use sitemap::structs::Location;
 
-        if !b.is_empty() && b.starts_with(b"<?xml") {
-            let reader = SiteMapReader::new(&*b);
+        let b = decode_sitemap(b).unwrap_or_default();
 
+        if !b.is_empty() && is_sitemap_xml(&b) {
             let retry = self.configuration.retry;
             let retry_strategy_ref = self.retry_strategy.clone();
             let cache_policy = self.configuration.cache_policy.clone();
@@ -12604,15 +13883,24 @@ impl Website {
 
             let mut set: JoinSet<()> = JoinSet::new();
 
-            for entity in reader {
+            for entity in read_sitemap(&b) {
                 if !self.handle_process(handle, interval, async {}).await {
                     break;
                 }
                 match entity {
-                    SiteMapEntity::Url(url_entry) => match url_entry.loc {
+                    SiteMapEntity::Url(url_entry) => match &url_entry.loc {
                         Location::Url(url) => {
                             let link: CaseInsensitiveString = url.as_str().into();
 
+                            if let Some(config) = &self.configuration.sitemap_config {
+                                let entry =
+                                    SitemapEntry::from_url_entry(url.as_str(), &url_entry, depth);
+                                if config.is_unchanged(entry.lastmod) {
+                                    continue;
+                                }
+                                self.sitemap_entries.insert(link.clone(), entry);
+                            }
+
                             let allowed = self.is_allowed(&link);
 
                             if allowed.eq(&ProcessLinkStatus::Blocked) {
@@ -12627,20 +13915,30 @@ impl Website {
                             self.insert_link(&link).await;
 
                             if crawl {
-                                // Wait for a permit before spawning (respects concurrency_limit)
-                                let permit = match crate::utils::get_semaphore(
+                                let semaphore = crate::utils::get_semaphore(
                                     semaphore,
                                     !self.configuration.shared_queue,
                                 )
-                                .await
-                                .clone()
-                                .acquire_owned()
-                                .await
-                                {
+                                .await;
+
+                                // Wait for a permit before spawning (respects concurrency_limit).
+                                // With politeness it is taken in the task, after the host slot.
+                                #[cfg(feature = "politeness")]
+                                let permit = crate::utils::politeness::CrawlPermit::new(
+                                    semaphore,
+                                    self.politeness.as_ref(),
+                                )
+                                .await;
+                                #[cfg(not(feature = "politeness"))]
+                                let permit = semaphore.clone().acquire_owned().await;
+
+                                let permit = match permit {
                                     Ok(p) => p,
                                     Err(_) => break,
                                 };
 
+                                #[cfg(feature = "politeness")]
+                                let politeness = self.politeness.clone();
                                 let client = client.clone();
                                 let tx = tx.clone();
                                 let cache_options_init = self.configuration.get_cache_options();
@@ -12649,6 +13947,10 @@ impl Website {
                                 let retry_strategy_ref = retry_strategy_ref.clone();
 
                                 set.spawn(async move {
+                                    // Wait for the host's slot, robots.txt delay and any Retry-After pause.
+                                    #[cfg(feature = "politeness")]
+                                    let (_host_permit, permit) =
+                                        permit.acquire(link.inner(), &client).await;
                                     let _permit = permit; // Held for duration of task
 
                                     let mut page = Page::new_page_with_cache(
@@ -12738,6 +14040,15 @@ impl Website {
                                         }
                                     }
 
+                                    #[cfg(feature = "politeness")]
+                                    if let Some(ref scheduler) = politeness {
+                                        scheduler.record_response(
+                                            link.inner(),
+                                            page.status_code,
+                                            page.headers.as_ref(),
+                                        );
+                                    }
+
                                     if let Ok(permit) = tx.reserve().await {
                                         permit.send(page);
                                     }
@@ -12746,9 +14057,17 @@ impl Website {
                         }
                         Location::None | Location::ParseErr(_) => (),
                     },
-                    SiteMapEntity::SiteMap(sitemap_entry) => match sitemap_entry.loc {
+                    SiteMapEntity::SiteMap(sitemap_entry) => match &sitemap_entry.loc {
                         Location::Url(url) => {
-                            sitemaps.push(Box::new(CompactString::new(url.as_str())));
+                            if self
+                                .configuration
+                                .sitemap_config
+                                .as_ref()
+                                .is_none_or(|c| c.follow(depth + 1, &sitemap_entry.lastmod))
+                            {
+                                sitemaps
+                                    .push((Box::new(CompactString::new(url.as_str())), depth + 1));
+                            }
                         }
                         Location::None | Location::ParseErr(_) => (),
                     },
@@ -12875,6 +14194,20 @@ impl Website {
         self
     }
 
+    /// Respect `nofollow` from robots meta tags and the `X-Robots-Tag` header.
+    pub fn with_respect_robots_meta(&mut self, respect_robots_meta: bool) -> &mut Self {
+        self.configuration
+            .with_respect_robots_meta(respect_robots_meta);
+        self
+    }
+
+    /// Skip links marked `rel="nofollow"`.
+    pub fn with_respect_rel_nofollow(&mut self, respect_rel_nofollow: bool) -> &mut Self {
+        self.configuration
+            .with_respect_rel_nofollow(respect_rel_nofollow);
+        self
+    }
+
     /// Include subdomains detection.
     pub fn with_subdomains(&mut self, subdomains: bool) -> &mut Self {
         self.configuration.with_subdomains(subdomains);
@@ -12992,6 +14325,51 @@ impl Website {
         self
     }
 
+    #[cfg(feature = "feeds")]
+    /// Seed the crawl queue with the items of RSS, Atom and JSON Feed documents. Items are kept with their published dates in [`get_feed_items`](Self::get_feed_items). This does nothing without the `feeds` flag enabled.
+    pub fn with_feeds(&mut self, feeds: Option<crate::utils::feeds::FeedConfig>) -> &mut Self {
+        self.configuration.with_feeds(feeds);
+        self
+    }
+
+    #[cfg(not(feature = "feeds"))]
+    /// Seed the crawl queue with the items of RSS, Atom and JSON Feed documents. This does nothing without the `feeds` flag enabled.
+    pub fn with_feeds(&mut self, _feeds: Option<()>) -> &mut Self {
+        self
+    }
+
+    #[cfg(feature = "monitor")]
+    /// Compare the pages of each crawl against the snapshots stored by the previous run and broadcast the differences to [`subscribe_changes`](Self::subscribe_changes). This does nothing without the `monitor` flag enabled.
+    pub fn with_monitor(
+        &mut self,
+        monitor: Option<crate::utils::monitor::MonitorConfig>,
+    ) -> &mut Self {
+        self.configuration.with_monitor(monitor);
+        self
+    }
+
+    #[cfg(not(feature = "monitor"))]
+    /// Compare the pages of each crawl against the snapshots stored by the previous run. This does nothing without the `monitor` flag enabled.
+    pub fn with_monitor(&mut self, _monitor: Option<()>) -> &mut Self {
+        self
+    }
+
+    #[cfg(feature = "cluster")]
+    /// Crawl as one node of a cluster. Links of hosts owned by other nodes are sent to them instead of being crawled. This does nothing without the `cluster` flag enabled.
+    pub fn with_cluster(
+        &mut self,
+        cluster: Option<crate::utils::cluster::ClusterConfig>,
+    ) -> &mut Self {
+        self.configuration.with_cluster(cluster);
+        self
+    }
+
+    #[cfg(not(feature = "cluster"))]
+    /// Crawl as one node of a cluster. This does nothing without the `cluster` flag enabled.
+    pub fn with_cluster(&mut self, _cluster: Option<()>) -> &mut Self {
+        self
+    }
+
     /// Use proxies for request.
     pub fn with_proxies(&mut self, proxies: Option<Vec<String>>) -> &mut Self {
         self.configuration.with_proxies(proxies);
@@ -13084,6 +14462,26 @@ impl Website {
         self
     }
 
+    /// Report the crawl's hedge tracker, auto-throttle (per domain) and
+    /// concurrency permits to a metrics registry, e.g.
+    /// [`crate::utils::metrics::global`]. Registered when the crawl starts,
+    /// replacing the previous crawl of the same URL, and removed when it
+    /// finishes.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(
+        &mut self,
+        registry: Option<crate::utils::metrics::MetricsRegistry>,
+    ) -> &mut Self {
+        self.metrics = registry;
+        self
+    }
+
+    /// Report crawl metrics (no-op without the `metrics` feature).
+    #[cfg(not(feature = "metrics"))]
+    pub fn with_metrics(&mut self, _registry: Option<()>) -> &mut Self {
+        self
+    }
+
     /// Set a crawl ID to use for tracking crawls. This does nothing without the `control` flag enabled.
     #[cfg(not(feature = "control"))]
     pub fn with_crawl_id(&mut self, _crawl_id: String) -> &mut Self {
@@ -13137,6 +14535,57 @@ impl Website {
         self
     }
 
+    #[cfg(feature = "har")]
+    /// Capture a HAR 1.2 network log for each page rendered with chrome, available on `Page::har`.
+    pub fn with_har(&mut self, har: Option<crate::utils::har::HarConfig>) -> &mut Self {
+        self.configuration.with_har(har);
+        self
+    }
+
+    /// Capture a HAR 1.2 network log for each page (no-op without `har` feature).
+    #[cfg(not(feature = "har"))]
+    pub fn with_har(&mut self, _har: Option<()>) -> &mut Self {
+        self
+    }
+
+    #[cfg(feature = "browser_logs")]
+    /// Capture the browser console messages, JS exceptions and failed resources for each page rendered with chrome, available on `Page::browser_logs`.
+    pub fn with_browser_logs(
+        &mut self,
+        browser_logs: Option<crate::utils::browser_logs::BrowserLogsConfig>,
+    ) -> &mut Self {
+        self.configuration.with_browser_logs(browser_logs);
+        self
+    }
+
+    /// Capture the browser logs for each page (no-op without `browser_logs` feature).
+    #[cfg(not(feature = "browser_logs"))]
+    pub fn with_browser_logs(&mut self, _browser_logs: Option<()>) -> &mut Self {
+        self
+    }
+
+    #[cfg(feature = "web_vitals")]
+    /// Collect the Core Web Vitals and performance metrics for each page rendered with chrome, available on `Page::web_vitals`. Pair with `Viewport::mobile` for a mobile profile.
+    pub fn with_web_vitals(&mut self, web_vitals: bool) -> &mut Self {
+        self.configuration.with_web_vitals(web_vitals);
+        self
+    }
+
+    /// Collect the Core Web Vitals for each page (no-op without `web_vitals` feature).
+    #[cfg(not(feature = "web_vitals"))]
+    pub fn with_web_vitals(&mut self, _web_vitals: bool) -> &mut Self {
+        self
+    }
+
+    /// Extract the accessibility tree and run the a11y audit for each page rendered with chrome, available on `Page::accessibility`. This does nothing without the `accessibility` flag enabled.
+    pub fn with_accessibility(
+        &mut self,
+        accessibility: Option<configuration::AccessibilityConfig>,
+    ) -> &mut Self {
+        self.configuration.with_accessibility(accessibility);
+        self
+    }
+
     /// Set HTTP headers for request using [reqwest::header::HeaderMap](https://docs.rs/reqwest/latest/reqwest/header/struct.HeaderMap.html).
     pub fn with_headers(&mut self, headers: Option<reqwest::header::HeaderMap>) -> &mut Self {
         self.configuration.with_headers(headers);
@@ -13292,6 +14741,30 @@ impl Website {
         self
     }
 
+    #[cfg(feature = "frontier_store")]
+    /// Keep the crawl frontier in `store`. Discovered links are queued in the
+    /// store and popped back highest priority first, at most
+    /// [`MEMORY_LINKS`](crate::utils::frontier_store::MEMORY_LINKS) at a time.
+    /// Visited links are recorded in the store as well as in the visited set
+    /// returned by [`Website::get_links`].
+    pub fn with_frontier_store<S: crate::utils::frontier_store::FrontierStore>(
+        &mut self,
+        store: S,
+    ) -> &mut Self {
+        self.frontier_store = Some(Arc::new(store));
+        self
+    }
+
+    #[cfg(feature = "frontier_store")]
+    /// Keep the crawl frontier in a pre-`Arc`d store.
+    pub fn with_shared_frontier_store(
+        &mut self,
+        store: Option<crate::utils::frontier_store::SharedFrontierStore>,
+    ) -> &mut Self {
+        self.frontier_store = store;
+        self
+    }
+
     /// Set a per-request [`crate::proxy_strategy::ProxyStrategy`].
     ///
     /// When set together with kind-specific proxy lists configured via
@@ -13663,6 +15136,16 @@ impl Website {
         self
     }
 
+    #[cfg(feature = "sitemap")]
+    /// Configure the sitemap crawl: nested `<sitemapindex>` depth, skipping entries unchanged since a timestamp, and recording each entry's `lastmod`, `changefreq` and `priority`.
+    pub fn with_sitemap_config(
+        &mut self,
+        sitemap_config: Option<crate::utils::sitemap_meta::SitemapConfig>,
+    ) -> &mut Self {
+        self.configuration.with_sitemap_config(sitemap_config);
+        self
+    }
+
     /// Overrides default host system timezone with the specified one. This does nothing without the `chrome` flag enabled.
     pub fn with_timezone_id(&mut self, timezone_id: Option<String>) -> &mut Self {
         self.configuration.with_timezone_id(timezone_id);
@@ -13729,6 +15212,18 @@ impl Website {
         self
     }
 
+    /// Set the chrome PDF configuration. This does nothing without the `chrome` flag enabled.
+    pub fn with_pdf(&mut self, pdf_config: Option<configuration::PdfConfig>) -> &mut Self {
+        self.configuration.with_pdf(pdf_config);
+        self
+    }
+
+    /// Set the chrome MHTML snapshot configuration. This does nothing without the `chrome` flag enabled.
+    pub fn with_mhtml(&mut self, mhtml_config: Option<configuration::MhtmlConfig>) -> &mut Self {
+        self.configuration.with_mhtml(mhtml_config);
+        self
+    }
+
     /// Use a shared semaphore to evenly handle workloads. The default is false.
     pub fn with_shared_queue(&mut self, shared_queue: bool) -> &mut Self {
         self.configuration.with_shared_queue(shared_queue);
@@ -13950,6 +15445,21 @@ impl Website {
         self
     }
 
+    /// De-duplicate pages by the URL of their `<link rel="canonical">` tag or `Link: <...>; rel=canonical` header. The page served at the canonical URL is kept; alternates are neither sent to subscribers nor have their links followed, and an alternate whose canonical URL was not crawled yet queues it instead.
+    pub fn with_canonical_dedup(&mut self, canonical_dedup: bool) -> &mut Self {
+        self.configuration.with_canonical_dedup(canonical_dedup);
+        self
+    }
+
+    /// Detect near-duplicate pages by the SimHash of their visible text. Matches are flagged with `Page::near_duplicate_of`, or skipped like canonical alternates when the config asks for it.
+    pub fn with_near_duplicate(
+        &mut self,
+        config: Option<crate::utils::near_duplicate::NearDuplicateConfig>,
+    ) -> &mut Self {
+        self.configuration.with_near_duplicate(config);
+        self
+    }
+
     /// Store all the links found on the disk to share the state. This does nothing without the `disk` flag enabled.
     pub fn with_shared_state(&mut self, shared: bool) -> &mut Self {
         self.configuration.with_shared_state(shared);
@@ -14047,6 +15557,24 @@ impl Website {
         self
     }
 
+    /// Pace each host on its own: robots.txt `Crawl-delay` and `Request-rate`, a
+    /// per-host concurrency cap and `Retry-After` back-off. Applies to HTTP, chrome,
+    /// smart, sitemap and WebDriver crawls.
+    #[cfg(feature = "politeness")]
+    pub fn with_politeness(
+        &mut self,
+        config: Option<crate::utils::politeness::PolitenessConfig>,
+    ) -> &mut Self {
+        self.configuration.with_politeness(config);
+        self
+    }
+
+    /// Set the per-host politeness configuration (no-op without `politeness` feature).
+    #[cfg(not(feature = "politeness"))]
+    pub fn with_politeness(&mut self, _config: Option<()>) -> &mut Self {
+        self
+    }
+
     /// Build the website configuration when using with_builder.
     pub fn build(&self) -> Result<Self, Self> {
         if self.domain_parsed.is_none() {
@@ -14133,6 +15661,49 @@ impl Website {
         channel.0.subscribe()
     }
 
+    /// Subscribe to the page changes found by [`with_monitor`](Self::with_monitor): pages added, removed, modified with the lines of text that changed or answered with a different status since the previous crawl run.
+    ///
+    /// ```rust,no_run
+    /// use spider::{tokio, website::Website};
+    /// use spider::utils::monitor::{MonitorConfig, PageChange};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut website = Website::new("http://example.com");
+    ///     website.with_monitor(Some(MonitorConfig::new("./monitor/example.com")));
+    ///     let mut rx = website.subscribe_changes(0);
+    ///
+    ///     tokio::spawn(async move {
+    ///         while let Ok(change) = rx.recv().await {
+    ///             if let PageChange::Modified { url, diff } = change {
+    ///                 println!("{url}\n+ {:?}\n- {:?}", diff.added, diff.removed);
+    ///             }
+    ///         }
+    ///     });
+    ///
+    ///     website.crawl().await;
+    /// }
+    /// ```
+    #[cfg(feature = "monitor")]
+    pub fn subscribe_changes(
+        &mut self,
+        capacity: usize,
+    ) -> broadcast::Receiver<crate::utils::monitor::PageChange> {
+        let channel = self.channel_changes.get_or_insert_with(|| {
+            let (tx, rx) = broadcast::channel(
+                (if capacity == 0 {
+                    *DEFAULT_PERMITS
+                } else {
+                    capacity
+                })
+                .max(1),
+            );
+            (tx, Arc::new(rx))
+        });
+
+        channel.0.subscribe()
+    }
+
     /// Get a sender for queueing extra links mid crawl. This does nothing unless the `sync` flag is enabled.
     #[cfg(feature = "sync")]
     pub fn queue(&mut self, capacity: usize) -> Option<broadcast::Sender<String>> {
@@ -14530,6 +16101,13 @@ pub async fn channel_send_page(
     #[cfg(any(not(feature = "balance"), feature = "decentralized"))] page: Page,
     channel_guard: &Option<ChannelGuard>,
 ) {
+    if page.is_robots_withheld() {
+        return;
+    }
+
+    #[cfg(feature = "tracing")]
+    crate::utils::trace::record_page(&tracing::Span::current(), &page);
+
     // When `balance` is enabled: large pages, high memory load, or system
     // pressure → spool to disk automatically.  Small pages stay in memory.
     #[cfg(all(feature = "balance", not(feature = "decentralized")))]
@@ -17807,3 +19385,246 @@ async fn test_channel_guard_lock_waits_for_consumers() {
         .await
         .expect("lock should resolve after all pages consumed");
 }
+
+#[cfg(all(test, feature = "checkpoint"))]
+#[tokio::test]
+async fn test_checkpoint_restore_round_trip() {
+    let mut website = Website::new("https://example.com");
+    website.insert_link(&"https://example.com/".into()).await;
+    website.insert_link(&"https://example.com/a".into()).await;
+    website.insert_signature(7).await;
+    website.extra_links_insert("https://example.com/b".into());
+
+    let snapshot = website.checkpoint_state().await;
+    assert_eq!(snapshot.visited.len(), 2);
+    assert_eq!(snapshot.pending.len(), 1);
+
+    let mut resumed = Website::new("https://example.com");
+    resumed.restore_checkpoint(snapshot.clone()).await.unwrap();
+
+    assert_eq!(resumed.get_status(), &CrawlStatus::Active);
+    assert!(resumed
+        .links_visited
+        .contains(&"https://example.com/a".into()));
+    assert!(resumed
+        .get_extra_links()
+        .contains(&CaseInsensitiveString::from("https://example.com/b")));
+
+    let resumed_snapshot = resumed.checkpoint_state().await;
+    assert_eq!(resumed_snapshot.visited, snapshot.visited);
+    assert_eq!(resumed_snapshot.pending, snapshot.pending);
+    assert_eq!(resumed_snapshot.signatures, snapshot.signatures);
+
+    let mut other = Website::new("https://example.org");
+    let err = other.restore_checkpoint(snapshot).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(other.get_extra_links().is_empty());
+}
+
+#[cfg(all(test, feature = "frontier_store"))]
+#[tokio::test]
+async fn test_frontier_store_backs_visited_links() {
+    use crate::utils::frontier_store::MemoryFrontierStore;
+
+    let mut website = Website::new("https://example.com");
+    website.with_frontier_store(MemoryFrontierStore::new(false));
+
+    let a = CaseInsensitiveString::from("https://example.com/a");
+    let b = CaseInsensitiveString::from("https://example.com/b");
+    let mut links: HashSet<CaseInsensitiveString> = [a.clone(), b.clone()].into();
+    website.frontier_store_balance(&mut links, false).await;
+    assert_eq!(links.len(), 2);
+
+    website.insert_link(&a).await;
+    let store = website.frontier_store.clone().unwrap();
+    assert!(store.contains(a.inner()).await);
+    assert!(website.links_visited.contains(&a));
+
+    // Visited links found again are dropped by the store, popped ones stay.
+    website.frontier_store_balance(&mut links, false).await;
+    assert_eq!(links, [b.clone()].into());
+
+    // Exceeding the budget keeps the popped links for the next crawl.
+    website.frontier_store_balance(&mut links, true).await;
+    assert!(links.is_empty());
+    assert!(website.get_extra_links().contains(&b));
+}
+
+#[cfg(all(test, feature = "frontier_store"))]
+#[tokio::test]
+async fn test_canonicals_visited_in_frontier_store() {
+    use crate::utils::frontier_store::MemoryFrontierStore;
+
+    let mut website = Website::new("https://a.com");
+    website.with_canonical_dedup(true);
+    website.with_frontier_store(MemoryFrontierStore::new(false));
+
+    // An alternate pointing at another host is kept and claims its canonical URL.
+    let mut page = Page::default();
+    page.set_url("https://a.com/p?ref=1".into());
+    page.canonical_url = Some("https://b.com/r".into());
+    assert!(website.dedup_page(&mut page).is_none());
+
+    website.insert_link(&"https://a.com/p?ref=1".into()).await;
+    let store = website.frontier_store.clone().unwrap();
+    assert!(store.contains("https://b.com/r").await);
+    assert!(website.links_visited.contains(&"https://b.com/r".into()));
+}
+
+#[cfg(all(test, feature = "frontier_store"))]
+#[tokio::test]
+async fn test_frontier_store_crawl_keeps_links() {
+    use crate::utils::frontier_store::MemoryFrontierStore;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 4096];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let body = if request.starts_with("GET / ") {
+                    r#"<html><body><a href="/a">a</a><a href="/b">b</a></body></html>"#
+                } else {
+                    "<html><body>leaf</body></html>"
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+
+    let mut website = Website::new(&url);
+    website.with_frontier_store(MemoryFrontierStore::new(false));
+    website.crawl_raw().await;
+
+    let links = website.get_links();
+    assert_eq!(links.len(), 3, "{links:?}");
+    assert!(links.contains(&CaseInsensitiveString::from(format!("{url}a"))));
+    assert_eq!(website.get_all_links_visited().await, links);
+}
+
+#[cfg(all(test, feature = "checkpoint", feature = "frontier_store"))]
+#[tokio::test]
+async fn test_checkpoint_keeps_frontier_priorities() {
+    use crate::utils::frontier::ScoredUrl;
+    use crate::utils::frontier_store::{FrontierStore, MemoryFrontierStore};
+
+    let store = MemoryFrontierStore::new(false);
+    store.push("https://example.com/low", 1).await;
+    store.push("https://example.com/high", 900).await;
+    let mut website = Website::new("https://example.com");
+    website.with_frontier_store(store);
+
+    let snapshot = website.checkpoint_state().await;
+    let mut frontier = snapshot.frontier.clone();
+    frontier.sort();
+    assert_eq!(
+        frontier,
+        [
+            ScoredUrl {
+                priority: 1,
+                url: "https://example.com/low".into(),
+            },
+            ScoredUrl {
+                priority: 900,
+                url: "https://example.com/high".into(),
+            },
+        ]
+    );
+
+    let mut resumed = Website::new("https://example.com");
+    resumed.restore_checkpoint(snapshot).await.unwrap();
+    assert!(resumed.get_extra_links().is_empty());
+
+    let store = resumed.frontier_store.as_ref().expect("restored store");
+    assert_eq!(
+        store.pop().await.unwrap().inner(),
+        "https://example.com/high"
+    );
+}
+
+#[cfg(all(test, feature = "checkpoint", feature = "frontier_store"))]
+#[tokio::test]
+async fn test_checkpoint_resumes_twice_with_frontier_store() {
+    use crate::utils::frontier_store::{FrontierStore, MemoryFrontierStore};
+
+    // A store reopened from an earlier run knows URLs the website never crawled.
+    let store = MemoryFrontierStore::new(false);
+    store.push("https://example.com/next", 5).await;
+    store.push("https://example.com/popped", 9).await;
+    store.mark_visited("https://example.com/done").await;
+    let mut website = Website::new("https://example.com");
+    website.with_frontier_store(store);
+    website.insert_link(&"https://example.com/a".into()).await;
+
+    // The popped link is still queued in the crawl loop.
+    let store = website.frontier_store.clone().unwrap();
+    let popped = store.pop().await.unwrap();
+    let queued: HashSet<CaseInsensitiveString> = [popped.clone()].into();
+    let first = website.checkpoint_state_with(Some(&queued)).await;
+    let visited: HashSet<CaseInsensitiveString> = [
+        "https://example.com/a".into(),
+        "https://example.com/done".into(),
+    ]
+    .into();
+    assert_eq!(first.visited, visited);
+    assert!(first.pending.contains(&popped));
+
+    let mut resumed = Website::new("https://example.com");
+    resumed.restore_checkpoint(first.clone()).await.unwrap();
+    let second = resumed.checkpoint_state().await;
+    assert_eq!(second.visited, first.visited);
+    assert_eq!(second.pending, first.pending);
+    assert_eq!(second.frontier, first.frontier);
+
+    let mut again = Website::new("https://example.com");
+    again.restore_checkpoint(second).await.unwrap();
+    let store = again.frontier_store.clone().expect("restored store");
+    assert!(store.contains("https://example.com/done").await);
+    assert!(again
+        .links_visited
+        .contains(&"https://example.com/a".into()));
+    assert_eq!(
+        store.pop().await.unwrap().inner(),
+        "https://example.com/next"
+    );
+    assert_eq!(again.checkpoint_state().await.visited.len(), 3);
+}
+
+#[cfg(all(test, feature = "metrics", feature = "auto_throttle"))]
+#[tokio::test]
+async fn test_metrics_registered_on_setup() {
+    let registry = crate::utils::metrics::MetricsRegistry::new();
+    let mut website = Website::new("https://example.com");
+    website.configuration.with_auto_throttle(Default::default());
+    website.with_metrics(Some(registry.clone()));
+    website.setup().await;
+
+    website
+        .get_auto_throttle()
+        .expect("auto throttle")
+        .record_latency("example.com", Duration::from_millis(40));
+
+    let out = registry.render();
+    assert_eq!(registry.len(), 1);
+    assert!(
+        out.contains("spider_throttle_latency_milliseconds{domain=\"example.com\"} 40\n"),
+        "{out}"
+    );
+    assert!(out.ends_with("# EOF\n"));
+
+    // A second crawl of the same URL replaces the collector.
+    website.setup().await;
+    assert_eq!(registry.len(), 1);
+
+    website.unregister_metrics();
+    assert!(registry.is_empty());
+}
@@ -0,0 +1,101 @@
+//! Integration tests for feed discovery: the feeds the start page
+//! advertises through `<link rel="alternate">` seed the crawl queue,
+//! read from the start page the crawl already fetched.
+
+#![cfg(feature = "feeds")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use spider::tokio;
+use spider::tokio::io::{AsyncReadExt, AsyncWriteExt};
+use spider::tokio::net::TcpListener;
+use spider::utils::feeds::FeedConfig;
+use spider::website::Website;
+
+/// Serve a start page that only advertises an RSS feed, the feed listing two
+/// posts nothing links to, and the posts. Returns the base URL and the
+/// number of start page requests.
+async fn start_feed_site() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let start_requests = Arc::new(AtomicUsize::new(0));
+    let counter = start_requests.clone();
+    let feed_base = base.clone();
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let counter = counter.clone();
+            let base = feed_base.clone();
+
+            tokio::spawn(async move {
+                let mut buf = vec![0u8; 8192];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let path = request
+                    .lines()
+                    .next()
+                    .and_then(|line| line.split(' ').nth(1))
+                    .unwrap_or("/");
+
+                let (content_type, body) = match path {
+                    "/" => {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        (
+                            "text/html",
+                            "<html><head><title>Blog</title>\
+                             <link rel=\"alternate\" type=\"application/rss+xml\" href=\"/feed.xml\">\
+                             </head><body>Latest posts are in the feed.</body></html>"
+                                .to_string(),
+                        )
+                    }
+                    "/feed.xml" => (
+                        "application/rss+xml",
+                        format!(
+                            "<?xml version=\"1.0\"?><rss version=\"2.0\"><channel><title>Blog</title>\
+                             <item><title>One</title><link>{base}/post/1</link></item>\
+                             <item><title>Two</title><link>{base}/post/2</link></item>\
+                             </channel></rss>"
+                        ),
+                    ),
+                    "/robots.txt" => ("text/plain", "User-agent: *\nAllow: /\n".to_string()),
+                    _ => ("text/html", "<html><body>post</body></html>".to_string()),
+                };
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+
+    (base, start_requests)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn feed_discovery_from_start_page() {
+    let (base, start_requests) = start_feed_site().await;
+
+    let mut website = Website::new(&base);
+    website.with_feeds(Some(FeedConfig::default().with_discover(true)));
+    website.crawl().await;
+
+    let links: Vec<String> = website
+        .get_links()
+        .iter()
+        .map(|link| link.as_ref().to_string())
+        .collect();
+
+    for post in ["/post/1", "/post/2"] {
+        let url = format!("{base}{post}");
+        assert!(links.contains(&url), "{url} missing from {links:?}");
+    }
+    assert_eq!(website.get_feed_items().len(), 2);
+    assert_eq!(
+        start_requests.load(Ordering::SeqCst),
+        1,
+        "the start page is fetched once"
+    );
+}
@@ -114,3 +114,178 @@ async fn remote_fetcher_respects_wildcard_budget() {
     );
     assert!(n >= 1, "should at least fetch the seed");
 }
+
+/// Site where `/a?utm=1` and `/a` are the same document: both declare
+/// `https://example.test/a` as canonical, one in the markup and one in a
+/// `Link` header. `/d?ref=1` points at `/d`, which nothing links to.
+struct CanonicalFetcher;
+
+#[async_trait::async_trait]
+impl RemoteFetcher for CanonicalFetcher {
+    async fn fetch(&self, ctx: FetchContext<'_>) -> PageResponse {
+        let mut headers = reqwest::header::HeaderMap::new();
+        let body = match ctx.url {
+            "https://example.test/a?utm=1" => {
+                "<html><head><link rel=\"canonical\" href=\"/a\"></head><body>\
+                    <a href=\"https://example.test/c\">c</a>\
+                </body></html>"
+            }
+            "https://example.test/a" => {
+                headers.insert(
+                    reqwest::header::LINK,
+                    "<https://example.test/a>; rel=\"canonical\""
+                        .parse()
+                        .unwrap(),
+                );
+                "<html><body><a href=\"https://example.test/c\">c</a></body></html>"
+            }
+            "https://example.test/d?ref=1" => {
+                "<html><head><link rel=\"canonical\" href=\"/d\"></head><body>leaf</body></html>"
+            }
+            "https://example.test/b" | "https://example.test/c" | "https://example.test/d" => {
+                "<html><body>leaf</body></html>"
+            }
+            _ => {
+                "<html><body>\
+                    <a href=\"https://example.test/a?utm=1\">a</a>\
+                    <a href=\"https://example.test/a\">a</a>\
+                    <a href=\"https://example.test/b\">b</a>\
+                    <a href=\"https://example.test/d?ref=1\">d</a>\
+                </body></html>"
+            }
+        };
+
+        PageResponse {
+            content: Some(body.as_bytes().to_vec()),
+            headers: Some(headers),
+            status_code: reqwest::StatusCode::OK,
+            final_url: Some(ctx.url.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Crawl `https://example.test/` through `fetcher`, with the site set up by
+/// `configure`, and collect every page sent to subscribers.
+async fn crawl_site(
+    fetcher: impl RemoteFetcher,
+    configure: impl FnOnce(&mut Website),
+) -> Vec<spider::page::Page> {
+    let mut site = Website::new("https://example.test/");
+    configure(&mut site);
+    site.with_remote_fetcher(fetcher);
+
+    let mut rx = site.subscribe(16);
+    let crawl_handle = tokio::spawn(async move { site.crawl().await });
+
+    let mut pages = Vec::new();
+    while let Ok(page) = rx.recv().await {
+        pages.push(page);
+    }
+    crawl_handle.await.unwrap();
+    pages
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn remote_fetcher_canonical_dedup() {
+    let alternates = |pages: &[spider::page::Page]| {
+        pages
+            .iter()
+            .filter(|p| p.canonical_url.as_deref() == Some("https://example.test/a"))
+            .count()
+    };
+
+    let pages = crawl_site(CanonicalFetcher, |site| {
+        site.with_canonical_dedup(false);
+    })
+    .await;
+    assert_eq!(
+        alternates(&pages),
+        2,
+        "both alternates emitted without dedup"
+    );
+    assert_eq!(pages.len(), 6);
+
+    let pages = crawl_site(CanonicalFetcher, |site| {
+        site.with_canonical_dedup(true);
+    })
+    .await;
+    let urls: Vec<_> = pages.iter().map(|p| p.get_url()).collect();
+    assert_eq!(
+        alternates(&pages),
+        1,
+        "only the canonical page should be emitted: {urls:?}"
+    );
+    // The page served at the canonical URL wins over the alternate, and the
+    // alternate of an unlinked canonical is crawled as that canonical.
+    assert!(urls.contains(&"https://example.test/a"), "{urls:?}");
+    assert!(urls.contains(&"https://example.test/d"), "{urls:?}");
+    assert!(!urls.iter().any(|u| u.contains('?')), "{urls:?}");
+    // Seed, /a, /b, /c and /d.
+    assert_eq!(pages.len(), 5, "{urls:?}");
+}
+
+/// Serves two sorts of the same listing that differ only by a render
+/// timestamp, plus an unrelated page.
+struct NearDuplicateFetcher;
+
+#[async_trait::async_trait]
+impl RemoteFetcher for NearDuplicateFetcher {
+    async fn fetch(&self, ctx: FetchContext<'_>) -> PageResponse {
+        let listing = |rendered: &str| {
+            let items: String = (0..64)
+                .map(|i| format!("<li>Item {i} ships within {} days.</li>", i % 7 + 1))
+                .collect();
+            format!(
+                "<html><body><ul>{items}</ul><footer>Rendered {rendered}</footer></body></html>"
+            )
+        };
+        let body = match ctx.url {
+            "https://example.test/list?sort=asc" => listing("10:00:01"),
+            "https://example.test/list?sort=desc" => listing("10:00:02"),
+            "https://example.test/about" => "<html><body><p>Shipping rates depend on the \
+                weight of the parcel, the destination country and the delivery speed.</p>\
+                </body></html>"
+                .to_string(),
+            _ => "<html><body>\
+                    <a href=\"https://example.test/list?sort=asc\">asc</a>\
+                    <a href=\"https://example.test/list?sort=desc\">desc</a>\
+                    <a href=\"https://example.test/about\">about</a>\
+                </body></html>"
+                .to_string(),
+        };
+
+        PageResponse {
+            content: Some(body.into_bytes()),
+            status_code: reqwest::StatusCode::OK,
+            final_url: Some(ctx.url.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn remote_fetcher_near_duplicates() {
+    use spider::utils::near_duplicate::NearDuplicateConfig;
+
+    let pages = crawl_site(NearDuplicateFetcher, |site| {
+        site.with_near_duplicate(Some(NearDuplicateConfig::default().with_skip(false)));
+    })
+    .await;
+    assert_eq!(pages.len(), 4);
+    let flagged: Vec<_> = pages
+        .iter()
+        .filter_map(|p| p.near_duplicate_of.as_deref().map(|of| (p.get_url(), of)))
+        .collect();
+    assert_eq!(flagged.len(), 1, "one listing sort flagged: {flagged:?}");
+    assert!(flagged[0].0.starts_with("https://example.test/list?sort="));
+    assert!(flagged[0].1.starts_with("https://example.test/list?sort="));
+    assert_ne!(flagged[0].0, flagged[0].1);
+
+    let pages = crawl_site(NearDuplicateFetcher, |site| {
+        site.with_near_duplicate(Some(NearDuplicateConfig::default().with_skip(true)));
+    })
+    .await;
+    assert_eq!(pages.len(), 3, "the second listing sort is skipped");
+    assert!(pages.iter().all(|p| p.near_duplicate_of.is_none()));
+}
@@ -252,7 +252,7 @@ async fn main() {
     }
 
     if cli.url.is_empty() {
-        eprintln!("Error: --url is required for crawl/scrape/download commands.");
+        eprintln!("Error: --url is required for crawl/scrape/download/export commands.");
         std::process::exit(1);
     }
 
@@ -581,6 +581,90 @@ async fn main() {
                         }
                     }
                 }
+                Some(Commands::EXPORT {
+                    target_destination,
+                    title,
+                    summary,
+                }) => {
+                    use spider::utils::site_export::SiteExport;
+
+                    let export_dir = target_destination
+                        .unwrap_or(String::from("./_temp_spider_export/"));
+
+                    let transform_conf = TransformConfig {
+                        return_format: ReturnFormat::from_str(&return_format),
+                        ..Default::default()
+                    };
+
+                    if let Err(e) = std::fs::create_dir_all(&export_dir) {
+                        eprintln!("Failed to create {:?}: {e}", export_dir);
+                        std::process::exit(1);
+                    }
+
+                    // Page content waits on disk until llms-full.txt is written.
+                    let mut export = match SiteExport::new(&url)
+                        .with_title(title)
+                        .with_summary(summary)
+                        .with_content_spool(
+                            std::path::Path::new(&export_dir).join(".llms-full.spool"),
+                        ) {
+                        Ok(export) => export,
+                        Err(e) => {
+                            eprintln!("Failed to create the export spool in {:?}: {e}", export_dir);
+                            std::process::exit(1);
+                        }
+                    };
+
+                    let crawl = tokio::spawn(async move {
+                        crawl_with_mode(&mut website, use_headless).await;
+                        log_website_status(&website);
+                        // Close the page channel so the export loop ends.
+                        website.unsubscribe();
+                        website
+                    });
+
+                    loop {
+                        let res = match rx2.recv().await {
+                            Ok(res) => res,
+                            Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                                eprintln!("Export fell behind the crawl, {skipped} pages are listed without content");
+                                continue;
+                            }
+                            Err(_) => break,
+                        };
+
+                        let content = {
+                            let input = TransformInput {
+                                url: res.get_url_parsed_ref().as_ref(),
+                                content: res.get_html_bytes_u8(),
+                                screenshot_bytes: None,
+                                encoding: None,
+                                selector_config: None,
+                                ignore_tags: None,
+                            };
+                            transform_content_input(input, &transform_conf)
+                        };
+
+                        export.push_page(&res, Some(content));
+                    }
+
+                    // Pages skipped after a lag still belong in the sitemap and llms.txt.
+                    if let Ok(website) = crawl.await {
+                        export.extend_links(website.get_links().iter());
+                    }
+
+                    match export.write_to(&export_dir) {
+                        Ok(paths) => {
+                            for path in paths {
+                                println!("{}", path.display());
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("Failed to write export to {:?}: {e}", export_dir);
+                            std::process::exit(1);
+                        }
+                    }
+                }
                 Some(Commands::AUTHENTICATE { .. }) => {},
                 None => ()
             }
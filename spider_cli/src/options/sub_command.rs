@@ -27,6 +27,19 @@ pub enum Commands {
         #[clap(short, long)]
         target_destination: Option<String>,
     },
+    /// Crawl the website and write a sitemap.xml (split with an index past 50k URLs),
+    /// llms.txt and llms-full.txt. Page content for llms-full.txt uses --return-format.
+    EXPORT {
+        /// store files at target destination
+        #[clap(short, long)]
+        target_destination: Option<String>,
+        /// The llms.txt heading. Defaults to the website host.
+        #[clap(long)]
+        title: Option<String>,
+        /// The llms.txt summary.
+        #[clap(long)]
+        summary: Option<String>,
+    },
     /// Authenticate with the Spider Cloud service. Stores your API key locally for remote crawls.
     /// With no arguments it signs you in through your browser (OAuth) and provisions a key.
     /// Sign up at https://spider.cloud to get started.
@@ -0,0 +1,510 @@
+//! Cluster crawl endpoints served next to the job routes.
+//!
+//! ```text
+//! POST   /cluster                     {"url": "...", "nodes": [...], "configuration": {...}}
+//! POST   /cluster/{crawl_id}          start this node, sent by the coordinator
+//! POST   /cluster/{crawl_id}/links    links of hosts this node owns, sent by peers
+//! GET    /cluster/{crawl_id}          node status
+//! GET    /cluster/{crawl_id}/visited  links visited by this node
+//! DELETE /cluster/{crawl_id}          stop this node
+//! ```
+//!
+//! `POST /cluster` makes the worker the coordinator: it starts the crawl on
+//! every node, waits for the cluster to go quiet and answers with the merged
+//! visited set. A node crawls the seed when it owns the seed host, then keeps
+//! crawling the links its peers send until the coordinator stops it.
+//!
+//! Every route needs the job token, which the nodes also send each other.
+//! Crawls take the same [`JobConfiguration`] as jobs, and only nodes listed
+//! in `SPIDER_WORKER_CLUSTER_PEERS` may take part, so a request cannot make
+//! the worker post to arbitrary addresses.
+
+use crate::jobs::{authorized, unauthorized, JobConfiguration};
+use spider::hashbrown::HashMap;
+use spider::tokio::{
+    self,
+    sync::{watch, Notify},
+};
+use spider::utils::cluster::{
+    ClusterCoordinator, ClusterNode, ClusterStart, LinkBatch, FLEXBUFFERS_CONTENT_TYPE,
+};
+use spider::website::Website;
+use spider::CaseInsensitiveString;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use warp::http::StatusCode;
+use warp::Filter;
+
+/// Largest accepted start or coordinator request body.
+const MAX_REQUEST_BYTES: u64 = 1024 * 1024;
+
+/// Largest accepted link batch.
+const MAX_BATCH_BYTES: u64 = 16 * 1024 * 1024;
+
+/// A cluster crawl submitted with `POST /cluster`.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct ClusterRequest {
+    /// The seed url.
+    pub url: String,
+    /// Base urls of the nodes, for example `http://10.0.0.2:3033`.
+    pub nodes: Vec<String>,
+    /// The crawl settings shared by every node.
+    pub configuration: Option<JobConfiguration>,
+}
+
+/// The `POST /cluster` response.
+#[derive(Debug, serde::Serialize)]
+struct ClusterResult {
+    /// The crawl id used on the nodes.
+    crawl_id: String,
+    /// Links visited across the cluster.
+    visited: Vec<String>,
+}
+
+/// The crawl of this node.
+#[derive(Debug)]
+struct NodeCrawl {
+    /// Routing and exchange counters.
+    node: Arc<ClusterNode>,
+    /// Links received from peers and not crawled yet.
+    inbound: Mutex<Vec<String>>,
+    /// Signalled when links are received.
+    wake: Notify,
+    /// A crawl run is in progress.
+    crawling: AtomicBool,
+    /// Links visited as of the last crawl run.
+    visited: Mutex<Vec<String>>,
+    /// Set to stop the crawl.
+    stop: watch::Sender<bool>,
+}
+
+impl NodeCrawl {
+    /// Whether nothing is being crawled or waiting to be.
+    fn idle(&self) -> bool {
+        !self.crawling.load(Ordering::SeqCst) && lock(&self.inbound).is_empty()
+    }
+
+    /// The status endpoint response.
+    fn status(&self) -> spider::utils::cluster::NodeStatus {
+        let visited = lock(&self.visited).len();
+        self.node.status(self.idle(), visited)
+    }
+
+    /// Keep the visited set of the finished run and go idle.
+    fn finish_run(&self, website: &Website) {
+        *lock(&self.visited) = website
+            .get_links()
+            .iter()
+            .map(|link| link.inner().to_string())
+            .collect();
+        self.crawling.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Lock a mutex, recovering from a poisoned lock.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The cluster crawls this worker takes part in.
+#[derive(Debug, Clone)]
+pub struct ClusterNodes {
+    /// Crawls by id.
+    crawls: Arc<Mutex<HashMap<String, Arc<NodeCrawl>>>>,
+    /// Source of coordinated crawl ids.
+    next_id: Arc<AtomicU64>,
+    /// Base urls of the nodes allowed in a crawl.
+    peers: Arc<[String]>,
+    /// Bearer token sent to the other nodes.
+    token: Arc<str>,
+}
+
+impl ClusterNodes {
+    /// Crawl with the nodes at the `peers` base urls, this worker included,
+    /// sending them `token`.
+    pub fn new(peers: Vec<String>, token: &str) -> Self {
+        Self {
+            crawls: Default::default(),
+            next_id: Default::default(),
+            peers: peers
+                .iter()
+                .map(|peer| peer.trim_end_matches('/').to_string())
+                .collect(),
+            token: token.into(),
+        }
+    }
+
+    /// Whether every node is a configured peer.
+    fn allowed(&self, nodes: &[String]) -> bool {
+        nodes.iter().all(|node| {
+            self.peers
+                .iter()
+                .any(|peer| peer == node.trim_end_matches('/'))
+        })
+    }
+
+    /// Get a crawl by id.
+    fn get(&self, crawl_id: &str) -> Option<Arc<NodeCrawl>> {
+        lock(&self.crawls).get(crawl_id).cloned()
+    }
+
+    /// Join a crawl and start crawling. Returns `None` when the crawl is
+    /// already running here.
+    fn start(&self, start: ClusterStart<JobConfiguration>) -> Option<Arc<NodeCrawl>> {
+        let crawl_id = start.cluster.crawl_id.clone();
+        let mut crawls = lock(&self.crawls);
+
+        if crawls.contains_key(&crawl_id) {
+            return None;
+        }
+
+        let mut website = Website::new(&start.url);
+        website.with_config(start.configuration.to_configuration());
+        website.with_cluster(Some(start.cluster.with_token(Some(self.token.to_string()))));
+        #[cfg(feature = "metrics")]
+        website.with_metrics(Some(spider::utils::metrics::global().clone()));
+
+        let node = website.cluster_node()?;
+        let crawl = Arc::new(NodeCrawl {
+            crawling: AtomicBool::new(node.owns(&start.url)),
+            node,
+            inbound: Default::default(),
+            wake: Notify::new(),
+            visited: Default::default(),
+            stop: watch::channel(false).0,
+        });
+
+        crawls.insert(crawl_id, crawl.clone());
+        tokio::spawn(run_node(website, crawl.clone()));
+
+        Some(crawl)
+    }
+
+    /// Stop a crawl and forget it.
+    fn stop(&self, crawl_id: &str) -> Option<Arc<NodeCrawl>> {
+        let crawl = lock(&self.crawls).remove(crawl_id)?;
+        crawl.stop.send_replace(true);
+        Some(crawl)
+    }
+}
+
+/// Crawl the seed when this node owns it, then the links sent by peers
+/// until the crawl is stopped.
+async fn run_node(mut website: Website, crawl: Arc<NodeCrawl>) {
+    let mut stop = crawl.stop.subscribe();
+
+    if crawl.crawling.load(Ordering::SeqCst) {
+        tokio::select! {
+            _ = website.crawl() => crawl.finish_run(&website),
+            _ = stop.wait_for(|s| *s) => return,
+        }
+    }
+
+    loop {
+        tokio::select! {
+            _ = crawl.wake.notified() => (),
+            _ = stop.wait_for(|s| *s) => return,
+        }
+
+        // Busy before the inbound links are taken, so the node never looks
+        // idle while holding links.
+        crawl.crawling.store(true, Ordering::SeqCst);
+        let links = std::mem::take(&mut *lock(&crawl.inbound));
+
+        if links.is_empty() {
+            crawl.crawling.store(false, Ordering::SeqCst);
+            continue;
+        }
+
+        website.set_extra_links(links.into_iter().map(CaseInsensitiveString::from).collect());
+        website.persist_links();
+
+        tokio::select! {
+            _ = website.crawl() => crawl.finish_run(&website),
+            _ = stop.wait_for(|s| *s) => return,
+        }
+    }
+}
+
+/// Reply with a JSON body and status.
+fn json_reply<T: serde::Serialize>(value: &T, status: StatusCode) -> warp::reply::Response {
+    use warp::Reply;
+    warp::reply::with_status(warp::reply::json(value), status).into_response()
+}
+
+/// Reply with a JSON error message.
+fn error_reply(message: &str, status: StatusCode) -> warp::reply::Response {
+    json_reply(&serde_json::json!({ "error": message }), status)
+}
+
+/// `POST /cluster`
+async fn coordinate(
+    nodes: ClusterNodes,
+    request: ClusterRequest,
+) -> Result<warp::reply::Response, Infallible> {
+    if request.url.is_empty() || request.nodes.is_empty() {
+        return Ok(error_reply(
+            "url and nodes must not be empty",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    if !nodes.allowed(&request.nodes) {
+        return Ok(error_reply(
+            "node not in the peer list",
+            StatusCode::FORBIDDEN,
+        ));
+    }
+
+    let crawl_id = format!(
+        "{:x}-{}",
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        nodes.next_id.fetch_add(1, Ordering::Relaxed)
+    );
+    let configuration = request.configuration.unwrap_or_default();
+
+    Ok(
+        match ClusterCoordinator::new(request.nodes)
+            .with_token(Some(nodes.token.to_string()))
+            .crawl(&crawl_id, &request.url, &configuration)
+            .await
+        {
+            Ok(visited) => json_reply(
+                &ClusterResult {
+                    crawl_id,
+                    visited: visited.iter().map(|l| l.inner().to_string()).collect(),
+                },
+                StatusCode::OK,
+            ),
+            Err(e) => error_reply(&e.to_string(), StatusCode::BAD_GATEWAY),
+        },
+    )
+}
+
+/// `POST /cluster/{crawl_id}`
+async fn start_node(
+    crawl_id: String,
+    nodes: ClusterNodes,
+    start: ClusterStart<JobConfiguration>,
+) -> Result<warp::reply::Response, Infallible> {
+    if start.cluster.crawl_id != crawl_id || start.cluster.node >= start.cluster.nodes.len() {
+        return Ok(error_reply("invalid cluster", StatusCode::BAD_REQUEST));
+    }
+    if !nodes.allowed(&start.cluster.nodes) {
+        return Ok(error_reply(
+            "node not in the peer list",
+            StatusCode::FORBIDDEN,
+        ));
+    }
+    Ok(match nodes.start(start) {
+        Some(crawl) => json_reply(&crawl.status(), StatusCode::ACCEPTED),
+        _ => error_reply("crawl already running", StatusCode::CONFLICT),
+    })
+}
+
+/// `POST /cluster/{crawl_id}/links`
+async fn receive_links(
+    crawl_id: String,
+    nodes: ClusterNodes,
+    body: spider::bytes::Bytes,
+) -> Result<warp::reply::Response, Infallible> {
+    let Some(crawl) = nodes.get(&crawl_id) else {
+        return Ok(error_reply("crawl not found", StatusCode::NOT_FOUND));
+    };
+    let Some(batch) = LinkBatch::from_bytes(&body).filter(|b| b.crawl_id == crawl_id) else {
+        return Ok(error_reply("invalid link batch", StatusCode::BAD_REQUEST));
+    };
+
+    let count = batch.links.len();
+    lock(&crawl.inbound).extend(batch.links);
+    crawl.node.record_received(count);
+    crawl.wake.notify_one();
+
+    Ok(json_reply(&crawl.status(), StatusCode::OK))
+}
+
+/// `GET /cluster/{crawl_id}`
+async fn node_status(
+    crawl_id: String,
+    nodes: ClusterNodes,
+) -> Result<warp::reply::Response, Infallible> {
+    Ok(match nodes.get(&crawl_id) {
+        Some(crawl) => json_reply(&crawl.status(), StatusCode::OK),
+        _ => error_reply("crawl not found", StatusCode::NOT_FOUND),
+    })
+}
+
+/// `GET /cluster/{crawl_id}/visited`
+async fn node_visited(
+    crawl_id: String,
+    nodes: ClusterNodes,
+) -> Result<warp::reply::Response, Infallible> {
+    use warp::Reply;
+
+    let Some(crawl) = nodes.get(&crawl_id) else {
+        return Ok(error_reply("crawl not found", StatusCode::NOT_FOUND));
+    };
+    let batch = LinkBatch {
+        crawl_id,
+        links: lock(&crawl.visited).clone(),
+    };
+
+    Ok(
+        warp::reply::with_header(batch.to_bytes(), "content-type", FLEXBUFFERS_CONTENT_TYPE)
+            .into_response(),
+    )
+}
+
+/// `DELETE /cluster/{crawl_id}`
+async fn stop_node(
+    crawl_id: String,
+    nodes: ClusterNodes,
+) -> Result<warp::reply::Response, Infallible> {
+    Ok(match nodes.stop(&crawl_id) {
+        Some(crawl) => json_reply(&crawl.status(), StatusCode::OK),
+        _ => error_reply("crawl not found", StatusCode::NOT_FOUND),
+    })
+}
+
+/// The cluster routes, requiring `token` as a bearer token.
+pub fn routes(
+    nodes: ClusterNodes,
+    token: &str,
+) -> warp::filters::BoxedFilter<(warp::reply::Response,)> {
+    let with_nodes = warp::any().map(move || nodes.clone());
+    let auth = authorized(token.into());
+
+    let coordinate = warp::path("cluster")
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(auth.clone())
+        .and(with_nodes.clone())
+        .and(warp::body::content_length_limit(MAX_REQUEST_BYTES))
+        .and(warp::body::json())
+        .and_then(coordinate);
+
+    let start = warp::path!("cluster" / String)
+        .and(warp::post())
+        .and(auth.clone())
+        .and(with_nodes.clone())
+        .and(warp::body::content_length_limit(MAX_REQUEST_BYTES))
+        .and(warp::body::json())
+        .and_then(start_node);
+
+    let links = warp::path!("cluster" / String / "links")
+        .and(warp::post())
+        .and(auth.clone())
+        .and(with_nodes.clone())
+        .and(warp::body::content_length_limit(MAX_BATCH_BYTES))
+        .and(warp::body::bytes())
+        .and_then(receive_links);
+
+    let status = warp::path!("cluster" / String)
+        .and(warp::get())
+        .and(auth.clone())
+        .and(with_nodes.clone())
+        .and_then(node_status);
+
+    let visited = warp::path!("cluster" / String / "visited")
+        .and(warp::get())
+        .and(auth.clone())
+        .and(with_nodes.clone())
+        .and_then(node_visited);
+
+    let stop = warp::path!("cluster" / String)
+        .and(warp::delete())
+        .and(auth)
+        .and(with_nodes)
+        .and_then(stop_node);
+
+    coordinate
+        .or(start)
+        .unify()
+        .or(links)
+        .unify()
+        .or(status)
+        .unify()
+        .or(visited)
+        .unify()
+        .or(stop)
+        .unify()
+        .recover(unauthorized)
+        .unify()
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_node_receives_links() {
+        let nodes = ClusterNodes::new(Vec::new(), "secret");
+        let start = ClusterStart {
+            url: "http://localhost:1".into(),
+            configuration: JobConfiguration::default(),
+            cluster: spider::utils::cluster::ClusterConfig::new(
+                "crawl",
+                vec!["http://127.0.0.1:1".into(), "http://127.0.0.1:2".into()],
+                0,
+            ),
+        };
+
+        let crawl = nodes.start(start.clone()).unwrap();
+        assert!(nodes.start(start).is_none());
+
+        let batch = LinkBatch {
+            crawl_id: "crawl".into(),
+            links: vec!["http://localhost:1/a".into()],
+        };
+        receive_links("crawl".into(), nodes.clone(), batch.to_bytes().into())
+            .await
+            .unwrap();
+        assert_eq!(crawl.node.status(true, 0).received, 1);
+
+        assert!(nodes.stop("crawl").is_some());
+        assert!(nodes.get("crawl").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_routes_require_token_and_peers() {
+        let routes = routes(
+            ClusterNodes::new(vec!["http://10.0.0.1:3033/".into()], "secret"),
+            "secret",
+        );
+
+        let response = warp::test::request()
+            .path("/cluster/crawl")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/cluster")
+            .header("authorization", "Bearer secret")
+            .json(&serde_json::json!({
+                "url": "https://example.com",
+                "nodes": ["http://10.0.0.1:3033", "http://169.254.169.254"]
+            }))
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/cluster/crawl")
+            .header("authorization", "Bearer secret")
+            .json(&serde_json::json!({
+                "url": "https://example.com",
+                "configuration": { "proxies": ["http://proxy:8080"] },
+                "cluster": { "crawl_id": "crawl", "nodes": ["http://10.0.0.1:3033"], "node": 0, "virtual_nodes": 64 }
+            }))
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,859 @@
+//! Crawl job queue served on `SPIDER_WORKER_JOBS_PORT`.
+//!
+//! ```text
+//! POST   /jobs              {"urls": [...], "configuration": {...}, "content": false}
+//! GET    /jobs/{id}         job status
+//! GET    /jobs/{id}/results pages as NDJSON, or SSE with `Accept: text/event-stream`
+//! DELETE /jobs/{id}         cancel an active job or drop a finished one
+//! ```
+//!
+//! Every request needs `Authorization: Bearer <SPIDER_WORKER_JOBS_TOKEN>`.
+//! Jobs run one `Website` crawl per seed url with the posted
+//! [`JobConfiguration`], an allow-list of crawl settings that cannot reach
+//! the worker's files or proxies. At most `SPIDER_WORKER_JOBS_CONCURRENCY`
+//! jobs crawl at once; up to `SPIDER_WORKER_JOBS_MAX_PENDING` more stay
+//! queued and further jobs are refused with `429`. The last
+//! `SPIDER_WORKER_JOBS_MAX_RESULTS` results are kept with the job until it is
+//! deleted or, once finished, for `SPIDER_WORKER_JOBS_TTL` seconds, so a
+//! result stream opened late replays the retained pages before following the
+//! live crawl.
+
+use spider::bytes::Bytes;
+use spider::configuration::Configuration;
+use spider::hashbrown::HashMap;
+use spider::tokio::{
+    self,
+    sync::{broadcast::error::RecvError, watch, Semaphore},
+};
+use spider::website::Website;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use warp::http::StatusCode;
+use warp::Filter;
+
+/// Largest accepted job request body.
+const MAX_REQUEST_BYTES: u64 = 1024 * 1024;
+
+/// How long finished jobs are kept by default, in seconds.
+const DEFAULT_TTL_SECS: u64 = 3600;
+
+/// Results kept per job by default.
+const DEFAULT_MAX_RESULTS: usize = 10_000;
+
+/// Jobs waiting for a pool slot by default.
+const DEFAULT_MAX_PENDING: usize = 100;
+
+/// A crawl job submitted with `POST /jobs`.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct JobRequest {
+    /// Seed urls, each crawled as its own website.
+    pub urls: Vec<String>,
+    /// The crawl settings applied to every seed.
+    pub configuration: Option<JobConfiguration>,
+    /// Include the page content in the results.
+    pub content: bool,
+}
+
+/// The crawl settings a job may set. Anything else, such as WARC or
+/// checkpoint paths and proxies, is rejected.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobConfiguration {
+    /// Respect robots.txt.
+    pub respect_robots_txt: bool,
+    /// Include subdomains.
+    pub subdomains: bool,
+    /// Include all tlds of the domain.
+    pub tld: bool,
+    /// The max link depth, `0` for none.
+    pub depth: usize,
+    /// Delay between requests in milliseconds.
+    pub delay: u64,
+    /// The request timeout in milliseconds.
+    pub request_timeout: Option<u64>,
+    /// The crawl timeout in milliseconds.
+    pub crawl_timeout: Option<u64>,
+    /// The user agent.
+    pub user_agent: Option<String>,
+    /// Urls or patterns to skip.
+    pub blacklist_url: Option<Vec<String>>,
+    /// Urls or patterns to only crawl.
+    pub whitelist_url: Option<Vec<String>>,
+    /// Pages allowed per path, `*` for the whole crawl.
+    pub budget: Option<HashMap<String, u32>>,
+    /// The max redirects followed.
+    pub redirect_limit: Option<usize>,
+}
+
+impl JobConfiguration {
+    /// The crawl configuration with these settings.
+    pub fn to_configuration(&self) -> Configuration {
+        let mut configuration = Configuration::new();
+
+        configuration
+            .with_respect_robots_txt(self.respect_robots_txt)
+            .with_subdomains(self.subdomains)
+            .with_tld(self.tld)
+            .with_depth(self.depth)
+            .with_delay(self.delay)
+            .with_request_timeout(self.request_timeout.map(Duration::from_millis))
+            .with_crawl_timeout(self.crawl_timeout.map(Duration::from_millis))
+            .with_user_agent(self.user_agent.as_deref())
+            .with_budget(self.budget.as_ref().map(|budget| {
+                budget
+                    .iter()
+                    .map(|(path, limit)| (path.as_str(), *limit))
+                    .collect()
+            }));
+
+        configuration.blacklist_url = self
+            .blacklist_url
+            .as_ref()
+            .map(|list| list.iter().map(|u| u.as_str().into()).collect());
+        configuration.whitelist_url = self
+            .whitelist_url
+            .as_ref()
+            .map(|list| list.iter().map(|u| u.as_str().into()).collect());
+
+        if let Some(redirect_limit) = self.redirect_limit {
+            configuration.with_redirect_limit(redirect_limit);
+        }
+
+        configuration
+    }
+}
+
+/// The lifecycle of a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// Waiting for a free slot in the pool.
+    Queued,
+    /// Crawling.
+    Running,
+    /// Every seed was crawled.
+    Completed,
+    /// Every seed was crawled but the results fell behind the crawl and
+    /// pages were dropped.
+    Failed,
+    /// Cancelled with `DELETE /jobs/{id}`.
+    Cancelled,
+}
+
+impl JobStatus {
+    /// The job will not produce more results.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// A page produced by a job, one line of the result stream.
+#[derive(Debug, serde::Serialize)]
+struct JobResult<'a> {
+    /// The page url.
+    url: &'a str,
+    /// The HTTP status code.
+    status_code: u16,
+    /// The page content when requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+}
+
+/// The mutable part of a job.
+#[derive(Debug)]
+struct JobState {
+    /// The job status.
+    status: JobStatus,
+    /// When the job finished, in unix seconds.
+    finished_at: Option<u64>,
+    /// The latest serialized results in crawl order.
+    results: VecDeque<Bytes>,
+    /// Results evicted from the front of `results` past the cap.
+    evicted: u64,
+    /// Pages dropped because the results fell behind the crawl.
+    dropped: u64,
+}
+
+/// A crawl job.
+#[derive(Debug)]
+pub struct Job {
+    /// The job id.
+    id: String,
+    /// The seed urls.
+    urls: Vec<String>,
+    /// When the job was submitted, in unix seconds.
+    created_at: u64,
+    /// Results kept before the oldest are evicted.
+    max_results: usize,
+    /// Status and results.
+    state: Mutex<JobState>,
+    /// Bumped whenever a result is added or the status changes.
+    updates: watch::Sender<usize>,
+    /// Set to cancel the job.
+    cancel: watch::Sender<bool>,
+}
+
+/// The `GET /jobs/{id}` response.
+#[derive(Debug, serde::Serialize)]
+pub struct JobInfo {
+    /// The job id.
+    pub id: String,
+    /// The job status.
+    pub status: JobStatus,
+    /// The seed urls.
+    pub urls: Vec<String>,
+    /// Number of pages crawled so far.
+    pub pages: u64,
+    /// Results kept for the result stream; older ones are evicted.
+    pub max_results: usize,
+    /// Results evicted past `max_results`, no longer replayed.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub evicted: u64,
+    /// Pages dropped because the results fell behind the crawl.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub dropped: u64,
+    /// When the job was submitted, in unix seconds.
+    pub created_at: u64,
+    /// When the job finished, in unix seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+}
+
+impl Job {
+    /// A queued job keeping up to `max_results` results.
+    fn new(id: String, urls: Vec<String>, max_results: usize) -> Self {
+        Self {
+            id,
+            urls,
+            created_at: unix_now(),
+            max_results: max_results.max(1),
+            state: Mutex::new(JobState {
+                status: JobStatus::Queued,
+                finished_at: None,
+                results: VecDeque::new(),
+                evicted: 0,
+                dropped: 0,
+            }),
+            updates: watch::channel(0).0,
+            cancel: watch::channel(false).0,
+        }
+    }
+
+    /// Lock the job state, recovering from a poisoned lock.
+    fn state(&self) -> std::sync::MutexGuard<'_, JobState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// A snapshot of the job for the status endpoint.
+    pub fn info(&self) -> JobInfo {
+        let state = self.state();
+        JobInfo {
+            id: self.id.clone(),
+            status: state.status,
+            urls: self.urls.clone(),
+            pages: state.evicted + state.results.len() as u64,
+            max_results: self.max_results,
+            evicted: state.evicted,
+            dropped: state.dropped,
+            created_at: self.created_at,
+            finished_at: state.finished_at,
+        }
+    }
+
+    /// Move to `status` unless the job already finished. Returns whether the status changed.
+    fn set_status(&self, status: JobStatus) -> bool {
+        let changed = {
+            let mut state = self.state();
+            if state.status.is_finished() {
+                false
+            } else {
+                state.status = status;
+                if status.is_finished() {
+                    state.finished_at = Some(unix_now());
+                }
+                true
+            }
+        };
+        if changed {
+            self.updates.send_modify(|v| *v += 1);
+        }
+        changed
+    }
+
+    /// Append a serialized result line, evicting the oldest past the cap.
+    fn push_result(&self, line: Bytes) {
+        {
+            let mut state = self.state();
+            if state.results.len() >= self.max_results {
+                state.results.pop_front();
+                state.evicted += 1;
+            }
+            state.results.push_back(line);
+        }
+        self.updates.send_modify(|v| *v += 1);
+    }
+
+    /// Count pages dropped by the result collector.
+    fn record_dropped(&self, pages: u64) {
+        self.state().dropped += pages;
+    }
+
+    /// Whether the job finished more than `ttl` ago.
+    fn expired(&self, now: u64, ttl: Duration) -> bool {
+        self.state()
+            .finished_at
+            .is_some_and(|finished_at| finished_at.saturating_add(ttl.as_secs()) < now)
+    }
+
+    /// Cancel the job. Returns false when it already finished.
+    pub fn cancel(&self) -> bool {
+        let cancelled = self.set_status(JobStatus::Cancelled);
+        if cancelled {
+            self.cancel.send_replace(true);
+        }
+        cancelled
+    }
+
+    /// The result at `index`, or the oldest retained one when it was
+    /// evicted, with its index. `Ok(None)` when it is not produced yet and
+    /// `Err(())` when the job finished before producing it.
+    fn result(&self, index: u64) -> Result<Option<(u64, Bytes)>, ()> {
+        let state = self.state();
+        let index = index.max(state.evicted);
+        match state.results.get((index - state.evicted) as usize) {
+            Some(line) => Ok(Some((index, line.clone()))),
+            None if state.status.is_finished() => Err(()),
+            None => Ok(None),
+        }
+    }
+
+    /// Stream the results from the oldest retained page until the job finishes.
+    pub fn results(
+        self: Arc<Self>,
+    ) -> impl futures_util::Stream<Item = Result<Bytes, Infallible>> + Send + Sync + 'static {
+        let updates = self.updates.subscribe();
+
+        futures_util::stream::unfold((self, 0, updates), |(job, index, mut updates)| async move {
+            loop {
+                match job.result(index) {
+                    Ok(Some((index, line))) => return Some((Ok(line), (job, index + 1, updates))),
+                    Ok(None) => {
+                        if updates.changed().await.is_err() {
+                            return None;
+                        }
+                    }
+                    Err(()) => return None,
+                }
+            }
+        })
+    }
+}
+
+/// The jobs of the worker and the pool they run in.
+#[derive(Debug, Clone)]
+pub struct JobQueue {
+    /// Jobs by id.
+    jobs: Arc<Mutex<HashMap<String, Arc<Job>>>>,
+    /// Slots of the crawl pool.
+    permits: Arc<Semaphore>,
+    /// Source of job ids.
+    next_id: Arc<AtomicU64>,
+    /// How long finished jobs are kept.
+    ttl: Duration,
+    /// Results kept per job.
+    max_results: usize,
+    /// Jobs allowed to wait for a slot.
+    max_pending: usize,
+}
+
+impl JobQueue {
+    /// A queue running at most `concurrency` jobs at once.
+    pub fn new(concurrency: usize) -> Self {
+        Self {
+            jobs: Default::default(),
+            permits: Arc::new(Semaphore::new(concurrency.max(1))),
+            next_id: Arc::new(AtomicU64::new(1)),
+            ttl: Duration::from_secs(DEFAULT_TTL_SECS),
+            max_results: DEFAULT_MAX_RESULTS,
+            max_pending: DEFAULT_MAX_PENDING,
+        }
+    }
+
+    /// Keep the last `max_results` results of each job.
+    pub fn with_max_results(mut self, max_results: usize) -> Self {
+        self.max_results = max_results;
+        self
+    }
+
+    /// Refuse new jobs while `max_pending` jobs wait for a slot.
+    pub fn with_max_pending(mut self, max_pending: usize) -> Self {
+        self.max_pending = max_pending;
+        self
+    }
+
+    /// Keep finished jobs and their results for `ttl`.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Forget the jobs that finished more than the ttl ago.
+    fn evict(&self) {
+        let now = unix_now();
+        self.jobs().retain(|_, job| !job.expired(now, self.ttl));
+    }
+
+    /// Lock the job map, recovering from a poisoned lock.
+    fn jobs(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<Job>>> {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Queue a job and start it once a slot frees up. Returns `None` when
+    /// the max pending jobs are already queued.
+    pub fn submit(&self, request: JobRequest) -> Option<Arc<Job>> {
+        let id = format!(
+            "{:x}-{}",
+            unix_now(),
+            self.next_id.fetch_add(1, Ordering::Relaxed)
+        );
+        let job = Arc::new(Job::new(id.clone(), request.urls.clone(), self.max_results));
+
+        self.evict();
+        {
+            let mut jobs = self.jobs();
+            let pending = jobs
+                .values()
+                .filter(|job| job.state().status == JobStatus::Queued)
+                .count();
+            if pending >= self.max_pending {
+                return None;
+            }
+            jobs.insert(id, job.clone());
+        }
+        tokio::spawn(run_job(self.permits.clone(), job.clone(), request));
+
+        Some(job)
+    }
+
+    /// Get a job by id.
+    pub fn get(&self, id: &str) -> Option<Arc<Job>> {
+        self.evict();
+        self.jobs().get(id).cloned()
+    }
+
+    /// Cancel an active job, or forget a finished one.
+    pub fn delete(&self, id: &str) -> Option<Arc<Job>> {
+        let job = self.get(id)?;
+        if !job.cancel() {
+            self.jobs().remove(id);
+        }
+        Some(job)
+    }
+}
+
+/// Wait for a pool slot and crawl every seed of the job.
+async fn run_job(permits: Arc<Semaphore>, job: Arc<Job>, request: JobRequest) {
+    let mut cancel = job.cancel.subscribe();
+
+    let permit = tokio::select! {
+        permit = permits.acquire_owned() => permit.ok(),
+        _ = cancel.wait_for(|c| *c) => None,
+    };
+
+    let Some(_permit) = permit else {
+        return;
+    };
+
+    if !job.set_status(JobStatus::Running) {
+        return;
+    }
+
+    for url in request.urls.iter() {
+        let mut website = Website::new(url);
+
+        if let Some(ref configuration) = request.configuration {
+            website.with_config(configuration.to_configuration());
+        }
+        #[cfg(feature = "metrics")]
+        website.with_metrics(Some(spider::utils::metrics::global().clone()));
+
+        let mut rx = website.subscribe(0);
+        let collector = job.clone();
+        let content = request.content;
+
+        let collect = tokio::spawn(async move {
+            loop {
+                let page = match rx.recv().await {
+                    Ok(page) => page,
+                    Err(RecvError::Lagged(skipped)) => {
+                        collector.record_dropped(skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                let result = JobResult {
+                    url: page.get_url(),
+                    status_code: page.status_code.as_u16(),
+                    content: if content { Some(page.get_html()) } else { None },
+                };
+                if let Ok(mut line) = serde_json::to_vec(&result) {
+                    line.push(b'\n');
+                    collector.push_result(line.into());
+                }
+            }
+        });
+
+        let cancelled = tokio::select! {
+            _ = website.crawl() => false,
+            _ = cancel.wait_for(|c| *c) => true,
+        };
+
+        website.unsubscribe();
+        drop(website);
+        let _ = collect.await;
+
+        if cancelled {
+            return;
+        }
+    }
+
+    let dropped = job.state().dropped > 0;
+    job.set_status(if dropped {
+        JobStatus::Failed
+    } else {
+        JobStatus::Completed
+    });
+}
+
+/// Serde helper to skip zero counters.
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+/// Seconds since the unix epoch.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Reply with a JSON body and status.
+fn json_reply<T: serde::Serialize>(value: &T, status: StatusCode) -> warp::reply::Response {
+    use warp::Reply;
+    warp::reply::with_status(warp::reply::json(value), status).into_response()
+}
+
+/// Reply with a JSON error message.
+fn error_reply(message: &str, status: StatusCode) -> warp::reply::Response {
+    json_reply(&serde_json::json!({ "error": message }), status)
+}
+
+/// `POST /jobs`
+async fn create_job(
+    queue: JobQueue,
+    request: JobRequest,
+) -> Result<warp::reply::Response, Infallible> {
+    if request.urls.is_empty() {
+        return Ok(error_reply(
+            "urls must not be empty",
+            StatusCode::BAD_REQUEST,
+        ));
+    }
+    Ok(match queue.submit(request) {
+        Some(job) => json_reply(&job.info(), StatusCode::ACCEPTED),
+        _ => error_reply("too many queued jobs", StatusCode::TOO_MANY_REQUESTS),
+    })
+}
+
+/// `GET /jobs/{id}`
+async fn get_job(id: String, queue: JobQueue) -> Result<warp::reply::Response, Infallible> {
+    Ok(match queue.get(&id) {
+        Some(job) => json_reply(&job.info(), StatusCode::OK),
+        _ => error_reply("job not found", StatusCode::NOT_FOUND),
+    })
+}
+
+/// `GET /jobs/{id}/results`
+async fn get_results(
+    id: String,
+    accept: Option<String>,
+    queue: JobQueue,
+) -> Result<warp::reply::Response, Infallible> {
+    use futures_util::StreamExt;
+    use warp::Reply;
+
+    let Some(job) = queue.get(&id) else {
+        return Ok(error_reply("job not found", StatusCode::NOT_FOUND));
+    };
+
+    let sse = accept.is_some_and(|a| a.contains("text/event-stream"));
+
+    Ok(if sse {
+        let events = job.results().map(|line| {
+            line.map(|line| {
+                warp::sse::Event::default()
+                    .event("page")
+                    .data(String::from_utf8_lossy(line.trim_ascii_end()))
+            })
+        });
+        warp::sse::reply(warp::sse::keep_alive().stream(events)).into_response()
+    } else {
+        warp::reply::with_header(
+            warp::reply::stream(job.results()),
+            "content-type",
+            "application/x-ndjson",
+        )
+        .into_response()
+    })
+}
+
+/// `DELETE /jobs/{id}`
+async fn delete_job(id: String, queue: JobQueue) -> Result<warp::reply::Response, Infallible> {
+    Ok(match queue.delete(&id) {
+        Some(job) => json_reply(&job.info(), StatusCode::OK),
+        _ => error_reply("job not found", StatusCode::NOT_FOUND),
+    })
+}
+
+/// Compare two byte strings in time independent of where they differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Reject requests without `Authorization: Bearer <token>`.
+pub(crate) fn authorized(token: Arc<str>) -> warp::filters::BoxedFilter<()> {
+    warp::header::optional::<String>("authorization")
+        .and_then(move |header: Option<String>| {
+            let token = token.clone();
+            async move {
+                match header.as_deref().and_then(|h| h.strip_prefix("Bearer ")) {
+                    Some(bearer) if constant_time_eq(bearer.as_bytes(), token.as_bytes()) => Ok(()),
+                    _ => Err(warp::reject::custom(Unauthorized)),
+                }
+            }
+        })
+        .untuple_one()
+        .boxed()
+}
+
+/// A request without a valid job token.
+#[derive(Debug)]
+struct Unauthorized;
+
+impl warp::reject::Reject for Unauthorized {}
+
+/// Answer `401` for requests without a valid job token.
+pub(crate) async fn unauthorized(
+    rejection: warp::Rejection,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    if rejection.find::<Unauthorized>().is_some() {
+        Ok(error_reply("unauthorized", StatusCode::UNAUTHORIZED))
+    } else {
+        Err(rejection)
+    }
+}
+
+/// The job routes, requiring `token` as a bearer token.
+pub fn routes(
+    queue: JobQueue,
+    token: &str,
+) -> warp::filters::BoxedFilter<(warp::reply::Response,)> {
+    let with_queue = warp::any().map(move || queue.clone());
+    let auth = authorized(token.into());
+
+    let create = warp::path("jobs")
+        .and(warp::path::end())
+        .and(warp::post())
+        .and(auth.clone())
+        .and(with_queue.clone())
+        .and(warp::body::content_length_limit(MAX_REQUEST_BYTES))
+        .and(warp::body::json())
+        .and_then(create_job);
+
+    let get = warp::path!("jobs" / String)
+        .and(warp::get())
+        .and(auth.clone())
+        .and(with_queue.clone())
+        .and_then(get_job);
+
+    let results = warp::path!("jobs" / String / "results")
+        .and(warp::get())
+        .and(auth.clone())
+        .and(warp::header::optional::<String>("accept"))
+        .and(with_queue.clone())
+        .and_then(get_results);
+
+    let delete = warp::path!("jobs" / String)
+        .and(warp::delete())
+        .and(auth)
+        .and(with_queue)
+        .and_then(delete_job);
+
+    create
+        .or(get)
+        .unify()
+        .or(results)
+        .unify()
+        .or(delete)
+        .unify()
+        .recover(unauthorized)
+        .unify()
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancel_queued_job() {
+        let queue = JobQueue::new(1);
+        let _permit = queue.permits.clone().acquire_owned().await.unwrap();
+
+        let job = queue
+            .submit(JobRequest {
+                urls: vec!["http://localhost:1".into()],
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(job.info().status, JobStatus::Queued);
+
+        let id = job.info().id;
+        assert!(queue.delete(&id).is_some());
+        assert_eq!(job.info().status, JobStatus::Cancelled);
+        assert!(job.info().finished_at.is_some());
+
+        // A second delete forgets the finished job.
+        assert!(queue.delete(&id).is_some());
+        assert!(queue.get(&id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_results_replay_then_end() {
+        use futures_util::StreamExt;
+
+        let job = Arc::new(Job::new("1".into(), Vec::new(), 8));
+        job.push_result(Bytes::from_static(b"{\"url\":\"a\"}\n"));
+
+        let stream = job.clone().results();
+        job.push_result(Bytes::from_static(b"{\"url\":\"b\"}\n"));
+        job.set_status(JobStatus::Completed);
+
+        let lines: Vec<_> = stream.map(|l| l.unwrap()).collect().await;
+        assert_eq!(lines.len(), 2);
+        assert!(!job.cancel());
+    }
+
+    #[tokio::test]
+    async fn test_results_evict_past_cap() {
+        use futures_util::StreamExt;
+
+        let job = Arc::new(Job::new("1".into(), Vec::new(), 2));
+        for line in ["a", "b", "c"] {
+            job.push_result(Bytes::from(line));
+        }
+        job.set_status(JobStatus::Completed);
+
+        let info = job.info();
+        assert_eq!((info.pages, info.max_results, info.evicted), (3, 2, 1));
+
+        let lines: Vec<_> = job.results().map(|l| l.unwrap()).collect().await;
+        assert_eq!(lines, ["b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_jobs_past_pending_cap() {
+        let queue = JobQueue::new(1).with_max_pending(1);
+        let _permit = queue.permits.clone().acquire_owned().await.unwrap();
+        let request = || JobRequest {
+            urls: vec!["http://localhost:1".into()],
+            ..Default::default()
+        };
+
+        let job = queue.submit(request()).unwrap();
+        assert!(queue.submit(request()).is_none());
+
+        let response = warp::test::request()
+            .method("POST")
+            .path("/jobs")
+            .header("authorization", "Bearer secret")
+            .json(&serde_json::json!({ "urls": ["http://localhost:1"] }))
+            .reply(&routes(queue.clone(), "secret"))
+            .await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        job.cancel();
+        assert!(queue.submit(request()).is_some());
+    }
+
+    #[test]
+    fn test_configuration_allow_list() {
+        let request: JobRequest = serde_json::from_str(
+            r#"{"urls": ["https://example.com"], "configuration": {"depth": 2, "subdomains": true}}"#,
+        )
+        .unwrap();
+        let configuration = request.configuration.unwrap().to_configuration();
+        assert_eq!(configuration.depth, 2);
+        assert!(configuration.subdomains);
+
+        for field in [
+            r#"{"proxies": ["http://proxy:8080"]}"#,
+            r#"{"warc": {"path": "/etc/passwd"}}"#,
+            r#"{"checkpoint": {"path": "/tmp/spider"}}"#,
+        ] {
+            let body = format!(r#"{{"urls": ["https://example.com"], "configuration": {field}}}"#);
+            assert!(
+                serde_json::from_str::<JobRequest>(&body).is_err(),
+                "{field}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evicts_expired_jobs() {
+        let queue = JobQueue::new(1).with_ttl(Duration::from_secs(60));
+        let _permit = queue.permits.clone().acquire_owned().await.unwrap();
+
+        let job = queue
+            .submit(JobRequest {
+                urls: vec!["http://localhost:1".into()],
+                ..Default::default()
+            })
+            .unwrap();
+        let id = job.info().id;
+        job.cancel();
+        assert!(queue.get(&id).is_some());
+
+        job.state().finished_at = Some(unix_now() - 120);
+        assert!(queue.get(&id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_routes_require_token() {
+        let routes = routes(JobQueue::new(1), "secret");
+
+        let response = warp::test::request()
+            .path("/jobs/missing")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = warp::test::request()
+            .path("/jobs/missing")
+            .header("authorization", "Bearer wrong")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = warp::test::request()
+            .path("/jobs/missing")
+            .header("authorization", "Bearer secret")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
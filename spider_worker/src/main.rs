@@ -5,6 +5,11 @@ use warp::{path::FullPath, Filter};
 #[macro_use]
 extern crate lazy_static;
 
+#[cfg(feature = "cluster")]
+mod cluster;
+#[cfg(feature = "jobs")]
+mod jobs;
+
 lazy_static! {
     /// top level request client to re-use
     static ref CLIENT: spider::Client = {
@@ -44,6 +49,118 @@ async fn serve_plain(routes: warp::filters::BoxedFilter<(impl warp::Reply + 'sta
     }
 }
 
+/// Serve the OpenMetrics exposition of the process on `/metrics` at `SPIDER_WORKER_METRICS_PORT`.
+#[cfg(feature = "metrics")]
+fn spawn_metrics() {
+    let routes = warp::path("metrics")
+        .and(warp::path::end())
+        .map(|| {
+            warp::http::Response::builder()
+                .header("content-type", utils::metrics::CONTENT_TYPE)
+                .body(utils::metrics::global().render())
+                .unwrap_or_else(|_| warp::http::Response::new(String::new()))
+        })
+        .boxed();
+
+    let port: u16 = std::env::var("SPIDER_WORKER_METRICS_PORT")
+        .unwrap_or_else(|_| "3032".into())
+        .parse()
+        .unwrap_or(3032);
+
+    utils::log(
+        "Spider_Worker metrics starting at 0.0.0.0:",
+        port.to_string(),
+    );
+
+    tokio::spawn(serve_plain(routes, port));
+}
+
+/// Metrics are disabled without the `metrics` flag.
+#[cfg(not(feature = "metrics"))]
+fn spawn_metrics() {}
+
+/// Serve the crawl job queue, and the cluster endpoints with the `cluster` flag, at `SPIDER_WORKER_JOBS_PORT`.
+#[cfg(feature = "jobs")]
+fn spawn_jobs() {
+    let concurrency: usize = std::env::var("SPIDER_WORKER_JOBS_CONCURRENCY")
+        .unwrap_or_else(|_| "2".into())
+        .parse()
+        .unwrap_or(2);
+
+    let ttl: u64 = std::env::var("SPIDER_WORKER_JOBS_TTL")
+        .unwrap_or_else(|_| "3600".into())
+        .parse()
+        .unwrap_or(3600);
+
+    let max_results: usize = std::env::var("SPIDER_WORKER_JOBS_MAX_RESULTS")
+        .unwrap_or_else(|_| "10000".into())
+        .parse()
+        .unwrap_or(10_000);
+
+    let max_pending: usize = std::env::var("SPIDER_WORKER_JOBS_MAX_PENDING")
+        .unwrap_or_else(|_| "100".into())
+        .parse()
+        .unwrap_or(100);
+
+    let port: u16 = std::env::var("SPIDER_WORKER_JOBS_PORT")
+        .unwrap_or_else(|_| "3033".into())
+        .parse()
+        .unwrap_or(3033);
+
+    let Ok(token) = std::env::var("SPIDER_WORKER_JOBS_TOKEN") else {
+        utils::log(
+            "Spider_Worker jobs need SPIDER_WORKER_JOBS_TOKEN, not serving 0.0.0.0:",
+            port.to_string(),
+        );
+        return;
+    };
+
+    let routes = jobs::routes(
+        jobs::JobQueue::new(concurrency)
+            .with_ttl(std::time::Duration::from_secs(ttl))
+            .with_max_results(max_results)
+            .with_max_pending(max_pending),
+        &token,
+    );
+
+    #[cfg(feature = "cluster")]
+    let peers: Vec<String> = std::env::var("SPIDER_WORKER_CLUSTER_PEERS")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|peer| !peer.is_empty())
+        .map(String::from)
+        .collect();
+
+    #[cfg(feature = "cluster")]
+    let routes = routes
+        .or(cluster::routes(
+            cluster::ClusterNodes::new(peers, &token),
+            &token,
+        ))
+        .unify()
+        .boxed();
+
+    utils::log("Spider_Worker jobs starting at 0.0.0.0:", port.to_string());
+
+    #[cfg(feature = "tls")]
+    tokio::spawn(async move {
+        let pem_cert: String =
+            std::env::var("SPIDER_WORKER_CERT_PATH").unwrap_or_else(|_| "/cert.pem".into());
+        let rsa_key: String =
+            std::env::var("SPIDER_WORKER_KEY_PATH").unwrap_or_else(|_| "/key.rsa".into());
+
+        serve_tls(routes, port, &pem_cert, &rsa_key).await;
+    });
+
+    #[cfg(not(feature = "tls"))]
+    tokio::spawn(serve_plain(routes, port));
+}
+
+/// The job queue is disabled without the `jobs` flag.
+#[cfg(not(feature = "jobs"))]
+fn spawn_jobs() {}
+
 /// Serve warp routes over TLS using tokio-rustls.
 #[cfg(feature = "tls")]
 async fn serve_tls(
@@ -298,6 +415,8 @@ async fn scrape(path: FullPath, host: String) -> Result<impl warp::Reply, Infall
 ))]
 async fn main() {
     env_logger::init();
+    spawn_metrics();
+    spawn_jobs();
 
     let host = warp::header::<String>("host");
     let referer = warp::header::optional::<String>("referer");
@@ -322,6 +441,8 @@ async fn main() {
 #[cfg(all(feature = "scrape", not(feature = "tls"),))]
 async fn main() {
     env_logger::init();
+    spawn_metrics();
+    spawn_jobs();
     let host = warp::header::<String>("host");
     let routes = warp::path::full().and(host).and_then(scrape).boxed();
     let port: u16 = std::env::var("SPIDER_WORKER_SCRAPER_PORT")
@@ -342,6 +463,8 @@ async fn main() {
 ))]
 async fn main() {
     env_logger::init();
+    spawn_metrics();
+    spawn_jobs();
     let host = warp::header::<String>("host");
     let referer = warp::header::optional::<String>("referer");
     let routes = warp::path::full()
@@ -385,6 +508,8 @@ async fn main() {
 ))]
 async fn main() {
     env_logger::init();
+    spawn_metrics();
+    spawn_jobs();
 
     let host = warp::header::<String>("host");
     let referer = warp::header::optional::<String>("referer");
@@ -414,6 +539,8 @@ async fn main() {
 #[cfg(all(feature = "scrape", feature = "tls"))]
 async fn main() {
     env_logger::init();
+    spawn_metrics();
+    spawn_jobs();
     let host = warp::header::<String>("host");
     let routes = warp::path::full().and(host).and_then(scrape).boxed();
     let port: u16 = std::env::var("SPIDER_WORKER_SCRAPER_PORT")
@@ -435,6 +562,8 @@ async fn main() {
 #[cfg(all(not(feature = "scrape"), feature = "full_resources", feature = "tls"))]
 async fn main() {
     env_logger::init();
+    spawn_metrics();
+    spawn_jobs();
     let host = warp::header::<String>("host");
     let referer = warp::header::optional::<String>("referer");
     let routes = warp::path::full()